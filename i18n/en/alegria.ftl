@@ -15,8 +15,6 @@ hotel = Hotel
 management = Management
 
 <#-- Bar Screen -->
-restaurant = Restaurant
-garden = Garden
 unknown = Unknown
 no-products = No products yet...
 
@@ -27,3 +25,12 @@ room-types = Room Types
 <#-- RoomTypes SubScreen -->
 no-room-types = No Room Types yet...
 
+<#-- Simple Invoices SubScreen -->
+chain-break-hash-mismatch = The hash doesn't match the record
+chain-break-previous-hash-mismatch = The previous hash doesn't match the previous record
+chain-break-missing-records = There are missing records
+
+<#-- Invoice Series SubScreen -->
+simplified-series = Simplified
+full-series = Full
+rectifying-series = Rectifying
@@ -23,8 +23,6 @@ hotel = Hotel
 management = Gestión
 
 <#-- Bar Screen -->
-restaurant = Restaurante
-garden = Jardín
 unknown = Desconocido
 no-products = Nada todavía...
 print = Imprimir
@@ -34,12 +32,44 @@ ticket-type = Tipo de Ticket
 unlock = Desbloquear
 pay = Pagar
 payment-method = Método de Pago
+recipient-name = Nombre o Razón Social
+recipient-nif = NIF
+recipient-address = Dirección Fiscal
+pay-whole = Pagar Todo
+split-by-lines = Dividir por Productos
+split-evenly = Dividir a Partes Iguales
+payers = Pagadores
+all = Todo
+pending = Pendiente
+amount = Importe
+add-payment = Añadir Pago
+remaining = Restante
+change = Cambio
+move = Mover
+merge = Juntar
+select-move-target = Selecciona la mesa de destino
+select-merge-target = Selecciona la mesa con la que juntar
+grid = Cuadrícula
+send-order = Enviar Comanda
+add-to-ticket = Añadir
+order-sent = Comanda enviada
+discount = Descuento
+ticket-discount = Descuento del Ticket
+selected-line = Línea Seleccionada
+whole-ticket = Ticket Completo
+discount-value = Valor
+apply-discount = Aplicar
+remove-discount = Quitar Descuento
+low-stock = Stock bajo
+void = Anular
+voided-by = Anulado por
 
 <#-- Hotel Screen -->
 reservations = Reservas
 room-types = Tipos de Habitación
 rooms = Habitaciones
 clients = Clientes
+companies = Empresas
 
 <#-- RoomTypes SubScreen -->
 no-room-types = Nada todavía...
@@ -69,6 +99,12 @@ phone-number = Número de Teléfono
 mobile-phone = Número de Móvil
 gender = Género
 
+<#-- Companies SubScreen -->
+company = Empresa
+no-companies = Nada todavía...
+cif = CIF
+email = Email
+
 <#-- Reservations SubScreen -->
 filter = Filtrar
 initial-date = Fecha Inicial
@@ -79,11 +115,37 @@ occupied = Ocupada
 no-client-selected = Cliente no seleccionado
 main-client = Cliente Principal
 add-reservation = Añadir Reserva
+edit-reservation = Editar Reserva
+cancel-reservation = Cancelar Reserva
+check-in = Check-in
+add-guest = Añadir Huésped
+no-guests = Ningún huésped registrado
+adult = Adulto
+minor = Menor
+check-out = Check-out
+nights = Noches
+extras = Extras
+add-extra = Añadir Extra
+adeudos = Adeudos
+final-invoice = Factura Final
+folio-total = Total a Pagar
+paid-by-company = Paga la Empresa
 
 <#-- Management Screen -->
 products = Productos
 product-categories = Categorías de Productos
 simple-invoices = Facturas Simplificadas
+cash-register = Caja
+table-locations = Ubicaciones de Mesas
+bar-tables = Mesas
+floor-plan = Plano de Sala
+preparation-stations = Estaciones de Preparación
+modifier-groups = Modificadores
+pricing-rules = Tarifas
+stock = Stock
+ingredients = Ingredientes
+recipes = Recetas
+voided-lines = Anulaciones
 
 <#-- ProductCategories SubScreen -->
 no-product-categories = Nada todavía...
@@ -93,9 +155,137 @@ product-category = Categoría de Producto
 inside-price = Precio Dentro
 outside-price = Precio Fuera
 tax-percentage = IVA
+low-stock-threshold = Stock Mínimo
 
 <#-- Simple Invoices SubScreen -->
 no-simple-invoices = Nada todavía...
 id = Id
 total-invoice = Total 
-details = Detalles
\ No newline at end of file
+details = Detalles
+refund = Devolver
+refund-of = Devolución de la factura
+refunded-by = Devuelta con la factura
+simple-invoice = Factura
+rectifying-invoice = Factura Rectificativa
+invoice-number = Número
+verify-chain = Verificar Registros
+submit-records = Enviar Registros
+records-submitted = Registros enviados
+chain-intact = La cadena de registros está intacta
+chain-broken = La cadena de registros está rota
+chain-break-hash-mismatch = La huella no corresponde al registro
+chain-break-previous-hash-mismatch = La huella anterior no corresponde al registro anterior
+chain-break-missing-records = Faltan registros
+export-facturae = Exportar Facturae
+facturae-exported = Factura exportada
+issue-full-invoice = Emitir Factura Completa
+replaces = Sustituye a la factura simplificada
+replaced-by = Sustituida por la factura
+
+<#-- Invoice Series SubScreen -->
+invoice-series = Series de Facturación
+simplified-series = Simplificadas
+full-series = Completas
+rectifying-series = Rectificativas
+prefix = Prefijo
+last-number = Último Número
+
+<#-- Table Locations SubScreen -->
+no-table-locations = Nada todavía...
+table-location = Ubicación
+sort-order = Orden
+inside-location = Interior (usa el precio de dentro)
+
+<#-- Bar Tables SubScreen -->
+no-bar-tables = Nada todavía...
+capacity = Capacidad
+
+<#-- Preparation Stations SubScreen -->
+no-preparation-stations = Nada todavía...
+preparation-station = Estación de Preparación
+
+<#-- Modifier Groups SubScreen -->
+no-modifier-groups = Nada todavía...
+modifier-group = Grupo de Modificadores
+applies-to = Aplica a
+product = Producto
+modifiers = Modificadores
+price-delta = Suplemento
+add-modifier = Añadir Modificador
+
+<#-- Pricing Rules SubScreen -->
+no-pricing-rules = Nada todavía...
+pricing-rule = Tarifa
+priority = Prioridad
+weekdays = Días
+schedule = Horario
+time-window = Horario (vacío = todo el día)
+all-locations = Todas las ubicaciones
+all-products = Todos los productos
+every-day = Todos los días
+monday = Lunes
+tuesday = Martes
+wednesday = Miércoles
+thursday = Jueves
+friday = Viernes
+saturday = Sábado
+sunday = Domingo
+price-preview = Vista Previa de Precio
+applied-rule = Tarifa aplicada
+no-pricing-rule = Ninguna (precio base)
+select-preview = Selecciona un producto, una ubicación y una hora
+
+<#-- Stock SubScreen -->
+stock-report = Informe de Stock
+no-tracked-stock = Ningún producto controla su stock todavía...
+stock-adjustment = Ajuste de Stock
+stock-movements = Movimientos de Stock
+quantity = Cantidad
+
+<#-- Ingredients SubScreen -->
+no-ingredients = Nada todavía...
+ingredient = Ingrediente
+unit = Unidad
+theoretical-consumption = Consumo Teórico
+calculate = Calcular
+no-consumption = Ningún consumo en esas fechas...
+
+<#-- Voided Lines SubScreen -->
+no-voided-lines = Ninguna anulación en esas fechas...
+date = Fecha
+table = Mesa
+total-voided = Total Anulado
+
+<#-- Recipes SubScreen -->
+no-recipes = Nada todavía...
+recipe = Receta
+add-ingredient = Añadir Ingrediente
+
+<#-- Floor Plan SubScreen -->
+floor-plan-saved = Plano guardado
+discard-changes = Descartar Cambios
+save = Guardar
+width = Ancho
+height = Alto
+shape = Forma
+select-table-to-edit = Selecciona una mesa para editarla
+
+<#-- Cash Register SubScreen -->
+cash-register-closed = Caja cerrada
+cash-register-open = Caja abierta
+open-cash-register = Abrir Caja
+close-cash-register = Cerrar Caja
+opening-float = Fondo de Caja
+opened-at = Apertura
+closed-at = Cierre
+cash-movements = Movimientos de Caja
+cash-in = Entrada
+cash-out = Salida
+reason = Motivo
+counted-cash = Efectivo Contado
+expected-cash = Efectivo Esperado
+cash-difference = Descuadre
+z-report = Informe Z
+tax-base = Base
+tax = IVA
+total-discounts = Total Descuentos
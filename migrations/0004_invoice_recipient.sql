@@ -0,0 +1,4 @@
+-- Add the recipient fiscal data to SimpleInvoice (needed to print full invoices)
+ALTER TABLE simple_invoices ADD COLUMN IF NOT EXISTS recipient_name TEXT;
+ALTER TABLE simple_invoices ADD COLUMN IF NOT EXISTS recipient_nif TEXT;
+ALTER TABLE simple_invoices ADD COLUMN IF NOT EXISTS recipient_address TEXT;
@@ -0,0 +1,2 @@
+-- Add the quantity of each line to SoldProduct (price keeps being the unit price)
+ALTER TABLE sold_products ADD COLUMN IF NOT EXISTS quantity INTEGER NOT NULL DEFAULT 1;
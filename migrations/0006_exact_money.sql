@@ -0,0 +1,17 @@
+-- Store money (and tax percentages) as exact decimals instead of REAL
+ALTER TABLE products
+    ALTER COLUMN inside_price TYPE NUMERIC(12,2) USING ROUND(inside_price::NUMERIC, 2),
+    ALTER COLUMN outside_price TYPE NUMERIC(12,2) USING ROUND(outside_price::NUMERIC, 2),
+    ALTER COLUMN tax_percentage TYPE NUMERIC(5,2) USING ROUND(tax_percentage::NUMERIC, 2);
+
+ALTER TABLE sold_products
+    ALTER COLUMN price TYPE NUMERIC(12,2) USING ROUND(price::NUMERIC, 2);
+
+ALTER TABLE temporal_products
+    ALTER COLUMN price TYPE NUMERIC(12,2) USING ROUND(price::NUMERIC, 2);
+
+ALTER TABLE room_types
+    ALTER COLUMN price TYPE NUMERIC(12,2) USING ROUND(price::NUMERIC, 2);
+
+ALTER TABLE sold_rooms
+    ALTER COLUMN price TYPE NUMERIC(12,2) USING ROUND(price::NUMERIC, 2);
@@ -0,0 +1,30 @@
+-- Payments of a simple invoice, an invoice can be paid with several methods (part in cash, part by card...)
+-- simple_invoices.payment_method_id keeps the method that covers most of the amount
+CREATE TABLE IF NOT EXISTS invoice_payments (
+    id SERIAL PRIMARY KEY,
+    simple_invoice_id INTEGER NOT NULL,
+    payment_method_id INTEGER NOT NULL,
+    amount NUMERIC(12,2) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (simple_invoice_id)
+        REFERENCES simple_invoices(id)
+        ON DELETE CASCADE -- Delete payments if the invoice is deleted
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_payments_simple_invoice_id
+ON invoice_payments(simple_invoice_id);
+
+-- Trigger for invoice_payments
+CREATE TRIGGER update_invoice_payments_updated_at
+BEFORE UPDATE ON invoice_payments
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
+
+-- Invoices paid before this migration were paid in full with their single payment method
+INSERT INTO invoice_payments (simple_invoice_id, payment_method_id, amount)
+SELECT si.id, si.payment_method_id, COALESCE(SUM(sp.price * sp.quantity), 0)
+FROM simple_invoices si
+LEFT JOIN sold_products sp ON sp.simple_invoice_id = si.id
+WHERE si.paid = TRUE
+GROUP BY si.id, si.payment_method_id;
@@ -0,0 +1,43 @@
+-- Cash register sessions (shifts), opened with a starting float and closed with the counted amount
+CREATE TABLE IF NOT EXISTS cash_sessions (
+    id SERIAL PRIMARY KEY,
+    opened_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    closed_at TIMESTAMP,
+    opening_float NUMERIC(12,2) NOT NULL,
+    counted_amount NUMERIC(12,2),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Only one session can be open at the same time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_cash_sessions_single_open
+ON cash_sessions ((closed_at IS NULL))
+WHERE closed_at IS NULL;
+
+-- Cash put into (positive amount) or taken out of (negative amount) the drawer during a session
+CREATE TABLE IF NOT EXISTS cash_movements (
+    id SERIAL PRIMARY KEY,
+    cash_session_id INTEGER NOT NULL,
+    amount NUMERIC(12,2) NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (cash_session_id)
+        REFERENCES cash_sessions(id)
+        ON DELETE CASCADE -- Delete movements if the session is deleted
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_payments_created_at
+ON invoice_payments(created_at);
+
+-- Trigger for cash_sessions
+CREATE TRIGGER update_cash_sessions_updated_at
+BEFORE UPDATE ON cash_sessions
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
+
+-- Trigger for cash_movements
+CREATE TRIGGER update_cash_movements_updated_at
+BEFORE UPDATE ON cash_movements
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
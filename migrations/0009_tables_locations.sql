@@ -0,0 +1,64 @@
+-- Locations where the bar tables are placed (bar, restaurant, garden...)
+CREATE TABLE IF NOT EXISTS table_locations (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    inside BOOLEAN NOT NULL DEFAULT FALSE, -- Tickets of inside locations use the inside price of the products
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Tables of the bar screen
+CREATE TABLE IF NOT EXISTS bar_tables (
+    id SERIAL PRIMARY KEY,
+    location_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    capacity INTEGER NOT NULL DEFAULT 0,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (location_id)
+        REFERENCES table_locations(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bar_tables_location_id
+ON bar_tables(location_id);
+
+-- Seed the previously hard-coded grid (3 locations with 30 tables each), the old location and
+-- table numbers are kept on sort_order so the open tickets can be moved to the new ids
+INSERT INTO table_locations (name, sort_order, inside) VALUES
+    ('Bar', 0, FALSE),
+    ('Restaurante', 1, TRUE),
+    ('Jardín', 2, FALSE);
+
+INSERT INTO bar_tables (location_id, name, capacity, sort_order)
+SELECT l.id, (t.n + 1)::TEXT, 4, t.n
+FROM table_locations l
+CROSS JOIN generate_series(0, 29) AS t(n);
+
+UPDATE temporal_tickets tt
+SET ticket_location = l.id, table_id = t.id
+FROM table_locations l
+JOIN bar_tables t ON t.location_id = l.id
+WHERE l.sort_order = tt.ticket_location AND t.sort_order = tt.table_id;
+
+-- From now on temporal tickets point to a configured table and location
+ALTER TABLE temporal_tickets
+ADD FOREIGN KEY (table_id) REFERENCES bar_tables(id);
+
+ALTER TABLE temporal_tickets
+ADD FOREIGN KEY (ticket_location) REFERENCES table_locations(id);
+
+-- Trigger for table_locations
+CREATE TRIGGER update_table_locations_updated_at
+BEFORE UPDATE ON table_locations
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
+
+-- Trigger for bar_tables
+CREATE TRIGGER update_bar_tables_updated_at
+BEFORE UPDATE ON bar_tables
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,17 @@
+-- Position, size and shape of the bar tables on the floor plan of their location
+ALTER TABLE bar_tables
+ADD COLUMN IF NOT EXISTS pos_x INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS pos_y INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS width INTEGER NOT NULL DEFAULT 80,
+ADD COLUMN IF NOT EXISTS height INTEGER NOT NULL DEFAULT 80,
+ADD COLUMN IF NOT EXISTS shape INTEGER NOT NULL DEFAULT 1; -- 1 Square, 2 Round
+
+-- Place the existing tables like the grid (5 tables per row) so the floor plan starts usable
+UPDATE bar_tables t
+SET pos_x = 10 + (ordered.position % 5) * 100,
+    pos_y = 10 + (ordered.position / 5) * 100
+FROM (
+    SELECT id, (ROW_NUMBER() OVER (PARTITION BY location_id ORDER BY sort_order, id) - 1)::INTEGER AS position
+    FROM bar_tables
+) ordered
+WHERE ordered.id = t.id;
@@ -0,0 +1,23 @@
+-- Preparation stations (kitchen, bar, grill...) that receive the orders of the products of their categories
+CREATE TABLE IF NOT EXISTS preparation_stations (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    printer_name TEXT NULL, -- System printer the orders of the station are printed on
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The products of a category are prepared on its station (none means nothing has to be prepared)
+ALTER TABLE product_categories
+ADD COLUMN IF NOT EXISTS preparation_station_id INTEGER NULL REFERENCES preparation_stations(id);
+
+-- How much of each line has already been sent to its preparation station
+ALTER TABLE temporal_products
+ADD COLUMN IF NOT EXISTS sent_quantity INTEGER NOT NULL DEFAULT 0;
+
+-- Trigger for preparation_stations
+CREATE TRIGGER update_preparation_stations_updated_at
+BEFORE UPDATE ON preparation_stations
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
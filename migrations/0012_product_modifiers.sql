@@ -0,0 +1,51 @@
+-- Groups of modifiers ("no onion", "extra cheese"...) offered when a product is added to a ticket,
+-- a group is attached either to a single product or to every product of a category
+CREATE TABLE IF NOT EXISTS modifier_groups (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    product_id INTEGER NULL,
+    category_id INTEGER NULL,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id)
+        REFERENCES products(id),
+    FOREIGN KEY (category_id)
+        REFERENCES product_categories(id),
+    CHECK (product_id IS NOT NULL OR category_id IS NOT NULL)
+);
+
+-- Modifiers of a group, the price delta is added to the unit price of the line
+CREATE TABLE IF NOT EXISTS modifiers (
+    id SERIAL PRIMARY KEY,
+    group_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    price_delta NUMERIC(12,2) NOT NULL DEFAULT 0,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id)
+        REFERENCES modifier_groups(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_modifiers_group_id
+ON modifiers(group_id);
+
+-- The selected modifiers are kept on the lines as they were when they were added (like the name and price)
+ALTER TABLE temporal_products
+ADD COLUMN IF NOT EXISTS modifiers TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE sold_products
+ADD COLUMN IF NOT EXISTS modifiers TEXT NOT NULL DEFAULT '';
+
+-- Trigger for modifier_groups
+CREATE TRIGGER update_modifier_groups_updated_at
+BEFORE UPDATE ON modifier_groups
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
+
+-- Trigger for modifiers
+CREATE TRIGGER update_modifiers_updated_at
+BEFORE UPDATE ON modifiers
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
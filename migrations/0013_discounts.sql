@@ -0,0 +1,20 @@
+-- Discounted lines keep the unit price they had before the discount and why it was given,
+-- the price column is always what's charged for each unit (the discount is already applied on it)
+ALTER TABLE temporal_products
+ADD COLUMN IF NOT EXISTS original_price NUMERIC(12,2) NULL, -- NULL means the line has no discount
+ADD COLUMN IF NOT EXISTS discount_reason TEXT NULL;
+
+ALTER TABLE sold_products
+ADD COLUMN IF NOT EXISTS original_price NUMERIC(12,2) NULL, -- NULL means the line had no discount
+ADD COLUMN IF NOT EXISTS discount_reason TEXT NULL;
+
+-- Discount given to the whole ticket (it's applied to its lines, this is kept to show it and to apply it to new lines)
+ALTER TABLE temporal_tickets
+ADD COLUMN IF NOT EXISTS discount_kind INTEGER NULL, -- 1 Percentage, 2 Fixed amount
+ADD COLUMN IF NOT EXISTS discount_value NUMERIC(12,2) NULL,
+ADD COLUMN IF NOT EXISTS discount_reason TEXT NULL;
+
+-- Invoices keep why their whole ticket was discounted, the lines discounted with that reason are printed
+-- together as the discount of the ticket
+ALTER TABLE simple_invoices
+ADD COLUMN IF NOT EXISTS ticket_discount_reason TEXT NULL;
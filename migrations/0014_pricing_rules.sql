@@ -0,0 +1,25 @@
+-- Rules that change the price of the products added to the bar tickets (price lists, happy hour, terrace surcharge...)
+CREATE TABLE IF NOT EXISTS pricing_rules (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0, -- When more than one rule applies the one with the highest priority wins
+    location_id INTEGER NULL REFERENCES table_locations(id), -- NULL means every location
+    product_id INTEGER NULL REFERENCES products(id), -- NULL (and no category) means every product
+    category_id INTEGER NULL REFERENCES product_categories(id),
+    weekdays INTEGER NOT NULL DEFAULT 127, -- Days the rule applies on (bitmask: 1 Monday, 2 Tuesday, 4 Wednesday... 64 Sunday)
+    start_time TIME NULL, -- NULL means the whole day, a window that ends before it starts goes past midnight
+    end_time TIME NULL,
+    adjustment_kind INTEGER NOT NULL, -- 1 Fixed price, 2 Percentage over the base price
+    adjustment_value NUMERIC(12,2) NOT NULL,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (product_id IS NULL OR category_id IS NULL),
+    CHECK ((start_time IS NULL) = (end_time IS NULL))
+);
+
+-- Trigger for pricing_rules
+CREATE TRIGGER update_pricing_rules_updated_at
+BEFORE UPDATE ON pricing_rules
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
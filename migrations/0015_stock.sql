@@ -0,0 +1,31 @@
+-- Stock of the products, only the products with a stock_quantity are tracked
+ALTER TABLE products
+ADD COLUMN IF NOT EXISTS stock_quantity INTEGER NULL, -- NULL means the stock of the product is not tracked
+ADD COLUMN IF NOT EXISTS low_stock_threshold INTEGER NULL; -- Warn when the stock gets to this quantity (NULL means never)
+
+-- Every change of the stock of a product (sales, restored sales and manual adjustments)
+CREATE TABLE IF NOT EXISTS stock_movements (
+    id SERIAL PRIMARY KEY,
+    product_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL, -- Positive adds stock, negative takes it out
+    kind INTEGER NOT NULL, -- 1 Sale, 2 Restored (deleted invoice), 3 Manual adjustment
+    reason TEXT NULL, -- Required for manual adjustments
+    simple_invoice_id INTEGER NULL, -- Invoice of the sale (so it can be restored if the invoice is deleted)
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (product_id)
+        REFERENCES products(id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (simple_invoice_id)
+        REFERENCES simple_invoices(id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_simple_invoice_id
+ON stock_movements(simple_invoice_id);
+
+-- Trigger for stock_movements
+CREATE TRIGGER update_stock_movements_updated_at
+BEFORE UPDATE ON stock_movements
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,68 @@
+-- Ingredients consumed by the products that are prepared (dishes, cocktails...)
+CREATE TABLE IF NOT EXISTS ingredients (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    unit TEXT NOT NULL, -- Unit the quantities of the ingredient are measured in (ml, g, units...)
+    stock_quantity NUMERIC(12,3) NOT NULL DEFAULT 0,
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Recipe of a product: quantity of each ingredient consumed by every unit sold of the product
+CREATE TABLE IF NOT EXISTS recipe_ingredients (
+    id SERIAL PRIMARY KEY,
+    product_id INTEGER NOT NULL,
+    ingredient_id INTEGER NOT NULL,
+    quantity NUMERIC(12,3) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (product_id, ingredient_id),
+    FOREIGN KEY (product_id)
+        REFERENCES products(id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (ingredient_id)
+        REFERENCES ingredients(id)
+        ON DELETE CASCADE
+);
+
+-- Ingredients taken out of the stock by sales (and put back when the invoice of the sale is deleted)
+CREATE TABLE IF NOT EXISTS ingredient_movements (
+    id SERIAL PRIMARY KEY,
+    ingredient_id INTEGER NOT NULL,
+    quantity NUMERIC(12,3) NOT NULL, -- Positive adds stock, negative takes it out
+    kind INTEGER NOT NULL, -- 1 Sale, 2 Restored (deleted invoice)
+    simple_invoice_id INTEGER NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ingredient_id)
+        REFERENCES ingredients(id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (simple_invoice_id)
+        REFERENCES simple_invoices(id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_ingredients_product_id
+ON recipe_ingredients(product_id);
+
+CREATE INDEX IF NOT EXISTS idx_ingredient_movements_simple_invoice_id
+ON ingredient_movements(simple_invoice_id);
+
+-- Trigger for ingredients
+CREATE TRIGGER update_ingredients_updated_at
+BEFORE UPDATE ON ingredients
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
+
+-- Trigger for recipe_ingredients
+CREATE TRIGGER update_recipe_ingredients_updated_at
+BEFORE UPDATE ON recipe_ingredients
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
+
+-- Trigger for ingredient_movements
+CREATE TRIGGER update_ingredient_movements_updated_at
+BEFORE UPDATE ON ingredient_movements
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,37 @@
+-- Lines removed from a ticket before it was paid, they are kept to be able to audit them instead of being deleted without a trace
+CREATE TABLE IF NOT EXISTS voided_lines (
+    id SERIAL PRIMARY KEY,
+    temporal_ticket_id INTEGER NOT NULL, -- Not a foreign key, the ticket is deleted once it has no lines left
+    table_id INTEGER NOT NULL,
+    original_product_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    price NUMERIC(12,2) NULL, -- Unit price of the line when it was voided
+    amount NUMERIC(12,2) NOT NULL, -- quantity * price
+    reason TEXT NOT NULL,
+    voided_by TEXT NOT NULL, -- Who voided the line
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (original_product_id)
+        REFERENCES products(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_voided_lines_created_at
+ON voided_lines(created_at);
+
+-- A refund of a paid invoice is a new (negative) invoice that rectifies the refunded one
+ALTER TABLE simple_invoices
+ADD COLUMN IF NOT EXISTS rectified_invoice_id INTEGER NULL REFERENCES simple_invoices(id), -- NULL for regular invoices
+ADD COLUMN IF NOT EXISTS rectification_reason TEXT NULL;
+
+-- An invoice can only be rectified once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_simple_invoices_rectified_invoice_id
+ON simple_invoices(rectified_invoice_id)
+WHERE rectified_invoice_id IS NOT NULL;
+
+-- Trigger for voided_lines
+CREATE TRIGGER update_voided_lines_updated_at
+BEFORE UPDATE ON voided_lines
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,60 @@
+-- Paid invoices are fiscal documents, they can't be deleted nor have their fiscal data changed
+-- (corrections are issued as rectifying invoices)
+CREATE OR REPLACE FUNCTION prevent_paid_invoice_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        IF OLD.paid THEN
+            RAISE EXCEPTION 'Paid invoices can''t be deleted, issue a rectifying invoice instead';
+        END IF;
+        RETURN OLD;
+    END IF;
+
+    IF OLD.paid AND (
+        NOT NEW.paid
+        OR NEW.is_deleted
+        OR NEW.payment_method_id IS DISTINCT FROM OLD.payment_method_id
+        OR NEW.rectified_invoice_id IS DISTINCT FROM OLD.rectified_invoice_id
+        OR NEW.rectification_reason IS DISTINCT FROM OLD.rectification_reason
+    ) THEN
+        RAISE EXCEPTION 'Paid invoices can''t be modified, issue a rectifying invoice instead';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_paid_simple_invoices_changes
+BEFORE UPDATE OR DELETE ON simple_invoices
+FOR EACH ROW
+EXECUTE FUNCTION prevent_paid_invoice_changes();
+
+-- The lines and the payments of a paid invoice make its totals (and the hash of its record),
+-- they can't be changed nor deleted once the invoice has been paid
+CREATE OR REPLACE FUNCTION prevent_paid_invoice_lines_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM simple_invoices WHERE id = OLD.simple_invoice_id AND paid) THEN
+        RAISE EXCEPTION 'The lines and payments of paid invoices can''t be modified, issue a rectifying invoice instead';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+
+    -- nor moved into a paid invoice
+    IF EXISTS (SELECT 1 FROM simple_invoices WHERE id = NEW.simple_invoice_id AND paid) THEN
+        RAISE EXCEPTION 'The lines and payments of paid invoices can''t be modified, issue a rectifying invoice instead';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_paid_sold_products_changes
+BEFORE UPDATE OR DELETE ON sold_products
+FOR EACH ROW
+EXECUTE FUNCTION prevent_paid_invoice_lines_changes();
+
+CREATE TRIGGER prevent_paid_invoice_payments_changes
+BEFORE UPDATE OR DELETE ON invoice_payments
+FOR EACH ROW
+EXECUTE FUNCTION prevent_paid_invoice_lines_changes();
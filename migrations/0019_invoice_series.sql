@@ -0,0 +1,104 @@
+-- Numbering series of the invoices, each with its own prefix
+CREATE TABLE IF NOT EXISTS invoice_series (
+    kind INTEGER PRIMARY KEY, -- 1 Simplified, 2 Full, 3 Rectifying
+    prefix TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO invoice_series (kind, prefix) VALUES
+    (1, 'FS'),
+    (2, 'F'),
+    (3, 'R')
+ON CONFLICT (kind) DO NOTHING;
+
+-- The printed numbers (prefix, year and number) have to be unique across all the series,
+-- so every series needs its own prefix
+ALTER TABLE invoice_series
+ADD CONSTRAINT invoice_series_prefix_unique UNIQUE (prefix);
+
+-- Last number given on each series each year (the numbers restart every year and have no gaps)
+CREATE TABLE IF NOT EXISTS invoice_series_counters (
+    kind INTEGER NOT NULL REFERENCES invoice_series(kind),
+    year INTEGER NOT NULL,
+    last_number INTEGER NOT NULL,
+    PRIMARY KEY (kind, year)
+);
+
+-- The number is assigned when the invoice becomes paid, unpaid invoices don't have one
+ALTER TABLE simple_invoices
+ADD COLUMN IF NOT EXISTS series_kind INTEGER NULL REFERENCES invoice_series(kind),
+ADD COLUMN IF NOT EXISTS fiscal_year INTEGER NULL,
+ADD COLUMN IF NOT EXISTS number INTEGER NULL,
+ADD COLUMN IF NOT EXISTS invoice_number TEXT NULL; -- Prefix, year and number as printed (FS2025-000001)
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_simple_invoices_series_number
+ON simple_invoices(series_kind, fiscal_year, number)
+WHERE number IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_simple_invoices_invoice_number
+ON simple_invoices(invoice_number)
+WHERE invoice_number IS NOT NULL;
+
+-- Number the already paid invoices
+
+UPDATE simple_invoices si
+SET series_kind = numbered.series_kind,
+    fiscal_year = numbered.fiscal_year,
+    number = numbered.number,
+    invoice_number = s.prefix || numbered.fiscal_year || '-' || LPAD(numbered.number::TEXT, 6, '0')
+FROM (
+    SELECT
+        id,
+        series_kind,
+        fiscal_year,
+        ROW_NUMBER() OVER (PARTITION BY series_kind, fiscal_year ORDER BY id) as number
+    FROM (
+        SELECT
+            id,
+            CASE
+                WHEN rectified_invoice_id IS NOT NULL THEN 3
+                WHEN recipient_name IS NOT NULL THEN 2
+                ELSE 1
+            END as series_kind,
+            EXTRACT(YEAR FROM COALESCE(created_at, CURRENT_TIMESTAMP))::INTEGER as fiscal_year
+        FROM simple_invoices
+        WHERE paid = TRUE
+    ) paid_invoices
+) numbered
+JOIN invoice_series s ON s.kind = numbered.series_kind
+WHERE si.id = numbered.id;
+
+INSERT INTO invoice_series_counters (kind, year, last_number)
+SELECT series_kind, fiscal_year, MAX(number)
+FROM simple_invoices
+WHERE number IS NOT NULL
+GROUP BY series_kind, fiscal_year
+ON CONFLICT (kind, year) DO NOTHING;
+
+-- The number of an invoice is part of its fiscal data, it can only be assigned once
+CREATE OR REPLACE FUNCTION prevent_invoice_number_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_number IS NOT NULL AND (
+        NEW.series_kind IS DISTINCT FROM OLD.series_kind
+        OR NEW.fiscal_year IS DISTINCT FROM OLD.fiscal_year
+        OR NEW.number IS DISTINCT FROM OLD.number
+        OR NEW.invoice_number IS DISTINCT FROM OLD.invoice_number
+    ) THEN
+        RAISE EXCEPTION 'The number of an invoice can''t be changed';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_simple_invoices_number_changes
+BEFORE UPDATE ON simple_invoices
+FOR EACH ROW
+EXECUTE FUNCTION prevent_invoice_number_changes();
+
+-- Trigger for invoice_series
+CREATE TRIGGER update_invoice_series_updated_at
+BEFORE UPDATE ON invoice_series
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,63 @@
+-- Every issued invoice gets a record chained to the previous one (each record hash includes the hash of the previous record)
+ALTER TABLE simple_invoices
+ADD COLUMN IF NOT EXISTS record_sequence INTEGER NULL, -- Position of the record on the chain
+ADD COLUMN IF NOT EXISTS record_hash TEXT NULL, -- SHA-256 of the canonical record
+ADD COLUMN IF NOT EXISTS previous_hash TEXT NULL, -- NULL for the first record of the chain
+ADD COLUMN IF NOT EXISTS record_issuer_nif TEXT NULL, -- NIF of the issuer when the record was generated
+ADD COLUMN IF NOT EXISTS record_generated_at TEXT NULL, -- ISO 8601 with the UTC offset, exactly as it's hashed
+ADD COLUMN IF NOT EXISTS record_submitted_at TIMESTAMP NULL; -- NULL until the record is sent
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_simple_invoices_record_sequence
+ON simple_invoices(record_sequence)
+WHERE record_sequence IS NOT NULL;
+
+-- Last record of the chain, there is only one row (locked while a new record is being chained)
+CREATE TABLE IF NOT EXISTS invoice_record_chain (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_sequence INTEGER NOT NULL,
+    last_hash TEXT NULL
+);
+
+INSERT INTO invoice_record_chain (id, last_sequence, last_hash)
+VALUES (1, 0, NULL)
+ON CONFLICT (id) DO NOTHING;
+
+-- Sold lines keep the tax percentage of the product when they were sold, changing the tax of a product
+-- can't change the taxes of the invoices that were already issued (nor the hashes of their records)
+ALTER TABLE sold_products
+ADD COLUMN IF NOT EXISTS tax_percentage NUMERIC(5,2) NULL;
+
+-- The lines of the paid invoices get it too (their trigger is disabled while doing it)
+ALTER TABLE sold_products DISABLE TRIGGER prevent_paid_sold_products_changes;
+
+UPDATE sold_products sp
+SET tax_percentage = COALESCE(p.tax_percentage, 21)
+FROM products p
+WHERE sp.original_product_id = p.id AND sp.tax_percentage IS NULL;
+
+ALTER TABLE sold_products ENABLE TRIGGER prevent_paid_sold_products_changes;
+
+ALTER TABLE sold_products
+ALTER COLUMN tax_percentage SET NOT NULL;
+
+-- The record of an invoice can only be assigned once (it's chained to the records that come after it)
+CREATE OR REPLACE FUNCTION prevent_invoice_record_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.record_hash IS NOT NULL AND (
+        NEW.record_sequence IS DISTINCT FROM OLD.record_sequence
+        OR NEW.record_hash IS DISTINCT FROM OLD.record_hash
+        OR NEW.previous_hash IS DISTINCT FROM OLD.previous_hash
+        OR NEW.record_issuer_nif IS DISTINCT FROM OLD.record_issuer_nif
+        OR NEW.record_generated_at IS DISTINCT FROM OLD.record_generated_at
+    ) THEN
+        RAISE EXCEPTION 'The record of an invoice can''t be changed';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_simple_invoices_record_changes
+BEFORE UPDATE ON simple_invoices
+FOR EACH ROW
+EXECUTE FUNCTION prevent_invoice_record_changes();
@@ -0,0 +1,34 @@
+-- A full invoice can be issued in place of a paid simplified invoice (for a recipient with fiscal data), the simplified
+-- invoice stays as it was and the full invoice references it
+ALTER TABLE simple_invoices
+ADD COLUMN IF NOT EXISTS replaced_invoice_id INTEGER NULL REFERENCES simple_invoices(id); -- NULL for regular invoices
+
+-- A simplified invoice can only be replaced once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_simple_invoices_replaced_invoice_id
+ON simple_invoices(replaced_invoice_id)
+WHERE replaced_invoice_id IS NOT NULL;
+
+-- The recipient decides the series (and the type of the record) of an invoice, it can't be changed once the invoice
+-- is numbered (a full invoice is issued in its place instead) and a paid invoice can't change the invoice it replaces
+CREATE OR REPLACE FUNCTION prevent_invoice_recipient_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.invoice_number IS NOT NULL AND (
+        NEW.recipient_name IS DISTINCT FROM OLD.recipient_name
+        OR NEW.recipient_nif IS DISTINCT FROM OLD.recipient_nif
+        OR NEW.recipient_address IS DISTINCT FROM OLD.recipient_address
+    ) THEN
+        RAISE EXCEPTION 'The recipient of a numbered invoice can''t be changed, issue a full invoice in its place instead';
+    END IF;
+
+    IF OLD.paid AND NEW.replaced_invoice_id IS DISTINCT FROM OLD.replaced_invoice_id THEN
+        RAISE EXCEPTION 'Paid invoices can''t be modified, issue a rectifying invoice instead';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER prevent_simple_invoices_recipient_changes
+BEFORE UPDATE ON simple_invoices
+FOR EACH ROW
+EXECUTE FUNCTION prevent_invoice_recipient_changes();
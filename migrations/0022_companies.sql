@@ -0,0 +1,30 @@
+-- Companies (fiscal customers) that can be invoiced and can pay the reservations of their guests
+CREATE TABLE IF NOT EXISTS companies (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL, -- Legal name
+    cif TEXT NOT NULL,
+    address TEXT NOT NULL, -- Fiscal address (Street, 00000 Town, Province)
+    email TEXT NOT NULL DEFAULT '', -- Where the invoices are sent
+    is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- There can't be two (not deleted) companies with the same CIF
+CREATE UNIQUE INDEX IF NOT EXISTS idx_companies_cif
+ON companies(cif)
+WHERE is_deleted = FALSE;
+
+-- Company that pays the reservation, NULL if the client pays it
+ALTER TABLE reservations
+ADD COLUMN IF NOT EXISTS company_id INTEGER NULL REFERENCES companies(id);
+
+-- Company the invoice has been issued to (its fiscal data is copied on the recipient columns), NULL for other recipients
+ALTER TABLE simple_invoices
+ADD COLUMN IF NOT EXISTS company_id INTEGER NULL REFERENCES companies(id);
+
+-- Trigger for companies
+CREATE TRIGGER update_companies_updated_at
+BEFORE UPDATE ON companies
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
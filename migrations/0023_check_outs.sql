@@ -0,0 +1,34 @@
+-- Product used for the room nights on the final invoices (accommodation has a reduced tax)
+ALTER TABLE products
+ADD COLUMN IF NOT EXISTS is_accommodation BOOLEAN NOT NULL DEFAULT FALSE;
+
+INSERT INTO products (category_id, name, inside_price, outside_price, tax_percentage, is_accommodation, is_deleted)
+SELECT NULL, 'Alojamiento', NULL, NULL, 10.0, TRUE, FALSE
+WHERE NOT EXISTS (SELECT 1 FROM products WHERE is_accommodation = TRUE);
+
+-- Check-out of a reservation, NULL until the guests leave
+ALTER TABLE reservations
+ADD COLUMN IF NOT EXISTS checked_out_at TIMESTAMP NULL,
+ADD COLUMN IF NOT EXISTS final_invoice_id INTEGER NULL REFERENCES simple_invoices(id); -- Room nights and extras
+
+-- Payments that settle, at check-out, the invoices charged to the rooms of a reservation (adeudos)
+-- the adeudo invoices were already issued, so these payments don't belong to any invoice
+CREATE TABLE IF NOT EXISTS adeudo_settlements (
+    id SERIAL PRIMARY KEY,
+    reservation_id INTEGER NOT NULL,
+    payment_method_id INTEGER NOT NULL,
+    amount NUMERIC(12,2) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (reservation_id)
+        REFERENCES reservations(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_adeudo_settlements_created_at
+ON adeudo_settlements(created_at);
+
+-- Trigger for adeudo_settlements
+CREATE TRIGGER update_adeudo_settlements_updated_at
+BEFORE UPDATE ON adeudo_settlements
+FOR EACH ROW
+EXECUTE FUNCTION update_timestamp();
@@ -0,0 +1,9 @@
+-- A ticket split evenly gives every bill all of its lines at a share of the price, every share takes its part of the
+-- quantity out of the stock and counts it on the quantity reports (consumption...), so refunding one of the shares
+-- only restores its part and a split ticket adds up to its quantities once
+ALTER TABLE sold_products
+ADD COLUMN IF NOT EXISTS counted_quantity INTEGER NULL; -- NULL means the whole quantity of the line is counted
+
+-- How much of each line the shares of a ticket split evenly that have already been paid took out of the stock
+ALTER TABLE temporal_products
+ADD COLUMN IF NOT EXISTS counted_quantity INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,884 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{env, path::PathBuf, sync::Arc};
+
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use super::{
+    models::simple_invoice::SimpleInvoice,
+    print::{Issuer, TaxGroup, group_taxes, line_taxes},
+};
+use crate::alegria::utils::money::Money;
+
+/// Version of the Facturae schema the files are generated with
+const SCHEMA_VERSION: &str = "3.2.2";
+const SCHEMA_NAMESPACE: &str = "http://www.facturae.gob.es/formato/Versiones/Facturaev3_2_2.xml";
+/// Folder where the exported files are saved if there is no FACTURAE_EXPORT_DIR
+const DEFAULT_EXPORT_DIR: &str = "facturae";
+
+/// Saves an issued full invoice as a Facturae file, returns the path of the saved file.
+/// Rectifying invoices need the invoice they rectify, it's retrieved from the db
+pub async fn export(pool: Arc<PgPool>, invoice: SimpleInvoice) -> Result<PathBuf, &'static str> {
+    let rectified_invoice = match invoice.rectified_invoice_id {
+        Some(rectified_invoice_id) => Some(
+            SimpleInvoice::get_single(pool, rectified_invoice_id)
+                .await
+                .map_err(|_| "Failed to retrieve the rectified invoice")?,
+        ),
+        None => None,
+    };
+
+    let xml = generate_facturae(&invoice, rectified_invoice.as_ref(), &Issuer::load())?;
+
+    dotenvy::dotenv().ok();
+    let export_dir =
+        PathBuf::from(env::var("FACTURAE_EXPORT_DIR").unwrap_or(String::from(DEFAULT_EXPORT_DIR)));
+    let path = export_dir.join(format!(
+        "{}.xml",
+        invoice.invoice_number.as_deref().unwrap_or_default()
+    ));
+
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&export_dir)
+            .map_err(|_| "Failed to create the Facturae export folder")?;
+        std::fs::write(&path, xml).map_err(|_| "Failed to save the Facturae file")?;
+        Ok(path)
+    })
+    .await
+    .unwrap_or(Err("Failed to spawn a blocking task"))
+}
+
+/// Generates the Facturae XML of an issued full invoice (one invoice per file, without signature)
+fn generate_facturae(
+    invoice: &SimpleInvoice,
+    rectified_invoice: Option<&SimpleInvoice>,
+    issuer: &Issuer,
+) -> Result<String, &'static str> {
+    let Some(recipient) = &invoice.recipient else {
+        return Err("Missing invoice recipient");
+    };
+    let Some(invoice_number) = &invoice.invoice_number else {
+        return Err("Only issued invoices can be exported");
+    };
+
+    // same taxes as the printed invoice and the invoice record
+    let taxes = group_taxes(invoice);
+    let total_base: Money = taxes.values().map(|t| t.base).sum();
+    let total_tax: Money = taxes.values().map(|t| t.tax).sum();
+    let total = total_base + total_tax;
+
+    let mut xml = XmlWriter::default();
+    xml.xml.push_str(&format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<fe:Facturae xmlns:fe=\"{SCHEMA_NAMESPACE}\" xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\">\n"
+    ));
+    xml.depth = 1;
+
+    // File header
+    xml.open("FileHeader");
+    xml.element("SchemaVersion", SCHEMA_VERSION);
+    xml.element("Modality", "I");
+    xml.element("InvoiceIssuerType", "EM");
+    xml.open("Batch");
+    xml.element(
+        "BatchIdentifier",
+        &format!("{}{invoice_number}", issuer.nif.trim()),
+    );
+    xml.element("InvoicesCount", "1");
+    for tag in [
+        "TotalInvoicesAmount",
+        "TotalOutstandingAmount",
+        "TotalExecutableAmount",
+    ] {
+        xml.open(tag);
+        xml.element("TotalAmount", &total.to_string());
+        xml.close(tag);
+    }
+    xml.element("InvoiceCurrencyCode", "EUR");
+    xml.close("Batch");
+    xml.close("FileHeader");
+
+    // Parties
+    xml.open("Parties");
+    xml.party("SellerParty", &issuer.nif, &issuer.name, &issuer.address)?;
+    xml.party(
+        "BuyerParty",
+        &recipient.nif,
+        &recipient.name,
+        &recipient.address,
+    )?;
+    xml.close("Parties");
+
+    xml.open("Invoices");
+    xml.open("Invoice");
+
+    // Invoice header
+    let (series_code, number) = split_invoice_number(invoice_number);
+    xml.open("InvoiceHeader");
+    xml.element("InvoiceNumber", number);
+    if !series_code.is_empty() {
+        xml.element("InvoiceSeriesCode", series_code);
+    }
+    xml.element("InvoiceDocumentType", "FC");
+    if let Some(rectified_invoice) = rectified_invoice {
+        let rectified_invoice_number = rectified_invoice
+            .invoice_number
+            .as_deref()
+            .unwrap_or_default();
+        let (rectified_series_code, rectified_number) =
+            split_invoice_number(rectified_invoice_number);
+        let rectified_issue_date = issue_date(rectified_invoice);
+
+        xml.element("InvoiceClass", "OR");
+        xml.open("Corrective");
+        xml.element("InvoiceNumber", rectified_number);
+        if !rectified_series_code.is_empty() {
+            xml.element("InvoiceSeriesCode", rectified_series_code);
+        }
+        xml.element("ReasonCode", "16");
+        xml.element("ReasonDescription", "Base imponible");
+        xml.open("TaxPeriod");
+        xml.element("StartDate", &rectified_issue_date);
+        xml.element("EndDate", &rectified_issue_date);
+        xml.close("TaxPeriod");
+        xml.element("CorrectionMethod", "01");
+        xml.element("CorrectionMethodDescription", "Rectificación íntegra");
+        if let Some(reason) = &invoice.rectification_reason {
+            xml.limited_element("AdditionalReasonDescription", reason, 2500);
+        }
+        xml.close("Corrective");
+    } else {
+        xml.element("InvoiceClass", "OO");
+    }
+    xml.close("InvoiceHeader");
+
+    xml.open("InvoiceIssueData");
+    xml.element("IssueDate", &issue_date(invoice));
+    xml.element("InvoiceCurrencyCode", "EUR");
+    xml.element("TaxCurrencyCode", "EUR");
+    xml.element("LanguageName", "es");
+    xml.close("InvoiceIssueData");
+
+    // Taxes summary
+    xml.open("TaxesOutputs");
+    for (tax_percentage, tax_group) in &taxes {
+        xml.tax(*tax_percentage, tax_group);
+    }
+    xml.close("TaxesOutputs");
+
+    // Totals
+    xml.open("InvoiceTotals");
+    xml.element("TotalGrossAmount", &total_base.to_string());
+    xml.element("TotalGrossAmountBeforeTaxes", &total_base.to_string());
+    xml.element("TotalTaxOutputs", &total_tax.to_string());
+    xml.element("TotalTaxesWithheld", &Money::ZERO.to_string());
+    xml.element("InvoiceTotal", &total.to_string());
+    xml.element("TotalOutstandingAmount", &total.to_string());
+    xml.element("TotalExecutableAmount", &total.to_string());
+    xml.close("InvoiceTotals");
+
+    // Lines
+    xml.open("Items");
+    for (product, line) in invoice.products.iter().zip(line_taxes(invoice)) {
+        let tax_percentage = product.tax_percentage;
+        // the amounts before taxes of the lines aren't rounded to cents, so they add up to the (rounded) bases of the taxes
+        let gross_amount = (Decimal::from(product.total_price()) * Decimal::ONE_HUNDRED
+            / (Decimal::ONE_HUNDRED + tax_percentage))
+            .round_dp(8);
+        let unit_price = if product.quantity == 0 {
+            Decimal::ZERO
+        } else {
+            (gross_amount / Decimal::from(product.quantity)).round_dp(8)
+        };
+        let description = if product.modifiers.is_empty() {
+            product.original_product.name.clone()
+        } else {
+            format!("{} ({})", product.original_product.name, product.modifiers)
+        };
+
+        xml.open("InvoiceLine");
+        xml.limited_element("ItemDescription", &description, 2500);
+        xml.element("Quantity", &format!("{}.00", product.quantity));
+        xml.element("UnitOfMeasure", "01");
+        xml.element("UnitPriceWithoutTax", &format!("{unit_price:.8}"));
+        xml.element("TotalCost", &format!("{gross_amount:.8}"));
+        xml.element("GrossAmount", &format!("{gross_amount:.8}"));
+        xml.open("TaxesOutputs");
+        xml.tax(tax_percentage, &line);
+        xml.close("TaxesOutputs");
+        xml.close("InvoiceLine");
+    }
+    xml.close("Items");
+
+    xml.close("Invoice");
+    xml.close("Invoices");
+
+    xml.xml.push_str("</fe:Facturae>\n");
+    Ok(xml.xml)
+}
+
+/// Minimal writer of indented XML
+#[derive(Default)]
+struct XmlWriter {
+    xml: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn indent(&mut self) {
+        self.xml.push_str(&"    ".repeat(self.depth));
+    }
+
+    fn open(&mut self, tag: &str) {
+        self.indent();
+        self.xml.push_str(&format!("<{tag}>\n"));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.indent();
+        self.xml.push_str(&format!("</{tag}>\n"));
+    }
+
+    fn element(&mut self, tag: &str, value: &str) {
+        self.indent();
+        self.xml
+            .push_str(&format!("<{tag}>{}</{tag}>\n", escape_xml(value.trim())));
+    }
+
+    /// Writes an element whose text can't be longer than the given number of characters (the TextMax types of the schema)
+    fn limited_element(&mut self, tag: &str, value: &str, max_length: usize) {
+        let value: String = value.trim().chars().take(max_length).collect();
+        self.element(tag, &value);
+    }
+
+    /// Writes a tax (VAT) with its base and amount
+    fn tax(&mut self, tax_percentage: Decimal, tax_group: &TaxGroup) {
+        self.open("Tax");
+        self.element("TaxTypeCode", "01");
+        self.element("TaxRate", &format!("{tax_percentage:.2}"));
+        self.open("TaxableBase");
+        self.element("TotalAmount", &tax_group.base.to_string());
+        self.close("TaxableBase");
+        self.open("TaxAmount");
+        self.element("TotalAmount", &tax_group.tax.to_string());
+        self.close("TaxAmount");
+        self.close("Tax");
+    }
+
+    /// Writes a party (seller or buyer) given its fiscal data
+    fn party(
+        &mut self,
+        tag: &str,
+        nif: &str,
+        name: &str,
+        address: &str,
+    ) -> Result<(), &'static str> {
+        let nif = nif.trim().to_uppercase();
+        if !(3..=30).contains(&nif.chars().count()) {
+            return Err("NIFs must have between 3 and 30 characters");
+        }
+        let Some((street, post_code, town, province)) = split_address(address) else {
+            return Err("Addresses must look like \"Street, 00000 Town, Province\"");
+        };
+        let is_legal_entity = is_legal_entity_nif(&nif);
+
+        // the names of individuals are written as "Name FirstSurname SecondSurname" (the second surname is optional)
+        let mut name_parts = name.split_whitespace();
+        let individual_name = match (name_parts.next(), name_parts.next()) {
+            _ if is_legal_entity => None,
+            (Some(first_name), Some(first_surname)) => Some((
+                first_name,
+                first_surname,
+                name_parts.collect::<Vec<_>>().join(" "),
+            )),
+            _ => return Err("Individuals need their name and at least one surname"),
+        };
+
+        self.open(tag);
+        self.open("TaxIdentification");
+        self.element("PersonTypeCode", if is_legal_entity { "J" } else { "F" });
+        self.element("ResidenceTypeCode", "R");
+        self.element("TaxIdentificationNumber", &nif);
+        self.close("TaxIdentification");
+
+        let entity_tag = if is_legal_entity {
+            "LegalEntity"
+        } else {
+            "Individual"
+        };
+        self.open(entity_tag);
+        match individual_name {
+            Some((first_name, first_surname, second_surname)) => {
+                self.limited_element("Name", first_name, 40);
+                self.limited_element("FirstSurname", first_surname, 40);
+                if !second_surname.is_empty() {
+                    self.limited_element("SecondSurname", &second_surname, 40);
+                }
+            }
+            None => self.limited_element("CorporateName", name, 80),
+        }
+        self.open("AddressInSpain");
+        self.limited_element("Address", &street, 80);
+        self.element("PostCode", &post_code);
+        self.limited_element("Town", &town, 50);
+        self.limited_element("Province", &province, 20);
+        self.element("CountryCode", "ESP");
+        self.close("AddressInSpain");
+        self.close(entity_tag);
+        self.close(tag);
+
+        Ok(())
+    }
+}
+
+/// Splits an invoice number (FS2025-000001) into its series code (FS2025) and number (000001)
+fn split_invoice_number(invoice_number: &str) -> (&str, &str) {
+    invoice_number
+        .rsplit_once('-')
+        .unwrap_or(("", invoice_number))
+}
+
+/// Returns the issue date (yyyy-mm-dd) of an invoice, the same one its printed copy shows
+fn issue_date(invoice: &SimpleInvoice) -> String {
+    invoice
+        .issued_at()
+        .map(|date| date.date().to_string())
+        .unwrap_or_default()
+}
+
+/// Splits an address written as "Street, 00000 Town, Province" into (street, post code, town, province),
+/// the province can be left out if it's the same as the town
+fn split_address(address: &str) -> Option<(String, String, String, String)> {
+    let parts: Vec<&str> = address.split(',').map(str::trim).collect();
+
+    // the post code is the part that starts with 5 digits (after the street)
+    let index = parts.iter().skip(1).position(|part| {
+        part.get(..5)
+            .is_some_and(|code| code.chars().all(|c| c.is_ascii_digit()))
+    })? + 1;
+    let (post_code, town) = parts[index].split_at(5);
+    let town = town.trim().to_string();
+    let province = parts
+        .get(index + 1)
+        .map(|province| province.to_string())
+        .unwrap_or(town.clone());
+
+    Some((
+        parts[..index].join(", "),
+        post_code.to_string(),
+        town,
+        province,
+    ))
+}
+
+/// Returns if the NIF belongs to a legal entity (a CIF), they start with one of the letters A-H, J, N, P-S, U-W.
+/// The rest are individuals: DNIs start with a number, NIEs with X, Y, Z and the special NIFs with K, L, M
+fn is_legal_entity_nif(nif: &str) -> bool {
+    nif.chars().next().is_some_and(|c| {
+        matches!(
+            c.to_ascii_uppercase(),
+            'A'..='H' | 'J' | 'N' | 'P'..='S' | 'U'..='W'
+        )
+    })
+}
+
+/// Escapes the characters that can't be written as they are on XML text
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::alegria::core::models::{
+        product::Product, simple_invoice::InvoiceRecipient, sold_product::SoldProduct,
+    };
+    use crate::alegria::utils::entities::payment_method::PaymentMethod;
+
+    fn issuer() -> Issuer {
+        Issuer {
+            name: String::from("Alegría Hostelería SL"),
+            nif: String::from("B12345678"),
+            address: String::from("Calle Mayor 1, 46001 Valencia, Valencia"),
+        }
+    }
+
+    fn product(name: &str, quantity: i32, price: &str, tax_percentage: i32) -> SoldProduct {
+        SoldProduct {
+            id: None,
+            simple_invoice_id: 1,
+            original_product_id: 1,
+            quantity,
+            price: Some(price.parse().unwrap()),
+            original_price: None,
+            discount_reason: None,
+            modifiers: String::new(),
+            tax_percentage: Decimal::from(tax_percentage),
+            original_product: Product {
+                name: String::from(name),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn invoice(invoice_number: &str, products: Vec<SoldProduct>) -> SimpleInvoice {
+        SimpleInvoice {
+            id: Some(1),
+            payment_method: PaymentMethod::Efectivo,
+            products,
+            paid: true,
+            recipient: Some(InvoiceRecipient {
+                name: String::from("Viajes & Eventos SA"),
+                nif: String::from("a87654321"),
+                address: String::from("Avenida del Puerto 20, 28001 Madrid"),
+                company_id: None,
+            }),
+            rectified_invoice_id: None,
+            rectification_reason: None,
+            replaced_invoice_id: None,
+            invoice_number: Some(String::from(invoice_number)),
+            record_generated_at: Some(String::from("2025-03-06T00:30:00+01:00")),
+            ticket_discount_reason: None,
+            is_deleted: false,
+            created_at: NaiveDate::from_ymd_opt(2025, 3, 4).and_then(|d| d.and_hms_opt(12, 0, 0)),
+            updated_at: None,
+            rectifying_invoice_id: None,
+            rectified_invoice_number: None,
+            replacing_invoice_id: None,
+            replaced_invoice_number: None,
+        }
+    }
+
+    /// Panics if any of the given pieces is missing from the xml or they're not in that order
+    fn assert_in_order(xml: &str, pieces: &[&str]) {
+        let mut position = 0;
+        for piece in pieces {
+            let index = xml[position..]
+                .find(piece)
+                .unwrap_or_else(|| panic!("{piece} is missing or out of order in:\n{xml}"));
+            position += index + piece.len();
+        }
+    }
+
+    /// Panics if the xml doesn't follow the Facturae 3.2.2 schema
+    fn assert_valid(xml: &str) {
+        use libxml::{
+            parser::Parser,
+            schemas::{SchemaParserContext, SchemaValidationContext},
+        };
+
+        /// Facturae 3.2.2 schema, it imports the signature schema from the same folder
+        const SCHEMA_PATH: &str = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/facturae/Facturaev3_2_2.xsd"
+        );
+
+        let mut schema_parser = SchemaParserContext::from_file(SCHEMA_PATH);
+        let mut validator = SchemaValidationContext::from_parser(&mut schema_parser)
+            .unwrap_or_else(|errors| panic!("Invalid Facturae schema: {errors:?}"));
+        let document = Parser::default()
+            .parse_string(xml)
+            .expect("The Facturae file isn't well formed");
+
+        if let Err(errors) = validator.validate_document(&document) {
+            panic!("The Facturae file doesn't follow the schema: {errors:?}\n{xml}");
+        }
+    }
+
+    /// Returns the values of every `<tag>value</tag>` of the xml as decimals
+    fn values(xml: &str, tag: &str) -> Vec<Decimal> {
+        let open = format!("<{tag}>");
+        let close = format!("</{tag}>");
+        xml.split(open.as_str())
+            .skip(1)
+            .filter_map(|part| part.split_once(close.as_str()))
+            .map(|(value, _)| value.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ordinary_invoice_has_every_required_element_in_order() {
+        let invoice = invoice(
+            "FF2025-000002",
+            vec![
+                product("Café", 2, "1.50", 10),
+                product("Menú", 1, "12.10", 21),
+            ],
+        );
+        let xml = generate_facturae(&invoice, None, &issuer()).unwrap();
+
+        assert_in_order(
+            &xml,
+            &[
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<fe:Facturae",
+                "<FileHeader>",
+                "<SchemaVersion>3.2.2</SchemaVersion>",
+                "<Modality>I</Modality>",
+                "<InvoiceIssuerType>EM</InvoiceIssuerType>",
+                "<Batch>",
+                "<BatchIdentifier>B12345678FF2025-000002</BatchIdentifier>",
+                "<InvoicesCount>1</InvoicesCount>",
+                "<TotalInvoicesAmount>",
+                "<TotalOutstandingAmount>",
+                "<TotalExecutableAmount>",
+                "<InvoiceCurrencyCode>EUR</InvoiceCurrencyCode>",
+                "</Batch>",
+                "</FileHeader>",
+                "<Parties>",
+                "<SellerParty>",
+                "<PersonTypeCode>J</PersonTypeCode>",
+                "<TaxIdentificationNumber>B12345678</TaxIdentificationNumber>",
+                "<LegalEntity>",
+                "<CorporateName>Alegría Hostelería SL</CorporateName>",
+                "<AddressInSpain>",
+                "<PostCode>46001</PostCode>",
+                "<CountryCode>ESP</CountryCode>",
+                "</SellerParty>",
+                "<BuyerParty>",
+                "<TaxIdentificationNumber>A87654321</TaxIdentificationNumber>",
+                "<CorporateName>Viajes &amp; Eventos SA</CorporateName>",
+                "<Province>Madrid</Province>",
+                "</BuyerParty>",
+                "</Parties>",
+                "<Invoices>",
+                "<Invoice>",
+                "<InvoiceHeader>",
+                "<InvoiceNumber>000002</InvoiceNumber>",
+                "<InvoiceSeriesCode>FF2025</InvoiceSeriesCode>",
+                "<InvoiceDocumentType>FC</InvoiceDocumentType>",
+                "<InvoiceClass>OO</InvoiceClass>",
+                "</InvoiceHeader>",
+                "<InvoiceIssueData>",
+                "<IssueDate>2025-03-06</IssueDate>",
+                "<InvoiceCurrencyCode>EUR</InvoiceCurrencyCode>",
+                "<TaxCurrencyCode>EUR</TaxCurrencyCode>",
+                "<LanguageName>es</LanguageName>",
+                "</InvoiceIssueData>",
+                "<TaxesOutputs>",
+                "</TaxesOutputs>",
+                "<InvoiceTotals>",
+                "<TotalGrossAmount>",
+                "<TotalGrossAmountBeforeTaxes>",
+                "<TotalTaxOutputs>",
+                "<TotalTaxesWithheld>0.00</TotalTaxesWithheld>",
+                "<InvoiceTotal>15.10</InvoiceTotal>",
+                "<TotalOutstandingAmount>15.10</TotalOutstandingAmount>",
+                "<TotalExecutableAmount>15.10</TotalExecutableAmount>",
+                "</InvoiceTotals>",
+                "<Items>",
+                "<InvoiceLine>",
+                "<ItemDescription>Café</ItemDescription>",
+                "<Quantity>2.00</Quantity>",
+                "<UnitOfMeasure>01</UnitOfMeasure>",
+                "<UnitPriceWithoutTax>",
+                "<TotalCost>",
+                "<GrossAmount>",
+                "<TaxesOutputs>",
+                "<TaxRate>10.00</TaxRate>",
+                "</InvoiceLine>",
+                "<InvoiceLine>",
+                "<ItemDescription>Menú</ItemDescription>",
+                "<TaxRate>21.00</TaxRate>",
+                "</InvoiceLine>",
+                "</Items>",
+                "</Invoice>",
+                "</Invoices>",
+                "</fe:Facturae>",
+            ],
+        );
+        assert!(!xml.contains("<Corrective>"));
+        assert_valid(&xml);
+    }
+
+    #[test]
+    fn rectifying_invoice_has_the_corrective_data_in_order() {
+        let mut rectified_invoice = invoice("FF2025-000001", vec![product("Menú", 1, "12.10", 21)]);
+        rectified_invoice.record_generated_at = Some(String::from("2025-03-01T10:00:00+01:00"));
+
+        let mut invoice = invoice("FR2025-000001", vec![product("Menú", -1, "12.10", 21)]);
+        invoice.rectified_invoice_id = Some(1);
+        invoice.rectification_reason = Some(String::from("Devolución <menú>"));
+
+        let xml = generate_facturae(&invoice, Some(&rectified_invoice), &issuer()).unwrap();
+
+        assert_in_order(
+            &xml,
+            &[
+                "<InvoiceHeader>",
+                "<InvoiceNumber>000001</InvoiceNumber>",
+                "<InvoiceSeriesCode>FR2025</InvoiceSeriesCode>",
+                "<InvoiceDocumentType>FC</InvoiceDocumentType>",
+                "<InvoiceClass>OR</InvoiceClass>",
+                "<Corrective>",
+                "<InvoiceNumber>000001</InvoiceNumber>",
+                "<InvoiceSeriesCode>FF2025</InvoiceSeriesCode>",
+                "<ReasonCode>16</ReasonCode>",
+                "<ReasonDescription>Base imponible</ReasonDescription>",
+                "<TaxPeriod>",
+                "<StartDate>2025-03-01</StartDate>",
+                "<EndDate>2025-03-01</EndDate>",
+                "</TaxPeriod>",
+                "<CorrectionMethod>01</CorrectionMethod>",
+                "<CorrectionMethodDescription>Rectificación íntegra</CorrectionMethodDescription>",
+                "<AdditionalReasonDescription>Devolución &lt;menú&gt;</AdditionalReasonDescription>",
+                "</Corrective>",
+                "</InvoiceHeader>",
+                "<IssueDate>2025-03-06</IssueDate>",
+                "<InvoiceTotal>-12.10</InvoiceTotal>",
+                "<Quantity>-1.00</Quantity>",
+            ],
+        );
+        assert_valid(&xml);
+    }
+
+    #[test]
+    fn individual_buyers_have_their_name_and_surnames() {
+        let mut invoice = invoice("FF2025-000006", vec![product("Café", 1, "1.50", 10)]);
+        invoice.recipient = Some(InvoiceRecipient {
+            name: String::from("María  García López"),
+            nif: String::from("12345678z"),
+            address: String::from("Calle Nueva 3, 03001 Alicante, Alicante"),
+            company_id: None,
+        });
+        let xml = generate_facturae(&invoice, None, &issuer()).unwrap();
+
+        assert_in_order(
+            &xml,
+            &[
+                "<BuyerParty>",
+                "<PersonTypeCode>F</PersonTypeCode>",
+                "<ResidenceTypeCode>R</ResidenceTypeCode>",
+                "<TaxIdentificationNumber>12345678Z</TaxIdentificationNumber>",
+                "<Individual>",
+                "<Name>María</Name>",
+                "<FirstSurname>García</FirstSurname>",
+                "<SecondSurname>López</SecondSurname>",
+                "<AddressInSpain>",
+                "</Individual>",
+                "</BuyerParty>",
+            ],
+        );
+        assert_valid(&xml);
+
+        // foreigners (NIE) are individuals too, the second surname is optional
+        if let Some(recipient) = &mut invoice.recipient {
+            recipient.name = String::from("John Smith");
+            recipient.nif = String::from("X1234567L");
+        }
+        let xml = generate_facturae(&invoice, None, &issuer()).unwrap();
+        assert_in_order(
+            &xml,
+            &[
+                "<PersonTypeCode>F</PersonTypeCode>",
+                "<Name>John</Name>",
+                "<FirstSurname>Smith</FirstSurname>",
+                "<AddressInSpain>",
+            ],
+        );
+        assert!(!xml.contains("<SecondSurname>"));
+        assert_valid(&xml);
+
+        // special NIFs (K, L, M) are individuals too
+        if let Some(recipient) = &mut invoice.recipient {
+            recipient.nif = String::from("K1234567L");
+        }
+        let xml = generate_facturae(&invoice, None, &issuer()).unwrap();
+        assert_in_order(
+            &xml,
+            &[
+                "<PersonTypeCode>F</PersonTypeCode>",
+                "<TaxIdentificationNumber>K1234567L</TaxIdentificationNumber>",
+                "<Individual>",
+            ],
+        );
+        assert_valid(&xml);
+
+        // an individual without surname can't be written
+        if let Some(recipient) = &mut invoice.recipient {
+            recipient.name = String::from("María");
+        }
+        assert!(generate_facturae(&invoice, None, &issuer()).is_err());
+    }
+
+    #[test]
+    fn texts_fit_the_schema_limits() {
+        let mut invoice = invoice("FF2025-000007", vec![product("Café", 1, "1.50", 10)]);
+        if let Some(recipient) = &mut invoice.recipient {
+            recipient.name = "A".repeat(100);
+            recipient.address = format!(
+                "{}, 28001 {}, {}",
+                "B".repeat(100),
+                "C".repeat(60),
+                "D".repeat(30)
+            );
+        }
+        let xml = generate_facturae(&invoice, None, &issuer()).unwrap();
+
+        assert!(xml.contains(&format!(
+            "<CorporateName>{}</CorporateName>",
+            "A".repeat(80)
+        )));
+        assert!(xml.contains(&format!("<Address>{}</Address>", "B".repeat(80))));
+        assert!(xml.contains(&format!("<Town>{}</Town>", "C".repeat(50))));
+        assert!(xml.contains(&format!("<Province>{}</Province>", "D".repeat(20))));
+        assert_valid(&xml);
+
+        if let Some(recipient) = &mut invoice.recipient {
+            recipient.nif = String::from("B1");
+        }
+        assert!(generate_facturae(&invoice, None, &issuer()).is_err());
+    }
+
+    #[test]
+    fn invoice_totals_add_up_the_taxes() {
+        let invoice = invoice(
+            "FF2025-000003",
+            vec![
+                product("Café", 3, "1.35", 10),
+                product("Tostada", 2, "2.15", 10),
+                product("Cerveza", 7, "2.30", 21),
+                product("Agua", 1, "0.99", 10),
+                product("Menú", 1, "14.95", 21),
+            ],
+        );
+        let xml = generate_facturae(&invoice, None, &issuer()).unwrap();
+
+        // the taxes summary is the first TaxesOutputs, the ones of the lines come after it
+        let (_, summary) = xml.split_once("<TaxesOutputs>").unwrap();
+        let (summary, _) = summary.split_once("</TaxesOutputs>").unwrap();
+        let bases: Decimal = summary
+            .split("<TaxableBase>")
+            .skip(1)
+            .map(|part| values(part, "TotalAmount")[0])
+            .sum();
+        let taxes: Decimal = summary
+            .split("<TaxAmount>")
+            .skip(1)
+            .map(|part| values(part, "TotalAmount")[0])
+            .sum();
+
+        assert_eq!(values(summary, "TaxRate").len(), 2);
+        assert_eq!(values(&xml, "TotalGrossAmount"), vec![bases]);
+        assert_eq!(values(&xml, "TotalGrossAmountBeforeTaxes"), vec![bases]);
+        assert_eq!(values(&xml, "TotalTaxOutputs"), vec![taxes]);
+        assert_eq!(values(&xml, "InvoiceTotal"), vec![bases + taxes]);
+        assert_eq!(bases + taxes, Decimal::from(invoice.total_price()));
+    }
+
+    #[test]
+    fn issue_date_is_the_one_of_the_printed_invoice() {
+        let mut invoice = invoice("FF2025-000004", vec![product("Café", 1, "1.50", 10)]);
+        // the record date is taken at its own offset (it's the 5th in UTC)
+        assert_eq!(issue_date(&invoice), "2025-03-06");
+        assert_eq!(
+            invoice.issued_at().map(|date| date.date().to_string()),
+            Some(issue_date(&invoice))
+        );
+
+        // invoices without a record are dated when they were paid
+        invoice.record_generated_at = None;
+        invoice.updated_at =
+            NaiveDate::from_ymd_opt(2025, 3, 5).and_then(|d| d.and_hms_opt(9, 0, 0));
+        assert_eq!(issue_date(&invoice), "2025-03-05");
+    }
+
+    #[test]
+    fn invalid_addresses_are_not_exported() {
+        let mut invoice = invoice("FF2025-000005", vec![product("Café", 1, "1.50", 10)]);
+        if let Some(recipient) = &mut invoice.recipient {
+            recipient.address = String::from("Avenida del Puerto 20, Madrid");
+        }
+
+        assert!(generate_facturae(&invoice, None, &issuer()).is_err());
+    }
+
+    #[test]
+    fn classifies_nifs() {
+        for nif in [
+            "A87654321",
+            "B12345678",
+            "h1234567j",
+            "J1234567A",
+            "N1234567C",
+            "P1234567D",
+            "S1234567E",
+            "U1234567F",
+            "W1234567G",
+        ] {
+            assert!(is_legal_entity_nif(nif), "{nif} is a legal entity");
+        }
+        for nif in [
+            "12345678Z",
+            "X1234567L",
+            "Y1234567X",
+            "Z1234567R",
+            "K1234567L",
+            "L1234567M",
+            "M1234567N",
+            "I1234567A",
+            "O1234567A",
+            "T1234567A",
+            "",
+        ] {
+            assert!(!is_legal_entity_nif(nif), "{nif} isn't a legal entity");
+        }
+    }
+
+    #[test]
+    fn escapes_xml() {
+        assert_eq!(
+            escape_xml("Bar & Grill <\"Pepe's\">"),
+            "Bar &amp; Grill &lt;&quot;Pepe&apos;s&quot;&gt;"
+        );
+        assert_eq!(escape_xml("&amp;"), "&amp;amp;");
+        assert_eq!(escape_xml("Café"), "Café");
+    }
+
+    #[test]
+    fn splits_addresses() {
+        assert_eq!(
+            split_address("Calle Mayor 1, 2º B, 46001 Valencia, Valencia"),
+            Some((
+                String::from("Calle Mayor 1, 2º B"),
+                String::from("46001"),
+                String::from("Valencia"),
+                String::from("Valencia")
+            ))
+        );
+        // without a province the town is used
+        assert_eq!(
+            split_address("Avenida del Puerto 20,28001   Madrid"),
+            Some((
+                String::from("Avenida del Puerto 20"),
+                String::from("28001"),
+                String::from("Madrid"),
+                String::from("Madrid")
+            ))
+        );
+        // without a street or a post code
+        assert_eq!(split_address("28001 Madrid, Madrid"), None);
+        assert_eq!(split_address("Avenida del Puerto 20, Madrid"), None);
+        assert_eq!(split_address("Avenida del Puerto 20, 2800 Madrid"), None);
+        assert_eq!(split_address(""), None);
+    }
+
+    #[test]
+    fn splits_invoice_numbers() {
+        assert_eq!(split_invoice_number("FS2025-000001"), ("FS2025", "000001"));
+        assert_eq!(
+            split_invoice_number("FR-2025-000010"),
+            ("FR-2025", "000010")
+        );
+        assert_eq!(split_invoice_number("000001"), ("", "000001"));
+    }
+}
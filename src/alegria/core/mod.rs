@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 pub mod database;
+pub mod facturae;
 pub mod models;
 pub mod print;
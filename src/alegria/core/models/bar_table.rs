@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::sync::Arc;
+
+use crate::alegria::utils::entities::table_shape::TableShape;
+
+/// A table of the bar screen, placed on a location
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BarTable {
+    pub id: Option<i32>,
+    pub location_id: Option<i32>,
+    pub name: String,
+    pub capacity: Option<i32>,
+    pub sort_order: Option<i32>,
+    /// Position of the table on the floor plan of its location
+    pub pos_x: i32,
+    pub pos_y: i32,
+    /// Size of the table on the floor plan of its location
+    pub width: i32,
+    pub height: i32,
+    pub shape: TableShape,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub location_name: Box<str>, // Helps us JOIN and return the location name of the selected location_id
+    pub capacity_input: String,  // Helps us input on TextInputs
+    pub sort_order_input: String, // Helps us input on TextInputs
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for BarTable {
+    fn default() -> Self {
+        Self {
+            id: None,
+            location_id: None,
+            name: String::new(),
+            capacity: Some(4),
+            sort_order: Some(0),
+            pos_x: 0,
+            pos_y: 0,
+            width: 80,
+            height: 80,
+            shape: TableShape::default(),
+
+            is_deleted: false,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            location_name: String::new().into_boxed_str(),
+            capacity_input: String::from("4"),
+            sort_order_input: String::from("0"),
+        }
+    }
+}
+
+impl BarTable {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        self.location_id.is_some()
+            && !self.name.trim().is_empty()
+            && self.capacity.is_some_and(|c| c >= 0)
+            && self.sort_order.is_some()
+    }
+
+    /// Returns all the tables (of non deleted locations) ordered by location and table order
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<BarTable>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                bar_tables.id,
+                bar_tables.location_id,
+                bar_tables.name,
+                bar_tables.capacity,
+                bar_tables.sort_order,
+                bar_tables.pos_x,
+                bar_tables.pos_y,
+                bar_tables.width,
+                bar_tables.height,
+                bar_tables.shape,
+                bar_tables.is_deleted,
+                bar_tables.created_at,
+                bar_tables.updated_at,
+                table_locations.name as location_name
+            FROM bar_tables
+            JOIN table_locations ON bar_tables.location_id = table_locations.id
+            WHERE bar_tables.is_deleted = $1 AND table_locations.is_deleted = $1
+            ORDER BY table_locations.sort_order ASC, table_locations.id ASC, bar_tables.sort_order ASC, bar_tables.id ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<BarTable>::new();
+        for row in rows {
+            result.push(Self::from_row(&row)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(pool: Arc<PgPool>, bar_table_id: i32) -> Result<BarTable, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT
+                bar_tables.id,
+                bar_tables.location_id,
+                bar_tables.name,
+                bar_tables.capacity,
+                bar_tables.sort_order,
+                bar_tables.pos_x,
+                bar_tables.pos_y,
+                bar_tables.width,
+                bar_tables.height,
+                bar_tables.shape,
+                bar_tables.is_deleted,
+                bar_tables.created_at,
+                bar_tables.updated_at,
+                table_locations.name as location_name
+            FROM bar_tables
+            JOIN table_locations ON bar_tables.location_id = table_locations.id
+            WHERE bar_tables.id = $1",
+        )
+        .bind(bar_table_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        Self::from_row(&row)
+    }
+
+    pub async fn add(pool: Arc<PgPool>, bar_table: BarTable) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO bar_tables (location_id, name, capacity, sort_order, is_deleted) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(bar_table.location_id)
+        .bind(bar_table.name.trim())
+        .bind(bar_table.capacity)
+        .bind(bar_table.sort_order)
+        .bind(false)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Edits the given table, its location can't be changed while it has an open ticket
+    pub async fn edit(pool: Arc<PgPool>, bar_table: BarTable) -> Result<(), sqlx::Error> {
+        let open_tickets: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM temporal_tickets WHERE table_id = $1 AND ticket_location <> $2",
+        )
+        .bind(bar_table.id)
+        .bind(bar_table.location_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        if open_tickets > 0 {
+            return Err(sqlx::Error::Protocol(
+                "The table has an open ticket, it can't be moved to another location".into(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE bar_tables SET location_id = $1, name = $2, capacity = $3, sort_order = $4 WHERE id = $5",
+        )
+        .bind(bar_table.location_id)
+        .bind(bar_table.name.trim())
+        .bind(bar_table.capacity)
+        .bind(bar_table.sort_order)
+        .bind(bar_table.id)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the given table, fails if it has an open ticket
+    pub async fn delete(pool: Arc<PgPool>, bar_table_id: i32) -> Result<(), sqlx::Error> {
+        let open_tickets: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM temporal_tickets WHERE table_id = $1")
+                .bind(bar_table_id)
+                .fetch_one(pool.as_ref())
+                .await?;
+
+        if open_tickets > 0 {
+            return Err(sqlx::Error::Protocol(
+                "The table has an open ticket, pay or move it first".into(),
+            ));
+        }
+
+        sqlx::query("UPDATE bar_tables SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(bar_table_id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Saves the floor plan position, size and shape of the given tables
+    pub async fn save_layout(
+        pool: Arc<PgPool>,
+        bar_tables: Vec<BarTable>,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        for bar_table in bar_tables {
+            sqlx::query(
+                "UPDATE bar_tables SET pos_x = $1, pos_y = $2, width = $3, height = $4, shape = $5 WHERE id = $6",
+            )
+            .bind(bar_table.pos_x)
+            .bind(bar_table.pos_y)
+            .bind(bar_table.width)
+            .bind(bar_table.height)
+            .bind(bar_table.shape)
+            .bind(bar_table.id)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    fn from_row(row: &PgRow) -> Result<BarTable, sqlx::Error> {
+        let capacity: Option<i32> = row.try_get("capacity")?;
+        let sort_order: Option<i32> = row.try_get("sort_order")?;
+        let location_name: String = row.try_get("location_name")?;
+
+        Ok(BarTable {
+            id: row.try_get("id")?,
+            location_id: row.try_get("location_id")?,
+            name: row.try_get("name")?,
+            capacity,
+            sort_order,
+            pos_x: row.try_get("pos_x")?,
+            pos_y: row.try_get("pos_y")?,
+            width: row.try_get("width")?,
+            height: row.try_get("height")?,
+            shape: row.try_get("shape")?,
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            location_name: location_name.into_boxed_str(),
+            capacity_input: capacity.unwrap_or_default().to_string(),
+            sort_order_input: sort_order.unwrap_or_default().to_string(),
+        })
+    }
+}
@@ -0,0 +1,371 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::alegria::utils::{
+    entities::payment_method::PaymentMethod,
+    money::{Money, split_tax},
+};
+
+/// A cash register session (shift), from the moment the drawer is opened with a starting float until it's counted and closed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashSession {
+    pub id: Option<i32>,
+    pub opened_at: Option<NaiveDateTime>,
+    pub closed_at: Option<NaiveDateTime>,
+    pub opening_float: Option<Money>,
+    pub counted_amount: Option<Money>,
+    pub movements: Vec<CashMovement>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub opening_float_input: String,
+    pub counted_amount_input: String,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CashSession {
+    fn default() -> Self {
+        Self {
+            id: None,
+            opened_at: None,
+            closed_at: None,
+            opening_float: None,
+            counted_amount: None,
+            movements: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            opening_float_input: String::new(),
+            counted_amount_input: String::new(),
+        }
+    }
+}
+
+/// Cash put into (positive amount) or taken out of (negative amount) the drawer during a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashMovement {
+    pub id: Option<i32>,
+    pub cash_session_id: Option<i32>,
+    pub amount: Option<Money>,
+    pub reason: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub amount_input: String,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for CashMovement {
+    fn default() -> Self {
+        Self {
+            id: None,
+            cash_session_id: None,
+            amount: None,
+            reason: String::new(),
+            created_at: None,
+            updated_at: None,
+            amount_input: String::new(),
+        }
+    }
+}
+
+/// Closing report of a cash session: what has been sold (by payment method and tax rate) and the drawer reconciliation
+#[derive(Debug, Clone)]
+pub struct ZReport {
+    pub cash_session_id: i32,
+    pub opened_at: NaiveDateTime,
+    pub closed_at: NaiveDateTime,
+    /// Number of invoices paid during the session
+    pub invoices: i64,
+    /// Amount paid with each payment method
+    pub payments: Vec<(PaymentMethod, Money)>,
+    /// Base and tax of everything sold, by tax percentage
+    pub taxes: Vec<ZReportTax>,
+    pub total: Money,
+    /// Amount discounted on what has been sold, by reason
+    pub discounts: Vec<(String, Money)>,
+    pub opening_float: Money,
+    /// Sum of the cash movements of the session
+    pub cash_movements: Money,
+    /// Cash that should be in the drawer (float + cash payments + movements)
+    pub expected_cash: Money,
+    pub counted_amount: Money,
+}
+
+/// Base and tax of everything sold with a given tax percentage
+#[derive(Debug, Clone)]
+pub struct ZReportTax {
+    pub tax_percentage: Decimal,
+    pub base: Money,
+    pub tax: Money,
+}
+
+impl ZReport {
+    /// Returns the difference between the counted and the expected cash (negative if cash is missing)
+    pub fn difference(&self) -> Money {
+        self.counted_amount - self.expected_cash
+    }
+
+    /// Returns the amount discounted on everything sold during the session
+    pub fn total_discounts(&self) -> Money {
+        self.discounts.iter().map(|(_, amount)| *amount).sum()
+    }
+}
+
+impl CashSession {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        self.opening_float.is_some_and(|f| f >= Money::ZERO)
+    }
+
+    /// Returns the currently open session (with its movements) if there is one
+    pub async fn get_open(pool: Arc<PgPool>) -> Result<Option<CashSession>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, opened_at, closed_at, opening_float, counted_amount, created_at, updated_at
+             FROM cash_sessions WHERE closed_at IS NULL",
+        )
+        .fetch_optional(pool.as_ref())
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let id: i32 = row.try_get("id")?;
+        let opening_float: Option<Money> = row.try_get("opening_float")?;
+
+        let movement_rows = sqlx::query(
+            "SELECT id, cash_session_id, amount, reason, created_at, updated_at
+             FROM cash_movements WHERE cash_session_id = $1 ORDER BY id ASC",
+        )
+        .bind(id)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut movements = Vec::with_capacity(movement_rows.len());
+        for movement_row in movement_rows {
+            let amount: Option<Money> = movement_row.try_get("amount")?;
+
+            movements.push(CashMovement {
+                id: movement_row.try_get("id")?,
+                cash_session_id: movement_row.try_get("cash_session_id")?,
+                amount,
+                reason: movement_row.try_get("reason")?,
+                created_at: movement_row.try_get("created_at")?,
+                updated_at: movement_row.try_get("updated_at")?,
+                amount_input: amount.unwrap_or_default().to_string(),
+            });
+        }
+
+        Ok(Some(CashSession {
+            id: Some(id),
+            opened_at: row.try_get("opened_at")?,
+            closed_at: row.try_get("closed_at")?,
+            opening_float,
+            counted_amount: row.try_get("counted_amount")?,
+            movements,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            opening_float_input: opening_float.unwrap_or_default().to_string(),
+            counted_amount_input: String::new(),
+        }))
+    }
+
+    /// Opens a new session with the opening float of the given one, fails if there is already an open session
+    /// (the db only allows one, even if two clients open a session at the same time)
+    pub async fn open(pool: Arc<PgPool>, cash_session: CashSession) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO cash_sessions (opening_float) VALUES ($1)")
+            .bind(cash_session.opening_float.unwrap_or_default())
+            .execute(pool.as_ref())
+            .await
+            .map_err(|err| match err.as_database_error() {
+                Some(db_err) if db_err.is_unique_violation() => {
+                    sqlx::Error::Protocol("There is already an open cash session".into())
+                }
+                _ => err,
+            })?;
+
+        Ok(())
+    }
+
+    /// Closes the given session with the counted amount of cash and returns its Z report
+    pub async fn close(
+        pool: Arc<PgPool>,
+        cash_session_id: i32,
+        counted_amount: Money,
+    ) -> Result<ZReport, sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        let session = sqlx::query(
+            "UPDATE cash_sessions SET closed_at = CURRENT_TIMESTAMP, counted_amount = $1
+             WHERE id = $2 AND closed_at IS NULL
+             RETURNING opened_at, closed_at, opening_float",
+        )
+        .bind(counted_amount)
+        .bind(cash_session_id)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or_else(|| sqlx::Error::Protocol("The cash session is not open".into()))?;
+
+        let opened_at: NaiveDateTime = session.try_get("opened_at")?;
+        let closed_at: NaiveDateTime = session.try_get("closed_at")?;
+        let opening_float: Money = session.try_get("opening_float")?;
+
+        // the invoices of the session are the ones paid (with payments registered) while it was open,
+        // adeudos settled at check-out move their amount from the adeudo method to the one they were settled with
+        let payment_rows = sqlx::query(
+            "SELECT payment_method_id, SUM(amount) as amount
+             FROM (
+                SELECT payment_method_id, amount FROM invoice_payments
+                WHERE created_at >= $1 AND created_at <= $2
+                UNION ALL
+                SELECT payment_method_id, amount FROM adeudo_settlements
+                WHERE created_at >= $1 AND created_at <= $2
+                UNION ALL
+                SELECT $3, -amount FROM adeudo_settlements
+                WHERE created_at >= $1 AND created_at <= $2
+             ) payments
+             GROUP BY payment_method_id
+             ORDER BY payment_method_id",
+        )
+        .bind(opened_at)
+        .bind(closed_at)
+        .bind(PaymentMethod::Adeudo.to_id())
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let mut payments = Vec::with_capacity(payment_rows.len());
+        for row in payment_rows {
+            let payment_method_id: i32 = row.try_get("payment_method_id")?;
+            let amount: Money = row.try_get("amount")?;
+
+            let payment_method = PaymentMethod::from_id(payment_method_id).ok_or_else(|| {
+                sqlx::Error::Protocol(format!("Invalid payment_method id: {payment_method_id}"))
+            })?;
+            payments.push((payment_method, amount));
+        }
+
+        let invoices: i64 = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT simple_invoice_id) FROM invoice_payments
+             WHERE created_at >= $1 AND created_at <= $2",
+        )
+        .bind(opened_at)
+        .bind(closed_at)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        // tax-included totals by the tax percentage the lines were sold with (same as on the printed invoices)
+        let tax_rows = sqlx::query(
+            "SELECT sp.tax_percentage, SUM(sp.price * sp.quantity) as total
+             FROM sold_products sp
+             WHERE sp.simple_invoice_id IN (
+                SELECT simple_invoice_id FROM invoice_payments
+                WHERE created_at >= $1 AND created_at <= $2
+             )
+             GROUP BY 1",
+        )
+        .bind(opened_at)
+        .bind(closed_at)
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let mut tax_totals: BTreeMap<Decimal, Money> = BTreeMap::new();
+        for row in tax_rows {
+            let tax_percentage: Decimal = row.try_get("tax_percentage")?;
+            let total: Option<Money> = row.try_get("total")?;
+            *tax_totals.entry(tax_percentage.normalize()).or_default() += total.unwrap_or_default();
+        }
+
+        let taxes = tax_totals
+            .into_iter()
+            .map(|(tax_percentage, total)| {
+                let (base, tax) = split_tax(total, tax_percentage);
+                ZReportTax {
+                    tax_percentage,
+                    base,
+                    tax,
+                }
+            })
+            .collect();
+
+        let discount_rows = sqlx::query(
+            "SELECT COALESCE(sp.discount_reason, '') as discount_reason, SUM((sp.original_price - sp.price) * sp.quantity) as total
+             FROM sold_products sp
+             WHERE sp.original_price IS NOT NULL AND sp.simple_invoice_id IN (
+                SELECT simple_invoice_id FROM invoice_payments
+                WHERE created_at >= $1 AND created_at <= $2
+             )
+             GROUP BY 1
+             ORDER BY 2 DESC",
+        )
+        .bind(opened_at)
+        .bind(closed_at)
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let mut discounts = Vec::with_capacity(discount_rows.len());
+        for row in discount_rows {
+            let reason: String = row.try_get("discount_reason")?;
+            let total: Option<Money> = row.try_get("total")?;
+            discounts.push((reason, total.unwrap_or_default()));
+        }
+
+        let cash_movements: Option<Money> =
+            sqlx::query_scalar("SELECT SUM(amount) FROM cash_movements WHERE cash_session_id = $1")
+                .bind(cash_session_id)
+                .fetch_one(&mut *transaction)
+                .await?;
+        let cash_movements = cash_movements.unwrap_or_default();
+
+        let cash_payments: Money = payments
+            .iter()
+            .filter(|(method, _)| *method == PaymentMethod::Efectivo)
+            .map(|(_, amount)| *amount)
+            .sum();
+
+        transaction.commit().await?;
+
+        Ok(ZReport {
+            cash_session_id,
+            opened_at,
+            closed_at,
+            invoices,
+            total: payments.iter().map(|(_, amount)| *amount).sum(),
+            payments,
+            taxes,
+            discounts,
+            opening_float,
+            cash_movements,
+            expected_cash: opening_float + cash_payments + cash_movements,
+            counted_amount,
+        })
+    }
+}
+
+impl CashMovement {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        self.cash_session_id.is_some()
+            && self.amount.is_some_and(|a| a != Money::ZERO)
+            && !self.reason.trim().is_empty()
+    }
+
+    pub async fn add(pool: Arc<PgPool>, cash_movement: CashMovement) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO cash_movements (cash_session_id, amount, reason) VALUES ($1, $2, $3)",
+        )
+        .bind(cash_movement.cash_session_id)
+        .bind(cash_movement.amount.unwrap_or_default())
+        .bind(cash_movement.reason.trim())
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use chrono::{Datelike, NaiveDateTime};
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::sync::Arc;
@@ -101,6 +101,37 @@ impl Client {
         true
     }
 
+    /// Returns true if the client is an adult (18 or older) on the given date, clients without a birthdate are considered adults
+    pub fn is_adult_on(&self, date: NaiveDate) -> bool {
+        self.birthdate.is_none_or(|birthdate| {
+            birthdate
+                .date()
+                .checked_add_months(Months::new(18 * 12))
+                .is_some_and(|coming_of_age| coming_of_age <= date)
+        })
+    }
+
+    /// Returns why the client can't be registered as a guest on the given date, None if it can
+    /// (adults need an identity document that has not expired)
+    pub fn guest_registration_error(&self, date: NaiveDate) -> Option<&'static str> {
+        if !self.is_adult_on(date) {
+            return None;
+        }
+
+        if self.identity_document_type.is_none() || self.identity_document.trim().is_empty() {
+            return Some("Adult guests need an identity document");
+        }
+
+        if self
+            .identity_document_expiration_date
+            .is_some_and(|expiration_date| expiration_date.date() < date)
+        {
+            return Some("The identity document has expired");
+        }
+
+        None
+    }
+
     pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<Client>, sqlx::Error> {
         // We retrieve only the fields needed for the grid
 
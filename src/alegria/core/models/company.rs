@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::{fmt, sync::Arc};
+
+/// Fiscal customer (a company) that can be invoiced and can pay reservations
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Company {
+    pub id: Option<i32>,
+    /// Legal name
+    pub name: String,
+    pub cif: String,
+    /// Fiscal address (Street, 00000 Town, Province)
+    pub address: String,
+    /// Contact email for the invoices
+    pub email: String,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl fmt::Display for Company {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.cif)
+    }
+}
+
+impl Company {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && !self.cif.trim().is_empty()
+            && !self.address.trim().is_empty()
+            && (self.email.trim().is_empty() || self.email.contains('@'))
+    }
+
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<Company>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, cif, address, email, is_deleted, created_at, updated_at
+            FROM companies
+            WHERE is_deleted = $1
+            ORDER BY name ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<Company>::new();
+        for row in rows {
+            result.push(Self::from_row(&row)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(pool: Arc<PgPool>, company_id: i32) -> Result<Company, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, name, cif, address, email, is_deleted, created_at, updated_at
+            FROM companies
+            WHERE id = $1",
+        )
+        .bind(company_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        Self::from_row(&row)
+    }
+
+    pub async fn add(pool: Arc<PgPool>, company: Company) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO companies (name, cif, address, email) VALUES ($1, $2, $3, $4)")
+            .bind(company.name.trim())
+            .bind(company.cif.trim().to_uppercase())
+            .bind(company.address.trim())
+            .bind(company.email.trim())
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Updates the company, the invoices already issued to it keep the fiscal data they were issued with
+    pub async fn edit(pool: Arc<PgPool>, company: Company) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE companies SET name = $1, cif = $2, address = $3, email = $4 WHERE id = $5",
+        )
+        .bind(company.name.trim())
+        .bind(company.cif.trim().to_uppercase())
+        .bind(company.address.trim())
+        .bind(company.email.trim())
+        .bind(company.id)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(pool: Arc<PgPool>, company_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE companies SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(company_id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    fn from_row(row: &PgRow) -> Result<Company, sqlx::Error> {
+        Ok(Company {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            cif: row.try_get("cif")?,
+            address: row.try_get("address")?,
+            email: row.try_get("email")?,
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
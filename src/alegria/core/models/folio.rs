@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{Days, Local, NaiveDate};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::sync::Arc;
+
+use crate::alegria::utils::{entities::payment_method::PaymentMethod, money::Money};
+
+use super::{
+    company::Company,
+    invoice_payment::InvoicePayment,
+    product::Product,
+    reservation::Reservation,
+    simple_invoice::{InvoiceRecipient, SimpleInvoice},
+};
+
+/// A line of the final invoice of a reservation (the nights of a room or an extra)
+#[derive(Debug, Clone)]
+pub struct FolioLine {
+    pub product_id: i32,
+    pub name: String,
+    /// Printed under the product name (the room of the nights)
+    pub modifiers: String,
+    pub quantity: i32,
+    /// Unit price (tax included)
+    pub price: Money,
+}
+
+impl FolioLine {
+    /// Returns the total price of the line (quantity * unit price)
+    pub fn total_price(&self) -> Money {
+        self.price * self.quantity
+    }
+}
+
+/// An invoice charged to a room of the reservation, only the part that was paid as an adeudo is owed
+#[derive(Debug, Clone)]
+pub struct FolioAdeudo {
+    pub invoice: SimpleInvoice,
+    /// Sum of the adeudo payments of the invoice (the rest was paid when the invoice was issued)
+    pub amount: Money,
+}
+
+/// Everything a reservation has to pay at check-out
+#[derive(Debug, Clone)]
+pub struct Folio {
+    pub reservation: Reservation,
+    /// Nights that are charged (up to today if the guests leave before the departure date)
+    pub nights: i32,
+    /// Nights of every room of the reservation
+    pub room_lines: Vec<FolioLine>,
+    pub extras: Vec<FolioLine>,
+    /// Invoices charged to the rooms (adeudos), they have already been issued so they're settled but not invoiced again
+    pub adeudos: Vec<FolioAdeudo>,
+    /// Recipient of the final invoice (the company that pays the reservation), None for a simplified invoice
+    pub recipient: Option<InvoiceRecipient>,
+}
+
+impl Folio {
+    /// Returns the total of the final invoice (room nights and extras)
+    pub fn invoice_total(&self) -> Money {
+        self.room_lines
+            .iter()
+            .chain(&self.extras)
+            .map(FolioLine::total_price)
+            .sum()
+    }
+
+    /// Returns what has been charged to the rooms (the adeudo payments of their invoices)
+    pub fn adeudos_total(&self) -> Money {
+        self.adeudos.iter().map(|adeudo| adeudo.amount).sum()
+    }
+
+    /// Returns what has to be paid at check-out
+    pub fn total(&self) -> Money {
+        self.invoice_total() + self.adeudos_total()
+    }
+
+    /// Adds one unit of the given product as an extra
+    pub fn add_extra(&mut self, product: &Product) {
+        let Some(product_id) = product.id else {
+            return;
+        };
+
+        match self
+            .extras
+            .iter_mut()
+            .find(|extra| extra.product_id == product_id)
+        {
+            Some(extra) => extra.quantity += 1,
+            None => self.extras.push(FolioLine {
+                product_id,
+                name: product.name.clone(),
+                modifiers: String::new(),
+                quantity: 1,
+                price: product.inside_price.unwrap_or_default(),
+            }),
+        }
+    }
+
+    /// Removes one unit of the given extra (by product id)
+    pub fn remove_extra(&mut self, product_id: i32) {
+        if let Some(extra) = self
+            .extras
+            .iter_mut()
+            .find(|extra| extra.product_id == product_id)
+        {
+            extra.quantity -= 1;
+        }
+        self.extras.retain(|extra| extra.quantity > 0);
+    }
+
+    /// Retrieves the folio of the given (occupied) reservation as of today
+    pub async fn get(pool: Arc<PgPool>, reservation_id: i32) -> Result<Folio, sqlx::Error> {
+        let reservation = Reservation::get_single(pool.clone(), reservation_id).await?;
+
+        let accommodation = sqlx::query(
+            "SELECT id, name FROM products WHERE is_accommodation = TRUE AND is_deleted = FALSE ORDER BY id LIMIT 1",
+        )
+        .fetch_optional(pool.as_ref())
+        .await?
+        .ok_or_else(|| sqlx::Error::Protocol("Missing the accommodation product".into()))?;
+        let accommodation_id: i32 = accommodation.try_get("id")?;
+        let accommodation_name: String = accommodation.try_get("name")?;
+
+        let nights = Self::nights(&reservation, Local::now().date_naive());
+
+        let room_lines = reservation
+            .rooms
+            .iter()
+            .map(|sold_room| FolioLine {
+                product_id: accommodation_id,
+                name: accommodation_name.clone(),
+                modifiers: sold_room.room_name.to_string(),
+                quantity: nights,
+                price: sold_room.price.unwrap_or_default(),
+            })
+            .collect();
+
+        // an invoice may have been paid only partially as an adeudo, the room only owes that part
+        let adeudo_rows = sqlx::query(
+            "SELECT sri.simple_invoice_id, SUM(ip.amount) as amount FROM sold_room_invoices sri
+            JOIN reservation_sold_rooms rsr ON sri.sold_room_id = rsr.sold_room_id
+            JOIN invoice_payments ip ON sri.simple_invoice_id = ip.simple_invoice_id
+            WHERE rsr.reservation_id = $1 AND ip.payment_method_id = $2
+            GROUP BY sri.simple_invoice_id
+            ORDER BY sri.simple_invoice_id ASC",
+        )
+        .bind(reservation_id)
+        .bind(PaymentMethod::Adeudo.to_id())
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut adeudos = Vec::new();
+        for row in adeudo_rows {
+            let simple_invoice_id: i32 = row.try_get("simple_invoice_id")?;
+            let Some(invoice) = reservation
+                .rooms
+                .iter()
+                .flat_map(|sold_room| &sold_room.invoices)
+                .find(|invoice| invoice.id == Some(simple_invoice_id))
+            else {
+                continue;
+            };
+
+            adeudos.push(FolioAdeudo {
+                invoice: invoice.clone(),
+                amount: Money::from(row.try_get::<Decimal, _>("amount")?),
+            });
+        }
+
+        let recipient = match reservation.company_id {
+            Some(company_id) => {
+                let company = Company::get_single(pool.clone(), company_id).await?;
+                Some(InvoiceRecipient {
+                    name: company.name,
+                    nif: company.cif,
+                    address: company.address,
+                    company_id: company.id,
+                })
+            }
+            None => None,
+        };
+
+        Ok(Folio {
+            reservation,
+            nights,
+            room_lines,
+            extras: Vec::new(),
+            adeudos,
+            recipient,
+        })
+    }
+
+    /// Checks out the reservation of the folio: issues the final invoice (room nights and extras), settles the adeudos
+    /// with the given payments (they have to add up to the folio total, the final invoice is paid first)
+    /// and frees the rooms. Returns the id of the final invoice
+    pub async fn check_out(
+        pool: Arc<PgPool>,
+        folio: Folio,
+        payments: Vec<InvoicePayment>,
+    ) -> Result<i32, sqlx::Error> {
+        let Some(reservation_id) = folio.reservation.id else {
+            return Err(sqlx::Error::Protocol(
+                "Can't check out a reservation that has not been added".into(),
+            ));
+        };
+
+        if payments
+            .iter()
+            .any(|p| p.payment_method == PaymentMethod::Adeudo)
+        {
+            return Err(sqlx::Error::Protocol(
+                "The check-out can't be charged to a room".into(),
+            ));
+        }
+
+        let paid: Money = payments.iter().map(|p| p.amount).sum();
+        if paid != folio.total() {
+            return Err(sqlx::Error::Protocol(
+                "The payments don't add up to the folio total".into(),
+            ));
+        }
+
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        let row = sqlx::query(
+            "SELECT occupied, checked_out_at IS NOT NULL as checked_out FROM reservations
+            WHERE id = $1 AND is_deleted = FALSE FOR UPDATE",
+        )
+        .bind(reservation_id)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or_else(|| sqlx::Error::Protocol("The reservation has been cancelled".into()))?;
+
+        let occupied: bool = row.try_get("occupied")?;
+        let checked_out: bool = row.try_get("checked_out")?;
+        if !occupied || checked_out {
+            return Err(sqlx::Error::Protocol(
+                "Only occupied reservations can be checked out".into(),
+            ));
+        }
+
+        // the rooms may have been charged while the folio was open
+        let adeudos_total: Decimal = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(ip.amount), 0) FROM invoice_payments ip
+            JOIN sold_room_invoices sri ON ip.simple_invoice_id = sri.simple_invoice_id
+            JOIN reservation_sold_rooms rsr ON sri.sold_room_id = rsr.sold_room_id
+            WHERE rsr.reservation_id = $1 AND ip.payment_method_id = $2",
+        )
+        .bind(reservation_id)
+        .bind(PaymentMethod::Adeudo.to_id())
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        if Money::from(adeudos_total) != folio.adeudos_total() {
+            return Err(sqlx::Error::Protocol(
+                "The rooms have new charges, reload the folio".into(),
+            ));
+        }
+
+        let (invoice_payments, settlement_payments) =
+            Self::split_payments(&payments, folio.invoice_total());
+
+        let lines: Vec<FolioLine> = folio
+            .room_lines
+            .iter()
+            .chain(&folio.extras)
+            .cloned()
+            .collect();
+        let final_invoice_id = SimpleInvoice::issue_final_invoice(
+            &mut transaction,
+            &lines,
+            &invoice_payments,
+            folio.recipient.as_ref(),
+        )
+        .await?;
+
+        for payment in &settlement_payments {
+            sqlx::query(
+                "INSERT INTO adeudo_settlements (reservation_id, payment_method_id, amount) VALUES ($1, $2, $3)",
+            )
+            .bind(reservation_id)
+            .bind(payment.payment_method.to_id())
+            .bind(payment.amount)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        // the rooms are free from the day after the last charged night
+        let departure_date = folio
+            .reservation
+            .entry_date
+            .and_then(|entry_date| entry_date.checked_add_days(Days::new(folio.nights as u64)));
+
+        sqlx::query(
+            "UPDATE reservations SET occupied = FALSE, checked_out_at = CURRENT_TIMESTAMP, final_invoice_id = $1,
+                departure_date = LEAST(departure_date, COALESCE($2, departure_date))
+            WHERE id = $3",
+        )
+        .bind(final_invoice_id)
+        .bind(departure_date)
+        .bind(reservation_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(final_invoice_id)
+    }
+
+    /// Returns the nights of the reservation that are charged on the given date (at least one)
+    fn nights(reservation: &Reservation, date: NaiveDate) -> i32 {
+        let (Some(entry_date), Some(departure_date)) =
+            (reservation.entry_date, reservation.departure_date)
+        else {
+            return 1;
+        };
+
+        let last_date = departure_date.date().min(date);
+        (last_date - entry_date.date()).num_days().max(1) as i32
+    }
+
+    /// Splits the given payments in the ones of the final invoice (up to its total) and the ones that settle the adeudos
+    fn split_payments(
+        payments: &[InvoicePayment],
+        invoice_total: Money,
+    ) -> (Vec<InvoicePayment>, Vec<InvoicePayment>) {
+        let mut invoice_payments = Vec::new();
+        let mut settlement_payments = Vec::new();
+        let mut remaining = invoice_total;
+
+        for payment in payments {
+            let invoice_amount = payment.amount.min(remaining).max(Money::ZERO);
+            let settlement_amount = payment.amount - invoice_amount;
+            remaining -= invoice_amount;
+
+            if invoice_amount != Money::ZERO {
+                invoice_payments.push(InvoicePayment::new(payment.payment_method, invoice_amount));
+            }
+            if settlement_amount != Money::ZERO {
+                settlement_payments.push(InvoicePayment::new(
+                    payment.payment_method,
+                    settlement_amount,
+                ));
+            }
+        }
+
+        (invoice_payments, settlement_payments)
+    }
+}
@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::{fmt, sync::Arc};
+
+/// Something the prepared products are made of (consumed through their recipes)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Ingredient {
+    pub id: Option<i32>,
+    pub name: String,
+    /// Unit the quantities of the ingredient are measured in (ml, g, units...)
+    pub unit: String,
+    pub stock_quantity: Option<Decimal>,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub stock_quantity_input: String, // Helps us input on TextInputs
+}
+
+/// Quantity of an ingredient that should have been consumed by what has been sold on a period of time
+#[derive(Debug, Clone)]
+pub struct IngredientConsumption {
+    pub name: String,
+    pub unit: String,
+    pub quantity: Decimal,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Ingredient {
+    fn default() -> Self {
+        Self {
+            id: None,
+            name: String::new(),
+            unit: String::new(),
+            stock_quantity: Some(Decimal::ZERO),
+            is_deleted: false,
+            created_at: None,
+            updated_at: None,
+            stock_quantity_input: String::from("0"),
+        }
+    }
+}
+
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.unit)
+    }
+}
+
+impl Ingredient {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && !self.unit.trim().is_empty()
+            && self.stock_quantity.is_some()
+    }
+
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<Ingredient>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, unit, stock_quantity, is_deleted, created_at, updated_at
+            FROM ingredients WHERE is_deleted = $1 ORDER BY name ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<Ingredient>::new();
+        for row in rows {
+            result.push(Self::from_row(&row)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        ingredient_id: i32,
+    ) -> Result<Ingredient, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, name, unit, stock_quantity, is_deleted, created_at, updated_at
+            FROM ingredients WHERE id = $1",
+        )
+        .bind(ingredient_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        Self::from_row(&row)
+    }
+
+    pub async fn add(pool: Arc<PgPool>, ingredient: Ingredient) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO ingredients (name, unit, stock_quantity, is_deleted) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(ingredient.name.trim())
+        .bind(ingredient.unit.trim())
+        .bind(ingredient.stock_quantity.unwrap_or_default())
+        .bind(false)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn edit(pool: Arc<PgPool>, ingredient: Ingredient) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ingredients SET name = $1, unit = $2, stock_quantity = $3 WHERE id = $4",
+        )
+        .bind(ingredient.name.trim())
+        .bind(ingredient.unit.trim())
+        .bind(ingredient.stock_quantity.unwrap_or_default())
+        .bind(ingredient.id)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(pool: Arc<PgPool>, ingredient_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE ingredients SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(ingredient_id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns how much of each ingredient the paid sold products of the given dates (both included)
+    /// should have consumed according to the current recipes (full invoices issued in place of a simplified one are left out,
+    /// their lines are already on the simplified one, and every share of a ticket split evenly only counts its part of the quantity)
+    pub async fn get_theoretical_consumption(
+        pool: Arc<PgPool>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<IngredientConsumption>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                i.name,
+                i.unit,
+                SUM(COALESCE(sp.counted_quantity, sp.quantity) * ri.quantity) as quantity
+            FROM sold_products sp
+            JOIN simple_invoices si ON si.id = sp.simple_invoice_id
+            JOIN recipe_ingredients ri ON ri.product_id = sp.original_product_id
+            JOIN ingredients i ON i.id = ri.ingredient_id
+            WHERE si.paid = TRUE AND si.replaced_invoice_id IS NULL
+            AND si.created_at::DATE BETWEEN $1 AND $2
+            GROUP BY i.id, i.name, i.unit
+            ORDER BY i.name ASC",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<IngredientConsumption>::new();
+        for row in rows {
+            result.push(IngredientConsumption {
+                name: row.try_get("name")?,
+                unit: row.try_get("unit")?,
+                quantity: row.try_get("quantity")?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn from_row(row: &PgRow) -> Result<Ingredient, sqlx::Error> {
+        let stock_quantity: Decimal = row.try_get("stock_quantity")?;
+
+        Ok(Ingredient {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            unit: row.try_get("unit")?,
+            stock_quantity: Some(stock_quantity),
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            stock_quantity_input: stock_quantity.normalize().to_string(),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use serde::{Deserialize, Serialize};
+
+use crate::alegria::utils::{entities::payment_method::PaymentMethod, money::Money};
+
+/// A (partial) payment of a simple invoice, an invoice may be paid with several methods
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvoicePayment {
+    pub id: Option<i32>,
+    pub simple_invoice_id: Option<i32>,
+    pub payment_method: PaymentMethod,
+    pub amount: Money,
+}
+
+impl InvoicePayment {
+    /// Creates a new payment that's not yet in the db
+    pub fn new(payment_method: PaymentMethod, amount: Money) -> Self {
+        Self {
+            id: None,
+            simple_invoice_id: None,
+            payment_method,
+            amount,
+        }
+    }
+
+    /// Returns the method that covers most of the given payments (the one recorded on the invoice itself)
+    pub fn main_method(payments: &[InvoicePayment]) -> PaymentMethod {
+        let mut totals: Vec<(PaymentMethod, Money)> = Vec::new();
+        for payment in payments {
+            match totals
+                .iter_mut()
+                .find(|(m, _)| *m == payment.payment_method)
+            {
+                Some((_, total)) => *total += payment.amount,
+                None => totals.push((payment.payment_method, payment.amount)),
+            }
+        }
+
+        // on a tie the first registered method wins
+        totals
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, total)| *total)
+            .map(|(method, _)| method)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,578 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{DateTime, Local, SecondsFormat};
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::{collections::HashMap, env, fmt::Display, path::PathBuf, sync::Arc};
+
+use crate::alegria::{
+    core::print::Issuer,
+    utils::{
+        entities::invoice_series_kind::InvoiceSeriesKind,
+        money::{Money, split_tax},
+    },
+};
+use crate::fl;
+
+use super::simple_invoice::SimpleInvoice;
+
+/// Service where the invoices can be checked from their QR code (AEAT testing environment)
+const DEFAULT_QR_URL: &str = "https://prewww2.aeat.es/wlpl/TIKE-CONT/ValidarQR";
+/// Folder of the local stand-in of the submission endpoint
+const DEFAULT_ENDPOINT_DIR: &str = "verifactu";
+
+/// Tamper-evident record of an issued invoice, its hash includes the hash of the previous record of the chain
+#[derive(Debug, Clone)]
+pub struct InvoiceRecord {
+    pub simple_invoice_id: i32,
+    pub sequence: i32,
+    pub issuer_nif: String,
+    pub invoice_number: String,
+    /// F1 full, F2 simplified, F3 full in place of a simplified one, R1 rectifying (full) or R5 rectifying (simplified)
+    pub invoice_type: &'static str,
+    pub tax_total: Money,
+    pub total: Money,
+    pub previous_hash: Option<String>,
+    /// ISO 8601 with the UTC offset
+    pub generated_at: String,
+    pub hash: String,
+}
+
+/// A point of the chain where the records don't match what they should be
+#[derive(Debug, Clone)]
+pub struct ChainBreak {
+    pub sequence: i32,
+    pub invoice_number: String,
+    pub kind: ChainBreakKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainBreakKind {
+    /// The hash doesn't match the record (the invoice or the record have been modified)
+    HashMismatch,
+    /// The previous hash doesn't match the hash of the previous record
+    PreviousHashMismatch,
+    /// There are records missing before this one (or at the end of the chain)
+    MissingRecords,
+}
+
+impl Display for ChainBreakKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            ChainBreakKind::HashMismatch => write!(f, "{}", fl!("chain-break-hash-mismatch")),
+            ChainBreakKind::PreviousHashMismatch => {
+                write!(f, "{}", fl!("chain-break-previous-hash-mismatch"))
+            }
+            ChainBreakKind::MissingRecords => write!(f, "{}", fl!("chain-break-missing-records")),
+        }
+    }
+}
+
+impl InvoiceRecord {
+    /// Returns the record as it's hashed
+    pub fn canonical(&self) -> String {
+        format!(
+            "IDEmisorFactura={}&NumSerieFactura={}&FechaExpedicionFactura={}&TipoFactura={}&CuotaTotal={}&ImporteTotal={}&Huella={}&FechaHoraHusoGenRegistro={}",
+            self.issuer_nif,
+            self.invoice_number,
+            expedition_date(&self.generated_at),
+            self.invoice_type,
+            self.tax_total,
+            self.total,
+            self.previous_hash.as_deref().unwrap_or_default(),
+            self.generated_at
+        )
+    }
+
+    /// Returns the SHA-256 (uppercase hex) of the canonical record
+    pub fn compute_hash(&self) -> String {
+        format!("{:X}", Sha256::digest(self.canonical().as_bytes()))
+    }
+
+    /// Generates the record of a simple invoice that has just been numbered, chaining it to the last record.
+    /// The chain stays locked until the transaction ends, so two records can't be chained to the same one
+    pub async fn register(
+        transaction: &mut Transaction<'_, Postgres>,
+        simple_invoice_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT invoice_number, series_kind, recipient_name IS NOT NULL as has_recipient,
+                replaced_invoice_id IS NOT NULL as replacement
+            FROM simple_invoices WHERE id = $1",
+        )
+        .bind(simple_invoice_id)
+        .fetch_one(&mut **transaction)
+        .await?;
+
+        let invoice_number: Option<String> = row.try_get("invoice_number")?;
+        let series_kind: Option<InvoiceSeriesKind> = row.try_get("series_kind")?;
+        let (Some(invoice_number), Some(series_kind)) = (invoice_number, series_kind) else {
+            return Err(sqlx::Error::Protocol(
+                "Only numbered invoices can be recorded".into(),
+            ));
+        };
+
+        let (sequence, previous_hash): (i32, Option<String>) = sqlx::query_as(
+            "UPDATE invoice_record_chain SET last_sequence = last_sequence + 1 WHERE id = 1
+            RETURNING last_sequence, last_hash",
+        )
+        .fetch_one(&mut **transaction)
+        .await?;
+
+        let (tax_total, total) = Self::amounts(&mut **transaction, &[simple_invoice_id])
+            .await?
+            .remove(&simple_invoice_id)
+            .unwrap_or_default();
+
+        let mut record = InvoiceRecord {
+            simple_invoice_id,
+            sequence,
+            issuer_nif: Issuer::load().nif,
+            invoice_number,
+            invoice_type: invoice_type(
+                series_kind,
+                row.try_get("has_recipient")?,
+                row.try_get("replacement")?,
+            ),
+            tax_total,
+            total,
+            previous_hash,
+            generated_at: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+
+        sqlx::query(
+            "UPDATE simple_invoices
+            SET record_sequence = $1, record_hash = $2, previous_hash = $3, record_issuer_nif = $4, record_generated_at = $5
+            WHERE id = $6",
+        )
+        .bind(record.sequence)
+        .bind(&record.hash)
+        .bind(&record.previous_hash)
+        .bind(&record.issuer_nif)
+        .bind(&record.generated_at)
+        .bind(simple_invoice_id)
+        .execute(&mut **transaction)
+        .await?;
+
+        sqlx::query("UPDATE invoice_record_chain SET last_hash = $1 WHERE id = 1")
+            .bind(&record.hash)
+            .execute(&mut **transaction)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Walks the whole chain recomputing every hash, returns the points where it's broken (empty if it's intact)
+    pub async fn verify_chain(pool: Arc<PgPool>) -> Result<Vec<ChainBreak>, sqlx::Error> {
+        let mut connection = pool.acquire().await?;
+        let records = Self::get_chain(&mut connection, false).await?;
+
+        let last_hash: Option<String> =
+            sqlx::query_scalar("SELECT last_hash FROM invoice_record_chain WHERE id = 1")
+                .fetch_one(&mut *connection)
+                .await?;
+
+        Ok(chain_breaks(&records, last_hash.as_deref()))
+    }
+
+    /// Sends the records that haven't been sent yet (oldest first), returns how many have been sent.
+    /// The endpoint is a local stand-in for testing: each record is written as a file on the VERIFACTU_ENDPOINT_DIR folder
+    pub async fn submit_pending(pool: Arc<PgPool>) -> Result<usize, sqlx::Error> {
+        let mut connection = pool.acquire().await?;
+        let records = Self::get_chain(&mut connection, true).await?;
+        if records.is_empty() {
+            return Ok(0);
+        }
+
+        dotenvy::dotenv().ok();
+        let endpoint_dir = PathBuf::from(
+            env::var("VERIFACTU_ENDPOINT_DIR").unwrap_or(String::from(DEFAULT_ENDPOINT_DIR)),
+        );
+
+        let files: Vec<(PathBuf, String)> = records
+            .iter()
+            .map(|record| {
+                (
+                    endpoint_dir.join(format!("{:08}.txt", record.sequence)),
+                    format!("{}\nHuellaRegistro={}\n", record.canonical(), record.hash),
+                )
+            })
+            .collect();
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&endpoint_dir)?;
+            files
+                .into_iter()
+                .try_for_each(|(path, contents)| std::fs::write(path, contents))
+        })
+        .await
+        .map_err(|_| sqlx::Error::Protocol("Failed to spawn a blocking task".into()))??;
+
+        let simple_invoice_ids: Vec<i32> = records
+            .iter()
+            .map(|record| record.simple_invoice_id)
+            .collect();
+        sqlx::query(
+            "UPDATE simple_invoices SET record_submitted_at = CURRENT_TIMESTAMP WHERE id = ANY($1)",
+        )
+        .bind(&simple_invoice_ids)
+        .execute(&mut *connection)
+        .await?;
+
+        Ok(records.len())
+    }
+
+    /// Returns the records of the chain in order (only the ones that haven't been sent yet if pending_only)
+    async fn get_chain(
+        connection: &mut PgConnection,
+        pending_only: bool,
+    ) -> Result<Vec<InvoiceRecord>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, record_sequence, record_hash, previous_hash, record_issuer_nif, record_generated_at,
+                invoice_number, series_kind, recipient_name IS NOT NULL as has_recipient,
+                replaced_invoice_id IS NOT NULL as replacement
+            FROM simple_invoices
+            WHERE record_sequence IS NOT NULL AND ($1 = FALSE OR record_submitted_at IS NULL)
+            ORDER BY record_sequence ASC",
+        )
+        .bind(pending_only)
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let ids = rows
+            .iter()
+            .map(|row| row.try_get("id"))
+            .collect::<Result<Vec<i32>, _>>()?;
+        let mut amounts = Self::amounts(connection, &ids).await?;
+
+        let mut result = Vec::<InvoiceRecord>::new();
+        for row in rows {
+            let simple_invoice_id: i32 = row.try_get("id")?;
+            let (tax_total, total) = amounts.remove(&simple_invoice_id).unwrap_or_default();
+            result.push(Self::from_row(&row, tax_total, total)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the (tax, total) of each of the given simple invoices, the tax is calculated per tax percentage
+    /// (the one each line was sold with) the same way it's printed on the invoices
+    async fn amounts(
+        connection: &mut PgConnection,
+        simple_invoice_ids: &[i32],
+    ) -> Result<HashMap<i32, (Money, Money)>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT sp.simple_invoice_id, sp.tax_percentage,
+                COALESCE(SUM(sp.price * sp.quantity), 0) as total
+            FROM sold_products sp
+            WHERE sp.simple_invoice_id = ANY($1)
+            GROUP BY sp.simple_invoice_id, sp.tax_percentage",
+        )
+        .bind(simple_invoice_ids)
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let mut result = HashMap::<i32, (Money, Money)>::new();
+        for row in rows {
+            let tax_percentage: Decimal = row.try_get("tax_percentage")?;
+            let total = Money::from(row.try_get::<Decimal, _>("total")?);
+            let (_, tax) = split_tax(total, tax_percentage);
+
+            let entry = result.entry(row.try_get("simple_invoice_id")?).or_default();
+            entry.0 += tax;
+            entry.1 += total;
+        }
+
+        Ok(result)
+    }
+
+    fn from_row(row: &PgRow, tax_total: Money, total: Money) -> Result<InvoiceRecord, sqlx::Error> {
+        let series_kind: Option<InvoiceSeriesKind> = row.try_get("series_kind")?;
+        let issuer_nif: Option<String> = row.try_get("record_issuer_nif")?;
+        let invoice_number: Option<String> = row.try_get("invoice_number")?;
+        let hash: Option<String> = row.try_get("record_hash")?;
+        let generated_at: Option<String> = row.try_get("record_generated_at")?;
+
+        Ok(InvoiceRecord {
+            simple_invoice_id: row.try_get("id")?,
+            sequence: row.try_get("record_sequence")?,
+            issuer_nif: issuer_nif.unwrap_or_default(),
+            invoice_number: invoice_number.unwrap_or_default(),
+            invoice_type: invoice_type(
+                series_kind.unwrap_or_default(),
+                row.try_get("has_recipient")?,
+                row.try_get("replacement")?,
+            ),
+            tax_total,
+            total,
+            previous_hash: row.try_get("previous_hash")?,
+            generated_at: generated_at.unwrap_or_default(),
+            hash: hash.unwrap_or_default(),
+        })
+    }
+}
+
+/// Returns the data of the QR code of a recorded invoice (the url where it can be checked), None if it hasn't been recorded
+pub fn qr_data(invoice: &SimpleInvoice, issuer_nif: &str) -> Option<String> {
+    let invoice_number = invoice.invoice_number.as_deref()?;
+    let generated_at = invoice.record_generated_at.as_deref()?;
+
+    dotenvy::dotenv().ok();
+    let url = env::var("VERIFACTU_QR_URL").unwrap_or(String::from(DEFAULT_QR_URL));
+
+    Some(format!(
+        "{url}?nif={}&numserie={}&fecha={}&importe={}",
+        encode_query_value(issuer_nif),
+        encode_query_value(invoice_number),
+        expedition_date(generated_at),
+        invoice.total_price()
+    ))
+}
+
+/// Returns the points where the given records (in order) don't make a chain ending on the given last chained hash
+/// (empty if it's intact)
+fn chain_breaks(records: &[InvoiceRecord], last_hash: Option<&str>) -> Vec<ChainBreak> {
+    let mut breaks = Vec::<ChainBreak>::new();
+    let mut expected_sequence = 1;
+    let mut expected_previous_hash: Option<&str> = None;
+    for record in records {
+        let mut push_break = |kind: ChainBreakKind| {
+            breaks.push(ChainBreak {
+                sequence: record.sequence,
+                invoice_number: record.invoice_number.clone(),
+                kind,
+            })
+        };
+
+        if record.sequence != expected_sequence {
+            push_break(ChainBreakKind::MissingRecords);
+        }
+        if record.previous_hash.as_deref() != expected_previous_hash {
+            push_break(ChainBreakKind::PreviousHashMismatch);
+        }
+        if record.compute_hash() != record.hash {
+            push_break(ChainBreakKind::HashMismatch);
+        }
+
+        expected_sequence = record.sequence + 1;
+        expected_previous_hash = Some(record.hash.as_str());
+    }
+
+    // the last chained record must still be there
+    if last_hash != expected_previous_hash {
+        breaks.push(ChainBreak {
+            sequence: expected_sequence,
+            invoice_number: String::new(),
+            kind: ChainBreakKind::MissingRecords,
+        });
+    }
+
+    breaks
+}
+
+/// Returns the type of the invoice as it's recorded
+fn invoice_type(
+    series_kind: InvoiceSeriesKind,
+    has_recipient: bool,
+    replacement: bool,
+) -> &'static str {
+    match series_kind {
+        InvoiceSeriesKind::Simplified => "F2",
+        InvoiceSeriesKind::Full if replacement => "F3",
+        InvoiceSeriesKind::Full => "F1",
+        InvoiceSeriesKind::Rectifying if has_recipient => "R1",
+        InvoiceSeriesKind::Rectifying => "R5",
+    }
+}
+
+/// Returns the expedition date (dd-mm-yyyy) of a record given its generation timestamp
+fn expedition_date(generated_at: &str) -> String {
+    DateTime::parse_from_rfc3339(generated_at)
+        .map(|date| date.format("%d-%m-%Y").to_string())
+        .unwrap_or_default()
+}
+
+/// Percent-encodes everything but the unreserved characters of an url query value
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alegria::{
+        core::models::{product::Product, sold_product::SoldProduct},
+        utils::entities::payment_method::PaymentMethod,
+    };
+
+    fn record(sequence: i32, previous_hash: Option<String>) -> InvoiceRecord {
+        let mut record = InvoiceRecord {
+            simple_invoice_id: sequence,
+            sequence,
+            issuer_nif: String::from("B00000000"),
+            invoice_number: format!("FS2026-{sequence:06}"),
+            invoice_type: "F2",
+            tax_total: "2.10".parse().unwrap(),
+            total: "12.10".parse().unwrap(),
+            previous_hash,
+            generated_at: String::from("2026-10-16T12:30:00+02:00"),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        record
+    }
+
+    /// Returns an intact chain of the given number of records
+    fn chain(len: i32) -> Vec<InvoiceRecord> {
+        let mut records = Vec::<InvoiceRecord>::new();
+        for sequence in 1..=len {
+            let previous_hash = records.last().map(|record| record.hash.clone());
+            records.push(record(sequence, previous_hash));
+        }
+        records
+    }
+
+    /// Returns the (sequence, kind) of the breaks of the given records
+    fn breaks(records: &[InvoiceRecord], last_hash: &str) -> Vec<(i32, ChainBreakKind)> {
+        chain_breaks(records, Some(last_hash))
+            .into_iter()
+            .map(|chain_break| (chain_break.sequence, chain_break.kind))
+            .collect()
+    }
+
+    #[test]
+    fn records_are_hashed_from_their_canonical_form() {
+        let record = record(1, None);
+        assert_eq!(
+            record.canonical(),
+            "IDEmisorFactura=B00000000&NumSerieFactura=FS2026-000001&FechaExpedicionFactura=16-10-2026&TipoFactura=F2&CuotaTotal=2.10&ImporteTotal=12.10&Huella=&FechaHoraHusoGenRegistro=2026-10-16T12:30:00+02:00"
+        );
+        assert_eq!(
+            record.compute_hash(),
+            "814B801EDEDD0823FDA780BD41812AEF3AC8BF5AA55E6AADB604AD10DBC80C61"
+        );
+    }
+
+    #[test]
+    fn an_intact_chain_has_no_breaks() {
+        let records = chain(3);
+        assert!(breaks(&records, &records[2].hash).is_empty());
+        assert!(chain_breaks(&[], None).is_empty());
+    }
+
+    #[test]
+    fn an_edited_amount_breaks_the_hash_of_its_record() {
+        let mut records = chain(3);
+        let last_hash = records[2].hash.clone();
+        records[1].total = "1.00".parse().unwrap();
+
+        assert_eq!(
+            breaks(&records, &last_hash),
+            vec![(2, ChainBreakKind::HashMismatch)]
+        );
+    }
+
+    #[test]
+    fn a_record_chained_to_another_hash_breaks_the_chain() {
+        let mut records = chain(3);
+        // even if the record and the ones after it are hashed again
+        records[1].previous_hash = Some(records[1].hash.clone());
+        records[1].hash = records[1].compute_hash();
+        records[2].previous_hash = Some(records[1].hash.clone());
+        records[2].hash = records[2].compute_hash();
+
+        assert_eq!(
+            breaks(&records, &records[2].hash),
+            vec![(2, ChainBreakKind::PreviousHashMismatch)]
+        );
+    }
+
+    #[test]
+    fn missing_records_break_the_chain() {
+        let mut records = chain(3);
+        let last_hash = records[2].hash.clone();
+
+        let removed = records.remove(1);
+        assert_eq!(
+            breaks(&records, &last_hash),
+            vec![
+                (3, ChainBreakKind::MissingRecords),
+                (3, ChainBreakKind::PreviousHashMismatch),
+            ]
+        );
+
+        // the last record can only be missed by the hash of the last chained record
+        records.insert(1, removed);
+        records.pop();
+        assert_eq!(
+            breaks(&records, &last_hash),
+            vec![(3, ChainBreakKind::MissingRecords)]
+        );
+        assert_eq!(
+            breaks(&[], &last_hash),
+            vec![(1, ChainBreakKind::MissingRecords)]
+        );
+    }
+
+    #[test]
+    fn the_qr_code_links_to_the_check_of_the_invoice() {
+        let invoice = SimpleInvoice {
+            id: Some(1),
+            payment_method: PaymentMethod::Efectivo,
+            products: vec![SoldProduct {
+                id: None,
+                simple_invoice_id: 1,
+                original_product_id: 1,
+                quantity: 2,
+                price: Some("6.05".parse().unwrap()),
+                original_price: None,
+                discount_reason: None,
+                modifiers: String::new(),
+                tax_percentage: Decimal::from(21),
+                original_product: Product::default(),
+            }],
+            paid: true,
+            recipient: None,
+            rectified_invoice_id: None,
+            rectification_reason: None,
+            replaced_invoice_id: None,
+            invoice_number: Some(String::from("FS2026-000001")),
+            record_generated_at: Some(String::from("2026-10-16T12:30:00+02:00")),
+            ticket_discount_reason: None,
+            is_deleted: false,
+            created_at: None,
+            updated_at: None,
+            rectifying_invoice_id: None,
+            rectified_invoice_number: None,
+            replacing_invoice_id: None,
+            replaced_invoice_number: None,
+        };
+
+        assert_eq!(
+            qr_data(&invoice, "B00000000").as_deref(),
+            Some(
+                "https://prewww2.aeat.es/wlpl/TIKE-CONT/ValidarQR?nif=B00000000&numserie=FS2026-000001&fecha=16-10-2026&importe=12.10"
+            )
+        );
+        assert_eq!(
+            qr_data(
+                &SimpleInvoice {
+                    record_generated_at: None,
+                    ..invoice
+                },
+                "B00000000"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::sync::Arc;
+
+use crate::alegria::utils::entities::invoice_series_kind::InvoiceSeriesKind;
+
+/// Numbering series of the invoices, the numbers restart every year
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InvoiceSeries {
+    pub kind: InvoiceSeriesKind,
+    pub prefix: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub last_number: i32, // Helps us know the last number given on the current year
+}
+
+impl InvoiceSeries {
+    /// Returns true if the entity is valid (ready for submission to the db).
+    /// The prefix can only have capital letters, so it can't run into the year nor break the name of an exported file
+    pub fn is_valid(&self) -> bool {
+        let prefix = self.prefix.trim();
+        !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_uppercase())
+    }
+
+    /// Returns all the series with the last number given on each of them this year
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<InvoiceSeries>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT s.kind, s.prefix, s.created_at, s.updated_at, COALESCE(c.last_number, 0) as last_number
+            FROM invoice_series s
+            LEFT JOIN invoice_series_counters c
+                ON c.kind = s.kind AND c.year = EXTRACT(YEAR FROM CURRENT_DATE)::INTEGER
+            ORDER BY s.kind ASC",
+        )
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<InvoiceSeries>::new();
+        for row in rows {
+            result.push(InvoiceSeries {
+                kind: row.try_get("kind")?,
+                prefix: row.try_get("prefix")?,
+                created_at: row.try_get("created_at")?,
+                updated_at: row.try_get("updated_at")?,
+                last_number: row.try_get("last_number")?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Updates the prefix of a series (the numbers already given keep the prefix they were given with),
+    /// every series needs its own prefix
+    pub async fn edit(pool: Arc<PgPool>, series: InvoiceSeries) -> Result<(), sqlx::Error> {
+        if !series.is_valid() {
+            return Err(sqlx::Error::Protocol(
+                "The prefix can only have capital letters (A-Z)".into(),
+            ));
+        }
+
+        sqlx::query("UPDATE invoice_series SET prefix = $1 WHERE kind = $2")
+            .bind(series.prefix.trim())
+            .bind(series.kind)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gives the next number of the current year to a simple invoice that's being paid. The series is chosen from the invoice:
+    /// rectifying if it rectifies another invoice, full if it has a recipient and simplified otherwise.
+    /// The counter stays locked until the transaction ends, so the numbers have no gaps nor duplicates
+    pub async fn assign_number(
+        transaction: &mut Transaction<'_, Postgres>,
+        simple_invoice_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        let kind: InvoiceSeriesKind = sqlx::query_scalar(
+            "SELECT CASE
+                WHEN rectified_invoice_id IS NOT NULL THEN 3
+                WHEN recipient_name IS NOT NULL THEN 2
+                ELSE 1
+            END
+            FROM simple_invoices WHERE id = $1",
+        )
+        .bind(simple_invoice_id)
+        .fetch_one(&mut **transaction)
+        .await?;
+
+        let prefix: String =
+            sqlx::query_scalar("SELECT prefix FROM invoice_series WHERE kind = $1")
+                .bind(kind)
+                .fetch_one(&mut **transaction)
+                .await?;
+
+        let (year, number): (i32, i32) = sqlx::query_as(
+            "INSERT INTO invoice_series_counters (kind, year, last_number)
+            VALUES ($1, EXTRACT(YEAR FROM CURRENT_DATE)::INTEGER, 1)
+            ON CONFLICT (kind, year) DO UPDATE SET last_number = invoice_series_counters.last_number + 1
+            RETURNING year, last_number",
+        )
+        .bind(kind)
+        .fetch_one(&mut **transaction)
+        .await?;
+
+        sqlx::query(
+            "UPDATE simple_invoices
+            SET series_kind = $1, fiscal_year = $2, number = $3, invoice_number = $4
+            WHERE id = $5",
+        )
+        .bind(kind)
+        .bind(year)
+        .bind(number)
+        .bind(format!("{prefix}{year}-{number:06}"))
+        .bind(simple_invoice_id)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+}
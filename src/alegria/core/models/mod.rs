@@ -1,13 +1,28 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+pub mod bar_table;
+pub mod cash_session;
 pub mod client;
+pub mod company;
+pub mod folio;
+pub mod ingredient;
+pub mod invoice_payment;
+pub mod invoice_record;
+pub mod invoice_series;
+pub mod modifier_group;
+pub mod preparation_station;
+pub mod pricing_rule;
 pub mod product;
 pub mod product_category;
+pub mod recipe;
 pub mod reservation;
 pub mod room;
 pub mod room_type;
 pub mod simple_invoice;
 pub mod sold_product;
 pub mod sold_room;
+pub mod stock_movement;
+pub mod table_location;
 pub mod temporal_product;
 pub mod temporal_ticket;
+pub mod voided_line;
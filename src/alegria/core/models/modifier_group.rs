@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use crate::alegria::utils::money::Money;
+
+/// A group of modifiers offered when a product (or any product of a category) is added to a ticket
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModifierGroup {
+    pub id: Option<i32>,
+    pub name: String,
+    /// The group applies to this product...
+    pub product_id: Option<i32>,
+    /// ...or to every product of this category
+    pub category_id: Option<i32>,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub modifiers: Vec<Modifier>, // Non deleted modifiers of the group
+    pub target_name: Box<str>, // Helps us JOIN and return the name of the product or category of the group
+}
+
+/// An option of a modifier group ("no onion", "extra cheese"...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Modifier {
+    pub id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub name: String,
+    /// Added to the unit price of the line (can be negative)
+    pub price_delta: Option<Money>,
+
+    // Not in the db
+    pub price_delta_input: String, // Helps us input on TextInputs
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ModifierGroup {
+    fn default() -> Self {
+        Self {
+            id: None,
+            name: String::new(),
+            product_id: None,
+            category_id: None,
+
+            is_deleted: false,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            modifiers: Vec::new(),
+            target_name: String::new().into_boxed_str(),
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Modifier {
+    fn default() -> Self {
+        Self {
+            id: None,
+            group_id: None,
+            name: String::new(),
+            price_delta: Some(Money::ZERO),
+            price_delta_input: String::from("0"),
+        }
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.price_delta {
+            Some(delta) if delta != Money::ZERO => write!(f, "{} ({delta}€)", self.name),
+            _ => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl ModifierGroup {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && (self.product_id.is_some() || self.category_id.is_some())
+            && !self.modifiers.is_empty()
+            && self
+                .modifiers
+                .iter()
+                .all(|m| !m.name.trim().is_empty() && m.price_delta.is_some())
+    }
+
+    /// Returns true if the group has to be offered when adding the given product of the given category
+    pub fn applies_to(&self, product_id: Option<i32>, category_id: Option<i32>) -> bool {
+        (self.product_id.is_some() && self.product_id == product_id)
+            || (self.category_id.is_some() && self.category_id == category_id)
+    }
+
+    /// Returns all the groups with their modifiers
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<ModifierGroup>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                g.id,
+                g.name,
+                g.product_id,
+                g.category_id,
+                g.is_deleted,
+                g.created_at,
+                g.updated_at,
+                COALESCE(p.name, c.name) as target_name
+            FROM modifier_groups g
+            LEFT JOIN products p ON g.product_id = p.id
+            LEFT JOIN product_categories c ON g.category_id = c.id
+            WHERE g.is_deleted = $1
+            ORDER BY g.id ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<ModifierGroup>::new();
+        for row in rows {
+            result.push(Self::from_row(&row)?);
+        }
+
+        let mut modifiers = Self::get_modifiers(pool, None).await?;
+        for group in &mut result {
+            group.modifiers = modifiers
+                .remove(&group.id.unwrap_or_default())
+                .unwrap_or_default();
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        modifier_group_id: i32,
+    ) -> Result<ModifierGroup, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT
+                g.id,
+                g.name,
+                g.product_id,
+                g.category_id,
+                g.is_deleted,
+                g.created_at,
+                g.updated_at,
+                COALESCE(p.name, c.name) as target_name
+            FROM modifier_groups g
+            LEFT JOIN products p ON g.product_id = p.id
+            LEFT JOIN product_categories c ON g.category_id = c.id
+            WHERE g.id = $1",
+        )
+        .bind(modifier_group_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        let mut group = Self::from_row(&row)?;
+        group.modifiers = Self::get_modifiers(pool, Some(modifier_group_id))
+            .await?
+            .remove(&modifier_group_id)
+            .unwrap_or_default();
+
+        Ok(group)
+    }
+
+    pub async fn add(pool: Arc<PgPool>, modifier_group: ModifierGroup) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        let group_id: i32 = sqlx::query_scalar(
+            "INSERT INTO modifier_groups (name, product_id, category_id, is_deleted) VALUES ($1, $2, $3, $4) RETURNING id",
+        )
+        .bind(modifier_group.name.trim())
+        .bind(modifier_group.product_id)
+        .bind(modifier_group.category_id)
+        .bind(false)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        for modifier in modifier_group.modifiers {
+            Self::insert_modifier(&mut transaction, group_id, modifier).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Edits the given group, the modifiers that are no longer on it are deleted
+    pub async fn edit(pool: Arc<PgPool>, modifier_group: ModifierGroup) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+        let group_id = modifier_group.id.unwrap_or_default();
+
+        sqlx::query(
+            "UPDATE modifier_groups SET name = $1, product_id = $2, category_id = $3 WHERE id = $4",
+        )
+        .bind(modifier_group.name.trim())
+        .bind(modifier_group.product_id)
+        .bind(modifier_group.category_id)
+        .bind(group_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        let kept_ids: Vec<i32> = modifier_group
+            .modifiers
+            .iter()
+            .filter_map(|m| m.id)
+            .collect();
+        sqlx::query(
+            "UPDATE modifiers SET is_deleted = $1 WHERE group_id = $2 AND NOT (id = ANY($3))",
+        )
+        .bind(true)
+        .bind(group_id)
+        .bind(&kept_ids)
+        .execute(&mut *transaction)
+        .await?;
+
+        for modifier in modifier_group.modifiers {
+            match modifier.id {
+                Some(modifier_id) => {
+                    sqlx::query("UPDATE modifiers SET name = $1, price_delta = $2 WHERE id = $3")
+                        .bind(modifier.name.trim())
+                        .bind(modifier.price_delta.unwrap_or_default())
+                        .bind(modifier_id)
+                        .execute(&mut *transaction)
+                        .await?;
+                }
+                None => Self::insert_modifier(&mut transaction, group_id, modifier).await?,
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: Arc<PgPool>, modifier_group_id: i32) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        sqlx::query("UPDATE modifiers SET is_deleted = $1 WHERE group_id = $2")
+            .bind(true)
+            .bind(modifier_group_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("UPDATE modifier_groups SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(modifier_group_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_modifier(
+        transaction: &mut Transaction<'_, Postgres>,
+        group_id: i32,
+        modifier: Modifier,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO modifiers (group_id, name, price_delta, is_deleted) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(group_id)
+        .bind(modifier.name.trim())
+        .bind(modifier.price_delta.unwrap_or_default())
+        .bind(false)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the non deleted modifiers (of the given group or of all of them) by group id
+    async fn get_modifiers(
+        pool: Arc<PgPool>,
+        modifier_group_id: Option<i32>,
+    ) -> Result<HashMap<i32, Vec<Modifier>>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, group_id, name, price_delta FROM modifiers
+             WHERE is_deleted = $1 AND ($2::INTEGER IS NULL OR group_id = $2)
+             ORDER BY id ASC",
+        )
+        .bind(false)
+        .bind(modifier_group_id)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result: HashMap<i32, Vec<Modifier>> = HashMap::new();
+        for row in rows {
+            let group_id: i32 = row.try_get("group_id")?;
+            let price_delta: Money = row.try_get("price_delta")?;
+
+            result.entry(group_id).or_default().push(Modifier {
+                id: row.try_get("id")?,
+                group_id: Some(group_id),
+                name: row.try_get("name")?,
+                price_delta: Some(price_delta),
+                price_delta_input: price_delta.to_string(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn from_row(row: &PgRow) -> Result<ModifierGroup, sqlx::Error> {
+        let target_name: Option<String> = row.try_get("target_name")?;
+
+        Ok(ModifierGroup {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            product_id: row.try_get("product_id")?,
+            category_id: row.try_get("category_id")?,
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            modifiers: Vec::new(),
+            target_name: target_name.unwrap_or_default().into_boxed_str(),
+        })
+    }
+}
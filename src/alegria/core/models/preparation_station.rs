@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::{fmt, sync::Arc};
+
+/// A place where the products of some categories are prepared (kitchen, bar, grill...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PreparationStation {
+    pub id: Option<i32>,
+    pub name: String,
+    /// Name of the system printer the orders of the station are printed on
+    pub printer_name: Option<String>,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for PreparationStation {
+    fn default() -> Self {
+        Self {
+            id: None,
+            name: String::new(),
+            printer_name: None,
+
+            is_deleted: false,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        }
+    }
+}
+
+impl fmt::Display for PreparationStation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// The lines of a temporal ticket that have to be sent to a preparation station
+#[derive(Debug, Clone)]
+pub struct KitchenOrder {
+    /// None for the lines whose category has no station (nothing to print, they are just marked as sent)
+    pub preparation_station_id: Option<i32>,
+    pub station_name: String,
+    pub printer_name: Option<String>,
+    pub location_name: String,
+    pub table_name: String,
+    pub lines: Vec<KitchenOrderLine>,
+}
+
+/// A line of a kitchen order
+#[derive(Debug, Clone)]
+pub struct KitchenOrderLine {
+    pub temporal_product_id: i32,
+    pub name: String,
+    pub modifiers: String,
+    /// Quantity that has to be prepared (what hasn't been sent yet)
+    pub quantity: i32,
+    /// Quantity of the temporal product, it's all sent once the order is printed
+    pub total_quantity: i32,
+}
+
+impl PreparationStation {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+    }
+
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<PreparationStation>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, printer_name, is_deleted, created_at, updated_at FROM preparation_stations WHERE is_deleted = $1 ORDER BY id ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<PreparationStation>::new();
+        for row in rows {
+            result.push(Self::from_row(&row)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        preparation_station_id: i32,
+    ) -> Result<PreparationStation, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, name, printer_name, is_deleted, created_at, updated_at FROM preparation_stations WHERE id = $1",
+        )
+        .bind(preparation_station_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        Self::from_row(&row)
+    }
+
+    pub async fn add(
+        pool: Arc<PgPool>,
+        preparation_station: PreparationStation,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO preparation_stations (name, printer_name, is_deleted) VALUES ($1, $2, $3)",
+        )
+        .bind(preparation_station.name.trim())
+        .bind(preparation_station.printer_name)
+        .bind(false)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn edit(
+        pool: Arc<PgPool>,
+        preparation_station: PreparationStation,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE preparation_stations SET name = $1, printer_name = $2 WHERE id = $3")
+            .bind(preparation_station.name.trim())
+            .bind(preparation_station.printer_name)
+            .bind(preparation_station.id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the given station, the categories prepared on it are left without station
+    pub async fn delete(pool: Arc<PgPool>, preparation_station_id: i32) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE product_categories SET preparation_station_id = NULL WHERE preparation_station_id = $1",
+        )
+        .bind(preparation_station_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query("UPDATE preparation_stations SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(preparation_station_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    fn from_row(row: &PgRow) -> Result<PreparationStation, sqlx::Error> {
+        Ok(PreparationStation {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            printer_name: row.try_get("printer_name")?,
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl KitchenOrder {
+    /// Returns the lines of the given temporal ticket that haven't been sent yet grouped by preparation station
+    pub async fn get_pending(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+    ) -> Result<Vec<KitchenOrder>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                tp.id as temporal_product_id,
+                tp.name,
+                tp.modifiers,
+                tp.quantity,
+                tp.sent_quantity,
+                ps.id as preparation_station_id,
+                ps.name as station_name,
+                ps.printer_name,
+                l.name as location_name,
+                bt.name as table_name
+             FROM temporal_products tp
+             JOIN temporal_tickets t ON t.id = tp.temporal_ticket_id
+             JOIN bar_tables bt ON bt.id = t.table_id
+             JOIN table_locations l ON l.id = t.ticket_location
+             LEFT JOIN products p ON p.id = tp.original_product_id
+             LEFT JOIN product_categories pc ON pc.id = p.category_id
+             LEFT JOIN preparation_stations ps ON ps.id = pc.preparation_station_id AND ps.is_deleted = FALSE
+             WHERE tp.temporal_ticket_id = $1 AND tp.quantity > tp.sent_quantity
+             ORDER BY ps.id NULLS LAST, tp.id",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut orders: Vec<KitchenOrder> = Vec::new();
+        for row in rows {
+            let preparation_station_id: Option<i32> = row.try_get("preparation_station_id")?;
+            let quantity: i32 = row.try_get("quantity")?;
+            let sent_quantity: i32 = row.try_get("sent_quantity")?;
+
+            let line = KitchenOrderLine {
+                temporal_product_id: row.try_get("temporal_product_id")?,
+                name: row.try_get("name")?,
+                modifiers: row.try_get("modifiers")?,
+                quantity: quantity - sent_quantity,
+                total_quantity: quantity,
+            };
+
+            // rows come ordered by station so the lines of the same station are together
+            match orders.last_mut() {
+                Some(order) if order.preparation_station_id == preparation_station_id => {
+                    order.lines.push(line)
+                }
+                _ => orders.push(KitchenOrder {
+                    preparation_station_id,
+                    station_name: row
+                        .try_get::<Option<String>, _>("station_name")?
+                        .unwrap_or_default(),
+                    printer_name: row.try_get("printer_name")?,
+                    location_name: row.try_get("location_name")?,
+                    table_name: row.try_get("table_name")?,
+                    lines: vec![line],
+                }),
+            }
+        }
+
+        Ok(orders)
+    }
+
+    /// Marks the lines of the order as sent to the preparation station
+    pub async fn mark_sent(pool: Arc<PgPool>, order: KitchenOrder) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        for line in order.lines {
+            sqlx::query(
+                "UPDATE temporal_products SET sent_quantity = GREATEST(sent_quantity, $1) WHERE id = $2",
+            )
+            .bind(line.total_quantity)
+            .bind(line.temporal_product_id)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,542 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::{cmp::Reverse, sync::Arc};
+
+use crate::alegria::utils::{entities::price_adjustment::PriceAdjustment, money::Money};
+
+use super::product::Product;
+
+/// Every day of the week on the weekdays bitmask of a rule
+pub const ALL_WEEKDAYS: i32 = 0b111_1111;
+
+/// A rule that changes the price of the products added to the bar tickets
+/// on some locations, days and hours (price lists, happy hour, terrace surcharge...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PricingRule {
+    pub id: Option<i32>,
+    pub name: String,
+    /// When more than one rule applies the one with the highest priority wins
+    pub priority: Option<i32>,
+    /// None means every location
+    pub location_id: Option<i32>,
+    /// The rule applies to this product...
+    pub product_id: Option<i32>,
+    /// ...or to every product of this category (or to every product if both are None)
+    pub category_id: Option<i32>,
+    /// Days the rule applies on (bitmask, 1 is Monday and 64 is Sunday)
+    pub weekdays: i32,
+    /// Time window of the rule (None means the whole day), it goes past midnight if it ends before it starts
+    /// (it can't end when it starts)
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub adjustment: PriceAdjustment,
+    /// Price or percentage, depending on the adjustment
+    pub adjustment_value: Option<Decimal>,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub location_name: Box<str>, // Helps us JOIN and return the name of the location of the rule
+    pub target_name: Box<str>, // Helps us JOIN and return the name of the product or category of the rule
+    pub priority_input: String, // Helps us input on TextInputs
+    pub start_time_input: String, // Helps us input on TextInputs
+    pub end_time_input: String, // Helps us input on TextInputs
+    pub adjustment_value_input: String, // Helps us input on TextInputs
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for PricingRule {
+    fn default() -> Self {
+        Self {
+            id: None,
+            name: String::new(),
+            priority: Some(0),
+            location_id: None,
+            product_id: None,
+            category_id: None,
+            weekdays: ALL_WEEKDAYS,
+            start_time: None,
+            end_time: None,
+            adjustment: PriceAdjustment::default(),
+            adjustment_value: None,
+
+            is_deleted: false,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            location_name: String::new().into_boxed_str(),
+            target_name: String::new().into_boxed_str(),
+            priority_input: String::from("0"),
+            start_time_input: String::new(),
+            end_time_input: String::new(),
+            adjustment_value_input: String::new(),
+        }
+    }
+}
+
+impl PricingRule {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        let valid_value = match self.adjustment {
+            PriceAdjustment::FixedPrice => {
+                self.adjustment_value.is_some_and(|v| v >= Decimal::ZERO)
+            }
+            PriceAdjustment::Percentage => self
+                .adjustment_value
+                .is_some_and(|v| v >= -Decimal::ONE_HUNDRED),
+        };
+
+        !self.name.trim().is_empty()
+            && self.priority.is_some()
+            && !(self.product_id.is_some() && self.category_id.is_some())
+            && self.weekdays & ALL_WEEKDAYS != 0
+            && self.start_time.is_some() == self.end_time.is_some()
+            && (self.start_time.is_none() || self.start_time != self.end_time)
+            && self.start_time_input.trim().is_empty() != self.start_time.is_some()
+            && self.end_time_input.trim().is_empty() != self.end_time.is_some()
+            && valid_value
+    }
+
+    /// Returns true if the rule applies on the given day of the week
+    pub fn applies_on(&self, weekday: Weekday) -> bool {
+        self.weekdays & (1 << weekday.num_days_from_monday()) != 0
+    }
+
+    /// Returns true if the rule applies at the given moment, the part of a window that goes past midnight
+    /// belongs to the day it started on (a friday 22:00-02:00 rule applies on saturday at 01:00)
+    pub fn applies_at(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) if start <= end => {
+                self.applies_on(at.weekday()) && start <= time && time < end
+            }
+            (Some(start), Some(end)) => {
+                (self.applies_on(at.weekday()) && time >= start)
+                    || (self.applies_on((at - Duration::days(1)).weekday()) && time < end)
+            }
+            _ => self.applies_on(at.weekday()),
+        }
+    }
+
+    /// Returns true if the rule applies to the given product (of the given category) on the given location at the given moment
+    pub fn applies_to(
+        &self,
+        location_id: i32,
+        product_id: Option<i32>,
+        category_id: Option<i32>,
+        at: NaiveDateTime,
+    ) -> bool {
+        let location_matches = self.location_id.is_none_or(|id| id == location_id);
+        let target_matches = match (self.product_id, self.category_id) {
+            (Some(id), _) => Some(id) == product_id,
+            (None, Some(id)) => Some(id) == category_id,
+            (None, None) => true,
+        };
+
+        location_matches && target_matches && self.applies_at(at)
+    }
+
+    /// Returns the price the rule gives to a product with the given base price
+    pub fn price(&self, base_price: Option<Money>) -> Option<Money> {
+        let value = self.adjustment_value.unwrap_or_default();
+        match self.adjustment {
+            PriceAdjustment::FixedPrice => Some(Money::from(value)),
+            PriceAdjustment::Percentage => base_price.map(|price| {
+                let price = Decimal::from(price);
+                Money::from(price + price * value / Decimal::ONE_HUNDRED)
+            }),
+        }
+    }
+
+    /// Returns the price of the given product on the given location at the given moment and the rule that gives it (if any).
+    /// The base price is the inside or outside price of the product (depending on the location), when more than one rule
+    /// applies the one with the highest priority wins and then the most specific one (product, category, location)
+    pub fn resolve<'a>(
+        rules: &'a [PricingRule],
+        product: &Product,
+        location_id: i32,
+        inside: bool,
+        at: NaiveDateTime,
+    ) -> (Option<Money>, Option<&'a PricingRule>) {
+        let base_price = if inside {
+            product.inside_price
+        } else {
+            product.outside_price
+        };
+
+        let rule = rules
+            .iter()
+            .filter(|r| r.applies_to(location_id, product.id, product.category_id, at))
+            .min_by_key(|r| {
+                let target = match (r.product_id, r.category_id) {
+                    (Some(_), _) => 2,
+                    (None, Some(_)) => 1,
+                    (None, None) => 0,
+                };
+                (
+                    Reverse(r.priority.unwrap_or_default()),
+                    Reverse(target),
+                    Reverse(r.location_id.is_some()),
+                    r.id,
+                )
+            });
+
+        match rule {
+            Some(rule) => (rule.price(base_price), Some(rule)),
+            None => (base_price, None),
+        }
+    }
+
+    /// Returns all the non deleted rules, by priority
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<PricingRule>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                r.id,
+                r.name,
+                r.priority,
+                r.location_id,
+                r.product_id,
+                r.category_id,
+                r.weekdays,
+                r.start_time,
+                r.end_time,
+                r.adjustment_kind,
+                r.adjustment_value,
+                r.is_deleted,
+                r.created_at,
+                r.updated_at,
+                l.name as location_name,
+                COALESCE(p.name, c.name) as target_name
+            FROM pricing_rules r
+            LEFT JOIN table_locations l ON r.location_id = l.id
+            LEFT JOIN products p ON r.product_id = p.id
+            LEFT JOIN product_categories c ON r.category_id = c.id
+            WHERE r.is_deleted = $1
+            ORDER BY r.priority DESC, r.id ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<PricingRule>::new();
+        for row in rows {
+            result.push(Self::from_row(&row)?);
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        pricing_rule_id: i32,
+    ) -> Result<PricingRule, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT
+                r.id,
+                r.name,
+                r.priority,
+                r.location_id,
+                r.product_id,
+                r.category_id,
+                r.weekdays,
+                r.start_time,
+                r.end_time,
+                r.adjustment_kind,
+                r.adjustment_value,
+                r.is_deleted,
+                r.created_at,
+                r.updated_at,
+                l.name as location_name,
+                COALESCE(p.name, c.name) as target_name
+            FROM pricing_rules r
+            LEFT JOIN table_locations l ON r.location_id = l.id
+            LEFT JOIN products p ON r.product_id = p.id
+            LEFT JOIN product_categories c ON r.category_id = c.id
+            WHERE r.id = $1",
+        )
+        .bind(pricing_rule_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        Self::from_row(&row)
+    }
+
+    pub async fn add(pool: Arc<PgPool>, pricing_rule: PricingRule) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO pricing_rules (name, priority, location_id, product_id, category_id, weekdays, start_time, end_time, adjustment_kind, adjustment_value, is_deleted)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(pricing_rule.name.trim())
+        .bind(pricing_rule.priority)
+        .bind(pricing_rule.location_id)
+        .bind(pricing_rule.product_id)
+        .bind(pricing_rule.category_id)
+        .bind(pricing_rule.weekdays)
+        .bind(pricing_rule.start_time)
+        .bind(pricing_rule.end_time)
+        .bind(pricing_rule.adjustment)
+        .bind(pricing_rule.adjustment_value)
+        .bind(false)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn edit(pool: Arc<PgPool>, pricing_rule: PricingRule) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE pricing_rules SET name = $1, priority = $2, location_id = $3, product_id = $4, category_id = $5, weekdays = $6,
+             start_time = $7, end_time = $8, adjustment_kind = $9, adjustment_value = $10 WHERE id = $11",
+        )
+        .bind(pricing_rule.name.trim())
+        .bind(pricing_rule.priority)
+        .bind(pricing_rule.location_id)
+        .bind(pricing_rule.product_id)
+        .bind(pricing_rule.category_id)
+        .bind(pricing_rule.weekdays)
+        .bind(pricing_rule.start_time)
+        .bind(pricing_rule.end_time)
+        .bind(pricing_rule.adjustment)
+        .bind(pricing_rule.adjustment_value)
+        .bind(pricing_rule.id)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(pool: Arc<PgPool>, pricing_rule_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE pricing_rules SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(pricing_rule_id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    fn from_row(row: &PgRow) -> Result<PricingRule, sqlx::Error> {
+        let priority: i32 = row.try_get("priority")?;
+        let start_time: Option<NaiveTime> = row.try_get("start_time")?;
+        let end_time: Option<NaiveTime> = row.try_get("end_time")?;
+        let adjustment_value: Decimal = row.try_get("adjustment_value")?;
+        let location_name: Option<String> = row.try_get("location_name")?;
+        let target_name: Option<String> = row.try_get("target_name")?;
+
+        Ok(PricingRule {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            priority: Some(priority),
+            location_id: row.try_get("location_id")?,
+            product_id: row.try_get("product_id")?,
+            category_id: row.try_get("category_id")?,
+            weekdays: row.try_get("weekdays")?,
+            start_time,
+            end_time,
+            adjustment: row.try_get("adjustment_kind")?,
+            adjustment_value: Some(adjustment_value),
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            location_name: location_name.unwrap_or_default().into_boxed_str(),
+            target_name: target_name.unwrap_or_default().into_boxed_str(),
+            priority_input: priority.to_string(),
+            start_time_input: start_time
+                .map(|t| t.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            end_time_input: end_time
+                .map(|t| t.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            adjustment_value_input: adjustment_value.normalize().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    /// Friday the 7th of March 2025 at the given time
+    fn friday(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 7)
+            .and_then(|d| d.and_hms_opt(hour, minute, 0))
+            .unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    fn rule(id: i32, priority: i32, adjustment: PriceAdjustment, value: &str) -> PricingRule {
+        PricingRule {
+            id: Some(id),
+            name: format!("Rule {id}"),
+            priority: Some(priority),
+            adjustment,
+            adjustment_value: Some(value.parse().unwrap()),
+            adjustment_value_input: String::from(value),
+            ..Default::default()
+        }
+    }
+
+    fn product() -> Product {
+        Product {
+            id: Some(1),
+            category_id: Some(2),
+            inside_price: Some("2.00".parse().unwrap()),
+            outside_price: Some("2.50".parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    fn price(value: &str) -> Option<Money> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn time_windows_cant_end_when_they_start() {
+        let mut rule = rule(1, 0, PriceAdjustment::Percentage, "-50");
+        assert!(rule.is_valid());
+
+        rule.start_time = time(18, 0);
+        rule.end_time = time(20, 0);
+        rule.start_time_input = String::from("18:00");
+        rule.end_time_input = String::from("20:00");
+        assert!(rule.is_valid());
+
+        rule.end_time = time(18, 0);
+        rule.end_time_input = String::from("18:00");
+        assert!(!rule.is_valid());
+    }
+
+    #[test]
+    fn windows_past_midnight_belong_to_the_day_they_start() {
+        let mut rule = rule(1, 0, PriceAdjustment::Percentage, "20");
+        rule.weekdays = 1 << Weekday::Fri.num_days_from_monday();
+        rule.start_time = time(22, 0);
+        rule.end_time = time(2, 0);
+        let saturday = |hour, minute| friday(hour, minute) + Duration::days(1);
+
+        assert!(!rule.applies_at(friday(21, 59)));
+        assert!(rule.applies_at(friday(22, 0)));
+        assert!(rule.applies_at(friday(23, 59)));
+        assert!(rule.applies_at(saturday(0, 0)));
+        assert!(rule.applies_at(saturday(1, 59)));
+        assert!(!rule.applies_at(saturday(2, 0)));
+        assert!(!rule.applies_at(saturday(22, 0)));
+        // the early hours of friday belong to thursday
+        assert!(!rule.applies_at(friday(1, 0)));
+    }
+
+    #[test]
+    fn windows_in_the_same_day_end_before_their_end_time() {
+        let mut rule = rule(1, 0, PriceAdjustment::Percentage, "-50");
+        rule.start_time = time(18, 0);
+        rule.end_time = time(20, 0);
+
+        assert!(!rule.applies_at(friday(17, 59)));
+        assert!(rule.applies_at(friday(18, 0)));
+        assert!(rule.applies_at(friday(19, 59)));
+        assert!(!rule.applies_at(friday(20, 0)));
+        assert!(!rule.applies_at(friday(1, 0)));
+    }
+
+    #[test]
+    fn most_specific_rule_wins_on_the_same_priority() {
+        let every_product = rule(1, 0, PriceAdjustment::Percentage, "10");
+        let category = PricingRule {
+            category_id: Some(2),
+            ..rule(2, 0, PriceAdjustment::FixedPrice, "1.80")
+        };
+        let product_rule = PricingRule {
+            product_id: Some(1),
+            ..rule(3, 0, PriceAdjustment::FixedPrice, "1.50")
+        };
+        let product_on_location = PricingRule {
+            product_id: Some(1),
+            location_id: Some(1),
+            ..rule(4, 0, PriceAdjustment::FixedPrice, "1.40")
+        };
+        let at = friday(12, 0);
+
+        let rules = vec![every_product.clone()];
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, true, at);
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("2.20"), Some(1))
+        );
+
+        let rules = vec![every_product.clone(), category.clone()];
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, true, at);
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("1.80"), Some(2))
+        );
+
+        let rules = vec![product_on_location, product_rule, category, every_product];
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, true, at);
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("1.40"), Some(4))
+        );
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 2, true, at);
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("1.50"), Some(3))
+        );
+    }
+
+    #[test]
+    fn highest_priority_wins_over_the_most_specific_rule() {
+        let product_rule = PricingRule {
+            product_id: Some(1),
+            location_id: Some(1),
+            ..rule(1, 0, PriceAdjustment::FixedPrice, "1.50")
+        };
+        let mut happy_hour = rule(2, 1, PriceAdjustment::Percentage, "-50");
+        happy_hour.start_time = time(18, 0);
+        happy_hour.end_time = time(20, 0);
+        let rules = vec![product_rule, happy_hour];
+
+        // percentages go over the inside or outside price
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, false, friday(19, 0));
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("1.25"), Some(2))
+        );
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, true, friday(21, 0));
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("1.50"), Some(1))
+        );
+    }
+
+    #[test]
+    fn ties_go_to_the_oldest_rule_and_no_rule_keeps_the_base_price() {
+        let rules = vec![
+            rule(7, 0, PriceAdjustment::FixedPrice, "1.70"),
+            rule(3, 0, PriceAdjustment::FixedPrice, "1.30"),
+        ];
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, true, friday(12, 0));
+        assert_eq!(
+            (resolved, winner.and_then(|r| r.id)),
+            (price("1.30"), Some(3))
+        );
+
+        let other_product = PricingRule {
+            product_id: Some(9),
+            ..rule(1, 5, PriceAdjustment::FixedPrice, "0.50")
+        };
+        let mut other_day = rule(2, 5, PriceAdjustment::FixedPrice, "0.50");
+        other_day.weekdays = 1 << Weekday::Mon.num_days_from_monday();
+        let rules = vec![other_product, other_day];
+        let (resolved, winner) = PricingRule::resolve(&rules, &product(), 1, false, friday(12, 0));
+        assert_eq!((resolved, winner), (price("2.50"), None));
+    }
+}
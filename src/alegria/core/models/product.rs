@@ -1,18 +1,25 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::{fmt, sync::Arc};
 
+use crate::alegria::utils::money::Money;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Product {
     pub id: Option<i32>,
     pub category_id: Option<i32>,
     pub name: String,
-    pub inside_price: Option<f32>,
-    pub outside_price: Option<f32>,
-    pub tax_percentage: Option<f32>,
+    pub inside_price: Option<Money>,
+    pub outside_price: Option<Money>,
+    pub tax_percentage: Option<Decimal>,
+    #[sqlx(default)]
+    pub stock_quantity: Option<i32>, // None means the stock of the product is not tracked
+    #[sqlx(default)]
+    pub low_stock_threshold: Option<i32>, // Warn when the stock gets to this quantity
     pub is_deleted: bool,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
@@ -26,6 +33,8 @@ pub struct Product {
     pub outside_price_input: String, // Helps us input on TextInputs
     #[sqlx(default)]
     pub tax_percentage_input: String, // Helps us input on TextInputs
+    #[sqlx(default)]
+    pub low_stock_threshold_input: String, // Helps us input on TextInputs
 }
 
 #[allow(clippy::derivable_impls)]
@@ -38,6 +47,8 @@ impl Default for Product {
             inside_price: None,
             outside_price: None,
             tax_percentage: None,
+            stock_quantity: None,
+            low_stock_threshold: None,
             is_deleted: false,
             created_at: Default::default(),
             updated_at: Default::default(),
@@ -45,6 +56,7 @@ impl Default for Product {
             inside_price_input: String::new(),
             outside_price_input: String::new(),
             tax_percentage_input: String::new(),
+            low_stock_threshold_input: String::new(),
         }
     }
 }
@@ -70,6 +82,13 @@ impl Product {
         true
     }
 
+    /// Returns true if the stock of the product is tracked and it's at (or below) it's low stock threshold
+    pub fn is_low_on_stock(&self) -> bool {
+        self.stock_quantity
+            .zip(self.low_stock_threshold)
+            .is_some_and(|(stock, threshold)| stock <= threshold)
+    }
+
     pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<Product>, sqlx::Error> {
         let rows = sqlx::query(
             "SELECT 
@@ -79,6 +98,8 @@ impl Product {
                 products.inside_price, 
                 products.outside_price, 
                 products.tax_percentage, 
+                products.stock_quantity, 
+                products.low_stock_threshold, 
                 products.is_deleted, 
                 products.created_at, 
                 products.updated_at,
@@ -97,9 +118,11 @@ impl Product {
             let id: Option<i32> = row.try_get("id")?;
             let category_id: Option<i32> = row.try_get("category_id")?;
             let name: String = row.try_get("name")?;
-            let inside_price: Option<f32> = row.try_get("inside_price")?;
-            let outside_price: Option<f32> = row.try_get("outside_price")?;
-            let tax_percentage: Option<f32> = row.try_get("tax_percentage")?;
+            let inside_price: Option<Money> = row.try_get("inside_price")?;
+            let outside_price: Option<Money> = row.try_get("outside_price")?;
+            let tax_percentage: Option<Decimal> = row.try_get("tax_percentage")?;
+            let stock_quantity: Option<i32> = row.try_get("stock_quantity")?;
+            let low_stock_threshold: Option<i32> = row.try_get("low_stock_threshold")?;
             let is_deleted: bool = row.try_get("is_deleted")?;
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -113,6 +136,8 @@ impl Product {
                 inside_price,
                 outside_price,
                 tax_percentage,
+                stock_quantity,
+                low_stock_threshold,
                 is_deleted,
                 created_at,
                 updated_at,
@@ -131,7 +156,7 @@ impl Product {
         category_id: i32,
     ) -> Result<Vec<Product>, sqlx::Error> {
         let rows = sqlx::query(
-            "SELECT id, category_id, name, inside_price, outside_price, tax_percentage, is_deleted, created_at, updated_at FROM products WHERE category_id = $1 AND is_deleted = $2 ORDER BY id ASC",
+            "SELECT id, category_id, name, inside_price, outside_price, tax_percentage, stock_quantity, low_stock_threshold, is_deleted, created_at, updated_at FROM products WHERE category_id = $1 AND is_deleted = $2 ORDER BY id ASC",
         )
         .bind(category_id)
         .bind(false)
@@ -143,9 +168,11 @@ impl Product {
             let id: Option<i32> = row.try_get("id")?;
             let category_id: Option<i32> = row.try_get("category_id")?;
             let name: String = row.try_get("name")?;
-            let inside_price: Option<f32> = row.try_get("inside_price")?;
-            let outside_price: Option<f32> = row.try_get("outside_price")?;
-            let tax_percentage: Option<f32> = row.try_get("tax_percentage")?;
+            let inside_price: Option<Money> = row.try_get("inside_price")?;
+            let outside_price: Option<Money> = row.try_get("outside_price")?;
+            let tax_percentage: Option<Decimal> = row.try_get("tax_percentage")?;
+            let stock_quantity: Option<i32> = row.try_get("stock_quantity")?;
+            let low_stock_threshold: Option<i32> = row.try_get("low_stock_threshold")?;
             let is_deleted: bool = row.try_get("is_deleted")?;
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -157,6 +184,8 @@ impl Product {
                 inside_price,
                 outside_price,
                 tax_percentage,
+                stock_quantity,
+                low_stock_threshold,
                 is_deleted,
                 created_at,
                 updated_at,
@@ -178,6 +207,8 @@ impl Product {
                 inside_price, 
                 outside_price, 
                 tax_percentage, 
+                stock_quantity, 
+                low_stock_threshold, 
                 is_deleted, 
                 created_at, 
                 updated_at
@@ -191,9 +222,11 @@ impl Product {
         let id: Option<i32> = row.try_get("id")?;
         let category_id: Option<i32> = row.try_get("category_id")?;
         let name: String = row.try_get("name")?;
-        let inside_price: Option<f32> = row.try_get("inside_price")?;
-        let outside_price: Option<f32> = row.try_get("outside_price")?;
-        let tax_percentage: Option<f32> = row.try_get("tax_percentage")?;
+        let inside_price: Option<Money> = row.try_get("inside_price")?;
+        let outside_price: Option<Money> = row.try_get("outside_price")?;
+        let tax_percentage: Option<Decimal> = row.try_get("tax_percentage")?;
+        let stock_quantity: Option<i32> = row.try_get("stock_quantity")?;
+        let low_stock_threshold: Option<i32> = row.try_get("low_stock_threshold")?;
         let is_deleted: bool = row.try_get("is_deleted")?;
         let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
         let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -205,6 +238,8 @@ impl Product {
             inside_price,
             outside_price,
             tax_percentage,
+            stock_quantity,
+            low_stock_threshold,
             is_deleted,
             created_at,
             updated_at,
@@ -212,20 +247,52 @@ impl Product {
             inside_price_input: inside_price.map_or(String::new(), |p| format!("{p:.2}")),
             outside_price_input: outside_price.map_or(String::new(), |p| format!("{p:.2}")),
             tax_percentage_input: tax_percentage.map_or(String::new(), |p| format!("{p:.2}")),
+            low_stock_threshold_input: low_stock_threshold.map_or(String::new(), |t| t.to_string()),
         };
 
         Ok(product)
     }
 
+    /// Returns the products (of the given ones) whose stock is at or below their low stock threshold
+    pub async fn get_low_stock(
+        pool: Arc<PgPool>,
+        product_ids: Vec<i32>,
+    ) -> Result<Vec<Product>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, stock_quantity, low_stock_threshold FROM products
+            WHERE id = ANY($1) AND stock_quantity <= low_stock_threshold AND is_deleted = $2
+            ORDER BY name ASC",
+        )
+        .bind(product_ids)
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<Product>::new();
+
+        for row in rows {
+            result.push(Product {
+                id: row.try_get("id")?,
+                name: row.try_get("name")?,
+                stock_quantity: row.try_get("stock_quantity")?,
+                low_stock_threshold: row.try_get("low_stock_threshold")?,
+                ..Default::default()
+            });
+        }
+
+        Ok(result)
+    }
+
     pub async fn add(pool: Arc<PgPool>, product: Product) -> Result<(), sqlx::Error> {
         sqlx::query(
-            "INSERT INTO products (category_id, name, inside_price, outside_price, tax_percentage, is_deleted) VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO products (category_id, name, inside_price, outside_price, tax_percentage, low_stock_threshold, is_deleted) VALUES ($1, $2, $3, $4, $5, $6, $7)",
         )
         .bind(product.category_id)
         .bind(product.name)
         .bind(product.inside_price)
         .bind(product.outside_price)
         .bind(product.tax_percentage)
+        .bind(product.low_stock_threshold)
         .bind(false)
         .execute(pool.as_ref())
         .await?;
@@ -234,12 +301,14 @@ impl Product {
     }
 
     pub async fn edit(pool: Arc<PgPool>, product: Product) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE products SET category_id = $1, name = $2, inside_price = $3, outside_price = $4, tax_percentage = $5 WHERE id = $6")
+        // the stock is not edited here, it only changes with sales and stock movements
+        sqlx::query("UPDATE products SET category_id = $1, name = $2, inside_price = $3, outside_price = $4, tax_percentage = $5, low_stock_threshold = $6 WHERE id = $7")
             .bind(product.category_id)
             .bind(product.name)
             .bind(product.inside_price)
             .bind(product.outside_price)
             .bind(product.tax_percentage)
+            .bind(product.low_stock_threshold)
             .bind(product.id)
             .execute(pool.as_ref())
             .await?;
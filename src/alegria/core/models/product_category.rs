@@ -9,6 +9,8 @@ use std::{fmt, sync::Arc};
 pub struct ProductCategory {
     pub id: Option<i32>,
     pub name: String,
+    /// Station the products of this category are prepared on (if they need any preparation)
+    pub preparation_station_id: Option<i32>,
     pub is_deleted: bool,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
@@ -20,6 +22,7 @@ impl Default for ProductCategory {
         Self {
             id: None,
             name: String::new(),
+            preparation_station_id: None,
 
             is_deleted: false,
             created_at: Default::default(),
@@ -46,7 +49,7 @@ impl ProductCategory {
 
     pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<ProductCategory>, sqlx::Error> {
         let rows = sqlx::query(
-            "SELECT id, name, is_deleted, created_at, updated_at FROM product_categories WHERE is_deleted = $1 ORDER BY id ASC",
+            "SELECT id, name, preparation_station_id, is_deleted, created_at, updated_at FROM product_categories WHERE is_deleted = $1 ORDER BY id ASC",
         )
         .bind(false)
         .fetch_all(pool.as_ref()).await?;
@@ -56,6 +59,7 @@ impl ProductCategory {
         for row in rows {
             let id: Option<i32> = row.try_get("id")?;
             let name: String = row.try_get("name")?;
+            let preparation_station_id: Option<i32> = row.try_get("preparation_station_id")?;
             let is_deleted: bool = row.try_get("is_deleted")?;
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -63,6 +67,7 @@ impl ProductCategory {
             let product_category = ProductCategory {
                 id,
                 name,
+                preparation_station_id,
                 is_deleted,
                 created_at,
                 updated_at,
@@ -82,6 +87,7 @@ impl ProductCategory {
             "SELECT 
                 product_categories.id, 
                 product_categories.name, 
+                product_categories.preparation_station_id, 
                 product_categories.is_deleted, 
                 product_categories.created_at, 
                 product_categories.updated_at
@@ -94,6 +100,7 @@ impl ProductCategory {
 
         let id: Option<i32> = row.try_get("id")?;
         let name: String = row.try_get("name")?;
+        let preparation_station_id: Option<i32> = row.try_get("preparation_station_id")?;
         let is_deleted: bool = row.try_get("is_deleted")?;
         let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
         let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -101,6 +108,7 @@ impl ProductCategory {
         let product_category = ProductCategory {
             id,
             name,
+            preparation_station_id,
             is_deleted,
             created_at,
             updated_at,
@@ -113,8 +121,11 @@ impl ProductCategory {
         pool: Arc<PgPool>,
         product_category: ProductCategory,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query("INSERT INTO product_categories (name, is_deleted) VALUES ($1, $2)")
+        sqlx::query(
+            "INSERT INTO product_categories (name, preparation_station_id, is_deleted) VALUES ($1, $2, $3)",
+        )
             .bind(product_category.name)
+            .bind(product_category.preparation_station_id)
             .bind(false)
             .execute(pool.as_ref())
             .await?;
@@ -126,11 +137,14 @@ impl ProductCategory {
         pool: Arc<PgPool>,
         product_category: ProductCategory,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE product_categories SET name = $1 WHERE id = $2")
-            .bind(product_category.name)
-            .bind(product_category.id)
-            .execute(pool.as_ref())
-            .await?;
+        sqlx::query(
+            "UPDATE product_categories SET name = $1, preparation_station_id = $2 WHERE id = $3",
+        )
+        .bind(product_category.name)
+        .bind(product_category.preparation_station_id)
+        .bind(product_category.id)
+        .execute(pool.as_ref())
+        .await?;
 
         Ok(())
     }
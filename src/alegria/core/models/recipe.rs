@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Ingredients consumed by every unit sold of a product
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Recipe {
+    pub product_id: Option<i32>,
+    pub ingredients: Vec<RecipeIngredient>,
+
+    // Not in the db
+    pub product_name: Box<str>, // Helps us JOIN and return the name of the product of the recipe
+}
+
+/// Quantity of an ingredient on a recipe
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecipeIngredient {
+    pub ingredient_id: Option<i32>,
+    pub quantity: Option<Decimal>,
+
+    // Not in the db
+    pub quantity_input: String, // Helps us input on TextInputs
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for RecipeIngredient {
+    fn default() -> Self {
+        Self {
+            ingredient_id: None,
+            quantity: None,
+            quantity_input: String::new(),
+        }
+    }
+}
+
+impl Recipe {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        self.product_id.is_some()
+            && !self.ingredients.is_empty()
+            && self.ingredients.iter().all(|i| {
+                i.ingredient_id.is_some() && i.quantity.is_some_and(|q| q > Decimal::ZERO)
+            })
+            // every ingredient can only be once on a recipe
+            && self.ingredients.iter().enumerate().all(|(index, i)| {
+                !self.ingredients[..index]
+                    .iter()
+                    .any(|other| other.ingredient_id == i.ingredient_id)
+            })
+    }
+
+    /// Returns the recipes of every (non deleted) product that has one
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<Recipe>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT ri.product_id, ri.ingredient_id, ri.quantity, p.name as product_name
+            FROM recipe_ingredients ri
+            JOIN products p ON p.id = ri.product_id
+            WHERE p.is_deleted = $1
+            ORDER BY p.name ASC, ri.id ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut recipes: BTreeMap<(String, i32), Recipe> = BTreeMap::new();
+        for row in rows {
+            let product_id: i32 = row.try_get("product_id")?;
+            let product_name: String = row.try_get("product_name")?;
+            let quantity: Decimal = row.try_get("quantity")?;
+
+            recipes
+                .entry((product_name.clone(), product_id))
+                .or_insert_with(|| Recipe {
+                    product_id: Some(product_id),
+                    ingredients: Vec::new(),
+                    product_name: product_name.into_boxed_str(),
+                })
+                .ingredients
+                .push(RecipeIngredient {
+                    ingredient_id: row.try_get("ingredient_id")?,
+                    quantity: Some(quantity),
+                    quantity_input: quantity.normalize().to_string(),
+                });
+        }
+
+        Ok(recipes.into_values().collect())
+    }
+
+    /// Returns the recipe of the given product (without ingredients if it has none)
+    pub async fn get_single(pool: Arc<PgPool>, product_id: i32) -> Result<Recipe, sqlx::Error> {
+        let product_name: String = sqlx::query_scalar("SELECT name FROM products WHERE id = $1")
+            .bind(product_id)
+            .fetch_one(pool.as_ref())
+            .await?;
+
+        let rows = sqlx::query(
+            "SELECT ingredient_id, quantity FROM recipe_ingredients WHERE product_id = $1 ORDER BY id ASC",
+        )
+        .bind(product_id)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut ingredients = Vec::<RecipeIngredient>::new();
+        for row in rows {
+            let quantity: Decimal = row.try_get("quantity")?;
+
+            ingredients.push(RecipeIngredient {
+                ingredient_id: row.try_get("ingredient_id")?,
+                quantity: Some(quantity),
+                quantity_input: quantity.normalize().to_string(),
+            });
+        }
+
+        Ok(Recipe {
+            product_id: Some(product_id),
+            ingredients,
+            product_name: product_name.into_boxed_str(),
+        })
+    }
+
+    /// Saves the recipe of its product, replacing the one it had (if any)
+    pub async fn save(pool: Arc<PgPool>, recipe: Recipe) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        sqlx::query("DELETE FROM recipe_ingredients WHERE product_id = $1")
+            .bind(recipe.product_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        for ingredient in recipe.ingredients {
+            sqlx::query(
+                "INSERT INTO recipe_ingredients (product_id, ingredient_id, quantity) VALUES ($1, $2, $3)",
+            )
+            .bind(recipe.product_id)
+            .bind(ingredient.ingredient_id)
+            .bind(ingredient.quantity.unwrap_or_default())
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Deletes the recipe of the given product
+    pub async fn delete(pool: Arc<PgPool>, product_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM recipe_ingredients WHERE product_id = $1")
+            .bind(product_id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,28 +1,33 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Local, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Row};
-use std::sync::Arc;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::{collections::HashSet, sync::Arc};
 
-use crate::alegria::utils::date::check_date_format;
+use crate::alegria::utils::{date::check_date_format, money::Money};
 
-use super::sold_room::SoldRoom;
+use super::{client::Client, simple_invoice::SimpleInvoice, sold_room::SoldRoom};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reservation {
     pub id: Option<i32>,
     pub client_id: Option<i32>,
+    /// Company that pays the reservation, None if the client pays it
+    pub company_id: Option<i32>,
     pub rooms: Vec<SoldRoom>,
     pub entry_date: Option<NaiveDateTime>,
     pub departure_date: Option<NaiveDateTime>,
     pub occupied: bool,
+    /// When the guests left (the rooms are free and the final invoice has been issued), None until the check-out
+    pub checked_out_at: Option<NaiveDateTime>,
     pub is_deleted: bool,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 
     // Not in the db
     pub client_name: String, // Helps us JOIN and return the name of the selected client
+    pub company_name: String, // Helps us JOIN and return the name of the paying company
     pub entry_date_string: String, // Helps us input the date
     pub departure_date_string: String, // Helps us input the date
 }
@@ -33,14 +38,17 @@ impl Default for Reservation {
         Self {
             id: None,
             client_id: None,
+            company_id: None,
             rooms: Vec::new(),
             entry_date: None,
             departure_date: None,
             occupied: false,
+            checked_out_at: None,
             is_deleted: false,
             created_at: None,
             updated_at: None,
             client_name: String::new(),
+            company_name: String::new(),
             entry_date_string: String::new(),
             departure_date_string: String::new(),
         }
@@ -80,17 +88,21 @@ impl Reservation {
             "SELECT 
                 reservations.id, 
                 reservations.client_id, 
+                reservations.company_id, 
                 reservations.entry_date, 
                 reservations.departure_date, 
                 reservations.occupied, 
+                reservations.checked_out_at, 
                 reservations.is_deleted, 
                 reservations.created_at, 
                 reservations.updated_at,
                 clients.name as client_name,
                 clients.first_surname as client_first_surname,
-                clients.second_surname as client_second_surname
+                clients.second_surname as client_second_surname,
+                companies.name as company_name
             FROM reservations 
             LEFT JOIN clients ON reservations.client_id = clients.id 
+            LEFT JOIN companies ON reservations.company_id = companies.id 
             WHERE reservations.is_deleted = $1
             AND (
                 (reservations.entry_date BETWEEN $2 AND $3) 
@@ -110,9 +122,11 @@ impl Reservation {
         for row in rows {
             let id: Option<i32> = row.try_get("id")?;
             let client_id: Option<i32> = row.try_get("client_id")?;
+            let company_id: Option<i32> = row.try_get("company_id")?;
             let entry_date: Option<NaiveDateTime> = row.try_get("entry_date")?;
             let departure_date: Option<NaiveDateTime> = row.try_get("departure_date")?;
             let occupied: bool = row.try_get("occupied")?;
+            let checked_out_at: Option<NaiveDateTime> = row.try_get("checked_out_at")?;
             let is_deleted: bool = row.try_get("is_deleted")?;
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -124,6 +138,7 @@ impl Reservation {
 
             let client_name =
                 format!("{client_name} {client_first_surname} {client_second_surname}");
+            let company_name: String = row.try_get("company_name").unwrap_or_default();
 
             // get rooms for this reservation
             let rooms = if let Some(reservation_id) = id {
@@ -152,6 +167,7 @@ impl Reservation {
                         guests: Vec::new(),
                         invoices: Vec::new(),
                         room_name: room_row.try_get("room_name")?,
+                        price_input: String::new(),
                     };
                     rooms.push(sold_room);
                 }
@@ -163,14 +179,17 @@ impl Reservation {
             let reservation = Reservation {
                 id,
                 client_id,
+                company_id,
                 rooms,
                 entry_date,
                 departure_date,
                 occupied,
+                checked_out_at,
                 is_deleted,
                 created_at,
                 updated_at,
                 client_name,
+                company_name,
                 entry_date_string: entry_date.map(|d| d.date().to_string()).unwrap_or_default(),
                 departure_date_string: departure_date
                     .map(|d| d.date().to_string())
@@ -183,61 +202,138 @@ impl Reservation {
         Ok(result)
     }
 
-    /// Adds the given reservation with it's rooms to the database
-    pub async fn add(pool: Arc<PgPool>, reservation: Reservation) -> Result<(), sqlx::Error> {
-        let mut tx = pool.begin().await?;
+    /// Retrieves the given reservation with it's rooms and the invoices charged to them
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        reservation_id: i32,
+    ) -> Result<Reservation, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT 
+                reservations.id, 
+                reservations.client_id, 
+                reservations.company_id, 
+                reservations.entry_date, 
+                reservations.departure_date, 
+                reservations.occupied, 
+                reservations.checked_out_at, 
+                reservations.is_deleted, 
+                reservations.created_at, 
+                reservations.updated_at,
+                clients.name as client_name,
+                clients.first_surname as client_first_surname,
+                clients.second_surname as client_second_surname,
+                companies.name as company_name
+            FROM reservations 
+            LEFT JOIN clients ON reservations.client_id = clients.id 
+            LEFT JOIN companies ON reservations.company_id = companies.id 
+            WHERE reservations.id = $1",
+        )
+        .bind(reservation_id)
+        .fetch_one(pool.as_ref())
+        .await?;
 
-        // check if rooms are available for the given date range
-        if let (Some(entry_date), Some(departure_date)) =
-            (reservation.entry_date, reservation.departure_date)
-        {
-            if entry_date >= departure_date {
-                return Err(sqlx::Error::Protocol(
-                    "entry date must be before departure date".to_string(),
-                ));
+        let entry_date: Option<NaiveDateTime> = row.try_get("entry_date")?;
+        let departure_date: Option<NaiveDateTime> = row.try_get("departure_date")?;
+        let client_name: String = row.try_get("client_name").unwrap_or_default();
+        let client_first_surname: String = row.try_get("client_first_surname").unwrap_or_default();
+        let client_second_surname: String =
+            row.try_get("client_second_surname").unwrap_or_default();
+
+        let room_rows = sqlx::query(
+            "SELECT 
+            sr.id, 
+            sr.room_id, 
+            sr.price,
+            r.name as room_name
+            FROM sold_rooms sr
+            JOIN reservation_sold_rooms rsr ON sr.id = rsr.sold_room_id
+            JOIN rooms r ON sr.room_id = r.id
+            WHERE rsr.reservation_id = $1
+            ORDER BY r.name ASC",
+        )
+        .bind(reservation_id)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut rooms = Vec::new();
+        for room_row in room_rows {
+            let sold_room_id: i32 = room_row.try_get("id")?;
+            let price: Option<Money> = room_row.try_get("price")?;
+
+            // invoices (adeudos) charged to the room
+            let invoice_ids: Vec<i32> = sqlx::query_scalar(
+                "SELECT simple_invoice_id FROM sold_room_invoices WHERE sold_room_id = $1 ORDER BY simple_invoice_id ASC",
+            )
+            .bind(sold_room_id)
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            let mut invoices = Vec::new();
+            for invoice_id in invoice_ids {
+                invoices.push(SimpleInvoice::get_single(pool.clone(), invoice_id).await?);
             }
 
-            for sold_room in &reservation.rooms {
-                let overlapping_count = sqlx::query(
-                    "SELECT COUNT(*) FROM reservations r
-                        JOIN reservation_sold_rooms rsr ON r.id = rsr.reservation_id
-                        JOIN sold_rooms sr ON rsr.sold_room_id = sr.id
-                        WHERE sr.room_id = $1
-                        AND r.is_deleted = false
-                        AND r.entry_date < $3  -- existing entry is before new departure
-                        AND r.departure_date > $2  -- existing departure is after new entry
-                        AND NOT (r.departure_date = $2)  -- allow booking when existing departure equals new entry
-                    "
-                )
-                .bind(sold_room.room_id)
-                .bind(entry_date)
-                .bind(departure_date)
-                .fetch_one(&mut *tx)
-                .await?;
+            // guests registered on the room
+            let guest_ids: Vec<i32> = sqlx::query_scalar(
+                "SELECT client_id FROM sold_room_clients WHERE sold_room_id = $1 ORDER BY client_id ASC",
+            )
+            .bind(sold_room_id)
+            .fetch_all(pool.as_ref())
+            .await?;
 
-                let count: i64 = overlapping_count.get(0);
-                if count > 0 {
-                    return Err(sqlx::Error::Protocol(format!(
-                        "Room {:?} is already reserved for the selected date range",
-                        sold_room.room_id
-                    )));
-                }
+            let mut guests = Vec::new();
+            for guest_id in guest_ids {
+                guests.push(Client::get_single(pool.clone(), guest_id).await?);
             }
-        } else {
-            return Err(sqlx::Error::Protocol(
-                "entry and departure dates are required".to_string(),
-            ));
+
+            rooms.push(SoldRoom {
+                id: Some(sold_room_id),
+                room_id: room_row.try_get("room_id")?,
+                price,
+                guests,
+                invoices,
+                room_name: room_row.try_get("room_name")?,
+                price_input: price.map(|p| p.to_string()).unwrap_or_default(),
+            });
         }
 
+        Ok(Reservation {
+            id: row.try_get("id")?,
+            client_id: row.try_get("client_id")?,
+            company_id: row.try_get("company_id")?,
+            rooms,
+            entry_date,
+            departure_date,
+            occupied: row.try_get("occupied")?,
+            checked_out_at: row.try_get("checked_out_at")?,
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            client_name: format!("{client_name} {client_first_surname} {client_second_surname}"),
+            company_name: row.try_get("company_name").unwrap_or_default(),
+            entry_date_string: entry_date.map(|d| d.date().to_string()).unwrap_or_default(),
+            departure_date_string: departure_date
+                .map(|d| d.date().to_string())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Adds the given reservation with it's rooms to the database, it's only occupied once it's checked in
+    pub async fn add(pool: Arc<PgPool>, reservation: Reservation) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        // check if rooms are available for the given date range
+        Self::check_availability(&mut tx, &reservation).await?;
+
         // Insert the reservation
-        let reservation_id = sqlx::query("INSERT INTO reservations (client_id, entry_date, departure_date, occupied, is_deleted, created_at, updated_at) 
-            VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) 
+        let reservation_id = sqlx::query("INSERT INTO reservations (client_id, company_id, entry_date, departure_date, occupied, is_deleted, created_at, updated_at) 
+            VALUES ($1, $2, $3, $4, FALSE, $5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) 
             RETURNING id",
         )
         .bind(reservation.client_id)
+        .bind(reservation.company_id)
         .bind(reservation.entry_date)
         .bind(reservation.departure_date)
-        .bind(reservation.occupied)
         .bind(reservation.is_deleted)
         .fetch_one(&mut *tx)
         .await?
@@ -245,23 +341,7 @@ impl Reservation {
 
         // create all sold_rooms for the reservation
         for sold_room in &reservation.rooms {
-            // create sold_room
-            let sold_room_id =
-                sqlx::query("INSERT INTO sold_rooms (room_id, price) VALUES ($1, $2) RETURNING id")
-                    .bind(sold_room.room_id)
-                    .bind(sold_room.price)
-                    .fetch_one(&mut *tx)
-                    .await?
-                    .get::<i32, _>(0);
-
-            // insert association in reservation_sold_rooms
-            sqlx::query(
-                "INSERT INTO reservation_sold_rooms (reservation_id, sold_room_id) VALUES ($1, $2)",
-            )
-            .bind(reservation_id)
-            .bind(sold_room_id)
-            .execute(&mut *tx)
-            .await?;
+            Self::add_sold_room(&mut tx, reservation_id, sold_room).await?;
         }
 
         // Commit the transaction
@@ -270,23 +350,334 @@ impl Reservation {
         Ok(())
     }
 
+    /// Updates the given reservation and it's rooms, the rooms that are no longer on the reservation are deleted
+    /// (rooms with invoices charged to them can't be removed). It doesn't change if it's occupied, only the check-in
+    /// and the check-out do
+    pub async fn edit(pool: Arc<PgPool>, reservation: Reservation) -> Result<(), sqlx::Error> {
+        let Some(reservation_id) = reservation.id else {
+            return Err(sqlx::Error::Protocol(
+                "can't edit a reservation that has not been added".to_string(),
+            ));
+        };
+
+        let mut tx = pool.begin().await?;
+
+        // check if rooms are available for the given date range (ignoring this same reservation)
+        Self::check_availability(&mut tx, &reservation).await?;
+
+        let updated = sqlx::query(
+            "UPDATE reservations SET client_id = $1, company_id = $2, entry_date = $3, departure_date = $4
+            WHERE id = $5 AND is_deleted = FALSE AND checked_out_at IS NULL",
+        )
+        .bind(reservation.client_id)
+        .bind(reservation.company_id)
+        .bind(reservation.entry_date)
+        .bind(reservation.departure_date)
+        .bind(reservation_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(sqlx::Error::Protocol(
+                "The reservation has been cancelled or checked out".to_string(),
+            ));
+        }
+
+        // delete the rooms that have been removed from the reservation
+        let kept_sold_room_ids: Vec<i32> = reservation
+            .rooms
+            .iter()
+            .filter_map(|room| room.id)
+            .collect();
+
+        let removed_with_invoices: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM reservation_sold_rooms rsr
+            JOIN sold_room_invoices sri ON rsr.sold_room_id = sri.sold_room_id
+            WHERE rsr.reservation_id = $1 AND NOT (rsr.sold_room_id = ANY($2))",
+        )
+        .bind(reservation_id)
+        .bind(&kept_sold_room_ids)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if removed_with_invoices > 0 {
+            return Err(sqlx::Error::Protocol(
+                "Rooms with invoices charged to them can't be removed from the reservation"
+                    .to_string(),
+            ));
+        }
+
+        sqlx::query(
+            "DELETE FROM sold_rooms
+            WHERE id IN (SELECT sold_room_id FROM reservation_sold_rooms WHERE reservation_id = $1)
+            AND NOT (id = ANY($2))",
+        )
+        .bind(reservation_id)
+        .bind(&kept_sold_room_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        // update the prices of the kept rooms and create the new ones
+        for sold_room in &reservation.rooms {
+            match sold_room.id {
+                Some(sold_room_id) => {
+                    sqlx::query("UPDATE sold_rooms SET price = $1 WHERE id = $2")
+                        .bind(sold_room.price)
+                        .bind(sold_room_id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                None => Self::add_sold_room(&mut tx, reservation_id, sold_room).await?,
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns true if the reservation can be checked in on the given date
+    /// (it's not occupied nor checked out yet and the date is one of it's nights)
+    pub fn can_check_in_on(&self, date: NaiveDate) -> bool {
+        self.id.is_some()
+            && !self.occupied
+            && self.checked_out_at.is_none()
+            && self
+                .entry_date
+                .is_some_and(|entry_date| entry_date.date() <= date)
+            && self
+                .departure_date
+                .is_some_and(|departure_date| departure_date.date() > date)
+    }
+
+    /// Checks in the given reservation: registers the guests of every room (replacing the previous ones)
+    /// and marks the reservation as occupied (the only way it becomes occupied), every room needs it's adult guests
+    /// with valid identity documents
+    pub async fn check_in(pool: Arc<PgPool>, reservation: Reservation) -> Result<(), sqlx::Error> {
+        let today = Local::now().date_naive();
+        if !reservation.can_check_in_on(today) {
+            return Err(sqlx::Error::Protocol(
+                "The reservation can't be checked in today".to_string(),
+            ));
+        }
+
+        // validate the guests with their current data
+        let mut registered_guests = HashSet::new();
+        for sold_room in &reservation.rooms {
+            let mut guests = Vec::new();
+            for guest in &sold_room.guests {
+                let guest_id = guest.id.unwrap_or_default();
+                if !registered_guests.insert(guest_id) {
+                    return Err(sqlx::Error::Protocol(
+                        "A guest can only be registered in one room".to_string(),
+                    ));
+                }
+                guests.push(Client::get_single(pool.clone(), guest_id).await?);
+            }
+
+            let room = SoldRoom {
+                guests,
+                ..sold_room.clone()
+            };
+            if let Some(err) = room.check_in_error(today) {
+                return Err(sqlx::Error::Protocol(format!(
+                    "{}: {err}",
+                    sold_room.room_name
+                )));
+            }
+        }
+
+        let mut tx = pool.begin().await?;
+
+        for sold_room in &reservation.rooms {
+            sqlx::query("DELETE FROM sold_room_clients WHERE sold_room_id = $1")
+                .bind(sold_room.id)
+                .execute(&mut *tx)
+                .await?;
+
+            for guest in &sold_room.guests {
+                sqlx::query(
+                    "INSERT INTO sold_room_clients (sold_room_id, client_id) VALUES ($1, $2)",
+                )
+                .bind(sold_room.id)
+                .bind(guest.id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        let updated = sqlx::query(
+            "UPDATE reservations SET occupied = TRUE
+            WHERE id = $1 AND is_deleted = FALSE AND occupied = FALSE AND checked_out_at IS NULL",
+        )
+        .bind(reservation.id)
+        .execute(&mut *tx)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(sqlx::Error::Protocol(
+                "The reservation has been cancelled or already checked in".to_string(),
+            ));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Cancels (soft deletes) the given reservation, reservations with invoices charged to their rooms,
+    /// checked in or already checked out can't be cancelled
+    pub async fn delete(pool: Arc<PgPool>, reservation_id: i32) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let charged_invoices: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM reservation_sold_rooms rsr
+            JOIN sold_room_invoices sri ON rsr.sold_room_id = sri.sold_room_id
+            WHERE rsr.reservation_id = $1",
+        )
+        .bind(reservation_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if charged_invoices > 0 {
+            return Err(sqlx::Error::Protocol(
+                "Reservations with invoices charged to their rooms can't be cancelled".to_string(),
+            ));
+        }
+
+        let row = sqlx::query(
+            "SELECT occupied, checked_out_at IS NOT NULL as checked_out FROM reservations WHERE id = $1 FOR UPDATE",
+        )
+        .bind(reservation_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if row.try_get::<bool, _>("checked_out")? {
+            return Err(sqlx::Error::Protocol(
+                "Checked out reservations can't be cancelled".to_string(),
+            ));
+        }
+
+        // the guests are in the rooms, their nights have to be billed
+        if row.try_get::<bool, _>("occupied")? {
+            return Err(sqlx::Error::Protocol(
+                "Checked in reservations have to be checked out".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE reservations SET is_deleted = $1 WHERE id = $2 AND occupied = FALSE AND checked_out_at IS NULL",
+        )
+        .bind(true)
+        .bind(reservation_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Checks that the dates of the given reservation are valid and that none of it's rooms is reserved
+    /// (by another reservation) for the given date range
+    async fn check_availability(
+        tx: &mut Transaction<'_, Postgres>,
+        reservation: &Reservation,
+    ) -> Result<(), sqlx::Error> {
+        let (Some(entry_date), Some(departure_date)) =
+            (reservation.entry_date, reservation.departure_date)
+        else {
+            return Err(sqlx::Error::Protocol(
+                "entry and departure dates are required".to_string(),
+            ));
+        };
+
+        if entry_date >= departure_date {
+            return Err(sqlx::Error::Protocol(
+                "entry date must be before departure date".to_string(),
+            ));
+        }
+
+        for sold_room in &reservation.rooms {
+            let overlapping_count = sqlx::query(
+                "SELECT COUNT(*) FROM reservations r
+                    JOIN reservation_sold_rooms rsr ON r.id = rsr.reservation_id
+                    JOIN sold_rooms sr ON rsr.sold_room_id = sr.id
+                    WHERE sr.room_id = $1
+                    AND r.is_deleted = false
+                    AND r.id IS DISTINCT FROM $4  -- the reservation being edited doesn't overlap itself
+                    AND r.entry_date < $3  -- existing entry is before new departure
+                    AND r.departure_date > $2  -- existing departure is after new entry
+                    AND NOT (r.departure_date = $2)  -- allow booking when existing departure equals new entry
+                ",
+            )
+            .bind(sold_room.room_id)
+            .bind(entry_date)
+            .bind(departure_date)
+            .bind(reservation.id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+            let count: i64 = overlapping_count.get(0);
+            if count > 0 {
+                return Err(sqlx::Error::Protocol(format!(
+                    "Room {:?} is already reserved for the selected date range",
+                    sold_room.room_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the given sold room and associates it to the given reservation
+    async fn add_sold_room(
+        tx: &mut Transaction<'_, Postgres>,
+        reservation_id: i32,
+        sold_room: &SoldRoom,
+    ) -> Result<(), sqlx::Error> {
+        // create sold_room
+        let sold_room_id =
+            sqlx::query("INSERT INTO sold_rooms (room_id, price) VALUES ($1, $2) RETURNING id")
+                .bind(sold_room.room_id)
+                .bind(sold_room.price)
+                .fetch_one(&mut **tx)
+                .await?
+                .get::<i32, _>(0);
+
+        // insert association in reservation_sold_rooms
+        sqlx::query(
+            "INSERT INTO reservation_sold_rooms (reservation_id, sold_room_id) VALUES ($1, $2)",
+        )
+        .bind(reservation_id)
+        .bind(sold_room_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     /// Retrieves all the currently occupied reservations
     pub async fn get_occupied(pool: Arc<PgPool>) -> Result<Vec<Reservation>, sqlx::Error> {
         let rows = sqlx::query(
             "SELECT 
                 reservations.id, 
                 reservations.client_id, 
+                reservations.company_id, 
                 reservations.entry_date, 
                 reservations.departure_date, 
                 reservations.occupied, 
+                reservations.checked_out_at, 
                 reservations.is_deleted, 
                 reservations.created_at, 
                 reservations.updated_at,
                 clients.name as client_name,
                 clients.first_surname as client_first_surname,
-                clients.second_surname as client_second_surname
+                clients.second_surname as client_second_surname,
+                companies.name as company_name
             FROM reservations 
             LEFT JOIN clients ON reservations.client_id = clients.id 
+            LEFT JOIN companies ON reservations.company_id = companies.id 
             WHERE reservations.is_deleted = $1
             AND reservations.occupied = $2
             ORDER BY reservations.id DESC",
@@ -301,9 +692,11 @@ impl Reservation {
         for row in rows {
             let id: Option<i32> = row.try_get("id")?;
             let client_id: Option<i32> = row.try_get("client_id")?;
+            let company_id: Option<i32> = row.try_get("company_id")?;
             let entry_date: Option<NaiveDateTime> = row.try_get("entry_date")?;
             let departure_date: Option<NaiveDateTime> = row.try_get("departure_date")?;
             let occupied: bool = row.try_get("occupied")?;
+            let checked_out_at: Option<NaiveDateTime> = row.try_get("checked_out_at")?;
             let is_deleted: bool = row.try_get("is_deleted")?;
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -315,6 +708,7 @@ impl Reservation {
 
             let client_name =
                 format!("{client_name} {client_first_surname} {client_second_surname}");
+            let company_name: String = row.try_get("company_name").unwrap_or_default();
 
             // get rooms for this reservation
             let rooms = if let Some(reservation_id) = id {
@@ -343,6 +737,7 @@ impl Reservation {
                         guests: Vec::new(),
                         invoices: Vec::new(),
                         room_name: room_row.try_get("room_name")?,
+                        price_input: String::new(),
                     };
                     rooms.push(sold_room);
                 }
@@ -354,14 +749,17 @@ impl Reservation {
             let reservation = Reservation {
                 id,
                 client_id,
+                company_id,
                 rooms,
                 entry_date,
                 departure_date,
                 occupied,
+                checked_out_at,
                 is_deleted,
                 created_at,
                 updated_at,
                 client_name,
+                company_name,
                 entry_date_string: entry_date.map(|d| d.date().to_string()).unwrap_or_default(),
                 departure_date_string: departure_date
                     .map(|d| d.date().to_string())
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::{fmt, sync::Arc};
 
+use crate::alegria::utils::money::Money;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Room {
     pub id: Option<i32>,
@@ -16,7 +18,7 @@ pub struct Room {
 
     // Not in the db
     pub room_type_name: Box<str>, // Helps us JOIN adn return the room type name of the selected room_type_id
-    pub default_room_price: Option<f32>, // Helps us JOIN the room_type_id and return the default price for this room
+    pub default_room_price: Option<Money>, // Helps us JOIN the room_type_id and return the default price for this room
 }
 
 #[allow(clippy::derivable_impls)]
@@ -81,7 +83,8 @@ impl Room {
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
             let room_type_name: String = row.try_get("room_type_name").unwrap_or_default();
-            let default_room_price: Option<f32> = row.try_get("default_room_price").unwrap_or(None);
+            let default_room_price: Option<Money> =
+                row.try_get("default_room_price").unwrap_or(None);
 
             let room = Room {
                 id,
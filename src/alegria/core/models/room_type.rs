@@ -5,11 +5,13 @@ use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::{fmt, sync::Arc};
 
+use crate::alegria::utils::money::Money;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RoomType {
     pub id: Option<i32>,
     pub name: String,
-    pub price: Option<f32>,
+    pub price: Option<Money>,
     pub is_deleted: bool,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
@@ -61,7 +63,7 @@ impl RoomType {
         for row in rows {
             let id: Option<i32> = row.try_get("id")?;
             let name: String = row.try_get("name")?;
-            let price: Option<f32> = row.try_get("price")?;
+            let price: Option<Money> = row.try_get("price")?;
             let is_deleted: bool = row.try_get("is_deleted")?;
             let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
             let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
@@ -73,7 +75,7 @@ impl RoomType {
                 is_deleted,
                 created_at,
                 updated_at,
-                price_input: price.unwrap_or(0.0).to_string(),
+                price_input: price.unwrap_or_default().to_string(),
             };
 
             result.push(room_type);
@@ -100,7 +102,7 @@ impl RoomType {
 
         let id: Option<i32> = row.try_get("id")?;
         let name: String = row.try_get("name")?;
-        let price: Option<f32> = row.try_get("price")?;
+        let price: Option<Money> = row.try_get("price")?;
         let is_deleted: bool = row.try_get("is_deleted")?;
         let created_at: Option<NaiveDateTime> = row.try_get("created_at")?;
         let updated_at: Option<NaiveDateTime> = row.try_get("updated_at")?;
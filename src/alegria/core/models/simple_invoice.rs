@@ -1,15 +1,25 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::{PgPool, Postgres, Row, Transaction};
 use std::sync::Arc;
 
 use crate::alegria::{
-    core::models::product::Product, utils::entities::payment_method::PaymentMethod,
+    core::models::product::Product,
+    utils::{entities::payment_method::PaymentMethod, money::Money},
 };
 
-use super::{sold_product::SoldProduct, temporal_ticket::TemporalTicket};
+use super::{
+    folio::FolioLine,
+    invoice_payment::InvoicePayment,
+    invoice_record::InvoiceRecord,
+    invoice_series::InvoiceSeries,
+    sold_product::SoldProduct,
+    stock_movement::StockMovement,
+    temporal_ticket::{TemporalTicket, TicketSplit},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimpleInvoice {
@@ -17,21 +27,141 @@ pub struct SimpleInvoice {
     pub payment_method: PaymentMethod,
     pub products: Vec<SoldProduct>,
     pub paid: bool,
+    pub recipient: Option<InvoiceRecipient>,
+    /// Invoice this one rectifies (a refund of it), None for regular invoices
+    pub rectified_invoice_id: Option<i32>,
+    pub rectification_reason: Option<String>,
+    /// Simplified invoice this full invoice has been issued in place of, None for regular invoices
+    pub replaced_invoice_id: Option<i32>,
+    /// Number of the invoice on its series (prefix, year and number), given when the invoice is paid
+    pub invoice_number: Option<String>,
+    /// When the record of the invoice was generated (ISO 8601 with the UTC offset), None until it's paid
+    pub record_generated_at: Option<String>,
+    /// Why the whole ticket was discounted, None if it had no discount (its lines discounted with this reason)
+    pub ticket_discount_reason: Option<String>,
     pub is_deleted: bool,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub rectifying_invoice_id: Option<i32>, // Helps us know if the invoice has already been rectified (and by which invoice)
+    pub rectified_invoice_number: Option<String>, // Helps us show the number of the invoice this one rectifies
+    pub replacing_invoice_id: Option<i32>, // Helps us know if a full invoice has already been issued in place of this one (and which)
+    pub replaced_invoice_number: Option<String>, // Helps us show the number of the simplified invoice this one replaces
+}
+
+/// Fiscal data of the recipient of a full invoice
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvoiceRecipient {
+    pub name: String,
+    pub nif: String,
+    pub address: String,
+    /// Company the fiscal data comes from, None if it has been typed or comes from a client
+    pub company_id: Option<i32>,
+}
+
+impl InvoiceRecipient {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && !self.nif.trim().is_empty()
+            && !self.address.trim().is_empty()
+    }
+
+    /// Searches the companies and clients whose name or fiscal id contain the given text, returns their fiscal data
+    /// (10 at most, companies first)
+    pub async fn search(
+        pool: Arc<PgPool>,
+        search: String,
+    ) -> Result<Vec<InvoiceRecipient>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT name, nif, address, company_id FROM (
+                SELECT 0 as source, name, cif as nif, address, id as company_id
+                FROM companies
+                WHERE is_deleted = FALSE AND (cif ILIKE $1 OR name ILIKE $1)
+                UNION ALL
+                SELECT 1 as source,
+                    CONCAT_WS(' ', name, NULLIF(first_surname, ''), NULLIF(second_surname, '')) as name,
+                    identity_document as nif,
+                    CONCAT_WS(', ', NULLIF(address, ''), NULLIF(CONCAT_WS(' ', NULLIF(postal_code, ''), NULLIF(city, '')), ''), NULLIF(province, '')) as address,
+                    NULL::INTEGER as company_id
+                FROM clients
+                WHERE is_deleted = FALSE
+                    AND (identity_document ILIKE $1 OR CONCAT_WS(' ', name, first_surname, second_surname) ILIKE $1)
+            ) recipients
+            ORDER BY source ASC, name ASC
+            LIMIT 10",
+        )
+        .bind(format!("%{}%", search.trim()))
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<InvoiceRecipient>::new();
+        for row in rows {
+            result.push(InvoiceRecipient {
+                name: row.try_get("name")?,
+                nif: row.try_get("nif")?,
+                address: row.try_get("address")?,
+                company_id: row.try_get("company_id")?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Builds the recipient from the (optional) db columns, returns None if there is no recipient name
+    fn from_columns(
+        name: Option<String>,
+        nif: Option<String>,
+        address: Option<String>,
+        company_id: Option<i32>,
+    ) -> Option<InvoiceRecipient> {
+        name.map(|name| InvoiceRecipient {
+            name,
+            nif: nif.unwrap_or_default(),
+            address: address.unwrap_or_default(),
+            company_id,
+        })
+    }
 }
 
 impl SimpleInvoice {
-    pub fn total_price(&self) -> f32 {
-        let mut price = 0.;
+    pub fn total_price(&self) -> Money {
+        let mut price = Money::ZERO;
         for product in &self.products {
-            price += product.price.unwrap_or(0.);
+            price += product.total_price();
         }
 
         price
     }
 
+    /// Returns true if the discount of the given line comes from the discount of the whole ticket
+    pub fn has_ticket_discount(&self, product: &SoldProduct) -> bool {
+        product.discount() != Money::ZERO
+            && self.ticket_discount_reason.is_some()
+            && product.discount_reason == self.ticket_discount_reason
+    }
+
+    /// Returns the amount discounted from the whole ticket (the discount of its lines that comes from it)
+    pub fn ticket_discount(&self) -> Money {
+        self.products
+            .iter()
+            .filter(|product| self.has_ticket_discount(product))
+            .map(SoldProduct::discount)
+            .sum()
+    }
+
+    /// Returns when the invoice was issued: when its record was generated (at its UTC offset) or, for invoices
+    /// without a record, when it was paid (its last update) or created
+    pub fn issued_at(&self) -> Option<NaiveDateTime> {
+        self.record_generated_at
+            .as_deref()
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.naive_local())
+            .or(self.updated_at.filter(|_| self.paid))
+            .or(self.created_at)
+    }
+
     /// Creates a simple invoice given a temporal ticket, returns the newly created invoice
     pub async fn create_from_temporal_ticket(
         pool: Arc<PgPool>,
@@ -42,11 +172,13 @@ impl SimpleInvoice {
         // Insert a new simple_invoice
         let invoice = sqlx::query!(
             r#"
-            INSERT INTO simple_invoices (payment_method_id, paid, is_deleted)
-            VALUES ($1, FALSE, FALSE)
-            RETURNING id, payment_method_id, paid, is_deleted, created_at, updated_at
+            INSERT INTO simple_invoices (payment_method_id, paid, is_deleted, ticket_discount_reason)
+            VALUES ($1, FALSE, FALSE, (SELECT discount_reason FROM temporal_tickets WHERE id = $2))
+            RETURNING id, payment_method_id, paid, recipient_name, recipient_nif, recipient_address, company_id,
+                ticket_discount_reason, is_deleted, created_at, updated_at
             "#,
-            PaymentMethod::to_id(PaymentMethod::Efectivo) // assume payment method is efectivo
+            PaymentMethod::to_id(PaymentMethod::Efectivo), // assume payment method is efectivo
+            temporal_ticket.id
         )
         .fetch_one(&mut *transaction)
         .await?;
@@ -57,13 +189,17 @@ impl SimpleInvoice {
         for product in &temporal_ticket.products {
             let sold_product = sqlx::query!(
                 r#"
-                INSERT INTO sold_products (simple_invoice_id, original_product_id, price)
-                VALUES ($1, $2, $3)
-                RETURNING id, simple_invoice_id, original_product_id, price
+                INSERT INTO sold_products (simple_invoice_id, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, (SELECT COALESCE(tax_percentage, 21) FROM products WHERE id = $2))
+                RETURNING id, simple_invoice_id, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage
                 "#,
                 invoice.id,
                 product.original_product_id,
-                product.price
+                product.quantity,
+                product.price.map(Decimal::from),
+                product.modifiers,
+                product.original_price.map(Decimal::from),
+                product.discount_reason
             )
             .fetch_one(&mut *transaction)
             .await?;
@@ -83,7 +219,12 @@ impl SimpleInvoice {
                 id: Some(sold_product.id),
                 simple_invoice_id: sold_product.simple_invoice_id,
                 original_product_id: sold_product.original_product_id,
-                price: sold_product.price,
+                quantity: sold_product.quantity,
+                price: sold_product.price.map(Money::from),
+                original_price: sold_product.original_price.map(Money::from),
+                discount_reason: sold_product.discount_reason,
+                modifiers: sold_product.modifiers,
+                tax_percentage: sold_product.tax_percentage,
                 original_product,
             });
         }
@@ -108,9 +249,25 @@ impl SimpleInvoice {
             payment_method: PaymentMethod::from_id(invoice.payment_method_id).unwrap_or_default(),
             products: sold_products,
             paid: invoice.paid,
+            recipient: InvoiceRecipient::from_columns(
+                invoice.recipient_name,
+                invoice.recipient_nif,
+                invoice.recipient_address,
+                invoice.company_id,
+            ),
+            rectified_invoice_id: None,
+            rectification_reason: None,
+            replaced_invoice_id: None,
             is_deleted: invoice.is_deleted,
             created_at: invoice.created_at,
             updated_at: invoice.updated_at,
+            invoice_number: None,
+            record_generated_at: None,
+            ticket_discount_reason: invoice.ticket_discount_reason,
+            rectifying_invoice_id: None,
+            rectified_invoice_number: None,
+            replacing_invoice_id: None,
+            replaced_invoice_number: None,
         })
     }
 
@@ -121,8 +278,14 @@ impl SimpleInvoice {
     ) -> Result<SimpleInvoice, sqlx::Error> {
         let invoice = sqlx::query!(
             r#"
-            SELECT id, payment_method_id, paid, is_deleted, created_at, updated_at
-            FROM simple_invoices
+            SELECT id, payment_method_id, paid, recipient_name, recipient_nif, recipient_address, company_id,
+                   rectified_invoice_id, rectification_reason, replaced_invoice_id, invoice_number, record_generated_at,
+                   ticket_discount_reason, is_deleted, created_at, updated_at,
+                   (SELECT r.id FROM simple_invoices r WHERE r.rectified_invoice_id = si.id) as rectifying_invoice_id,
+                   (SELECT o.invoice_number FROM simple_invoices o WHERE o.id = si.rectified_invoice_id) as rectified_invoice_number,
+                   (SELECT f.id FROM simple_invoices f WHERE f.replaced_invoice_id = si.id) as replacing_invoice_id,
+                   (SELECT o.invoice_number FROM simple_invoices o WHERE o.id = si.replaced_invoice_id) as replaced_invoice_number
+            FROM simple_invoices si
             WHERE id = $1
             "#,
             simple_invoice_id
@@ -132,7 +295,8 @@ impl SimpleInvoice {
 
         let sold_products = sqlx::query!(
             r#"
-            SELECT sp.id, sp.simple_invoice_id, sp.original_product_id, sp.price,
+            SELECT sp.id, sp.simple_invoice_id, sp.original_product_id, sp.quantity, sp.price, sp.modifiers,
+                   sp.original_price, sp.discount_reason, sp.tax_percentage,
                    p.id as "p_id", p.category_id as "p_category_id", p.name as "p_name",
                    p.inside_price as "p_inside_price", p.outside_price as "p_outside_price",
                    p.tax_percentage as "p_tax_percentage", p.is_deleted as "p_is_deleted",
@@ -150,13 +314,18 @@ impl SimpleInvoice {
             id: Some(row.id),
             simple_invoice_id: row.simple_invoice_id,
             original_product_id: row.original_product_id,
-            price: row.price,
+            quantity: row.quantity,
+            price: row.price.map(Money::from),
+            original_price: row.original_price.map(Money::from),
+            discount_reason: row.discount_reason,
+            modifiers: row.modifiers,
+            tax_percentage: row.tax_percentage,
             original_product: Product {
                 id: Some(row.p_id),
                 category_id: row.p_category_id,
                 name: row.p_name,
-                inside_price: row.p_inside_price,
-                outside_price: row.p_outside_price,
+                inside_price: row.p_inside_price.map(Money::from),
+                outside_price: row.p_outside_price.map(Money::from),
                 tax_percentage: row.p_tax_percentage,
                 is_deleted: row.p_is_deleted,
                 created_at: row.p_created_at,
@@ -172,12 +341,49 @@ impl SimpleInvoice {
             payment_method: PaymentMethod::from_id(invoice.payment_method_id).unwrap_or_default(),
             products: sold_products,
             paid: invoice.paid,
+            recipient: InvoiceRecipient::from_columns(
+                invoice.recipient_name,
+                invoice.recipient_nif,
+                invoice.recipient_address,
+                invoice.company_id,
+            ),
+            rectified_invoice_id: invoice.rectified_invoice_id,
+            rectification_reason: invoice.rectification_reason,
+            replaced_invoice_id: invoice.replaced_invoice_id,
+            invoice_number: invoice.invoice_number,
+            record_generated_at: invoice.record_generated_at,
+            ticket_discount_reason: invoice.ticket_discount_reason,
             is_deleted: invoice.is_deleted,
             created_at: invoice.created_at,
             updated_at: invoice.updated_at,
+            rectifying_invoice_id: invoice.rectifying_invoice_id,
+            rectified_invoice_number: invoice.rectified_invoice_number,
+            replacing_invoice_id: invoice.replacing_invoice_id,
+            replaced_invoice_number: invoice.replaced_invoice_number,
         })
     }
 
+    /// Saves the recipient fiscal data of a simple invoice (needed to print it as a full invoice)
+    pub async fn set_recipient(
+        pool: Arc<PgPool>,
+        simple_invoice_id: i32,
+        recipient: InvoiceRecipient,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE simple_invoices SET recipient_name = $1, recipient_nif = $2, recipient_address = $3, company_id = $4
+            WHERE id = $5",
+        )
+        .bind(recipient.name.trim())
+        .bind(recipient.nif.trim())
+        .bind(recipient.address.trim())
+        .bind(recipient.company_id)
+        .bind(simple_invoice_id)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
     /// Deletes a simple invoice given a TemporalTicket
     pub async fn unlock_temporal_ticket(
         pool: Arc<PgPool>,
@@ -221,14 +427,16 @@ impl SimpleInvoice {
         Ok(())
     }
 
-    /// Creates a simple invoice given a temporal ticket, returns the newly created invoice
+    /// Pays a whole temporal ticket with the given payments (which must add up to the ticket total),
+    /// the printed invoice of the ticket is reused if it exists
     pub async fn pay_temporal_ticket(
         pool: Arc<PgPool>,
         temporal_ticket_id: i32,
-        payment_method: PaymentMethod,
-        sold_room_id: Option<i32>, // TODO: If payment method is adeudo assign the ticket to the sold_room
+        payments: Vec<InvoicePayment>,
+        sold_room_id: Option<i32>,
     ) -> Result<(), sqlx::Error> {
         let mut transaction: Transaction<Postgres> = pool.begin().await?;
+        let payment_method = InvoicePayment::main_method(&payments);
 
         // get the temporal ticket with the given id
         let temporal_ticket = sqlx::query!(
@@ -241,7 +449,7 @@ impl SimpleInvoice {
         .await?;
 
         // if the temporal ticket has simple_invoice_id, update it's paid bool and payment_method_id
-        if let Some(simple_invoice_id) = temporal_ticket.simple_invoice_id {
+        let simple_invoice_id = if let Some(simple_invoice_id) = temporal_ticket.simple_invoice_id {
             sqlx::query!(
                 r#"
                 UPDATE simple_invoices SET paid = TRUE, payment_method_id = $1 WHERE id = $2
@@ -252,36 +460,17 @@ impl SimpleInvoice {
             .execute(&mut *transaction)
             .await?;
 
-            // if the paymeent method is adeudo add the simple invoice to the sold room
-            if payment_method == PaymentMethod::Adeudo {
-                if let Some(sold_room_id) = sold_room_id {
-                    sqlx::query!(
-                        r#"
-                            INSERT INTO sold_room_invoices (sold_room_id, simple_invoice_id)
-                            VALUES ($1, $2)
-                        
-                        "#,
-                        sold_room_id,
-                        simple_invoice_id
-                    )
-                    .execute(&mut *transaction)
-                    .await?;
-                } else {
-                    eprintln!("Error, adeudo without sold_room_id hit the db");
-                    return Err(sqlx::Error::Protocol(
-                        "Missing sold_room_id for adeudo hit the db".into(),
-                    ));
-                }
-            }
+            simple_invoice_id
         } else {
             // if the temporal ticket is not yet a simple_invoice_id create it with the data of the retrieved temporal ticket
             let invoice = sqlx::query!(
                 r#"
-                INSERT INTO simple_invoices (payment_method_id, paid, is_deleted)
-                VALUES ($1, TRUE, FALSE)
+                INSERT INTO simple_invoices (payment_method_id, paid, is_deleted, ticket_discount_reason)
+                VALUES ($1, TRUE, FALSE, (SELECT discount_reason FROM temporal_tickets WHERE id = $2))
                 RETURNING id
                 "#,
-                payment_method.to_id()
+                payment_method.to_id(),
+                temporal_ticket_id
             )
             .fetch_one(&mut *transaction)
             .await?;
@@ -289,7 +478,7 @@ impl SimpleInvoice {
             // retrieve temporal products associated with the temporal ticket
             let temporal_products = sqlx::query!(
                 r#"
-                SELECT original_product_id, price FROM temporal_products WHERE temporal_ticket_id = $1
+                SELECT original_product_id, quantity, price, modifiers, original_price, discount_reason FROM temporal_products WHERE temporal_ticket_id = $1
                 "#,
                 temporal_ticket_id
             )
@@ -300,39 +489,29 @@ impl SimpleInvoice {
             for product in temporal_products {
                 sqlx::query!(
                     r#"
-                    INSERT INTO sold_products (simple_invoice_id, original_product_id, price)
-                    VALUES ($1, $2, $3)
+                    INSERT INTO sold_products (simple_invoice_id, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, (SELECT COALESCE(tax_percentage, 21) FROM products WHERE id = $2))
                     "#,
                     invoice.id,
                     product.original_product_id,
-                    product.price
+                    product.quantity,
+                    product.price,
+                    product.modifiers,
+                    product.original_price,
+                    product.discount_reason
                 )
                 .execute(&mut *transaction)
                 .await?;
             }
 
-            // if the paymeent method is adeudo add the simple invoice to the sold room
-            if payment_method == PaymentMethod::Adeudo {
-                if let Some(sold_room_id) = sold_room_id {
-                    sqlx::query!(
-                        r#"
-                            INSERT INTO sold_room_invoices (sold_room_id, simple_invoice_id)
-                            VALUES ($1, $2)
-                        
-                        "#,
-                        sold_room_id,
-                        invoice.id
-                    )
-                    .execute(&mut *transaction)
-                    .await?;
-                } else {
-                    eprintln!("Error, adeudo without sold_room_id hit the db");
-                    return Err(sqlx::Error::Protocol(
-                        "Missing sold_room_id for adeudo hit the db".into(),
-                    ));
-                }
-            }
-        }
+            invoice.id
+        };
+
+        Self::register_payments(&mut transaction, simple_invoice_id, &payments, sold_room_id)
+            .await?;
+        InvoiceSeries::assign_number(&mut transaction, simple_invoice_id).await?;
+        InvoiceRecord::register(&mut transaction, simple_invoice_id).await?;
+        StockMovement::register_invoice_sale(&mut transaction, simple_invoice_id).await?;
 
         // delete the temporal ticket (temporal products will be deleted by on_cascade of the db)
         sqlx::query!(
@@ -348,22 +527,259 @@ impl SimpleInvoice {
         Ok(())
     }
 
+    /// Pays part of a temporal ticket as its own (paid) simple invoice, removing the paid part from the ticket.
+    /// Returns true if nothing is left to pay (the temporal ticket has been deleted)
+    pub async fn pay_temporal_ticket_split(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+        split: TicketSplit,
+        payments: Vec<InvoicePayment>,
+        sold_room_id: Option<i32>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+        let payment_method = InvoicePayment::main_method(&payments);
+
+        let temporal_ticket = sqlx::query!(
+            r#"
+            SELECT simple_invoice_id FROM temporal_tickets WHERE id = $1 FOR UPDATE
+            "#,
+            temporal_ticket_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        // a printed (locked) ticket has its own invoice (maybe with a recipient) waiting to be paid as a whole
+        if temporal_ticket.simple_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is locked, unlock it before splitting it".into(),
+            ));
+        }
+
+        let invoice = sqlx::query!(
+            r#"
+            INSERT INTO simple_invoices (payment_method_id, paid, is_deleted, ticket_discount_reason)
+            VALUES ($1, TRUE, FALSE, (SELECT discount_reason FROM temporal_tickets WHERE id = $2))
+            RETURNING id
+            "#,
+            payment_method.to_id(),
+            temporal_ticket_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let temporal_products = sqlx::query!(
+            r#"
+            SELECT id, original_product_id, quantity, price, modifiers, original_price, discount_reason, counted_quantity FROM temporal_products
+            WHERE temporal_ticket_id = $1 AND quantity > 0
+            ORDER BY id
+            "#,
+            temporal_ticket_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let mut paid_lines = 0;
+        for product in temporal_products {
+            let line = split_line(
+                &split,
+                product.id,
+                product.quantity,
+                product.price.map(Money::from).unwrap_or_default(),
+                product.original_price.map(Money::from),
+                product.counted_quantity,
+            );
+
+            if line.quantity == 0 {
+                continue;
+            }
+
+            sqlx::query!(
+                r#"
+                INSERT INTO sold_products (simple_invoice_id, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage, counted_quantity)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, (SELECT COALESCE(tax_percentage, 21) FROM products WHERE id = $2), $8)
+                "#,
+                invoice.id,
+                product.original_product_id,
+                line.quantity,
+                Some(Decimal::from(line.price)),
+                product.modifiers,
+                line.original_price.map(Decimal::from),
+                product.discount_reason,
+                line.counted_quantity
+            )
+            .execute(&mut *transaction)
+            .await?;
+            paid_lines += 1;
+
+            StockMovement::register_sale(
+                &mut transaction,
+                invoice.id,
+                product.original_product_id,
+                line.counted_quantity,
+            )
+            .await?;
+
+            if line.remaining_quantity == 0 {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM temporal_products WHERE id = $1
+                    "#,
+                    product.id
+                )
+                .execute(&mut *transaction)
+                .await?;
+            } else {
+                sqlx::query!(
+                    r#"
+                    UPDATE temporal_products SET quantity = $1, price = $2, original_price = $3, sent_quantity = LEAST(sent_quantity, $1),
+                        counted_quantity = $4
+                    WHERE id = $5
+                    "#,
+                    line.remaining_quantity,
+                    Some(Decimal::from(line.remaining_price)),
+                    line.remaining_original_price.map(Decimal::from),
+                    line.remaining_counted_quantity,
+                    product.id
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        if paid_lines == 0 {
+            return Err(sqlx::Error::Protocol("Nothing selected to pay".into()));
+        }
+
+        Self::register_payments(&mut transaction, invoice.id, &payments, sold_room_id).await?;
+        InvoiceSeries::assign_number(&mut transaction, invoice.id).await?;
+        InvoiceRecord::register(&mut transaction, invoice.id).await?;
+
+        // once everything has been paid the temporal ticket is deleted (and the table freed)
+        let remaining = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM temporal_products
+            WHERE temporal_ticket_id = $1 AND quantity > 0
+            "#,
+            temporal_ticket_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let closed = remaining.count == 0;
+        if closed {
+            sqlx::query!(
+                r#"
+                DELETE FROM temporal_tickets WHERE id = $1
+                "#,
+                temporal_ticket_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(closed)
+    }
+
+    /// Stores the payments of a paid simple invoice, checking they add up to its total,
+    /// and links the invoice to the sold room if any of them is an adeudo
+    /// (the room only owes the amount of the adeudo payments, not the whole invoice)
+    async fn register_payments(
+        transaction: &mut Transaction<'_, Postgres>,
+        simple_invoice_id: i32,
+        payments: &[InvoicePayment],
+        sold_room_id: Option<i32>,
+    ) -> Result<(), sqlx::Error> {
+        let invoice_total = sqlx::query!(
+            r#"
+            SELECT COALESCE(SUM(price * quantity), 0) as "total!" FROM sold_products WHERE simple_invoice_id = $1
+            "#,
+            simple_invoice_id
+        )
+        .fetch_one(&mut **transaction)
+        .await?;
+
+        let paid: Money = payments.iter().map(|p| p.amount).sum();
+        if paid != Money::from(invoice_total.total) {
+            return Err(sqlx::Error::Protocol(
+                "The payments don't add up to the invoice total".into(),
+            ));
+        }
+
+        for payment in payments {
+            sqlx::query!(
+                r#"
+                INSERT INTO invoice_payments (simple_invoice_id, payment_method_id, amount)
+                VALUES ($1, $2, $3)
+                "#,
+                simple_invoice_id,
+                payment.payment_method.to_id(),
+                Decimal::from(payment.amount)
+            )
+            .execute(&mut **transaction)
+            .await?;
+        }
+
+        // if any of the payments is an adeudo add the simple invoice to the sold room
+        if payments
+            .iter()
+            .any(|p| p.payment_method == PaymentMethod::Adeudo)
+        {
+            if let Some(sold_room_id) = sold_room_id {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO sold_room_invoices (sold_room_id, simple_invoice_id)
+                    VALUES ($1, $2)
+                    "#,
+                    sold_room_id,
+                    simple_invoice_id
+                )
+                .execute(&mut **transaction)
+                .await?;
+            } else {
+                eprintln!("Error, adeudo without sold_room_id hit the db");
+                return Err(sqlx::Error::Protocol(
+                    "Missing sold_room_id for adeudo hit the db".into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Retrieves all simple invoices from the database
     pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<SimpleInvoice>, sqlx::Error> {
-        use sqlx::Row;
-
         let rows = sqlx::query(
             "SELECT
             si.id,
             si.payment_method_id,
             si.paid,
+            si.recipient_name,
+            si.recipient_nif,
+            si.recipient_address,
+            si.company_id,
+            si.rectified_invoice_id,
+            si.rectification_reason,
+            si.replaced_invoice_id,
+            si.invoice_number,
+            si.record_generated_at,
+            si.ticket_discount_reason,
+            (SELECT r.id FROM simple_invoices r WHERE r.rectified_invoice_id = si.id) as rectifying_invoice_id,
+            (SELECT o.invoice_number FROM simple_invoices o WHERE o.id = si.rectified_invoice_id) as rectified_invoice_number,
+            (SELECT f.id FROM simple_invoices f WHERE f.replaced_invoice_id = si.id) as replacing_invoice_id,
+            (SELECT o.invoice_number FROM simple_invoices o WHERE o.id = si.replaced_invoice_id) as replaced_invoice_number,
             si.is_deleted,
             si.created_at,
             si.updated_at,
             sp.id as product_id,
             sp.simple_invoice_id,
             sp.original_product_id,
+            sp.quantity,
             sp.price,
+            sp.modifiers,
+            sp.original_price,
+            sp.discount_reason,
+            sp.tax_percentage as sold_tax_percentage,
             p.id as original_product_id_field,
             p.category_id,
             p.name as product_name,
@@ -393,6 +809,30 @@ impl SimpleInvoice {
                 let payment_method_id: Option<i32> =
                     row.try_get("payment_method_id").unwrap_or(None);
                 let paid: bool = row.try_get("paid").unwrap_or(false);
+                let recipient_name: Option<String> = row.try_get("recipient_name").unwrap_or(None);
+                let recipient_nif: Option<String> = row.try_get("recipient_nif").unwrap_or(None);
+                let recipient_address: Option<String> =
+                    row.try_get("recipient_address").unwrap_or(None);
+                let company_id: Option<i32> = row.try_get("company_id").unwrap_or(None);
+                let rectified_invoice_id: Option<i32> =
+                    row.try_get("rectified_invoice_id").unwrap_or(None);
+                let rectification_reason: Option<String> =
+                    row.try_get("rectification_reason").unwrap_or(None);
+                let replaced_invoice_id: Option<i32> =
+                    row.try_get("replaced_invoice_id").unwrap_or(None);
+                let invoice_number: Option<String> = row.try_get("invoice_number").unwrap_or(None);
+                let record_generated_at: Option<String> =
+                    row.try_get("record_generated_at").unwrap_or(None);
+                let ticket_discount_reason: Option<String> =
+                    row.try_get("ticket_discount_reason").unwrap_or(None);
+                let rectifying_invoice_id: Option<i32> =
+                    row.try_get("rectifying_invoice_id").unwrap_or(None);
+                let rectified_invoice_number: Option<String> =
+                    row.try_get("rectified_invoice_number").unwrap_or(None);
+                let replacing_invoice_id: Option<i32> =
+                    row.try_get("replacing_invoice_id").unwrap_or(None);
+                let replaced_invoice_number: Option<String> =
+                    row.try_get("replaced_invoice_number").unwrap_or(None);
                 let is_deleted: bool = row.try_get("is_deleted").unwrap_or(false);
                 let created_at: Option<NaiveDateTime> = row.try_get("created_at").unwrap_or(None);
                 let updated_at: Option<NaiveDateTime> = row.try_get("updated_at").unwrap_or(None);
@@ -403,9 +843,25 @@ impl SimpleInvoice {
                         .unwrap_or_default(),
                     products: Vec::new(),
                     paid,
+                    recipient: InvoiceRecipient::from_columns(
+                        recipient_name,
+                        recipient_nif,
+                        recipient_address,
+                        company_id,
+                    ),
+                    rectified_invoice_id,
+                    rectification_reason,
+                    replaced_invoice_id,
+                    invoice_number,
+                    record_generated_at,
+                    ticket_discount_reason,
                     is_deleted,
                     created_at,
                     updated_at,
+                    rectifying_invoice_id,
+                    rectified_invoice_number,
+                    replacing_invoice_id,
+                    replaced_invoice_number,
                 }
             });
 
@@ -413,15 +869,20 @@ impl SimpleInvoice {
             if let Some(product_id) = product_id {
                 let simple_invoice_id: i32 = row.try_get("simple_invoice_id")?;
                 let original_product_id: i32 = row.try_get("original_product_id")?;
-                let price: Option<f32> = row.try_get("price")?;
+                let quantity: i32 = row.try_get("quantity")?;
+                let price: Option<Money> = row.try_get("price")?;
+                let modifiers: String = row.try_get("modifiers")?;
+                let original_price: Option<Money> = row.try_get("original_price")?;
+                let discount_reason: Option<String> = row.try_get("discount_reason")?;
+                let sold_tax_percentage: Decimal = row.try_get("sold_tax_percentage")?;
 
                 let original_product_id_field: Option<i32> =
                     row.try_get("original_product_id_field")?;
                 let category_id: Option<i32> = row.try_get("category_id")?;
                 let product_name: String = row.try_get("product_name")?;
-                let inside_price: Option<f32> = row.try_get("inside_price")?;
-                let outside_price: Option<f32> = row.try_get("outside_price")?;
-                let tax_percentage: Option<f32> = row.try_get("tax_percentage")?;
+                let inside_price: Option<Money> = row.try_get("inside_price")?;
+                let outside_price: Option<Money> = row.try_get("outside_price")?;
+                let tax_percentage: Option<Decimal> = row.try_get("tax_percentage")?;
                 let product_is_deleted: bool = row.try_get("product_is_deleted")?;
                 let product_created_at: Option<NaiveDateTime> =
                     row.try_get("product_created_at")?;
@@ -435,6 +896,8 @@ impl SimpleInvoice {
                     inside_price,
                     outside_price,
                     tax_percentage,
+                    stock_quantity: None,
+                    low_stock_threshold: None,
                     is_deleted: product_is_deleted,
                     created_at: product_created_at,
                     updated_at: product_updated_at,
@@ -442,13 +905,19 @@ impl SimpleInvoice {
                     inside_price_input: String::new(),
                     outside_price_input: String::new(),
                     tax_percentage_input: String::new(),
+                    low_stock_threshold_input: String::new(),
                 };
 
                 let sold_product = SoldProduct {
                     id: Some(product_id),
                     simple_invoice_id,
                     original_product_id,
+                    quantity,
                     price,
+                    original_price,
+                    discount_reason,
+                    modifiers,
+                    tax_percentage: sold_tax_percentage,
                     original_product,
                 };
 
@@ -462,13 +931,457 @@ impl SimpleInvoice {
         Ok(result)
     }
 
-    /// delete the simple_invoice (the db cascade will handle sold_products deletion, will delete the simple invoice from any adeudo...)
-    pub async fn delete(pool: Arc<PgPool>, simple_invoice_id: i32) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM simple_invoices WHERE id = $1")
-            .bind(simple_invoice_id)
-            .execute(pool.as_ref())
+    /// Refunds a paid simple invoice issuing a new (negative) invoice that rectifies it, numbered on the rectifying series.
+    /// The payments are returned with the same methods, it's charged to the same sold room (if it was an adeudo)
+    /// and what was sold is put back in stock. Full invoices issued in place of a simplified invoice are refunded
+    /// with the payments of the simplified invoice. Returns the id of the rectifying invoice
+    pub async fn refund(
+        pool: Arc<PgPool>,
+        simple_invoice_id: i32,
+        reason: String,
+    ) -> Result<i32, sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        let row = sqlx::query(
+            "SELECT paid, rectified_invoice_id, replaced_invoice_id, payment_method_id, recipient_name, recipient_nif, recipient_address,
+                company_id,
+                EXISTS (SELECT 1 FROM simple_invoices r WHERE r.rectified_invoice_id = si.id) as rectified,
+                EXISTS (SELECT 1 FROM simple_invoices f WHERE f.replaced_invoice_id = si.id) as replaced
+            FROM simple_invoices si WHERE id = $1 FOR UPDATE",
+        )
+        .bind(simple_invoice_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let paid: bool = row.try_get("paid")?;
+        let rectified_invoice_id: Option<i32> = row.try_get("rectified_invoice_id")?;
+        let rectified: bool = row.try_get("rectified")?;
+        let replaced: bool = row.try_get("replaced")?;
+        if !paid || rectified_invoice_id.is_some() || rectified || replaced {
+            return Err(sqlx::Error::Protocol(
+                "Only paid invoices that haven't been refunded nor replaced yet can be refunded"
+                    .into(),
+            ));
+        }
+        // the sale (payments, room charge and stock) belongs to the simplified invoice if this one replaces it
+        let replaced_invoice_id: Option<i32> = row.try_get("replaced_invoice_id")?;
+        let sale_invoice_id = replaced_invoice_id.unwrap_or(simple_invoice_id);
+
+        let rectifying_invoice_id: i32 = sqlx::query_scalar(
+            "INSERT INTO simple_invoices (payment_method_id, paid, recipient_name, recipient_nif, recipient_address,
+                company_id, rectified_invoice_id, rectification_reason, ticket_discount_reason, is_deleted)
+            VALUES ($1, TRUE, $2, $3, $4, $5, $6, $7, (SELECT ticket_discount_reason FROM simple_invoices WHERE id = $6), FALSE)
+            RETURNING id",
+        )
+        .bind(row.try_get::<i32, _>("payment_method_id")?)
+        .bind(row.try_get::<Option<String>, _>("recipient_name")?)
+        .bind(row.try_get::<Option<String>, _>("recipient_nif")?)
+        .bind(row.try_get::<Option<String>, _>("recipient_address")?)
+        .bind(row.try_get::<Option<i32>, _>("company_id")?)
+        .bind(simple_invoice_id)
+        .bind(reason.trim())
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO sold_products (simple_invoice_id, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage, counted_quantity)
+            SELECT $1, original_product_id, -quantity, price, modifiers, original_price, discount_reason, tax_percentage, -counted_quantity
+            FROM sold_products WHERE simple_invoice_id = $2
+            ORDER BY id",
+        )
+        .bind(rectifying_invoice_id)
+        .bind(simple_invoice_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO invoice_payments (simple_invoice_id, payment_method_id, amount)
+            SELECT $1, payment_method_id, -amount FROM invoice_payments WHERE simple_invoice_id = $2",
+        )
+        .bind(rectifying_invoice_id)
+        .bind(sale_invoice_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO sold_room_invoices (sold_room_id, simple_invoice_id)
+            SELECT sold_room_id, $1 FROM sold_room_invoices WHERE simple_invoice_id = $2",
+        )
+        .bind(rectifying_invoice_id)
+        .bind(sale_invoice_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        StockMovement::restore_invoice_sale(&mut transaction, sale_invoice_id).await?;
+        InvoiceSeries::assign_number(&mut transaction, rectifying_invoice_id).await?;
+        InvoiceRecord::register(&mut transaction, rectifying_invoice_id).await?;
+
+        transaction.commit().await?;
+        Ok(rectifying_invoice_id)
+    }
+
+    /// Issues a full invoice for the given recipient in place of a paid simplified invoice, numbered on the full series.
+    /// The simplified invoice stays as it was and keeps the sale (payments, room charge and stock),
+    /// the full invoice only has its same lines. Returns the id of the full invoice
+    pub async fn issue_full_invoice(
+        pool: Arc<PgPool>,
+        simple_invoice_id: i32,
+        recipient: InvoiceRecipient,
+    ) -> Result<i32, sqlx::Error> {
+        if !recipient.is_valid() {
+            return Err(sqlx::Error::Protocol(
+                "Missing invoice recipient data".into(),
+            ));
+        }
+
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        let row = sqlx::query(
+            "SELECT paid, payment_method_id, recipient_name IS NOT NULL as has_recipient, rectified_invoice_id IS NOT NULL as rectifying,
+                EXISTS (SELECT 1 FROM simple_invoices r WHERE r.rectified_invoice_id = si.id) as rectified,
+                EXISTS (SELECT 1 FROM simple_invoices f WHERE f.replaced_invoice_id = si.id) as replaced
+            FROM simple_invoices si WHERE id = $1 FOR UPDATE",
+        )
+        .bind(simple_invoice_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let paid: bool = row.try_get("paid")?;
+        let has_recipient: bool = row.try_get("has_recipient")?;
+        let rectifying: bool = row.try_get("rectifying")?;
+        let rectified: bool = row.try_get("rectified")?;
+        let replaced: bool = row.try_get("replaced")?;
+        if !paid || has_recipient || rectifying || rectified || replaced {
+            return Err(sqlx::Error::Protocol(
+                "Only paid simplified invoices that haven't been refunded nor replaced yet can be replaced by a full invoice".into(),
+            ));
+        }
+
+        let full_invoice_id: i32 = sqlx::query_scalar(
+            "INSERT INTO simple_invoices (payment_method_id, paid, recipient_name, recipient_nif, recipient_address,
+                company_id, replaced_invoice_id, ticket_discount_reason, is_deleted)
+            VALUES ($1, TRUE, $2, $3, $4, $5, $6, (SELECT ticket_discount_reason FROM simple_invoices WHERE id = $6), FALSE)
+            RETURNING id",
+        )
+        .bind(row.try_get::<i32, _>("payment_method_id")?)
+        .bind(recipient.name.trim())
+        .bind(recipient.nif.trim())
+        .bind(recipient.address.trim())
+        .bind(recipient.company_id)
+        .bind(simple_invoice_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO sold_products (simple_invoice_id, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage, counted_quantity)
+            SELECT $1, original_product_id, quantity, price, modifiers, original_price, discount_reason, tax_percentage, counted_quantity
+            FROM sold_products WHERE simple_invoice_id = $2
+            ORDER BY id",
+        )
+        .bind(full_invoice_id)
+        .bind(simple_invoice_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        InvoiceSeries::assign_number(&mut transaction, full_invoice_id).await?;
+        InvoiceRecord::register(&mut transaction, full_invoice_id).await?;
+
+        transaction.commit().await?;
+        Ok(full_invoice_id)
+    }
+
+    /// Issues the (paid) final invoice of a check-out with the given lines, a full invoice if there is a recipient.
+    /// Returns the id of the final invoice
+    pub(crate) async fn issue_final_invoice(
+        transaction: &mut Transaction<'_, Postgres>,
+        lines: &[FolioLine],
+        payments: &[InvoicePayment],
+        recipient: Option<&InvoiceRecipient>,
+    ) -> Result<i32, sqlx::Error> {
+        if let Some(recipient) = recipient
+            && !recipient.is_valid()
+        {
+            return Err(sqlx::Error::Protocol(
+                "Missing invoice recipient data".into(),
+            ));
+        }
+
+        let final_invoice_id: i32 = sqlx::query_scalar(
+            "INSERT INTO simple_invoices (payment_method_id, paid, recipient_name, recipient_nif, recipient_address,
+                company_id, is_deleted)
+            VALUES ($1, TRUE, $2, $3, $4, $5, FALSE)
+            RETURNING id",
+        )
+        .bind(InvoicePayment::main_method(payments).to_id())
+        .bind(recipient.map(|r| r.name.trim()))
+        .bind(recipient.map(|r| r.nif.trim()))
+        .bind(recipient.map(|r| r.address.trim()))
+        .bind(recipient.and_then(|r| r.company_id))
+        .fetch_one(&mut **transaction)
+        .await?;
+
+        for line in lines {
+            sqlx::query(
+                "INSERT INTO sold_products (simple_invoice_id, original_product_id, quantity, price, modifiers, tax_percentage)
+                VALUES ($1, $2, $3, $4, $5, (SELECT COALESCE(tax_percentage, 21) FROM products WHERE id = $2))",
+            )
+            .bind(final_invoice_id)
+            .bind(line.product_id)
+            .bind(line.quantity)
+            .bind(line.price)
+            .bind(&line.modifiers)
+            .execute(&mut **transaction)
             .await?;
+        }
 
-        Ok(())
+        Self::register_payments(transaction, final_invoice_id, payments, None).await?;
+        InvoiceSeries::assign_number(transaction, final_invoice_id).await?;
+        InvoiceRecord::register(transaction, final_invoice_id).await?;
+        StockMovement::register_invoice_sale(transaction, final_invoice_id).await?;
+
+        Ok(final_invoice_id)
+    }
+}
+
+/// Part of a temporal product that goes into one bill of a split ticket and what's left of it on the ticket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SplitLine {
+    quantity: i32,
+    price: Money,
+    original_price: Option<Money>,
+    /// Quantity the bill takes out of the stock (what refunding the bill puts back)
+    counted_quantity: i32,
+    /// Nothing is left of the temporal product when it's 0
+    remaining_quantity: i32,
+    remaining_price: Money,
+    remaining_original_price: Option<Money>,
+    /// Quantity of what's left that the bills already paid have taken out of the stock
+    remaining_counted_quantity: i32,
+}
+
+/// Returns the part of the given temporal product (id, quantity, unit price, unit price before the discount
+/// and quantity already taken out of the stock) that goes into the bill of the given split
+fn split_line(
+    split: &TicketSplit,
+    id: i32,
+    quantity: i32,
+    price: Money,
+    original_price: Option<Money>,
+    counted_quantity: i32,
+) -> SplitLine {
+    // part of the quantity that no earlier share of an even split has taken out of the stock
+    let uncounted_quantity = (quantity - counted_quantity).max(0);
+
+    let (
+        paid_quantity,
+        paid_price,
+        paid_original_price,
+        paid_counted_quantity,
+        remaining_quantity,
+        remaining_price,
+        remaining_original_price,
+    ) = match split {
+        TicketSplit::Lines(lines) => {
+            let paid_quantity = lines
+                .iter()
+                .find(|(line_id, _)| *line_id == id)
+                .map(|(_, paid_quantity)| (*paid_quantity).clamp(0, quantity))
+                .unwrap_or_default();
+            (
+                paid_quantity,
+                price,
+                original_price,
+                paid_quantity.min(uncounted_quantity),
+                quantity - paid_quantity,
+                price,
+                original_price,
+            )
+        }
+        TicketSplit::Evenly(payers) => {
+            let share = price.share(*payers);
+            let original_share = original_price.map(|price| price.share(*payers));
+            let (remaining_quantity, counted_quantity) = if share == price {
+                (0, uncounted_quantity)
+            } else {
+                (quantity, share_quantity(uncounted_quantity, *payers))
+            };
+            (
+                quantity,
+                share,
+                original_share,
+                counted_quantity,
+                remaining_quantity,
+                price - share,
+                original_price
+                    .zip(original_share)
+                    .map(|(price, share)| price - share),
+            )
+        }
+    };
+
+    SplitLine {
+        quantity: paid_quantity,
+        price: paid_price,
+        original_price: paid_original_price,
+        counted_quantity: paid_counted_quantity,
+        remaining_quantity,
+        remaining_price,
+        remaining_original_price,
+        remaining_counted_quantity: (remaining_quantity
+            - (uncounted_quantity - paid_counted_quantity))
+            .max(0),
+    }
+}
+
+/// Returns the part of the quantity of a line that one share of a ticket split evenly takes out of the stock,
+/// given the quantity no earlier share has taken yet and the number of payers still to pay (rounded to units,
+/// the last payer takes whatever is left)
+fn share_quantity(uncounted_quantity: i32, payers: i32) -> i32 {
+    let uncounted_quantity = uncounted_quantity.max(0);
+    if payers <= 1 {
+        return uncounted_quantity;
+    }
+
+    (uncounted_quantity + payers / 2) / payers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the quantity every share of a line split evenly between the given payers takes out of the stock
+    fn shares(quantity: i32, payers: i32) -> Vec<i32> {
+        let mut counted = 0;
+        (1..=payers)
+            .rev()
+            .map(|payers| {
+                let share = share_quantity(quantity - counted, payers);
+                counted += share;
+                share
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shares_of_an_even_split_count_the_quantity_once() {
+        for quantity in 0..=12 {
+            for payers in 1..=7 {
+                let shares = shares(quantity, payers);
+                assert_eq!(shares.len(), payers as usize);
+                assert_eq!(
+                    shares.iter().sum::<i32>(),
+                    quantity,
+                    "{quantity} / {payers}"
+                );
+                // the quantity is spread between the shares, not left to the last one
+                let (min, max) = (shares.iter().min(), shares.iter().max());
+                assert!(
+                    max.zip(min).is_some_and(|(max, min)| max - min <= 1),
+                    "{shares:?}"
+                );
+            }
+        }
+
+        assert_eq!(shares(3, 2), vec![2, 1]);
+        assert_eq!(shares(1, 3), vec![0, 1, 0]);
+        assert_eq!(share_quantity(-1, 2), 0);
+    }
+
+    /// Pays a line (quantity and unit price) split evenly between the given payers, returns the line of every bill
+    fn pay_evenly(quantity: i32, price: Money, payers: i32) -> Vec<SplitLine> {
+        let (mut price, mut counted_quantity) = (price, 0);
+        (1..=payers)
+            .rev()
+            .map(|payers| {
+                let line = split_line(
+                    &TicketSplit::Evenly(payers),
+                    1,
+                    quantity,
+                    price,
+                    None,
+                    counted_quantity,
+                );
+                (price, counted_quantity) = (line.remaining_price, line.remaining_counted_quantity);
+                line
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_bill_of_an_even_split_takes_only_its_part_of_the_stock() {
+        let bills = pay_evenly(5, Money::from(Decimal::new(300, 2)), 3);
+
+        // refunding a bill puts back what it took out of the stock (the movements of its counted quantity)
+        let counted: Vec<i32> = bills.iter().map(|line| line.counted_quantity).collect();
+        assert_eq!(counted, vec![2, 2, 1]);
+        assert!(bills.iter().all(|line| line.quantity == 5));
+        assert!(
+            bills
+                .iter()
+                .all(|line| line.price == Money::from(Decimal::new(100, 2)))
+        );
+        assert_eq!(bills.last().map(|line| line.remaining_quantity), Some(0));
+
+        for quantity in 0..=12 {
+            for payers in 1..=7 {
+                let bills = pay_evenly(quantity, Money::from(Decimal::new(1000, 2)), payers);
+                assert_eq!(
+                    bills.iter().map(|line| line.counted_quantity).sum::<i32>(),
+                    quantity,
+                    "{quantity} / {payers}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn paying_by_lines_after_an_even_bill_takes_only_what_is_left_of_the_stock() {
+        let price = Money::from(Decimal::new(300, 2));
+        let first = split_line(&TicketSplit::Evenly(3), 1, 5, price, None, 0);
+        assert_eq!(first.counted_quantity, 2);
+        assert_eq!(first.remaining_counted_quantity, 2);
+
+        let second = split_line(
+            &TicketSplit::Lines(vec![(1, 1)]),
+            1,
+            first.remaining_quantity,
+            first.remaining_price,
+            None,
+            first.remaining_counted_quantity,
+        );
+        assert_eq!(second.quantity, 1);
+        assert_eq!(second.price, Money::from(Decimal::new(200, 2)));
+        assert_eq!(second.counted_quantity, 1);
+        assert_eq!(second.remaining_quantity, 4);
+        assert_eq!(second.remaining_counted_quantity, 2);
+
+        // lines that aren't selected aren't paid
+        let other_line = split_line(
+            &TicketSplit::Lines(vec![(2, 4)]),
+            1,
+            second.remaining_quantity,
+            second.remaining_price,
+            None,
+            second.remaining_counted_quantity,
+        );
+        assert_eq!(other_line.quantity, 0);
+        assert_eq!(other_line.counted_quantity, 0);
+
+        // nor more than the quantity of the line
+        let third = split_line(
+            &TicketSplit::Lines(vec![(1, 9)]),
+            1,
+            second.remaining_quantity,
+            second.remaining_price,
+            None,
+            second.remaining_counted_quantity,
+        );
+        assert_eq!(third.quantity, 4);
+        assert_eq!(third.counted_quantity, 2);
+        assert_eq!(third.remaining_quantity, 0);
+        assert_eq!(
+            first.counted_quantity + second.counted_quantity + third.counted_quantity,
+            5
+        );
     }
 }
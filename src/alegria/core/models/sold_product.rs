@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::alegria::utils::money::Money;
+
 use super::product::Product;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,8 +12,33 @@ pub struct SoldProduct {
     pub id: Option<i32>,
     pub simple_invoice_id: i32,
     pub original_product_id: i32,
-    pub price: Option<f32>,
+    pub quantity: i32,
+    pub price: Option<Money>, // Unit price (with the discount applied)
+    /// Unit price before the discount, None if the line had no discount
+    pub original_price: Option<Money>,
+    pub discount_reason: Option<String>,
+    /// Modifiers of the line ("no onion, extra cheese"), already included on the price
+    pub modifiers: String,
+    /// Tax percentage of the product when the line was sold (the taxes of issued invoices never change)
+    pub tax_percentage: Decimal,
 
     // Not in the db
     pub original_product: Product,
 }
+
+impl SoldProduct {
+    /// Returns the total price of the line (quantity * unit price)
+    pub fn total_price(&self) -> Money {
+        self.price.unwrap_or_default() * self.quantity
+    }
+
+    /// Returns the total price of the line before its discount
+    pub fn original_total_price(&self) -> Money {
+        self.original_price.or(self.price).unwrap_or_default() * self.quantity
+    }
+
+    /// Returns the amount discounted from the whole line
+    pub fn discount(&self) -> Money {
+        self.original_total_price() - self.total_price()
+    }
+}
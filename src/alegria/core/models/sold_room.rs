@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use crate::alegria::utils::money::Money;
+
 use super::{client::Client, simple_invoice::SimpleInvoice};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,9 +12,24 @@ pub struct SoldRoom {
     pub id: Option<i32>,
     pub room_id: Option<i32>,
     pub guests: Vec<Client>,
-    pub price: Option<f32>,
+    pub price: Option<Money>,
     pub invoices: Vec<SimpleInvoice>,
 
     // not in the db
     pub room_name: Box<str>,
+    pub price_input: String, // Helps us input prices on TextInputs
+}
+
+impl SoldRoom {
+    /// Returns why the room can't be checked in on the given date, None if it can
+    /// (it needs at least one adult guest and every guest has to be registrable)
+    pub fn check_in_error(&self, date: NaiveDate) -> Option<&'static str> {
+        if !self.guests.iter().any(|guest| guest.is_adult_on(date)) {
+            return Some("Every room needs at least one adult guest");
+        }
+
+        self.guests
+            .iter()
+            .find_map(|guest| guest.guest_registration_error(date))
+    }
 }
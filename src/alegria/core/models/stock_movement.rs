@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::sync::Arc;
+
+use crate::alegria::utils::entities::stock_movement_kind::StockMovementKind;
+
+/// Change of the stock of a product
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockMovement {
+    pub id: Option<i32>,
+    pub product_id: Option<i32>,
+    /// Positive adds stock, negative takes it out
+    pub quantity: Option<i32>,
+    pub kind: StockMovementKind,
+    pub reason: String,
+    pub simple_invoice_id: Option<i32>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub product_name: Box<str>, // Helps us JOIN and return the name of the product
+    pub quantity_input: String, // Helps us input on TextInputs
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for StockMovement {
+    fn default() -> Self {
+        Self {
+            id: None,
+            product_id: None,
+            quantity: None,
+            kind: StockMovementKind::default(),
+            reason: String::new(),
+            simple_invoice_id: None,
+            created_at: None,
+            updated_at: None,
+            product_name: String::new().into_boxed_str(),
+            quantity_input: String::new(),
+        }
+    }
+}
+
+impl StockMovement {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        self.product_id.is_some()
+            && self.quantity.is_some_and(|q| q != 0)
+            && !self.reason.trim().is_empty()
+    }
+
+    /// Returns the latest stock movements (newest first)
+    pub async fn get_latest(pool: Arc<PgPool>) -> Result<Vec<StockMovement>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                sm.id,
+                sm.product_id,
+                sm.quantity,
+                sm.kind,
+                sm.reason,
+                sm.simple_invoice_id,
+                sm.created_at,
+                sm.updated_at,
+                p.name as product_name
+            FROM stock_movements sm
+            JOIN products p ON p.id = sm.product_id
+            ORDER BY sm.created_at DESC, sm.id DESC
+            LIMIT 200",
+        )
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<StockMovement>::new();
+
+        for row in rows {
+            let reason: Option<String> = row.try_get("reason")?;
+            let product_name: String = row.try_get("product_name")?;
+
+            result.push(StockMovement {
+                id: row.try_get("id")?,
+                product_id: row.try_get("product_id")?,
+                quantity: row.try_get("quantity")?,
+                kind: row.try_get("kind")?,
+                reason: reason.unwrap_or_default(),
+                simple_invoice_id: row.try_get("simple_invoice_id")?,
+                created_at: row.try_get("created_at")?,
+                updated_at: row.try_get("updated_at")?,
+                product_name: product_name.into_boxed_str(),
+                ..Default::default()
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Adds a manual adjustment of the stock of a product, a product without tracked stock starts being tracked
+    pub async fn add(pool: Arc<PgPool>, stock_movement: StockMovement) -> Result<(), sqlx::Error> {
+        let mut transaction: Transaction<Postgres> = pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE products SET stock_quantity = COALESCE(stock_quantity, 0) + $1 WHERE id = $2",
+        )
+        .bind(stock_movement.quantity)
+        .bind(stock_movement.product_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO stock_movements (product_id, quantity, kind, reason) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(stock_movement.product_id)
+        .bind(stock_movement.quantity)
+        .bind(StockMovementKind::Adjustment)
+        .bind(stock_movement.reason.trim())
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Takes the sold quantity of a product out of its stock (only if it's stock is tracked) and the ingredients of its recipe
+    /// out of theirs, the movements keep the invoice so they can be restored if the invoice is deleted
+    pub async fn register_sale(
+        transaction: &mut Transaction<'_, Postgres>,
+        simple_invoice_id: i32,
+        product_id: i32,
+        quantity: i32,
+    ) -> Result<(), sqlx::Error> {
+        if quantity == 0 {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "WITH updated AS (
+                UPDATE products SET stock_quantity = stock_quantity - $1
+                WHERE id = $2 AND stock_quantity IS NOT NULL
+                RETURNING id
+            )
+            INSERT INTO stock_movements (product_id, quantity, kind, simple_invoice_id)
+            SELECT id, -$1, $3, $4 FROM updated",
+        )
+        .bind(quantity)
+        .bind(product_id)
+        .bind(StockMovementKind::Sale)
+        .bind(simple_invoice_id)
+        .execute(&mut **transaction)
+        .await?;
+
+        sqlx::query(
+            "WITH consumed AS (
+                SELECT ingredient_id, quantity * $1::INTEGER as quantity
+                FROM recipe_ingredients
+                WHERE product_id = $2
+            ),
+            updated AS (
+                UPDATE ingredients SET stock_quantity = ingredients.stock_quantity - consumed.quantity
+                FROM consumed
+                WHERE ingredients.id = consumed.ingredient_id
+                RETURNING ingredients.id, consumed.quantity
+            )
+            INSERT INTO ingredient_movements (ingredient_id, quantity, kind, simple_invoice_id)
+            SELECT id, -quantity, $3, $4 FROM updated",
+        )
+        .bind(quantity)
+        .bind(product_id)
+        .bind(StockMovementKind::Sale)
+        .bind(simple_invoice_id)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Takes every product of the given invoice out of the stock (only the products with tracked stock)
+    /// and the ingredients of their recipes
+    pub async fn register_invoice_sale(
+        transaction: &mut Transaction<'_, Postgres>,
+        simple_invoice_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "WITH sold AS (
+                SELECT original_product_id as product_id, SUM(COALESCE(counted_quantity, quantity))::INTEGER as quantity
+                FROM sold_products
+                WHERE simple_invoice_id = $1
+                GROUP BY original_product_id
+            ),
+            updated AS (
+                UPDATE products SET stock_quantity = products.stock_quantity - sold.quantity
+                FROM sold
+                WHERE products.id = sold.product_id AND products.stock_quantity IS NOT NULL
+                RETURNING products.id, sold.quantity
+            )
+            INSERT INTO stock_movements (product_id, quantity, kind, simple_invoice_id)
+            SELECT id, -quantity, $2, $1 FROM updated",
+        )
+        .bind(simple_invoice_id)
+        .bind(StockMovementKind::Sale)
+        .execute(&mut **transaction)
+        .await?;
+
+        sqlx::query(
+            "WITH consumed AS (
+                SELECT ri.ingredient_id, SUM(COALESCE(sp.counted_quantity, sp.quantity) * ri.quantity) as quantity
+                FROM sold_products sp
+                JOIN recipe_ingredients ri ON ri.product_id = sp.original_product_id
+                WHERE sp.simple_invoice_id = $1
+                GROUP BY ri.ingredient_id
+            ),
+            updated AS (
+                UPDATE ingredients SET stock_quantity = ingredients.stock_quantity - consumed.quantity
+                FROM consumed
+                WHERE ingredients.id = consumed.ingredient_id
+                RETURNING ingredients.id, consumed.quantity
+            )
+            INSERT INTO ingredient_movements (ingredient_id, quantity, kind, simple_invoice_id)
+            SELECT id, -quantity, $2, $1 FROM updated",
+        )
+        .bind(simple_invoice_id)
+        .bind(StockMovementKind::Sale)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Puts back in stock what was sold with the given invoice (intended to be called when refunding it)
+    pub async fn restore_invoice_sale(
+        transaction: &mut Transaction<'_, Postgres>,
+        simple_invoice_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "WITH sold AS (
+                SELECT product_id, SUM(quantity)::INTEGER as quantity
+                FROM stock_movements
+                WHERE simple_invoice_id = $1 AND kind = $2
+                GROUP BY product_id
+            ),
+            updated AS (
+                UPDATE products SET stock_quantity = COALESCE(products.stock_quantity, 0) - sold.quantity
+                FROM sold
+                WHERE products.id = sold.product_id
+                RETURNING products.id, sold.quantity
+            )
+            INSERT INTO stock_movements (product_id, quantity, kind, reason)
+            SELECT id, -quantity, $3, $4 FROM updated",
+        )
+        .bind(simple_invoice_id)
+        .bind(StockMovementKind::Sale)
+        .bind(StockMovementKind::Restored)
+        .bind(format!("#{simple_invoice_id}"))
+        .execute(&mut **transaction)
+        .await?;
+
+        sqlx::query(
+            "WITH consumed AS (
+                SELECT ingredient_id, SUM(quantity) as quantity
+                FROM ingredient_movements
+                WHERE simple_invoice_id = $1 AND kind = $2
+                GROUP BY ingredient_id
+            ),
+            updated AS (
+                UPDATE ingredients SET stock_quantity = ingredients.stock_quantity - consumed.quantity
+                FROM consumed
+                WHERE ingredients.id = consumed.ingredient_id
+                RETURNING ingredients.id, consumed.quantity
+            )
+            INSERT INTO ingredient_movements (ingredient_id, quantity, kind)
+            SELECT id, -quantity, $3 FROM updated",
+        )
+        .bind(simple_invoice_id)
+        .bind(StockMovementKind::Sale)
+        .bind(StockMovementKind::Restored)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::{fmt, sync::Arc};
+
+/// A location of the bar in which tables are placed (bar, restaurant, garden...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableLocation {
+    pub id: Option<i32>,
+    pub name: String,
+    pub sort_order: Option<i32>,
+    /// Tickets of inside locations use the inside price of the products, the rest use the outside price
+    pub inside: bool,
+    pub is_deleted: bool,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub sort_order_input: String, // Helps us input on TextInputs
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TableLocation {
+    fn default() -> Self {
+        Self {
+            id: None,
+            name: String::new(),
+            sort_order: Some(0),
+            inside: false,
+
+            is_deleted: false,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            sort_order_input: String::from("0"),
+        }
+    }
+}
+
+impl fmt::Display for TableLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl TableLocation {
+    /// Returns true if the entity is valid (ready for submission to the db)
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty() && self.sort_order.is_some()
+    }
+
+    pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<TableLocation>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, sort_order, inside, is_deleted, created_at, updated_at FROM table_locations WHERE is_deleted = $1 ORDER BY sort_order ASC, id ASC",
+        )
+        .bind(false)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<TableLocation>::new();
+
+        for row in rows {
+            let sort_order: Option<i32> = row.try_get("sort_order")?;
+
+            result.push(TableLocation {
+                id: row.try_get("id")?,
+                name: row.try_get("name")?,
+                sort_order,
+                inside: row.try_get("inside")?,
+                is_deleted: row.try_get("is_deleted")?,
+                created_at: row.try_get("created_at")?,
+                updated_at: row.try_get("updated_at")?,
+                sort_order_input: sort_order.unwrap_or_default().to_string(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        table_location_id: i32,
+    ) -> Result<TableLocation, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, name, sort_order, inside, is_deleted, created_at, updated_at FROM table_locations WHERE id = $1",
+        )
+        .bind(table_location_id)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        let sort_order: Option<i32> = row.try_get("sort_order")?;
+
+        Ok(TableLocation {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            sort_order,
+            inside: row.try_get("inside")?,
+            is_deleted: row.try_get("is_deleted")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            sort_order_input: sort_order.unwrap_or_default().to_string(),
+        })
+    }
+
+    pub async fn add(pool: Arc<PgPool>, table_location: TableLocation) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO table_locations (name, sort_order, inside, is_deleted) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(table_location.name.trim())
+        .bind(table_location.sort_order)
+        .bind(table_location.inside)
+        .bind(false)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn edit(pool: Arc<PgPool>, table_location: TableLocation) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE table_locations SET name = $1, sort_order = $2, inside = $3 WHERE id = $4",
+        )
+        .bind(table_location.name.trim())
+        .bind(table_location.sort_order)
+        .bind(table_location.inside)
+        .bind(table_location.id)
+        .execute(pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the given location, fails if it still has tables
+    pub async fn delete(pool: Arc<PgPool>, table_location_id: i32) -> Result<(), sqlx::Error> {
+        let tables: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM bar_tables WHERE location_id = $1 AND is_deleted = $2",
+        )
+        .bind(table_location_id)
+        .bind(false)
+        .fetch_one(pool.as_ref())
+        .await?;
+
+        if tables > 0 {
+            return Err(sqlx::Error::Protocol(
+                "The location still has tables, delete them first".into(),
+            ));
+        }
+
+        sqlx::query("UPDATE table_locations SET is_deleted = $1 WHERE id = $2")
+            .bind(true)
+            .bind(table_location_id)
+            .execute(pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Row, Transaction};
 use std::sync::Arc;
 
+use crate::alegria::utils::{entities::discount::Discount, money::Money};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalProduct {
     pub id: Option<i32>,
@@ -11,33 +13,251 @@ pub struct TemporalProduct {
     pub temporal_ticket_id: i32,
     pub name: String,
     pub quantity: i32,
-    pub price: Option<f32>,
+    pub price: Option<Money>, // Unit price (with the discount applied)
+    /// Unit price before the discount, None if the line has no discount
+    pub original_price: Option<Money>,
+    pub discount_reason: Option<String>,
+    /// Modifiers selected when the product was added ("no onion, extra cheese"), already included on the price
+    pub modifiers: String,
+    /// How much of the quantity has already been sent to the preparation station
+    pub sent_quantity: i32,
 
     // Not in the db
     pub price_input: String, // Helps us input prices on TextInputs
 }
 
 impl TemporalProduct {
+    /// Returns the quantity that hasn't been sent to the preparation station yet
+    pub fn unsent_quantity(&self) -> i32 {
+        (self.quantity - self.sent_quantity).max(0)
+    }
+
+    /// Returns the amount discounted from the whole line (quantity * unit discount)
+    pub fn discount(&self) -> Money {
+        match self.original_price {
+            Some(original_price) => {
+                (original_price - self.price.unwrap_or_default()) * self.quantity
+            }
+            None => Money::ZERO,
+        }
+    }
+
+    /// Returns the total price of the line (quantity * unit price)
+    pub fn total_price(&self) -> Money {
+        self.price.unwrap_or_default() * self.quantity
+    }
+
+    /// Edits the quantity and price of the given line, changing the price by hand removes its discount.
+    /// The total of the line can't be lowered this way, what's removed has to be voided with [`Self::void_reduction`]
     pub async fn edit(
         pool: Arc<PgPool>,
         temporal_product: TemporalProduct,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE temporal_products SET quantity = $1, price = $2 WHERE id = $3")
-            .bind(temporal_product.quantity)
-            .bind(temporal_product.price)
-            .bind(temporal_product.id)
-            .execute(pool.as_ref())
-            .await?;
+        let updated = sqlx::query(
+            "UPDATE temporal_products SET
+                quantity = $1,
+                price = $2,
+                original_price = CASE WHEN price IS NOT DISTINCT FROM $2 THEN original_price ELSE NULL END,
+                discount_reason = CASE WHEN price IS NOT DISTINCT FROM $2 THEN discount_reason ELSE NULL END
+             WHERE id = $3 AND COALESCE($2, 0) * $1 >= COALESCE(price, 0) * quantity",
+        )
+        .bind(temporal_product.quantity)
+        .bind(temporal_product.price)
+        .bind(temporal_product.id)
+        .execute(pool.as_ref())
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(sqlx::Error::Protocol(
+                "Lowering a line has to be voided with a reason".into(),
+            ));
+        }
+
+        Ok(())
+    }
 
+    /// Lowers the quantity or the price of the given line (its new values) keeping what's removed on the voided lines
+    /// with who removed it and why, a line left without quantity is voided whole
+    pub async fn void_reduction(
+        pool: Arc<PgPool>,
+        temporal_product: TemporalProduct,
+        reason: String,
+        voided_by: String,
+    ) -> Result<(), sqlx::Error> {
+        let Some(temporal_product_id) = temporal_product.id else {
+            return Err(sqlx::Error::Protocol(
+                "Can't void a line that has not been added".into(),
+            ));
+        };
+
+        if temporal_product.quantity <= 0 {
+            return Self::void(pool, temporal_product_id, reason, voided_by).await;
+        }
+
+        let mut tx = pool.begin().await?;
+
+        // the voided quantity is the number of units removed (0 if only the price is lowered), the amount is what the total drops
+        let voided_line_id: Option<i32> = sqlx::query_scalar(
+            "INSERT INTO voided_lines (temporal_ticket_id, table_id, original_product_id, name, quantity, price, amount, reason, voided_by)
+            SELECT tp.temporal_ticket_id, tt.table_id, tp.original_product_id, tp.name, GREATEST(tp.quantity - $2, 0), tp.price,
+                COALESCE(tp.price, 0) * tp.quantity - COALESCE($3, 0) * $2, $4, $5
+            FROM temporal_products tp
+            JOIN temporal_tickets tt ON tt.id = tp.temporal_ticket_id
+            WHERE tp.id = $1 AND tt.simple_invoice_id IS NULL
+            AND COALESCE($3, 0) * $2 < COALESCE(tp.price, 0) * tp.quantity
+            RETURNING id",
+        )
+        .bind(temporal_product_id)
+        .bind(temporal_product.quantity)
+        .bind(temporal_product.price)
+        .bind(reason.trim())
+        .bind(voided_by.trim())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if voided_line_id.is_none() {
+            return Err(sqlx::Error::Protocol(
+                "The line doesn't exist, its ticket is locked or its total isn't lowered".into(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE temporal_products SET
+                quantity = $1,
+                price = $2,
+                original_price = CASE WHEN price IS NOT DISTINCT FROM $2 THEN original_price ELSE NULL END,
+                discount_reason = CASE WHEN price IS NOT DISTINCT FROM $2 THEN discount_reason ELSE NULL END,
+                sent_quantity = LEAST(sent_quantity, $1)
+             WHERE id = $3",
+        )
+        .bind(temporal_product.quantity)
+        .bind(temporal_product.price)
+        .bind(temporal_product_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn delete(pool: Arc<PgPool>, temporal_product_id: i32) -> Result<(), sqlx::Error> {
+    /// Applies the given discount to the line (replacing the discount it may already have)
+    pub async fn apply_discount(
+        pool: Arc<PgPool>,
+        temporal_product_id: i32,
+        discount: Discount,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let original_price = Self::lock_original_price(&mut tx, temporal_product_id).await?;
+
+        sqlx::query(
+            "UPDATE temporal_products SET price = $1, original_price = $2, discount_reason = $3 WHERE id = $4",
+        )
+        .bind(discount.apply(original_price))
+        .bind(original_price)
+        .bind(discount.reason.trim())
+        .bind(temporal_product_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Removes the discount of the line, restoring its original price
+    pub async fn remove_discount(
+        pool: Arc<PgPool>,
+        temporal_product_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        Self::lock_original_price(&mut tx, temporal_product_id).await?;
+
+        sqlx::query(
+            "UPDATE temporal_products SET price = COALESCE(original_price, price), original_price = NULL, discount_reason = NULL WHERE id = $1",
+        )
+        .bind(temporal_product_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Locks the given line and its ticket returning the unit price of the line before its discount,
+    /// errors if the ticket is locked by a simple invoice (the prices of a printed ticket can't change)
+    async fn lock_original_price(
+        tx: &mut Transaction<'_, Postgres>,
+        temporal_product_id: i32,
+    ) -> Result<Money, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT tt.simple_invoice_id, COALESCE(tp.original_price, tp.price) as original_price
+            FROM temporal_products tp
+            JOIN temporal_tickets tt ON tt.id = tp.temporal_ticket_id
+            WHERE tp.id = $1
+            FOR UPDATE",
+        )
+        .bind(temporal_product_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let simple_invoice_id: Option<i32> = row.try_get("simple_invoice_id")?;
+        if simple_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is locked, unlock it before changing its discounts".into(),
+            ));
+        }
+
+        let original_price: Option<Money> = row.try_get("original_price")?;
+        Ok(original_price.unwrap_or_default())
+    }
+
+    /// Voids the given line, keeping it on the voided lines with who voided it and why,
+    /// the ticket of the line is deleted if it has no lines left
+    pub async fn void(
+        pool: Arc<PgPool>,
+        temporal_product_id: i32,
+        reason: String,
+        voided_by: String,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        // lines of a locked ticket can't be voided
+        let temporal_ticket_id: Option<i32> = sqlx::query_scalar(
+            "INSERT INTO voided_lines (temporal_ticket_id, table_id, original_product_id, name, quantity, price, amount, reason, voided_by)
+            SELECT tp.temporal_ticket_id, tt.table_id, tp.original_product_id, tp.name, tp.quantity, tp.price,
+                COALESCE(tp.price, 0) * tp.quantity, $2, $3
+            FROM temporal_products tp
+            JOIN temporal_tickets tt ON tt.id = tp.temporal_ticket_id
+            WHERE tp.id = $1 AND tt.simple_invoice_id IS NULL
+            RETURNING temporal_ticket_id",
+        )
+        .bind(temporal_product_id)
+        .bind(reason.trim())
+        .bind(voided_by.trim())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(temporal_ticket_id) = temporal_ticket_id else {
+            return Err(sqlx::Error::Protocol(
+                "The line doesn't exist or its ticket is locked".into(),
+            ));
+        };
+
         sqlx::query("DELETE FROM temporal_products WHERE id = $1")
             .bind(temporal_product_id)
-            .execute(pool.as_ref())
+            .execute(&mut *tx)
             .await?;
 
+        sqlx::query(
+            "DELETE FROM temporal_tickets WHERE id = $1
+            AND NOT EXISTS (SELECT 1 FROM temporal_products WHERE temporal_ticket_id = $1)",
+        )
+        .bind(temporal_ticket_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
         Ok(())
     }
 }
@@ -1,10 +1,17 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use chrono::Local;
+use rust_decimal::{Decimal, prelude::ToPrimitive};
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
 use std::{collections::HashMap, sync::Arc};
 
-use super::{product::Product, temporal_product::TemporalProduct};
+use crate::alegria::utils::{
+    entities::discount::{Discount, DiscountKind},
+    money::Money,
+};
+
+use super::{pricing_rule::PricingRule, product::Product, temporal_product::TemporalProduct};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalTicket {
@@ -13,21 +20,55 @@ pub struct TemporalTicket {
     pub ticket_location: i32,
     pub ticket_status: i32,
     pub simple_invoice_id: Option<i32>,
+    /// Discount given to the whole ticket, it's already applied on the price of its lines
+    pub discount: Option<Discount>,
     pub products: Vec<TemporalProduct>,
 }
 
+/// Identifies the part of a temporal ticket that is going to be paid as its own bill
+#[derive(Debug, Clone, PartialEq)]
+pub enum TicketSplit {
+    /// Pays the given quantities of the given temporal products (temporal_product_id, quantity)
+    Lines(Vec<(i32, i32)>),
+    /// Pays an even share of everything left on the ticket given the number of payers still to pay
+    Evenly(i32),
+}
+
 impl TemporalTicket {
-    pub fn total_price(&self) -> f32 {
-        let mut price = 0.;
+    pub fn total_price(&self) -> Money {
+        let mut price = Money::ZERO;
         for product in &self.products {
-            for _ in 0..product.quantity {
-                price += product.price.unwrap_or(0.);
-            }
+            price += product.price.unwrap_or_default() * product.quantity;
         }
 
         price
     }
 
+    /// Returns the amount of the bill defined by the given split of the ticket
+    pub fn split_price(&self, split: &TicketSplit) -> Money {
+        match split {
+            TicketSplit::Lines(lines) => self
+                .products
+                .iter()
+                .map(|product| {
+                    let quantity = lines
+                        .iter()
+                        .find(|(id, _)| Some(*id) == product.id)
+                        .map(|(_, quantity)| (*quantity).clamp(0, product.quantity))
+                        .unwrap_or_default();
+                    product.price.unwrap_or_default() * quantity
+                })
+                .sum(),
+            TicketSplit::Evenly(payers) => self
+                .products
+                .iter()
+                .map(|product| {
+                    product.price.unwrap_or_default().share(*payers) * product.quantity.max(0)
+                })
+                .sum(),
+        }
+    }
+
     pub async fn get_all(pool: Arc<PgPool>) -> Result<Vec<TemporalTicket>, sqlx::Error> {
         let rows = sqlx::query(
             "SELECT 
@@ -36,12 +77,19 @@ impl TemporalTicket {
                 t.ticket_location,
                 t.ticket_status,
                 t.simple_invoice_id,
+                t.discount_kind,
+                t.discount_value,
+                t.discount_reason,
                 p.id as product_id,
                 p.original_product_id,
                 p.temporal_ticket_id,
                 p.name as product_name,
                 p.quantity as product_quantity,
-                p.price as product_price
+                p.sent_quantity as product_sent_quantity,
+                p.modifiers as product_modifiers,
+                p.price as product_price,
+                p.original_price as product_original_price,
+                p.discount_reason as product_discount_reason
              FROM temporal_tickets t
              LEFT JOIN temporal_products p ON p.temporal_ticket_id = t.id
              ORDER BY t.id ASC",
@@ -49,6 +97,47 @@ impl TemporalTicket {
         .fetch_all(pool.as_ref())
         .await?;
 
+        Self::from_rows(rows)
+    }
+
+    /// Retrieves a single temporal ticket (with its products) from the database
+    pub async fn get_single(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+    ) -> Result<Option<TemporalTicket>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT 
+                t.id as ticket_id,
+                t.table_id,
+                t.ticket_location,
+                t.ticket_status,
+                t.simple_invoice_id,
+                t.discount_kind,
+                t.discount_value,
+                t.discount_reason,
+                p.id as product_id,
+                p.original_product_id,
+                p.temporal_ticket_id,
+                p.name as product_name,
+                p.quantity as product_quantity,
+                p.sent_quantity as product_sent_quantity,
+                p.modifiers as product_modifiers,
+                p.price as product_price,
+                p.original_price as product_original_price,
+                p.discount_reason as product_discount_reason
+             FROM temporal_tickets t
+             LEFT JOIN temporal_products p ON p.temporal_ticket_id = t.id
+             WHERE t.id = $1",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        Ok(Self::from_rows(rows)?.into_iter().next())
+    }
+
+    /// Groups the rows of a temporal_tickets LEFT JOIN temporal_products query into tickets
+    fn from_rows(rows: Vec<PgRow>) -> Result<Vec<TemporalTicket>, sqlx::Error> {
         // group rows by ticket id
         let mut tickets_map: HashMap<i32, TemporalTicket> = HashMap::with_capacity(rows.len());
 
@@ -58,6 +147,7 @@ impl TemporalTicket {
             let ticket_location: i32 = row.try_get("ticket_location")?;
             let ticket_status: i32 = row.try_get("ticket_status")?;
             let simple_invoice_id: Option<i32> = row.try_get("simple_invoice_id")?;
+            let discount = Self::discount_from_row(&row)?;
 
             // Insert the ticket into the map if it doesn't exist yet
             let ticket = tickets_map.entry(ticket_id).or_insert(TemporalTicket {
@@ -66,6 +156,7 @@ impl TemporalTicket {
                 ticket_location,
                 ticket_status,
                 simple_invoice_id,
+                discount,
                 products: Vec::new(),
             });
 
@@ -76,7 +167,13 @@ impl TemporalTicket {
                 let temporal_ticket_id: i32 = row.try_get("temporal_ticket_id")?;
                 let product_name: String = row.try_get("product_name")?;
                 let product_quantity: i32 = row.try_get("product_quantity")?;
-                let product_price: Option<f32> = row.try_get("product_price")?;
+                let product_sent_quantity: i32 = row.try_get("product_sent_quantity")?;
+                let product_price: Option<Money> = row.try_get("product_price")?;
+                let product_modifiers: String = row.try_get("product_modifiers")?;
+                let product_original_price: Option<Money> =
+                    row.try_get("product_original_price")?;
+                let product_discount_reason: Option<String> =
+                    row.try_get("product_discount_reason")?;
 
                 let product = TemporalProduct {
                     id: Some(pid),
@@ -85,6 +182,10 @@ impl TemporalTicket {
                     name: product_name,
                     quantity: product_quantity,
                     price: product_price,
+                    original_price: product_original_price,
+                    discount_reason: product_discount_reason,
+                    modifiers: product_modifiers,
+                    sent_quantity: product_sent_quantity,
                     price_input: product_price.unwrap_or_default().to_string(),
                 };
 
@@ -103,26 +204,47 @@ impl TemporalTicket {
         Ok(tickets)
     }
 
+    /// Reads the discount columns of a temporal_tickets row
+    fn discount_from_row(row: &PgRow) -> Result<Option<Discount>, sqlx::Error> {
+        let discount_kind: Option<DiscountKind> = row.try_get("discount_kind")?;
+        let discount_value: Option<Decimal> = row.try_get("discount_value")?;
+        let discount_reason: Option<String> = row.try_get("discount_reason")?;
+
+        Ok(match (discount_kind, discount_value) {
+            (Some(kind), Some(value)) => Some(Discount {
+                kind,
+                value,
+                reason: discount_reason.unwrap_or_default(),
+            }),
+            _ => None,
+        })
+    }
+
+    /// Adds a line of the given product (with the given modifiers) to the ticket of the table, creating the ticket if needed
     pub async fn upsert_ticket_by_id_and_tableloc(
         pool: Arc<PgPool>,
         temporal_ticket: TemporalTicket,
         new_product_id: i32,
+        modifier_ids: Vec<i32>,
     ) -> Result<(), sqlx::Error> {
-        let product_row =
-            sqlx::query("SELECT id, name, inside_price, outside_price FROM products WHERE id = $1")
-                .bind(new_product_id)
-                .fetch_optional(pool.as_ref())
-                .await?;
+        let product_row = sqlx::query(
+            "SELECT id, category_id, name, inside_price, outside_price FROM products WHERE id = $1",
+        )
+        .bind(new_product_id)
+        .fetch_optional(pool.as_ref())
+        .await?;
 
         let product: Product = match product_row {
             Some(row) => {
                 let id: Option<i32> = row.try_get("id")?;
+                let category_id: Option<i32> = row.try_get("category_id")?;
                 let name: String = row.try_get("name")?;
-                let inside_price: Option<f32> = row.try_get("inside_price")?;
-                let outside_price: Option<f32> = row.try_get("outside_price")?;
+                let inside_price: Option<Money> = row.try_get("inside_price")?;
+                let outside_price: Option<Money> = row.try_get("outside_price")?;
 
                 Product {
                     id,
+                    category_id,
                     name,
                     inside_price,
                     outside_price,
@@ -132,9 +254,40 @@ impl TemporalTicket {
             None => return Err(sqlx::Error::RowNotFound),
         };
 
+        // tickets of inside locations use the inside price of the products
+        let inside: bool = sqlx::query_scalar("SELECT inside FROM table_locations WHERE id = $1")
+            .bind(temporal_ticket.ticket_location)
+            .fetch_one(pool.as_ref())
+            .await?;
+
+        // the modifiers are kept on the line by name, their price is added to the unit price
+        let modifier_rows = sqlx::query(
+            "SELECT name, price_delta FROM modifiers WHERE id = ANY($1) AND is_deleted = FALSE ORDER BY id",
+        )
+        .bind(&modifier_ids)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut modifier_names = Vec::with_capacity(modifier_rows.len());
+        let mut modifiers_price = Money::ZERO;
+        for row in modifier_rows {
+            modifier_names.push(row.try_get::<String, _>("name")?);
+            modifiers_price += row.try_get::<Money, _>("price_delta")?;
+        }
+
+        // the pricing rules that apply right now may change the price of the product on this location
+        let pricing_rules = PricingRule::get_all(pool.clone()).await?;
+        let (base_price, _) = PricingRule::resolve(
+            &pricing_rules,
+            &product,
+            temporal_ticket.ticket_location,
+            inside,
+            Local::now().naive_local(),
+        );
+
         // check if a ticket already exists with the same table_id and ticket_location.
         let existing_ticket = sqlx::query(
-            "SELECT id FROM temporal_tickets WHERE table_id = $1 AND ticket_location = $2",
+            "SELECT id, discount_kind, discount_value, discount_reason FROM temporal_tickets WHERE table_id = $1 AND ticket_location = $2",
         )
         .bind(temporal_ticket.table_id)
         .bind(temporal_ticket.ticket_location)
@@ -145,8 +298,8 @@ impl TemporalTicket {
         let mut tx = pool.begin().await?;
 
         // check if the ticket already exists; if not, insert a new temporal_ticket.
-        let ticket_id = if let Some(row) = existing_ticket {
-            row.try_get("id")?
+        let (ticket_id, ticket_discount) = if let Some(row) = existing_ticket {
+            (row.try_get("id")?, Self::discount_from_row(&row)?)
         } else {
             let ticket_id: i32 = sqlx::query_scalar(
                 "INSERT INTO temporal_tickets (table_id, ticket_location, ticket_status) 
@@ -159,38 +312,558 @@ impl TemporalTicket {
             .fetch_one(&mut *tx)
             .await?;
 
-            ticket_id
+            (ticket_id, None)
         };
 
+        let price = base_price.map(|price| price + modifiers_price);
+        // a percentage discount of the ticket also applies to the new lines, a fixed one has already been given
+        let discount = ticket_discount.filter(|d| d.kind == DiscountKind::Percentage);
+
         sqlx::query(
-            "INSERT INTO temporal_products (original_product_id, temporal_ticket_id, quantity, name, price) 
-             VALUES ($1, $2, $3, $4, $5)"
+            "INSERT INTO temporal_products (original_product_id, temporal_ticket_id, quantity, name, price, modifiers, original_price, discount_reason) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
         )
             .bind(product.id)
             .bind(ticket_id)
             .bind(1) // quantity is hard-coded as 1
             .bind(&product.name)
-            .bind(
-                if temporal_ticket.ticket_location == 1 {
-                    product.inside_price
-                } else {
-                    product.outside_price
-                }
+            .bind(match &discount {
+                Some(discount) => price.map(|price| discount.apply(price)),
+                None => price,
+            })
+            .bind(modifier_names.join(", "))
+            .bind(discount.as_ref().and(price))
+            .bind(discount.map(|d| d.reason))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Applies the given discount to the whole ticket, replacing the discounts its lines may already have.
+    /// A fixed amount drops the total exactly by the amount (see `discounted_prices`)
+    pub async fn apply_discount(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+        discount: Discount,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let simple_invoice_id: Option<i32> = sqlx::query_scalar(
+            "SELECT simple_invoice_id FROM temporal_tickets WHERE id = $1 FOR UPDATE",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if simple_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is locked, unlock it before giving a discount".into(),
+            ));
+        }
+
+        let rows = sqlx::query(
+            "SELECT id, quantity, COALESCE(original_price, price) as original_price FROM temporal_products
+            WHERE temporal_ticket_id = $1 ORDER BY id",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut lines = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: i32 = row.try_get("id")?;
+            let quantity: i32 = row.try_get("quantity")?;
+            let original_price: Option<Money> = row.try_get("original_price")?;
+            lines.push((id, quantity, original_price.unwrap_or_default()));
+        }
+
+        let (prices, split_units) = discounted_prices(&lines, &discount)
+            .map_err(|err| sqlx::Error::Protocol(err.into()))?;
+
+        for ((id, _, original_price), price) in lines.iter().zip(&prices) {
+            sqlx::query(
+                "UPDATE temporal_products SET price = $1, original_price = $2, discount_reason = $3 WHERE id = $4",
+            )
+            .bind(*price)
+            .bind(*original_price)
+            .bind(discount.reason.trim())
+            .bind(*id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // the units split off are the last ones to be sent to their preparation station and counted
+        for (id, quantity, unit_price) in split_units {
+            sqlx::query(
+                "INSERT INTO temporal_products (original_product_id, temporal_ticket_id, quantity, name, price, modifiers,
+                    original_price, discount_reason, sent_quantity, counted_quantity)
+                SELECT original_product_id, temporal_ticket_id, $1, name, $2, modifiers, original_price, discount_reason,
+                    GREATEST(sent_quantity - (quantity - $1), 0),
+                    GREATEST(counted_quantity - (quantity - $1), 0)
+                FROM temporal_products WHERE id = $3",
             )
+            .bind(quantity)
+            .bind(unit_price)
+            .bind(id)
             .execute(&mut *tx)
             .await?;
 
+            sqlx::query(
+                "UPDATE temporal_products SET quantity = quantity - $1, sent_quantity = LEAST(sent_quantity, quantity - $1),
+                    counted_quantity = LEAST(counted_quantity, quantity - $1)
+                WHERE id = $2",
+            )
+            .bind(quantity)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            "UPDATE temporal_tickets SET discount_kind = $1, discount_value = $2, discount_reason = $3 WHERE id = $4",
+        )
+        .bind(discount.kind)
+        .bind(discount.value)
+        .bind(discount.reason.trim())
+        .bind(temporal_ticket_id)
+        .execute(&mut *tx)
+        .await?;
+
         tx.commit().await?;
+        Ok(())
+    }
+
+    /// Removes the discount of the ticket and the discounts of all its lines, restoring their original price
+    pub async fn remove_discount(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let simple_invoice_id: Option<i32> = sqlx::query_scalar(
+            "SELECT simple_invoice_id FROM temporal_tickets WHERE id = $1 FOR UPDATE",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if simple_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is locked, unlock it before removing its discount".into(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE temporal_products SET price = COALESCE(original_price, price), original_price = NULL, discount_reason = NULL
+             WHERE temporal_ticket_id = $1",
+        )
+        .bind(temporal_ticket_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE temporal_tickets SET discount_kind = NULL, discount_value = NULL, discount_reason = NULL WHERE id = $1",
+        )
+        .bind(temporal_ticket_id)
+        .execute(&mut *tx)
+        .await?;
 
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn delete(pool: Arc<PgPool>, temporal_ticket_id: i32) -> Result<(), sqlx::Error> {
+    /// Moves a temporal ticket to another (free) table and location
+    pub async fn move_to_table(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+        table_id: i32,
+        ticket_location: i32,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let target =
+            Self::lock_target(&mut tx, temporal_ticket_id, table_id, ticket_location).await?;
+        if target.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The target table already has a ticket, merge into it instead".into(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE temporal_tickets SET table_id = $1, ticket_location = $2 WHERE id = $3",
+        )
+        .bind(table_id)
+        .bind(ticket_location)
+        .bind(temporal_ticket_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Merges the products of a temporal ticket into the ticket of another table and location
+    /// (the ticket is just moved if the target table is free), the original ticket is deleted
+    pub async fn merge_into_table(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+        table_id: i32,
+        ticket_location: i32,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let source_invoice_id: Option<i32> = sqlx::query_scalar(
+            "SELECT simple_invoice_id FROM temporal_tickets WHERE id = $1 FOR UPDATE",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // the invoice of a printed ticket would no longer match its products
+        if source_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is locked, unlock it before merging it".into(),
+            ));
+        }
+
+        let target =
+            Self::lock_target(&mut tx, temporal_ticket_id, table_id, ticket_location).await?;
+        match target {
+            Some(target_id) => {
+                sqlx::query(
+                    "UPDATE temporal_products SET temporal_ticket_id = $1 WHERE temporal_ticket_id = $2",
+                )
+                .bind(target_id)
+                .bind(temporal_ticket_id)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query("DELETE FROM temporal_tickets WHERE id = $1")
+                    .bind(temporal_ticket_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            None => {
+                sqlx::query(
+                    "UPDATE temporal_tickets SET table_id = $1, ticket_location = $2 WHERE id = $3",
+                )
+                .bind(table_id)
+                .bind(ticket_location)
+                .bind(temporal_ticket_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Locks the ticket on the given table and location (if any) returning its id,
+    /// errors if it's the same ticket or if the target is locked by a simple invoice
+    async fn lock_target(
+        tx: &mut Transaction<'_, Postgres>,
+        temporal_ticket_id: i32,
+        table_id: i32,
+        ticket_location: i32,
+    ) -> Result<Option<i32>, sqlx::Error> {
+        let target = sqlx::query(
+            "SELECT id, simple_invoice_id FROM temporal_tickets
+             WHERE table_id = $1 AND ticket_location = $2
+             FOR UPDATE",
+        )
+        .bind(table_id)
+        .bind(ticket_location)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let Some(row) = target else {
+            return Ok(None);
+        };
+
+        let target_id: i32 = row.try_get("id")?;
+        let simple_invoice_id: Option<i32> = row.try_get("simple_invoice_id")?;
+
+        if target_id == temporal_ticket_id {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is already on the target table".into(),
+            ));
+        }
+        if simple_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket of the target table is locked".into(),
+            ));
+        }
+
+        Ok(Some(target_id))
+    }
+
+    /// Voids every line of the given ticket and deletes it, the lines are kept on the voided lines with who voided them and why
+    pub async fn void(
+        pool: Arc<PgPool>,
+        temporal_ticket_id: i32,
+        reason: String,
+        voided_by: String,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let simple_invoice_id: Option<i32> = sqlx::query_scalar(
+            "SELECT simple_invoice_id FROM temporal_tickets WHERE id = $1 FOR UPDATE",
+        )
+        .bind(temporal_ticket_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if simple_invoice_id.is_some() {
+            return Err(sqlx::Error::Protocol(
+                "The ticket is locked, unlock it before voiding it".into(),
+            ));
+        }
+
+        sqlx::query(
+            "INSERT INTO voided_lines (temporal_ticket_id, table_id, original_product_id, name, quantity, price, amount, reason, voided_by)
+            SELECT tp.temporal_ticket_id, tt.table_id, tp.original_product_id, tp.name, tp.quantity, tp.price,
+                COALESCE(tp.price, 0) * tp.quantity, $2, $3
+            FROM temporal_products tp
+            JOIN temporal_tickets tt ON tt.id = tp.temporal_ticket_id
+            WHERE tp.temporal_ticket_id = $1",
+        )
+        .bind(temporal_ticket_id)
+        .bind(reason.trim())
+        .bind(voided_by.trim())
+        .execute(&mut *tx)
+        .await?;
+
+        // the temporal products will be deleted by on_cascade of the db
         sqlx::query("DELETE FROM temporal_tickets WHERE id = $1")
             .bind(temporal_ticket_id)
-            .execute(pool.as_ref())
+            .execute(&mut *tx)
             .await?;
 
+        tx.commit().await?;
         Ok(())
     }
 }
+
+/// Returns the unit prices of the given lines of a ticket (id, quantity, unit price before the discount) once the given
+/// discount is applied to the whole ticket. A fixed amount is spread over the lines as the percentage of the ticket total
+/// it represents and the cents lost rounding the unit prices go to the last lines, when they can't be shared evenly among
+/// the units of a line it also returns the (line, quantity, unit price) of the units to split off that line, a cent more
+/// expensive than the rest, so the total drops exactly by the amount
+fn discounted_prices(
+    lines: &[(i32, i32, Money)],
+    discount: &Discount,
+) -> Result<(Vec<Money>, Vec<(i32, i32, Money)>), &'static str> {
+    let total: Money = lines
+        .iter()
+        .map(|(_, quantity, price)| *price * *quantity)
+        .sum();
+
+    let line_discount = match discount.kind {
+        DiscountKind::Percentage => discount.clone(),
+        DiscountKind::Fixed => {
+            if total <= Money::ZERO {
+                return Err("The ticket has nothing to discount");
+            }
+
+            Discount {
+                kind: DiscountKind::Percentage,
+                value: (discount.value * Decimal::ONE_HUNDRED / Decimal::from(total))
+                    .min(Decimal::ONE_HUNDRED),
+                reason: discount.reason.clone(),
+            }
+        }
+    };
+
+    let mut prices: Vec<Money> = lines
+        .iter()
+        .map(|(_, _, original_price)| line_discount.apply(*original_price))
+        .collect();
+
+    // (line, quantity, unit price) of the units split off the lines whose total can't be shared evenly among their units
+    let mut split_units = Vec::new();
+    if discount.kind == DiscountKind::Fixed {
+        let discounted_total: Money = lines
+            .iter()
+            .zip(&prices)
+            .map(|((_, quantity, _), price)| *price * *quantity)
+            .sum();
+        let mut remainder = total - Money::from(discount.value).min(total) - discounted_total;
+
+        // the last lines take the remainder, as many of them as needed when it's more than what a line costs
+        for (index, (id, quantity, _)) in lines.iter().enumerate().rev() {
+            if remainder == Money::ZERO {
+                break;
+            }
+            if *quantity <= 0 {
+                continue;
+            }
+
+            let line_total = prices[index] * *quantity;
+            let adjustment = remainder.max(-line_total);
+            let cents = Decimal::from(line_total + adjustment) * Decimal::ONE_HUNDRED;
+            let unit_cents = (cents / Decimal::from(*quantity)).floor();
+            let extra_cents = cents - unit_cents * Decimal::from(*quantity);
+
+            prices[index] = Money::from(unit_cents / Decimal::ONE_HUNDRED);
+            if !extra_cents.is_zero() {
+                // some units cost a cent more than the rest
+                split_units.push((
+                    *id,
+                    extra_cents.to_i32().unwrap_or_default(),
+                    Money::from((unit_cents + Decimal::ONE) / Decimal::ONE_HUNDRED),
+                ));
+            }
+            remainder -= adjustment;
+        }
+    }
+
+    Ok((prices, split_units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn fixed(value: &str) -> Discount {
+        Discount {
+            kind: DiscountKind::Fixed,
+            value: value.parse().unwrap(),
+            reason: String::from("Cliente habitual"),
+        }
+    }
+
+    /// Returns the total of the given lines once discounted with the given prices and units split off
+    fn discounted_total(
+        lines: &[(i32, i32, Money)],
+        prices: &[Money],
+        split_units: &[(i32, i32, Money)],
+    ) -> Money {
+        let mut total = Money::ZERO;
+        for ((id, quantity, _), price) in lines.iter().zip(prices) {
+            let split_quantity: i32 = split_units
+                .iter()
+                .filter(|(split_id, _, _)| split_id == id)
+                .map(|(_, quantity, _)| quantity)
+                .sum();
+            assert!((0..*quantity).contains(&split_quantity));
+            total += *price * (quantity - split_quantity);
+        }
+
+        total
+            + split_units
+                .iter()
+                .map(|(_, quantity, price)| *price * *quantity)
+                .sum::<Money>()
+    }
+
+    /// Panics if the lines discounted with the given fixed amount don't add up to their total minus the amount
+    fn assert_exact(
+        lines: &[(i32, i32, Money)],
+        amount: &str,
+    ) -> (Vec<Money>, Vec<(i32, i32, Money)>) {
+        let (prices, split_units) = discounted_prices(lines, &fixed(amount)).unwrap();
+        let subtotal: Money = lines
+            .iter()
+            .map(|(_, quantity, price)| *price * *quantity)
+            .sum();
+
+        assert!(prices.iter().all(|price| *price >= Money::ZERO));
+        assert_eq!(
+            discounted_total(lines, &prices, &split_units),
+            subtotal - money(amount).min(subtotal),
+            "{lines:?} -{amount}"
+        );
+        (prices, split_units)
+    }
+
+    #[test]
+    fn fixed_discounts_that_divide_evenly() {
+        let lines = [(1, 3, money("1.00")), (2, 1, money("2.00"))];
+        let (prices, split_units) = assert_exact(&lines, "1.00");
+
+        assert_eq!(prices, vec![money("0.80"), money("1.60")]);
+        assert!(split_units.is_empty());
+    }
+
+    #[test]
+    fn cents_left_rounding_go_to_the_last_line() {
+        // -5.00 over 16.92 is a 29.55% that leaves 11.95 after rounding the unit prices, 3 cents over the 11.92 to pay
+        let lines = [(1, 3, money("3.33")), (2, 7, money("0.99"))];
+        let (prices, split_units) = assert_exact(&lines, "5.00");
+
+        assert_eq!(prices[0], money("2.35"));
+        assert_eq!(prices[1], money("0.69"));
+        assert_eq!(split_units, vec![(2, 4, money("0.70"))]);
+    }
+
+    #[test]
+    fn remainders_bigger_than_a_unit_are_split_among_the_units() {
+        // 20.80 - 20.77 leaves 0.03 to pay, but every unit rounds to 0.01 (0.05 in total)
+        let lines = [(1, 5, money("4.16"))];
+        let (prices, split_units) = assert_exact(&lines, "20.77");
+
+        assert_eq!(prices, vec![Money::ZERO]);
+        assert_eq!(split_units, vec![(1, 3, money("0.01"))]);
+    }
+
+    #[test]
+    fn remainders_bigger_than_a_line_go_to_the_previous_lines() {
+        let lines = [
+            (1, 1, money("0.04")),
+            (2, 2, money("0.03")),
+            (3, 1, money("0.02")),
+        ];
+        for amount in ["0.01", "0.02", "0.05", "0.07", "0.09", "0.11", "0.12"] {
+            assert_exact(&lines, amount);
+        }
+    }
+
+    #[test]
+    fn fixed_discounts_are_exact_for_any_line_and_quantity() {
+        let prices = [
+            "0.01", "0.07", "0.99", "1.35", "2.15", "3.33", "4.16", "14.95",
+        ];
+        let amounts = [
+            "0.01", "0.03", "0.50", "1.00", "2.99", "5.00", "7.77", "20.77", "100.00",
+        ];
+
+        for (first, first_price) in prices.iter().enumerate() {
+            for second_price in &prices[first..] {
+                for first_quantity in 1..=4 {
+                    for second_quantity in 1..=7 {
+                        let lines = [
+                            (1, first_quantity, money(first_price)),
+                            (2, second_quantity, money(second_price)),
+                        ];
+                        for amount in amounts {
+                            assert_exact(&lines, amount);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn discounts_bigger_than_the_ticket_leave_it_free() {
+        let lines = [(1, 2, money("1.00"))];
+        let (prices, split_units) = assert_exact(&lines, "5.00");
+
+        assert_eq!(prices, vec![Money::ZERO]);
+        assert!(split_units.is_empty());
+    }
+
+    #[test]
+    fn fixed_discounts_need_something_to_discount() {
+        assert!(discounted_prices(&[], &fixed("1.00")).is_err());
+        assert!(discounted_prices(&[(1, 2, Money::ZERO)], &fixed("1.00")).is_err());
+    }
+}
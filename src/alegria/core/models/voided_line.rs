@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::alegria::utils::money::Money;
+
+/// A line removed from a ticket before it was paid, kept to be able to audit it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoidedLine {
+    pub id: Option<i32>,
+    pub temporal_ticket_id: i32,
+    pub table_id: i32,
+    pub original_product_id: i32,
+    pub name: String,
+    pub quantity: i32,
+    pub price: Option<Money>, // Unit price of the line when it was voided
+    pub amount: Money,
+    pub reason: String,
+    /// Who voided the line
+    pub voided_by: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+
+    // Not in the db
+    pub table_name: Box<str>, // Helps us JOIN and return the name of the table of the ticket
+}
+
+impl VoidedLine {
+    /// Returns the lines voided between the given dates (both included), newest first
+    pub async fn get_between(
+        pool: Arc<PgPool>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<VoidedLine>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT
+                vl.id,
+                vl.temporal_ticket_id,
+                vl.table_id,
+                vl.original_product_id,
+                vl.name,
+                vl.quantity,
+                vl.price,
+                vl.amount,
+                vl.reason,
+                vl.voided_by,
+                vl.created_at,
+                vl.updated_at,
+                bt.name as table_name
+            FROM voided_lines vl
+            LEFT JOIN bar_tables bt ON bt.id = vl.table_id
+            WHERE vl.created_at::DATE BETWEEN $1 AND $2
+            ORDER BY vl.created_at DESC, vl.id DESC",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool.as_ref())
+        .await?;
+
+        let mut result = Vec::<VoidedLine>::new();
+        for row in rows {
+            let table_name: Option<String> = row.try_get("table_name")?;
+
+            result.push(VoidedLine {
+                id: row.try_get("id")?,
+                temporal_ticket_id: row.try_get("temporal_ticket_id")?,
+                table_id: row.try_get("table_id")?,
+                original_product_id: row.try_get("original_product_id")?,
+                name: row.try_get("name")?,
+                quantity: row.try_get("quantity")?,
+                price: row.try_get("price")?,
+                amount: row.try_get("amount")?,
+                reason: row.try_get("reason")?,
+                voided_by: row.try_get("voided_by")?,
+                created_at: row.try_get("created_at")?,
+                updated_at: row.try_get("updated_at")?,
+                table_name: table_name.unwrap_or_default().into_boxed_str(),
+            });
+        }
+
+        Ok(result)
+    }
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{collections::HashMap, fmt::Display, sync::Arc};
+use std::{collections::BTreeMap, env, fmt::Display, sync::Arc};
 
 use printers::{
     common::base::{job::PrinterJobOptions, printer::Printer},
@@ -8,10 +8,28 @@ use printers::{
 };
 use printpdf::*;
 
-use super::models::simple_invoice::SimpleInvoice;
+use rust_decimal::Decimal;
+
+use super::models::{
+    cash_session::ZReport,
+    invoice_record,
+    preparation_station::KitchenOrder,
+    simple_invoice::{InvoiceRecipient, SimpleInvoice},
+};
+use crate::alegria::utils::money::{Money, split_tax};
 
 static TICKET_FONT_TTF: &[u8] = include_bytes!("../../../resources/fonts/RobotoFlex.ttf");
 
+// Invoice Page (mm)
+const A4_WIDTH: f32 = 210.;
+const A4_HEIGHT: f32 = 297.;
+const INVOICE_LINES_START: f32 = 217.;
+const INVOICE_MARGIN_BOTTOM: f32 = 20.;
+
+// Receipt Page (mm)
+const RECEIPT_WIDTH: f32 = 80.;
+const RECEIPT_QR_SIZE: f32 = 30.;
+
 #[derive(Debug, Clone)]
 pub struct AlegriaPrinter(Printer);
 
@@ -33,6 +51,34 @@ impl From<Printer> for AlegriaPrinter {
     }
 }
 
+/// Fiscal data of the business issuing the documents
+#[derive(Debug, Clone, Default)]
+pub struct Issuer {
+    pub name: String,
+    pub nif: String,
+    pub address: String,
+}
+
+impl Issuer {
+    /// Loads the issuer fiscal data from the .env file
+    pub fn load() -> Self {
+        dotenvy::dotenv().ok();
+
+        Self {
+            name: env::var("ISSUER_NAME").unwrap_or_default(),
+            nif: env::var("ISSUER_NIF").unwrap_or_default(),
+            address: env::var("ISSUER_ADDRESS").unwrap_or_default(),
+        }
+    }
+}
+
+/// Base and tax amounts of all the products of an invoice that share the same tax percentage
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TaxGroup {
+    pub base: Money,
+    pub tax: Money,
+}
+
 #[derive(Default, Debug, Clone, PartialEq)]
 pub enum TicketType {
     Invoice,
@@ -99,11 +145,413 @@ impl AlegriaPrinter {
         .await
         .unwrap_or(Err("Failed to spawn a blocking task"))
     }
+
+    /// Prints the Z report of a closed cash session
+    pub async fn print_z_report(self: Arc<Self>, report: ZReport) -> Result<(), &'static str> {
+        tokio::task::spawn_blocking(move || {
+            let doc = generate_z_report(&report)?;
+            match self.0.print(&doc, PrinterJobOptions::none()) {
+                Ok(_) => Ok(()),
+                Err(_e) => Err("Failed to print Z report"),
+            }
+        })
+        .await
+        .unwrap_or(Err("Failed to spawn a blocking task"))
+    }
+
+    /// Returns the printer with the given name from the given printers (if it's there)
+    pub fn find_by_name(printers: &[AlegriaPrinter], name: &str) -> Option<AlegriaPrinter> {
+        printers.iter().find(|p| p.0.name == name).cloned()
+    }
+
+    /// Prints an order for its preparation station
+    pub async fn print_kitchen_order(
+        self: Arc<Self>,
+        order: KitchenOrder,
+    ) -> Result<(), &'static str> {
+        tokio::task::spawn_blocking(move || {
+            let doc = generate_kitchen_order(&order)?;
+            match self.0.print(&doc, PrinterJobOptions::none()) {
+                Ok(_) => Ok(()),
+                Err(_e) => Err("Failed to print kitchen order"),
+            }
+        })
+        .await
+        .unwrap_or(Err("Failed to spawn a blocking task"))
+    }
+}
+
+/// Generates an A4 full invoice (issuer and recipient fiscal data, per line tax breakdown and per rate tax summary),
+/// rectifying invoices use the same layout referencing the invoice they rectify (the recipient is optional for them)
+fn generate_invoice(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
+    let recipient = invoice.recipient.as_ref();
+    if recipient.is_none() && invoice.rectified_invoice_id.is_none() {
+        return Err("Missing invoice recipient");
+    }
+    let issuer = Issuer::load();
+
+    // Create a new PDF document
+    let mut doc = PdfDocument::new("Invoice");
+
+    // Load and register an external font
+    let custom_font = ParsedFont::from_bytes(TICKET_FONT_TTF, 0, &mut Vec::new())
+        .ok_or("Failed to load invoice font")?;
+    let custom_font_id = doc.add_font(&custom_font);
+    let font_data = &custom_font.original_bytes;
+
+    let taxes = group_taxes(invoice);
+    let mut pages = Vec::new();
+    let mut ops = invoice_header_ops(invoice, &issuer, recipient, font_data, &custom_font_id);
+    let mut current_height = INVOICE_LINES_START;
+
+    // Write each product line
+    for (product, line) in invoice.products.iter().zip(line_taxes(invoice)) {
+        // Start a new page if this line does not fit on the current one
+        if current_height < INVOICE_MARGIN_BOTTOM {
+            pages.push(PdfPage::new(Mm(A4_WIDTH), Mm(A4_HEIGHT), ops));
+            ops = invoice_header_ops(invoice, &issuer, recipient, font_data, &custom_font_id);
+            current_height = INVOICE_LINES_START;
+        }
+
+        let tax_percentage = product.tax_percentage;
+        let total = product.total_price();
+        let TaxGroup { base, tax } = line;
+
+        let product_name = fit_text(
+            font_data,
+            &format!("{} x {}", product.quantity, product.original_product.name),
+            10.0,
+            230.,
+        );
+        ops.extend(text_ops(
+            product_name,
+            15.,
+            current_height,
+            10.,
+            &custom_font_id,
+        ));
+        ops.extend(right_aligned_text_ops(
+            format!("{base:.2}€"),
+            125.,
+            current_height,
+            10.,
+            font_data,
+            &custom_font_id,
+        ));
+        ops.extend(right_aligned_text_ops(
+            format!("{tax_percentage:.2}%"),
+            145.,
+            current_height,
+            10.,
+            font_data,
+            &custom_font_id,
+        ));
+        ops.extend(right_aligned_text_ops(
+            format!("{tax:.2}€"),
+            170.,
+            current_height,
+            10.,
+            font_data,
+            &custom_font_id,
+        ));
+        ops.extend(right_aligned_text_ops(
+            format!("{total:.2}€"),
+            195.,
+            current_height,
+            10.,
+            font_data,
+            &custom_font_id,
+        ));
+        if !product.modifiers.is_empty() {
+            current_height -= 4.;
+            ops.extend(text_ops(
+                fit_text(font_data, &product.modifiers, 8.0, 230.),
+                20.,
+                current_height,
+                8.,
+                &custom_font_id,
+            ));
+        }
+        if product.discount() != Money::ZERO {
+            current_height -= 4.;
+            ops.extend(text_ops(
+                fit_text(
+                    font_data,
+                    &format!(
+                        "Precio sin descuento {:.2}€, descuento -{:.2}€ ({})",
+                        product.original_total_price(),
+                        product.discount(),
+                        product.discount_reason.as_deref().unwrap_or_default()
+                    ),
+                    8.0,
+                    230.,
+                ),
+                20.,
+                current_height,
+                8.,
+                &custom_font_id,
+            ));
+        }
+
+        current_height -= 6.;
+    }
+
+    // The tax summary and the totals always go together, start a new page if they don't fit
+    let summary_height = 25. + (taxes.len() as f32 * 6.) + 25.;
+    if current_height - summary_height < 15. {
+        pages.push(PdfPage::new(Mm(A4_WIDTH), Mm(A4_HEIGHT), ops));
+        ops = invoice_header_ops(invoice, &issuer, recipient, font_data, &custom_font_id);
+        current_height = INVOICE_LINES_START;
+    }
+
+    // Write the tax summary
+    current_height -= 4.;
+    ops.extend(separator_ops(current_height + 4.));
+    current_height -= 4.;
+    ops.extend(text_ops(
+        String::from("Tipo IVA"),
+        80.,
+        current_height,
+        10.,
+        &custom_font_id,
+    ));
+    ops.extend(right_aligned_text_ops(
+        String::from("Base Imponible"),
+        145.,
+        current_height,
+        10.,
+        font_data,
+        &custom_font_id,
+    ));
+    ops.extend(right_aligned_text_ops(
+        String::from("Cuota IVA"),
+        195.,
+        current_height,
+        10.,
+        font_data,
+        &custom_font_id,
+    ));
+    current_height -= 6.;
+
+    let mut total_base = Money::ZERO;
+    let mut total_tax = Money::ZERO;
+    for (tax_per, tax_group) in &taxes {
+        ops.extend(text_ops(
+            format!("{tax_per:.2}%"),
+            80.,
+            current_height,
+            10.,
+            &custom_font_id,
+        ));
+        ops.extend(right_aligned_text_ops(
+            format!("{:.2}€", tax_group.base),
+            145.,
+            current_height,
+            10.,
+            font_data,
+            &custom_font_id,
+        ));
+        ops.extend(right_aligned_text_ops(
+            format!("{:.2}€", tax_group.tax),
+            195.,
+            current_height,
+            10.,
+            font_data,
+            &custom_font_id,
+        ));
+
+        total_base += tax_group.base;
+        total_tax += tax_group.tax;
+        current_height -= 6.;
+    }
+
+    // Write the totals
+    current_height -= 4.;
+    ops.extend(separator_ops(current_height + 4.));
+    current_height -= 4.;
+    ops.extend(text_ops(
+        String::from("Total Base Imponible"),
+        80.,
+        current_height,
+        10.,
+        &custom_font_id,
+    ));
+    ops.extend(right_aligned_text_ops(
+        format!("{total_base:.2}€"),
+        195.,
+        current_height,
+        10.,
+        font_data,
+        &custom_font_id,
+    ));
+    current_height -= 6.;
+    ops.extend(text_ops(
+        String::from("Total IVA"),
+        80.,
+        current_height,
+        10.,
+        &custom_font_id,
+    ));
+    ops.extend(right_aligned_text_ops(
+        format!("{total_tax:.2}€"),
+        195.,
+        current_height,
+        10.,
+        font_data,
+        &custom_font_id,
+    ));
+    current_height -= 8.;
+    ops.extend(text_ops(
+        String::from("TOTAL"),
+        80.,
+        current_height,
+        14.,
+        &custom_font_id,
+    ));
+    ops.extend(right_aligned_text_ops(
+        format!("{:.2}€", invoice.total_price()),
+        195.,
+        current_height,
+        14.,
+        font_data,
+        &custom_font_id,
+    ));
+
+    pages.push(PdfPage::new(Mm(A4_WIDTH), Mm(A4_HEIGHT), ops));
+
+    // Save the PDF to a file
+    Ok(doc
+        .with_pages(pages)
+        .save(&PdfSaveOptions::default(), &mut Vec::new()))
 }
 
-/// TODO: Proper Doc Generation (Given a Simple Invoice)
-fn generate_invoice(_invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
-    todo!()
+/// Returns the operations needed to write the header of each page of an invoice (issuer, invoice data, recipient and lines header)
+fn invoice_header_ops(
+    invoice: &SimpleInvoice,
+    issuer: &Issuer,
+    recipient: Option<&InvoiceRecipient>,
+    font_data: &[u8],
+    font_id: &FontId,
+) -> Vec<Op> {
+    let mut ops = Vec::new();
+
+    // Issuer
+    ops.extend(text_ops(issuer.name.clone(), 15., 280., 16., font_id));
+    ops.extend(text_ops(
+        format!("NIF: {}", issuer.nif),
+        15.,
+        273.,
+        10.,
+        font_id,
+    ));
+    ops.extend(text_ops(issuer.address.clone(), 15., 268., 10., font_id));
+
+    // Invoice data
+    // invoices printed before being paid don't have a number yet
+    let (title, number) = match &invoice.invoice_number {
+        Some(number) if invoice.rectified_invoice_id.is_some() => {
+            ("FACTURA RECTIFICATIVA", format!("Nº: {number}"))
+        }
+        Some(number) => ("FACTURA", format!("Nº: {number}")),
+        None => (
+            "FACTURA PROFORMA",
+            format!("Ref.: {}", invoice.id.unwrap_or_default()),
+        ),
+    };
+    ops.extend(text_ops(String::from(title), 130., 280., 16., font_id));
+    ops.extend(text_ops(number, 130., 273., 10., font_id));
+    ops.extend(text_ops(
+        format!(
+            "Fecha: {}",
+            invoice
+                .issued_at()
+                .map(|date| date.format("%d/%m/%Y").to_string())
+                .unwrap_or_default()
+        ),
+        130.,
+        268.,
+        10.,
+        font_id,
+    ));
+
+    // Rectified invoice
+    if invoice.rectified_invoice_id.is_some() {
+        ops.extend(text_ops(
+            format!(
+                "Rectifica a la factura Nº: {}",
+                invoice
+                    .rectified_invoice_number
+                    .as_deref()
+                    .unwrap_or_default()
+            ),
+            130.,
+            263.,
+            10.,
+            font_id,
+        ));
+        ops.extend(text_ops(
+            fit_text(
+                font_data,
+                &format!(
+                    "Motivo: {}",
+                    invoice.rectification_reason.as_deref().unwrap_or_default()
+                ),
+                10.0,
+                80.,
+            ),
+            130.,
+            258.,
+            10.,
+            font_id,
+        ));
+    }
+
+    // Replaced simplified invoice
+    if invoice.replaced_invoice_id.is_some() {
+        ops.extend(text_ops(
+            fit_text(
+                font_data,
+                &format!(
+                    "Sustituye a la factura simplificada Nº: {}",
+                    invoice
+                        .replaced_invoice_number
+                        .as_deref()
+                        .unwrap_or_default()
+                ),
+                10.0,
+                80.,
+            ),
+            130.,
+            263.,
+            10.,
+            font_id,
+        ));
+    }
+
+    // Recipient
+    if let Some(recipient) = recipient {
+        ops.extend(text_ops(String::from("Cliente"), 15., 255., 12., font_id));
+        ops.extend(text_ops(recipient.name.clone(), 15., 249., 10., font_id));
+        ops.extend(text_ops(
+            format!("NIF: {}", recipient.nif),
+            15.,
+            244.,
+            10.,
+            font_id,
+        ));
+        ops.extend(text_ops(recipient.address.clone(), 15., 239., 10., font_id));
+    }
+
+    // Lines header
+    ops.extend(separator_ops(231.));
+    ops.extend(text_ops(String::from("Concepto"), 15., 226., 10., font_id));
+    ops.extend(text_ops(String::from("Base"), 114., 226., 10., font_id));
+    ops.extend(text_ops(String::from("IVA"), 137., 226., 10., font_id));
+    ops.extend(text_ops(String::from("Cuota"), 159., 226., 10., font_id));
+    ops.extend(text_ops(String::from("Total"), 185., 226., 10., font_id));
+    ops.extend(separator_ops(223.));
+
+    ops
 }
 
 fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
@@ -114,8 +562,12 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
     let custom_font = ParsedFont::from_bytes(TICKET_FONT_TTF, 0, &mut Vec::new()).unwrap();
     let custom_font_id = doc.add_font(&custom_font);
 
-    let (needed_doc_height, taxes) = calculate_needed_height_and_tax(invoice);
-    let mut total_price = 0.0;
+    let issuer = Issuer::load();
+    // only the invoices that have been issued (paid) have a record and its QR code
+    let qr_data = invoice_record::qr_data(invoice, &issuer.nif);
+
+    let (needed_doc_height, taxes) = calculate_needed_height_and_tax(invoice, qr_data.is_some());
+    let mut total_price = Money::ZERO;
     let mut current_height = needed_doc_height - 10.;
 
     // Write the title
@@ -145,7 +597,7 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
         },
         // Write text with the built-in font
         Op::WriteText {
-            items: vec![TextItem::Text("Hotel Name Name".to_string())],
+            items: vec![TextItem::Text(issuer.name)],
             font: custom_font_id.clone(),
         },
         // Add a line break to move down
@@ -184,10 +636,14 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
         },
         // Write text with the built-in font
         Op::WriteText {
-            items: vec![TextItem::Text(format!(
-                "Factura Simplificada Nº:{}",
-                &invoice.id.unwrap_or_default()
-            ))],
+            items: vec![TextItem::Text(match &invoice.invoice_number {
+                Some(number) if invoice.rectified_invoice_id.is_some() => {
+                    format!("Factura Rectificativa Nº:{number}")
+                }
+                Some(number) => format!("Factura Simplificada Nº:{number}"),
+                // tickets printed before being paid don't have a number yet
+                None => format!("Proforma Ref.:{}", invoice.id.unwrap_or_default()),
+            })],
             font: custom_font_id.clone(),
         },
         // Add a line break to move down
@@ -202,13 +658,13 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
     current_height -= 10.;
     for product in &invoice.products {
         // Check text width
-        let mut product_name = product.original_product.name.clone();
-        let mut text_width = get_text_width(&custom_font.original_bytes, &product_name, 12.0);
         // Adjust this number as needed
-        while text_width > 150. {
-            product_name.pop();
-            text_width = get_text_width(&custom_font.original_bytes, &product_name, 12.0);
-        }
+        let product_name = fit_text(
+            &custom_font.original_bytes,
+            &format!("{} x {}", product.quantity, product.original_product.name),
+            12.0,
+            150.,
+        );
 
         // Write the product name
         ops.extend(vec![
@@ -275,7 +731,7 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
             Op::WriteText {
                 items: vec![TextItem::Text(format!(
                     "{:.2}€",
-                    product.price.unwrap_or_default()
+                    product.original_total_price()
                 ))],
                 font: custom_font_id.clone(),
             },
@@ -287,12 +743,82 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
             Op::RestoreGraphicsState,
         ]);
 
-        total_price += product.price.unwrap_or_default();
+        if !product.modifiers.is_empty() {
+            current_height -= 4.;
+            ops.extend(text_ops(
+                fit_text(&custom_font.original_bytes, &product.modifiers, 10.0, 150.),
+                10.,
+                current_height,
+                10.,
+                &custom_font_id,
+            ));
+        }
+
+        // the discount goes on its own line under the product (which shows the price before it),
+        // the discount of the whole ticket goes under all the products
+        if product.discount() != Money::ZERO && !invoice.has_ticket_discount(product) {
+            current_height -= 5.;
+            ops.extend(text_ops(
+                fit_text(
+                    &custom_font.original_bytes,
+                    &format!(
+                        "Dto. {}",
+                        product.discount_reason.as_deref().unwrap_or_default()
+                    ),
+                    12.0,
+                    140.,
+                ),
+                10.,
+                current_height,
+                12.,
+                &custom_font_id,
+            ));
+            ops.extend(text_ops(
+                format!("{:.2}€", -product.discount()),
+                60.,
+                current_height,
+                12.,
+                &custom_font_id,
+            ));
+        }
+
+        total_price += product.total_price();
+        current_height -= 5.;
+    }
+
+    // Write the discount of the whole ticket
+    let ticket_discount = invoice.ticket_discount();
+    if ticket_discount != Money::ZERO {
+        ops.extend(text_ops(
+            fit_text(
+                &custom_font.original_bytes,
+                &format!(
+                    "Dto. ticket {}",
+                    invoice
+                        .ticket_discount_reason
+                        .as_deref()
+                        .unwrap_or_default()
+                ),
+                12.0,
+                150.,
+            ),
+            5.,
+            current_height,
+            12.,
+            &custom_font_id,
+        ));
+        ops.extend(text_ops(
+            format!("{:.2}€", -ticket_discount),
+            60.,
+            current_height,
+            12.,
+            &custom_font_id,
+        ));
         current_height -= 5.;
     }
 
     // Write total taxes
-    for (tax_per, tax_ammount) in taxes {
+    for (tax_per, tax_group) in taxes {
         ops.extend(vec![
             // Save the graphics state to allow for position resets later
             Op::SaveGraphicsState,
@@ -321,8 +847,7 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
             Op::WriteText {
                 items: vec![TextItem::Text(format!(
                     "IVA: {:.2}% {:.2}€",
-                    tax_per.to_string(),
-                    tax_ammount
+                    tax_per, tax_group.tax
                 ))],
                 font: custom_font_id.clone(),
             },
@@ -371,6 +896,17 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
         Op::RestoreGraphicsState,
     ]);
 
+    // Write the QR code of the invoice record
+    if let Some(qr_data) = &qr_data {
+        current_height -= RECEIPT_QR_SIZE + 5.;
+        ops.extend(qr_ops(
+            qr_data,
+            (RECEIPT_WIDTH - RECEIPT_QR_SIZE) / 2.,
+            current_height,
+            RECEIPT_QR_SIZE,
+        )?);
+    }
+
     // Create a page with our operations
     let page = PdfPage::new(Mm(80.0), Mm(needed_doc_height), ops);
 
@@ -380,35 +916,450 @@ fn generate_receipt(invoice: &SimpleInvoice) -> Result<Vec<u8>, &'static str> {
         .save(&PdfSaveOptions::default(), &mut Vec::new()))
 }
 
-fn calculate_needed_height_and_tax(invoice: &SimpleInvoice) -> (f32, HashMap<u64, f64>) {
-    let mut result = 20.; // 10 for title and 5 for subtitle and 5 for spacing between subtitle and products
-    let mut tax_totals: HashMap<u64, f64> = HashMap::new();
+/// Generates the receipt sized Z report of a closed cash session
+fn generate_z_report(report: &ZReport) -> Result<Vec<u8>, &'static str> {
+    let mut doc = PdfDocument::new("Z Report");
 
-    for product in &invoice.products {
-        result += 5.; // we need 5 for each product
+    let custom_font = ParsedFont::from_bytes(TICKET_FONT_TTF, 0, &mut Vec::new())
+        .ok_or("Failed to load Z report font")?;
+    let custom_font_id = doc.add_font(&custom_font);
+    let font_data = &custom_font.original_bytes;
+
+    // (label, amount) lines of each block of the report, blocks are separated by an empty line
+    let mut blocks: Vec<Vec<(String, String)>> = vec![
+        vec![
+            (
+                String::from("Apertura"),
+                report.opened_at.format("%d/%m/%Y %H:%M").to_string(),
+            ),
+            (
+                String::from("Cierre"),
+                report.closed_at.format("%d/%m/%Y %H:%M").to_string(),
+            ),
+            (String::from("Facturas"), report.invoices.to_string()),
+        ],
+        report
+            .payments
+            .iter()
+            .map(|(method, amount)| (method.to_string(), format!("{amount:.2}€")))
+            .collect(),
+    ];
+
+    let mut taxes_block = Vec::new();
+    for tax in &report.taxes {
+        taxes_block.push((
+            format!("Base {:.2}%", tax.tax_percentage),
+            format!("{:.2}€", tax.base),
+        ));
+        taxes_block.push((
+            format!("IVA {:.2}%", tax.tax_percentage),
+            format!("{:.2}€", tax.tax),
+        ));
+    }
+    taxes_block.push((String::from("TOTAL"), format!("{:.2}€", report.total)));
+    blocks.push(taxes_block);
+
+    if !report.discounts.is_empty() {
+        let mut discounts_block: Vec<(String, String)> = report
+            .discounts
+            .iter()
+            .map(|(reason, amount)| {
+                (
+                    fit_text(font_data, &format!("Dto. {reason}"), 10., 140.),
+                    format!("{amount:.2}€"),
+                )
+            })
+            .collect();
+        discounts_block.push((
+            String::from("Total descuentos"),
+            format!("{:.2}€", report.total_discounts()),
+        ));
+        blocks.push(discounts_block);
+    }
+
+    blocks.push(vec![
+        (
+            String::from("Fondo de caja"),
+            format!("{:.2}€", report.opening_float),
+        ),
+        (
+            String::from("Movimientos"),
+            format!("{:.2}€", report.cash_movements),
+        ),
+        (
+            String::from("Efectivo esperado"),
+            format!("{:.2}€", report.expected_cash),
+        ),
+        (
+            String::from("Efectivo contado"),
+            format!("{:.2}€", report.counted_amount),
+        ),
+        (
+            String::from("Descuadre"),
+            format!("{:.2}€", report.difference()),
+        ),
+    ]);
+
+    // 10 for the title, 10 for the subtitle, 5 for each line, 5 between blocks and 10 for margin bottom
+    let lines: usize = blocks.iter().map(|b| b.len()).sum();
+    let needed_doc_height = 20. + lines as f32 * 5. + blocks.len() as f32 * 5. + 10.;
+    let mut current_height = needed_doc_height - 10.;
+
+    let mut ops = text_ops(
+        Issuer::load().name,
+        10.,
+        current_height,
+        24.,
+        &custom_font_id,
+    );
+    current_height -= 8.;
+    ops.extend(text_ops(
+        format!("Informe Z - Caja Nº:{}", report.cash_session_id),
+        5.,
+        current_height,
+        12.,
+        &custom_font_id,
+    ));
+    current_height -= 2.;
+
+    for block in blocks {
+        current_height -= 5.;
+        for (label, amount) in block {
+            current_height -= 5.;
+            ops.extend(text_ops(label, 5., current_height, 10., &custom_font_id));
+            ops.extend(right_aligned_text_ops(
+                amount,
+                RECEIPT_WIDTH - 5.,
+                current_height,
+                10.,
+                font_data,
+                &custom_font_id,
+            ));
+        }
+    }
+
+    let page = PdfPage::new(Mm(RECEIPT_WIDTH), Mm(needed_doc_height), ops);
+
+    Ok(doc
+        .with_pages(vec![page])
+        .save(&PdfSaveOptions::default(), &mut Vec::new()))
+}
+
+/// Generates the receipt sized order of a preparation station, big enough to be read from a distance
+fn generate_kitchen_order(order: &KitchenOrder) -> Result<Vec<u8>, &'static str> {
+    let mut doc = PdfDocument::new("Kitchen Order");
+
+    let custom_font = ParsedFont::from_bytes(TICKET_FONT_TTF, 0, &mut Vec::new())
+        .ok_or("Failed to load kitchen order font")?;
+    let custom_font_id = doc.add_font(&custom_font);
+    let font_data = &custom_font.original_bytes;
+
+    // 10 for the station, 8 for the table, 6 for the time, 8 for each line (and 6 for its modifiers) and 10 for margin bottom
+    let modifier_lines = order
+        .lines
+        .iter()
+        .filter(|l| !l.modifiers.is_empty())
+        .count();
+    let needed_doc_height = 24. + order.lines.len() as f32 * 8. + modifier_lines as f32 * 6. + 10.;
+    let mut current_height = needed_doc_height - 10.;
 
-        // Calculate tax for the current product
-        let tax_percentage =
-            round_tax_percentage(&product.original_product.tax_percentage.unwrap_or(21.)); // Round tax percentage to two decimals
-        let tax_amount = calculate_tax(
-            &product.price,
-            &product.original_product.tax_percentage.unwrap_or(21.),
-        ) as f64; // This is the tax amount for this product
+    let mut ops = text_ops(
+        order.station_name.clone(),
+        5.,
+        current_height,
+        20.,
+        &custom_font_id,
+    );
+    current_height -= 8.;
+    ops.extend(text_ops(
+        format!("Mesa {} - {}", order.table_name, order.location_name),
+        5.,
+        current_height,
+        14.,
+        &custom_font_id,
+    ));
+    current_height -= 6.;
+    ops.extend(text_ops(
+        chrono::Local::now().format("%d/%m/%Y %H:%M").to_string(),
+        5.,
+        current_height,
+        10.,
+        &custom_font_id,
+    ));
 
-        // Accumulate the tax in the correct group (rounding tax percentage to two decimal places)
-        let entry = tax_totals.entry(tax_percentage).or_insert(0.0);
-        *entry += tax_amount;
+    for line in &order.lines {
+        current_height -= 8.;
+        ops.extend(text_ops(
+            format!("{}x", line.quantity),
+            5.,
+            current_height,
+            14.,
+            &custom_font_id,
+        ));
+        ops.extend(text_ops(
+            fit_text(font_data, &line.name, 14., 160.),
+            18.,
+            current_height,
+            14.,
+            &custom_font_id,
+        ));
+        if !line.modifiers.is_empty() {
+            current_height -= 6.;
+            ops.extend(text_ops(
+                fit_text(font_data, &line.modifiers, 12., 160.),
+                18.,
+                current_height,
+                12.,
+                &custom_font_id,
+            ));
+        }
     }
 
-    for _ in tax_totals.keys() {
-        result += 5.; // For each different tax we need 5 more space
+    let page = PdfPage::new(Mm(RECEIPT_WIDTH), Mm(needed_doc_height), ops);
+
+    Ok(doc
+        .with_pages(vec![page])
+        .save(&PdfSaveOptions::default(), &mut Vec::new()))
+}
+
+fn calculate_needed_height_and_tax(
+    invoice: &SimpleInvoice,
+    has_qr: bool,
+) -> (f32, BTreeMap<Decimal, TaxGroup>) {
+    let mut result = 20.; // 10 for title and 5 for subtitle and 5 for spacing between subtitle and products
+    let tax_totals = group_taxes(invoice);
+
+    result += invoice.products.len() as f32 * 5.; // we need 5 for each product
+    result += invoice
+        .products
+        .iter()
+        .filter(|p| !p.modifiers.is_empty())
+        .count() as f32
+        * 4.; // and 4 more for the modifiers of a product
+    result += invoice
+        .products
+        .iter()
+        .filter(|p| p.discount() != Money::ZERO && !invoice.has_ticket_discount(p))
+        .count() as f32
+        * 5.; // and 5 more for the discount of a product
+    if invoice.ticket_discount() != Money::ZERO {
+        result += 5.; // For the discount of the whole ticket
     }
+    result += tax_totals.len() as f32 * 5.; // For each different tax we need 5 more space
 
     result += 10.; // For the price
+    if has_qr {
+        result += RECEIPT_QR_SIZE + 5.; // For the QR code
+    }
     result += 10.; // For margin bottom
     (result, tax_totals)
 }
 
+/// Groups the products of an invoice by their tax percentage accumulating the tax-included total,
+/// the base and tax of each group are calculated from that total so they always reconcile with it
+pub(crate) fn group_taxes(invoice: &SimpleInvoice) -> BTreeMap<Decimal, TaxGroup> {
+    let mut group_totals: BTreeMap<Decimal, Money> = BTreeMap::new();
+
+    for product in &invoice.products {
+        // Accumulate the line total (quantity * unit price) in the correct group
+        let entry = group_totals
+            .entry(product.tax_percentage.normalize())
+            .or_default();
+        *entry += product.total_price();
+    }
+
+    group_totals
+        .into_iter()
+        .map(|(tax_percentage, total)| {
+            let (base, tax) = split_tax(total, tax_percentage);
+            (tax_percentage, TaxGroup { base, tax })
+        })
+        .collect()
+}
+
+/// Returns the base and tax of every product of an invoice (in the same order), taken out of the ones of its tax group:
+/// the cents lost rounding the bases of the lines one by one go to the biggest lines, so the lines of a group always
+/// add up to the base and tax of its summary
+pub(crate) fn line_taxes(invoice: &SimpleInvoice) -> Vec<TaxGroup> {
+    let groups = group_taxes(invoice);
+    let mut lines: Vec<TaxGroup> = invoice
+        .products
+        .iter()
+        .map(|product| {
+            let (base, tax) = split_tax(product.total_price(), product.tax_percentage);
+            TaxGroup { base, tax }
+        })
+        .collect();
+
+    for (tax_percentage, group) in &groups {
+        let mut indexes: Vec<usize> = (0..lines.len())
+            .filter(|index| invoice.products[*index].tax_percentage.normalize() == *tax_percentage)
+            .collect();
+        indexes.sort_by_key(|index| {
+            std::cmp::Reverse(Decimal::from(invoice.products[*index].total_price()).abs())
+        });
+
+        let cent = Money::from(Decimal::new(1, 2));
+        let mut difference = group.base
+            - indexes
+                .iter()
+                .map(|index| lines[*index].base)
+                .sum::<Money>();
+        let mut biggest_lines = indexes.iter().cycle();
+        while difference != Money::ZERO
+            && let Some(index) = biggest_lines.next()
+        {
+            let adjustment = if difference > Money::ZERO {
+                cent
+            } else {
+                -cent
+            };
+            lines[*index].base += adjustment;
+            lines[*index].tax -= adjustment;
+            difference -= adjustment;
+        }
+    }
+
+    lines
+}
+
+/// Returns the operations needed to write a text on the given position (in mm, from the bottom left)
+fn text_ops(text: String, x: f32, y: f32, font_size: f32, font_id: &FontId) -> Vec<Op> {
+    vec![
+        // Save the graphics state to allow for position resets later
+        Op::SaveGraphicsState,
+        // Start a text section (required for text operations)
+        Op::StartTextSection,
+        // Position the text cursor from the bottom left
+        Op::SetTextCursor {
+            pos: Point::new(Mm(x), Mm(y)),
+        },
+        Op::SetFontSize {
+            size: Pt(font_size),
+            font: font_id.clone(),
+        },
+        Op::SetLineHeight { lh: Pt(font_size) },
+        Op::SetFillColor {
+            col: Color::Rgb(Rgb {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                icc_profile: None,
+            }),
+        },
+        Op::WriteText {
+            items: vec![TextItem::Text(text)],
+            font: font_id.clone(),
+        },
+        // End the text section
+        Op::EndTextSection,
+        // Restore the graphics state
+        Op::RestoreGraphicsState,
+    ]
+}
+
+/// Returns the operations needed to write a text that ends on the given x position (in mm, from the bottom left)
+fn right_aligned_text_ops(
+    text: String,
+    right_x: f32,
+    y: f32,
+    font_size: f32,
+    font_data: &[u8],
+    font_id: &FontId,
+) -> Vec<Op> {
+    let width = Mm::from(Pt(get_text_width(font_data, &text, font_size))).0;
+    text_ops(text, right_x - width, y, font_size, font_id)
+}
+
+/// Returns the operations needed to draw an horizontal line across the A4 page on the given y position
+fn separator_ops(y: f32) -> Vec<Op> {
+    vec![
+        Op::SaveGraphicsState,
+        Op::SetOutlineThickness { pt: Pt(0.5) },
+        Op::DrawLine {
+            line: Line {
+                points: vec![
+                    LinePoint {
+                        p: Point::new(Mm(15.), Mm(y)),
+                        bezier: false,
+                    },
+                    LinePoint {
+                        p: Point::new(Mm(A4_WIDTH - 15.), Mm(y)),
+                        bezier: false,
+                    },
+                ],
+                is_closed: false,
+            },
+        },
+        Op::RestoreGraphicsState,
+    ]
+}
+
+/// Returns the operations needed to draw a QR code with the given data (a square of the given size in mm, from its bottom left)
+fn qr_ops(data: &str, x: f32, y: f32, size: f32) -> Result<Vec<Op>, &'static str> {
+    let code = qrcode::QrCode::new(data.as_bytes()).map_err(|_| "Failed to generate QR code")?;
+    let width = code.width();
+    let module_size = size / width as f32;
+
+    let mut ops = vec![
+        Op::SaveGraphicsState,
+        Op::SetFillColor {
+            col: Color::Rgb(Rgb {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                icc_profile: None,
+            }),
+        },
+    ];
+
+    // each dark module is a filled square, the first row of the code is the top one
+    for (i, color) in code.to_colors().iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+
+        let left = x + (i % width) as f32 * module_size;
+        let bottom = y + (width - 1 - i / width) as f32 * module_size;
+        let corners = [
+            (left, bottom),
+            (left + module_size, bottom),
+            (left + module_size, bottom + module_size),
+            (left, bottom + module_size),
+        ];
+
+        ops.push(Op::DrawPolygon {
+            polygon: Polygon {
+                rings: vec![PolygonRing {
+                    points: corners
+                        .iter()
+                        .map(|(x, y)| LinePoint {
+                            p: Point::new(Mm(*x), Mm(*y)),
+                            bezier: false,
+                        })
+                        .collect(),
+                }],
+                mode: PaintMode::Fill,
+                winding_order: WindingOrder::NonZero,
+            },
+        });
+    }
+
+    ops.push(Op::RestoreGraphicsState);
+    Ok(ops)
+}
+
+/// Removes characters from the end of the text until it fits in the given width
+fn fit_text(font_data: &[u8], text: &str, font_size: f32, max_width: f32) -> String {
+    let mut text = text.to_string();
+    let mut text_width = get_text_width(font_data, &text, font_size);
+    while text_width > max_width {
+        text.pop();
+        text_width = get_text_width(font_data, &text, font_size);
+    }
+    text
+}
+
 fn get_text_width(font_data: &[u8], text: &str, font_size: f32) -> f32 {
     let font = fontdue::Font::from_bytes(font_data, fontdue::FontSettings::default())
         .expect("Failed to load font");
@@ -418,13 +1369,126 @@ fn get_text_width(font_data: &[u8], text: &str, font_size: f32) -> f32 {
     })
 }
 
-fn round_tax_percentage(tax_perc: &f32) -> u64 {
-    (tax_perc * 100.0).round() as u64
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alegria::core::models::{product::Product, sold_product::SoldProduct};
+    use crate::alegria::utils::entities::payment_method::PaymentMethod;
+
+    fn product(quantity: i32, price: &str, tax_percentage: i32) -> SoldProduct {
+        SoldProduct {
+            id: None,
+            simple_invoice_id: 1,
+            original_product_id: 1,
+            quantity,
+            price: Some(price.parse().unwrap()),
+            original_price: None,
+            discount_reason: None,
+            modifiers: String::new(),
+            tax_percentage: Decimal::from(tax_percentage),
+            original_product: Product::default(),
+        }
+    }
+
+    fn invoice(products: Vec<SoldProduct>) -> SimpleInvoice {
+        SimpleInvoice {
+            id: Some(1),
+            payment_method: PaymentMethod::Efectivo,
+            products,
+            paid: true,
+            recipient: None,
+            rectified_invoice_id: None,
+            rectification_reason: None,
+            replaced_invoice_id: None,
+            invoice_number: None,
+            record_generated_at: None,
+            ticket_discount_reason: None,
+            is_deleted: false,
+            created_at: None,
+            updated_at: None,
+            rectifying_invoice_id: None,
+            rectified_invoice_number: None,
+            replacing_invoice_id: None,
+            replaced_invoice_number: None,
+        }
+    }
+
+    /// Panics if the lines of any tax group don't add up to the base and tax of its summary
+    fn assert_lines_add_up(invoice: &SimpleInvoice) {
+        let lines = line_taxes(invoice);
+        assert_eq!(lines.len(), invoice.products.len());
+
+        for (product, line) in invoice.products.iter().zip(&lines) {
+            assert_eq!(line.base + line.tax, product.total_price());
+        }
+        for (tax_percentage, group) in group_taxes(invoice) {
+            let group_lines: Vec<&TaxGroup> = invoice
+                .products
+                .iter()
+                .zip(&lines)
+                .filter(|(product, _)| product.tax_percentage.normalize() == tax_percentage)
+                .map(|(_, line)| line)
+                .collect();
+            let base: Money = group_lines.iter().map(|line| line.base).sum();
+            let tax: Money = group_lines.iter().map(|line| line.tax).sum();
+            assert_eq!((base, tax), (group.base, group.tax), "{tax_percentage}%");
+        }
+    }
+
+    #[test]
+    fn line_bases_add_up_to_the_tax_summary() {
+        // every 0.05 at 10% has a base of 0.05 on its own, but the three of them have a base of 0.14
+        let invoice = invoice(vec![
+            product(1, "0.05", 10),
+            product(1, "0.05", 10),
+            product(1, "0.05", 10),
+        ]);
+        let lines = line_taxes(&invoice);
+        assert_eq!(
+            lines.iter().map(|line| line.base).sum::<Money>(),
+            "0.14".parse().unwrap()
+        );
+        assert_lines_add_up(&invoice);
+
+        assert_lines_add_up(&invoice(vec![
+            product(3, "1.35", 10),
+            product(2, "2.15", 10),
+            product(7, "2.30", 21),
+            product(1, "0.99", 10),
+            product(1, "14.95", 21),
+            product(1, "0.07", 21),
+            product(1, "0.07", 21),
+            product(1, "0.07", 21),
+            product(1, "0.07", 21),
+        ]));
+    }
+
+    #[test]
+    fn the_cents_go_to_the_biggest_lines() {
+        let invoice = invoice(vec![
+            product(1, "0.05", 10),
+            product(2, "7.33", 10),
+            product(1, "0.05", 10),
+        ]);
+        let lines = line_taxes(&invoice);
+
+        for (product, line) in invoice.products.iter().zip(&lines) {
+            let (base, _) = split_tax(product.total_price(), product.tax_percentage);
+            if product.quantity == 1 {
+                assert_eq!(line.base, base);
+            }
+        }
+        assert_lines_add_up(&invoice);
+    }
 
-pub fn calculate_tax(price: &Option<f32>, tax_perc: &f32) -> f32 {
-    if let Some(price) = price {
-        return price * tax_perc / (100.0 + tax_perc);
+    #[test]
+    fn rectifying_lines_add_up_too() {
+        assert_lines_add_up(&invoice(vec![
+            product(-1, "0.05", 10),
+            product(-1, "0.05", 10),
+            product(-1, "0.05", 10),
+            product(-2, "3.99", 4),
+        ]));
+        assert_lines_add_up(&invoice(Vec::new()));
     }
-    0.0
 }
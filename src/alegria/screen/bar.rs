@@ -1,17 +1,25 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use iced::time::Instant;
 use iced::{Subscription, Task};
 use sqlx::{Pool, Postgres};
 
+use crate::alegria::core::models::bar_table::BarTable;
+use crate::alegria::core::models::invoice_payment::InvoicePayment;
+use crate::alegria::core::models::modifier_group::ModifierGroup;
+use crate::alegria::core::models::preparation_station::KitchenOrder;
 use crate::alegria::core::models::product::Product;
 use crate::alegria::core::models::product_category::ProductCategory;
 use crate::alegria::core::models::reservation::Reservation;
-use crate::alegria::core::models::simple_invoice::SimpleInvoice;
+use crate::alegria::core::models::simple_invoice::{InvoiceRecipient, SimpleInvoice};
+use crate::alegria::core::models::table_location::TableLocation;
 use crate::alegria::core::models::temporal_product::TemporalProduct;
-use crate::alegria::core::models::temporal_ticket::TemporalTicket;
+use crate::alegria::core::models::temporal_ticket::{TemporalTicket, TicketSplit};
 use crate::alegria::core::print::{AlegriaPrinter, TicketType};
+use crate::alegria::utils::entities::discount::{Discount, DiscountKind};
 use crate::alegria::utils::entities::payment_method::PaymentMethod;
+use crate::alegria::utils::money::{Money, parse_decimal};
 use crate::alegria::utils::pagination::{PaginationAction, PaginationConfig};
 use crate::alegria::widgets::toast::Toast;
 
@@ -58,12 +66,20 @@ pub enum Message {
     /// Callback after a numpad key (not a number) has been clicked
     OnNumpadKeyClicked(NumPadAction),
 
-    /// Callback after a table has been clicked
-    OnTableChange(usize),
-    /// Callback after we ask to change our current TableLocation
-    ChangeCurrentTablesLocation(TableLocation),
+    /// Callback after a table (by id) has been clicked
+    OnTableChange(i32),
+    /// Callback after we ask to change our current TableLocation (by id)
+    ChangeCurrentTablesLocation(i32),
+    /// Switches the tables between the grid and the floor plan
+    ToggleTablesView,
     /// When we click a product on the product list we have to add it to the temporal ticket...
     OnProductClicked(Option<i32>),
+    /// Selects or deselects a modifier (by id) for the product that's going to be added
+    ToggleModifier(i32),
+    /// Adds the product with the selected modifiers to the temporal ticket
+    ConfirmModifiers,
+    /// Closes the modifier selection without adding the product
+    CancelModifiers,
 
     /// Asks to unlock (delete the related invoice) of a locked ticket
     UnlockTicket(TemporalTicket),
@@ -73,11 +89,65 @@ pub enum Message {
     UpdateSelectedPrinter(AlegriaPrinter),
     /// Updates the selected ticket type  
     UpdateSelectedTicketType(TicketType),
+    /// Updates a field of the recipient of the invoice that's going to be printed
+    UpdateInvoiceRecipient(InvoiceRecipientField, String),
+    /// Callback when using the invoice recipient search text input
+    RecipientSearchInput(String),
+    /// Searches the companies and clients that match the invoice recipient search
+    SearchRecipients,
+    /// Callback after searching the invoice recipient
+    RecipientsFound(Vec<InvoiceRecipient>),
+    /// Fills the invoice recipient with the fiscal data of a search result
+    SelectRecipient(InvoiceRecipient),
     /// Callback after creating a simple invoice from the selected temporal ticket in order to print it  
     PrintTicket(Box<SimpleInvoice>),
     /// Callback after print job is completed
     PrintJobCompleted(Result<(), &'static str>),
 
+    /// Starts moving or merging the given temporal ticket (by id), the next clicked table will be the target
+    StartTicketTransfer(i32, TicketTransferKind),
+    /// Cancels the current ticket move or merge
+    CancelTicketTransfer,
+    /// Callback after moving or merging a temporal ticket
+    TicketTransferred(Result<(), String>),
+
+    /// Sends the lines of the given temporal ticket (by id) that haven't been sent yet to their preparation stations
+    SendKitchenOrder(i32),
+    /// Callback after sending the pending lines of a temporal ticket to their preparation stations
+    KitchenOrderSent(Result<(), String>),
+
+    /// Opens the discount modal for the given temporal ticket (by id) and its active line (if any)
+    OpenDiscountModal(i32),
+    /// Closes the discount modal without changing anything
+    CloseDiscountModal,
+    /// Changes what the discount of the discount modal is given to
+    UpdateDiscountTarget(DiscountTarget),
+    /// Updates the kind of the discount of the discount modal
+    UpdateDiscountKind(DiscountKind),
+    /// text_input of the value of the discount of the discount modal
+    DiscountValueInput(String),
+    /// text_input of the reason of the discount of the discount modal
+    DiscountReasonInput(String),
+    /// Applies the discount of the discount modal to its target
+    ApplyDiscount,
+    /// Removes the discount of the target of the discount modal
+    RemoveDiscount,
+    /// Callback after applying or removing a discount
+    DiscountUpdated(Result<(), String>),
+
+    /// Closes the void modal without voiding anything
+    CloseVoidModal,
+    /// Changes what is going to be voided on the void modal
+    UpdateVoidTarget(VoidTarget),
+    /// text_input of the reason of the void modal
+    VoidReasonInput(String),
+    /// text_input of who is voiding on the void modal
+    VoidedByInput(String),
+    /// Voids the target of the void modal
+    ConfirmVoid,
+    /// Callback after voiding a line or a whole ticket
+    Voided(Result<(), String>),
+
     /// Attempts to open the pay screen for the given temporal ticket
     OpenPayScreen(TemporalTicket),
     /// Attempts to load the currently occupied reservations for the PayScreeb
@@ -92,6 +162,24 @@ pub enum Message {
     PayTicket,
     /// Callback after executing the pay temporal ticket transaction
     PaidTemporalTicket(Result<(), String>),
+    /// text_input of the amount of the payment that's going to be added on the pay screen
+    PaymentAmountInput(String),
+    /// Adds a payment (selected method and amount) to the current bill of the pay screen
+    AddPayment,
+    /// Removes a registered payment (by index) of the current bill of the pay screen
+    RemovePayment(usize),
+    /// Changes how the ticket of the pay screen is going to be split
+    UpdateSplitMode(SplitMode),
+    /// Sets the quantity of a temporal product (by id) that goes into the current bill
+    UpdateSplitLineQuantity(i32, i32),
+    /// Sets the number of payers left when splitting the ticket evenly
+    UpdateSplitPayers(i32),
+    /// Callback after paying a split bill, true if the whole ticket has been paid
+    PaidTemporalTicketSplit(Result<bool, String>),
+    /// Callback after reloading the pay screen ticket once part of it has been paid
+    ReloadedPayTicket(Option<TemporalTicket>),
+    /// Callback after checking the stock of the products that have just been paid, warns about the ones that are low on stock
+    LowStockChecked(Vec<Product>),
 }
 
 // We only need to derive Debug and Clone because we're passing a State through the Loaded Message, there may be a better way to do this
@@ -115,12 +203,23 @@ impl std::fmt::Debug for State {
 #[derive(Clone)]
 pub enum SubScreen {
     Bar {
+        table_locations: Vec<TableLocation>,
+        bar_tables: Vec<BarTable>,
         temporal_tickets: Vec<TemporalTicket>,
         product_categories: Vec<ProductCategory>,
         product_category_products: Option<Vec<Product>>,
+        modifier_groups: Vec<ModifierGroup>,
         pagination: BarPagination,
         current_position: CurrentPosition,
         active_temporal_product: ActiveTemporalProduct,
+        /// Move or merge of a ticket waiting for the target table to be selected
+        ticket_transfer: Option<TicketTransfer>,
+        /// Product waiting for its modifiers to be chosen before being added to the ticket
+        modifier_selection: Option<ModifierSelection>,
+        /// Discount that's being given to a line or to the whole ticket
+        discount_form: Option<DiscountForm>,
+        /// Void of a line or of the whole ticket waiting for its reason
+        void_form: Option<VoidForm>,
     },
     Pay {
         origin_position: CurrentPosition,
@@ -128,6 +227,8 @@ pub enum SubScreen {
         selected_payment_method: PaymentMethod,
         selected_adeudo_room_id: Option<i32>,
         occupied_reservations: Vec<Reservation>,
+        split: SplitState,
+        payments: PaymentsState,
     },
 }
 
@@ -150,10 +251,12 @@ pub struct BarPagination {
 /// Holds the pagination state (generic, for various entities)
 #[derive(Default, Debug, Clone)]
 pub struct CurrentPosition {
-    /// Currently selected table location
-    table_location: TableLocation,
-    /// Currently selected table index
-    table_index: i32,
+    /// Currently selected table location id
+    table_location: i32,
+    /// Currently selected table id
+    table_id: i32,
+    /// How the tables are currently being shown
+    tables_view: TablesView,
     /// Currently selected product_category id (needed for correct button styling)
     selected_product_category: Option<i32>,
 }
@@ -173,13 +276,188 @@ pub enum TemporalProductField {
     Price,
 }
 
-/// Defines the different locations in which a table can be located at
+/// How the ticket of the pay screen is going to be paid
+#[derive(Default, Debug, Clone, PartialEq)]
+pub enum SplitMode {
+    /// The whole ticket in a single bill
+    #[default]
+    Whole,
+    /// Only the selected lines (or part of their quantity) go into the bill
+    ByLines,
+    /// The ticket is split evenly between a number of payers
+    Evenly,
+}
+
+/// Holds the state of the bill splitting of the pay screen
+#[derive(Debug, Clone)]
+pub struct SplitState {
+    mode: SplitMode,
+    /// Quantity of each temporal product (by id) that goes into the current bill
+    selected_quantities: HashMap<i32, i32>,
+    /// Number of payers that still have to pay when splitting evenly
+    payers: i32,
+}
+
+impl Default for SplitState {
+    fn default() -> Self {
+        Self {
+            mode: SplitMode::default(),
+            selected_quantities: HashMap::new(),
+            payers: 2,
+        }
+    }
+}
+
+impl SplitState {
+    /// Returns the amount of the bill that's currently being paid
+    fn bill_price(&self, ticket: &TemporalTicket) -> Money {
+        match self.ticket_split() {
+            Some(ticket_split) => ticket.split_price(&ticket_split),
+            None => ticket.total_price(),
+        }
+    }
+
+    /// Returns the part of the ticket that is going to be paid, None if it's the whole ticket
+    fn ticket_split(&self) -> Option<TicketSplit> {
+        match self.mode {
+            SplitMode::Whole => None,
+            SplitMode::ByLines => Some(TicketSplit::Lines(
+                self.selected_quantities
+                    .iter()
+                    .map(|(id, quantity)| (*id, *quantity))
+                    .collect(),
+            )),
+            SplitMode::Evenly => Some(TicketSplit::Evenly(self.payers)),
+        }
+    }
+}
+
+/// Holds the payments registered on the pay screen for the bill that's currently being paid
+#[derive(Default, Debug, Clone)]
+pub struct PaymentsState {
+    /// Payments registered so far, the bill is paid once they add up to its amount
+    registered: Vec<InvoicePayment>,
+    /// Amount of the next payment (for cash, what the customer gives), empty means whatever is left
+    amount_input: String,
+    /// Change due to the customer after the last cash payment
+    change: Option<Money>,
+}
+
+impl PaymentsState {
+    /// Returns what's left to pay of the given bill amount
+    fn remaining(&self, bill_price: Money) -> Money {
+        bill_price - self.registered.iter().map(|p| p.amount).sum::<Money>()
+    }
+
+    /// Returns true if any of the registered payments is an adeudo
+    fn has_adeudo(&self) -> bool {
+        self.registered
+            .iter()
+            .any(|p| p.payment_method == PaymentMethod::Adeudo)
+    }
+}
+
+/// A move or merge of a ticket that's waiting for the user to select the target table
+#[derive(Debug, Clone)]
+pub struct TicketTransfer {
+    temporal_ticket_id: i32,
+    kind: TicketTransferKind,
+}
+
+/// A product that's going to be added to the ticket once its modifiers are chosen
+#[derive(Debug, Clone)]
+pub struct ModifierSelection {
+    product_id: i32,
+    product_name: String,
+    /// Modifier groups of the product and of its category
+    groups: Vec<ModifierGroup>,
+    /// Ids of the chosen modifiers
+    selected: Vec<i32>,
+}
+
+/// A discount that's being given on the discount modal
+#[derive(Debug, Clone)]
+pub struct DiscountForm {
+    temporal_ticket_id: i32,
+    /// Line of the ticket that was active when the modal was opened
+    temporal_product_id: Option<i32>,
+    target: DiscountTarget,
+    kind: DiscountKind,
+    value_input: String,
+    reason: String,
+}
+
+impl DiscountForm {
+    /// Returns the discount of the form if it's valid
+    fn discount(&self) -> Option<Discount> {
+        let discount = Discount {
+            kind: self.kind,
+            value: parse_decimal(&self.value_input).ok()?,
+            reason: self.reason.clone(),
+        };
+
+        discount.is_valid().then_some(discount)
+    }
+}
+
+/// Identifies what a discount is given to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiscountTarget {
+    /// The line that was active when the discount modal was opened
+    Line,
+    /// Every line of the ticket
+    Ticket,
+}
+
+/// A void that's waiting for its reason (and who is doing it) on the void modal
+#[derive(Debug, Clone)]
+pub struct VoidForm {
+    temporal_ticket_id: i32,
+    /// Line of the ticket that's going to be voided (the active one or the first one of the ticket)
+    temporal_product_id: Option<i32>,
+    target: VoidTarget,
+    reason: String,
+    voided_by: String,
+    /// New values of the line when its total has been lowered on the numpad (only the difference is voided),
+    /// None voids the whole line
+    edited_line: Option<TemporalProduct>,
+}
+
+impl VoidForm {
+    /// Returns true if the void can be submitted
+    fn is_valid(&self) -> bool {
+        !self.reason.trim().is_empty()
+            && !self.voided_by.trim().is_empty()
+            && (self.target == VoidTarget::Ticket || self.temporal_product_id.is_some())
+    }
+}
+
+/// Identifies what is voided
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoidTarget {
+    /// A single line of the ticket
+    Line,
+    /// Every line of the ticket (the ticket is deleted)
+    Ticket,
+}
+
+/// Identifies what to do with a ticket when transferring it to another table
+#[derive(Debug, Clone, PartialEq)]
+pub enum TicketTransferKind {
+    /// Moves the ticket to a free table
+    Move,
+    /// Adds the products of the ticket to the ticket of another table
+    Merge,
+}
+
+/// Defines the ways the tables of a location can be shown
 #[derive(Default, Debug, Clone, PartialEq)]
-pub enum TableLocation {
+pub enum TablesView {
+    /// Tables in rows, by their order
     #[default]
-    Bar,
-    Resturant,
-    Garden,
+    Grid,
+    /// Tables placed where they are on the floor plan of the location
+    FloorPlan,
 }
 
 /// Identifies an action of the numpad
@@ -197,6 +475,19 @@ pub struct PrintModal {
     selected_printer: Box<Option<AlegriaPrinter>>,
     all_printers: Arc<Vec<AlegriaPrinter>>,
     //default_printer: Arc<Option<AlegriaPrinter>>,
+    /// Recipient of the document when printing a full invoice
+    invoice_recipient: InvoiceRecipient,
+    /// Search of the invoice recipient among the companies and clients
+    recipient_search: String,
+    recipient_search_results: Vec<InvoiceRecipient>,
+}
+
+/// Identifies a field of the invoice recipient on the print modal
+#[derive(Debug, Clone)]
+pub enum InvoiceRecipientField {
+    Name,
+    Nif,
+    Address,
 }
 
 /// Identifies a modal action (the only modal is the print ticket one)
@@ -239,27 +530,88 @@ async fn init_page(
     database: Arc<Pool<Postgres>>,
     position: Option<CurrentPosition>,
 ) -> Result<Box<State>, anywho::Error> {
+    let table_locations = TableLocation::get_all(database.clone()).await?;
+    let bar_tables = BarTable::get_all(database.clone()).await?;
     let temporal_tickets = TemporalTicket::get_all(database.clone()).await?;
     let product_categories = ProductCategory::get_all(database.clone()).await?;
+    let modifier_groups = ModifierGroup::get_all(database.clone()).await?;
 
-    let current_position = position.unwrap_or_default();
+    // by default we select the first table of the first location
+    let current_position = position.unwrap_or_else(|| {
+        let table_location = table_locations
+            .first()
+            .and_then(|l| l.id)
+            .unwrap_or_default();
+        let table_id = bar_tables
+            .iter()
+            .find(|t| t.location_id == Some(table_location))
+            .and_then(|t| t.id)
+            .unwrap_or_default();
+
+        CurrentPosition {
+            table_location,
+            table_id,
+            ..Default::default()
+        }
+    });
 
     Ok(Box::from(State::Ready {
         sub_screen: SubScreen::Bar {
+            table_locations,
+            bar_tables,
             temporal_tickets,
             product_categories,
             product_category_products: None,
+            modifier_groups,
             pagination: BarPagination::default(),
             current_position,
             active_temporal_product: ActiveTemporalProduct::default(),
+            ticket_transfer: None,
+            modifier_selection: None,
+            discount_form: None,
+            void_form: None,
         },
     }))
 }
 
-pub fn match_table_location_with_number(tl: &TableLocation) -> i32 {
-    match tl {
-        TableLocation::Bar => 0,
-        TableLocation::Resturant => 1,
-        TableLocation::Garden => 2,
+/// Prints the pending lines of the given temporal ticket on the printer of their preparation station
+/// and marks them as sent, lines without a station are just marked as sent.
+/// If a station can't be printed the rest are still sent, their lines will stay pending.
+async fn send_kitchen_orders(
+    database: Arc<Pool<Postgres>>,
+    temporal_ticket_id: i32,
+    printers: Arc<Vec<AlegriaPrinter>>,
+) -> Result<(), String> {
+    let orders = KitchenOrder::get_pending(database.clone(), temporal_ticket_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut errors = Vec::new();
+    for order in orders {
+        if order.preparation_station_id.is_some() {
+            let Some(printer) = order
+                .printer_name
+                .as_deref()
+                .and_then(|name| AlegriaPrinter::find_by_name(&printers, name))
+            else {
+                errors.push(format!("{}: printer not found", order.station_name));
+                continue;
+            };
+
+            if let Err(e) = Arc::new(printer).print_kitchen_order(order.clone()).await {
+                errors.push(format!("{}: {e}", order.station_name));
+                continue;
+            }
+        }
+
+        KitchenOrder::mark_sent(database.clone(), order)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
     }
 }
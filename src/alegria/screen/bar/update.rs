@@ -8,18 +8,29 @@ use super::{Bar, State};
 use crate::alegria::{
     core::{
         models::{
-            product::Product, reservation::Reservation, simple_invoice::SimpleInvoice,
-            temporal_product::TemporalProduct, temporal_ticket::TemporalTicket,
+            invoice_payment::InvoicePayment,
+            modifier_group::ModifierGroup,
+            product::Product,
+            reservation::Reservation,
+            simple_invoice::{InvoiceRecipient, SimpleInvoice},
+            temporal_product::TemporalProduct,
+            temporal_ticket::TemporalTicket,
         },
         print::TicketType,
     },
     screen::bar::{
-        Action, Message, NumPadAction, PaginationAction, PrintModal, SubScreen,
-        TemporalProductField,
+        Action, CurrentPosition, DiscountForm, DiscountTarget, InvoiceRecipientField, Message,
+        ModifierSelection, NumPadAction, PaginationAction, PaymentsState, PrintModal, SplitMode,
+        SplitState, SubScreen, TablesView, TemporalProductField, TicketTransfer,
+        TicketTransferKind, VoidForm, VoidTarget,
+    },
+    utils::{
+        entities::{discount::DiscountKind, payment_method::PaymentMethod},
+        money::{Money, parse_decimal},
     },
-    utils::entities::payment_method::PaymentMethod,
     widgets::toast::Toast,
 };
+use crate::fl;
 
 impl Bar {
     #[allow(clippy::only_used_in_recursion)]
@@ -131,6 +142,9 @@ impl Bar {
                     selected_printer: default_printer,
                     all_printers: Arc::new(all_printers),
                     //default_printer: Arc::new(*default_printer),
+                    invoice_recipient: InvoiceRecipient::default(),
+                    recipient_search: String::new(),
+                    recipient_search_results: Vec::new(),
                 };
                 Action::None
             }
@@ -224,6 +238,8 @@ impl Bar {
                     #[allow(clippy::collapsible_match)]
                     if let SubScreen::Bar {
                         active_temporal_product,
+                        temporal_tickets,
+                        void_form,
                         ..
                     } = sub_screen
                     {
@@ -250,7 +266,7 @@ impl Bar {
                                         );
 
                                     if !ignore_action {
-                                        if let Ok(num) = new_value.parse::<f32>() {
+                                        if let Ok(num) = new_value.parse::<Money>() {
                                             mutable_product.price = Some(num);
 
                                             if let Some(active_product) =
@@ -259,7 +275,7 @@ impl Bar {
                                                 active_product.price_input = new_value;
                                             }
                                         } else if new_value.is_empty() {
-                                            mutable_product.price = Some(0.0);
+                                            mutable_product.price = Some(Money::ZERO);
 
                                             if let Some(active_product) =
                                                 &mut active_temporal_product.temporal_product
@@ -271,6 +287,51 @@ impl Bar {
                                 }
                             }
 
+                            // lowering the total of a line removes part of it, it has to be voided with a reason
+                            let saved_product = temporal_tickets
+                                .iter()
+                                .flat_map(|ticket| &ticket.products)
+                                .find(|product| product.id == mutable_product.id);
+                            if let Some(saved_product) = saved_product
+                                && mutable_product.total_price() < saved_product.total_price()
+                            {
+                                // we keep editing the new values until the void is confirmed
+                                if let Some(active_product) =
+                                    &mut active_temporal_product.temporal_product
+                                {
+                                    active_product.quantity = mutable_product.quantity;
+                                    active_product.price = mutable_product.price;
+                                }
+
+                                match void_form {
+                                    Some(form)
+                                        if form.temporal_product_id == mutable_product.id
+                                            && form.edited_line.is_some() =>
+                                    {
+                                        form.edited_line = Some(mutable_product);
+                                    }
+                                    _ => {
+                                        *void_form = Some(VoidForm {
+                                            temporal_ticket_id: saved_product.temporal_ticket_id,
+                                            temporal_product_id: saved_product.id,
+                                            target: VoidTarget::Line,
+                                            reason: String::new(),
+                                            voided_by: String::new(),
+                                            edited_line: Some(mutable_product),
+                                        });
+                                    }
+                                }
+                                return Action::None;
+                            }
+
+                            // the line is back to (at least) its total, nothing has to be voided
+                            if void_form.as_ref().is_some_and(|form| {
+                                form.edited_line.is_some()
+                                    && form.temporal_product_id == mutable_product.id
+                            }) {
+                                *void_form = None;
+                            }
+
                             return Action::Run(Task::perform(
                                 TemporalProduct::edit(database.clone(), mutable_product),
                                 |res| match res {
@@ -290,6 +351,16 @@ impl Bar {
             Message::OnNumpadNumberClicked(num) => {
                 #[allow(clippy::collapsible_match)]
                 if let State::Ready { sub_screen, .. } = &mut self.state {
+                    // on the pay screen the numpad writes the amount of the next payment
+                    if let SubScreen::Pay { payments, .. } = sub_screen {
+                        let value = format!("{}{}", payments.amount_input, num);
+                        return self.update(
+                            Message::PaymentAmountInput(value),
+                            &database.clone(),
+                            now,
+                        );
+                    }
+
                     #[allow(clippy::collapsible_match)]
                     if let SubScreen::Bar {
                         active_temporal_product,
@@ -334,22 +405,35 @@ impl Bar {
             Message::OnNumpadKeyClicked(action_type) => {
                 #[allow(clippy::collapsible_match)]
                 if let State::Ready { sub_screen, .. } = &mut self.state {
+                    if let SubScreen::Pay { payments, .. } = sub_screen {
+                        match action_type {
+                            NumPadAction::Delete => payments.amount_input.clear(),
+                            NumPadAction::Erase => {
+                                payments.amount_input.pop();
+                            }
+                            NumPadAction::Decimal => {
+                                if !payments.amount_input.contains('.') {
+                                    payments.amount_input.push('.');
+                                }
+                            }
+                        }
+                        return Action::None;
+                    }
+
                     #[allow(clippy::collapsible_match)]
                     if let SubScreen::Bar {
                         active_temporal_product,
                         temporal_tickets,
                         current_position,
+                        void_form,
                         ..
                     } = sub_screen
                     {
-                        // we will need the current ticket to check if there are no more products we will need to delete the temporal ticket
+                        // we will need the current ticket to know what can be voided
                         // and we also need to not allow input the current temporal ticket is_some and simple_invoice_id is_some
                         let current_ticket = temporal_tickets.iter().find(|x| {
-                            x.ticket_location
-                                == super::match_table_location_with_number(
-                                    &current_position.table_location,
-                                )
-                                && x.table_id == current_position.table_index
+                            x.ticket_location == current_position.table_location
+                                && x.table_id == current_position.table_id
                         });
 
                         if current_ticket.is_some_and(|x| x.simple_invoice_id.is_some()) {
@@ -357,90 +441,35 @@ impl Bar {
                         }
 
                         match action_type {
-                            // we clicked the delete button of the numpad
+                            // we clicked the delete button of the numpad, lines are voided (with a reason) instead of deleted
                             NumPadAction::Delete => {
-                                // if we have a product selected we delete that product
-                                if let Some(active_product) =
-                                    &active_temporal_product.temporal_product
-                                {
-                                    let mut tasks = vec![];
-                                    tasks.push(Task::perform(
-                                        TemporalProduct::delete(
-                                            database.clone(),
-                                            active_product.id.unwrap_or_default(),
-                                        ),
-                                        |res| match res {
-                                            Ok(_) => Message::FetchTemporalTickets,
-                                            Err(err) => {
-                                                eprintln!("{err}");
-                                                Message::FetchTemporalTickets
-                                            }
-                                        },
-                                    ));
-
-                                    active_temporal_product.temporal_product = None;
-                                    active_temporal_product.temporal_product_field = None;
+                                let Some(ticket) = current_ticket else {
+                                    return Action::None;
+                                };
 
-                                    // check if there are no more products we will need to delete the temporal ticket
-                                    if let Some(ticket) = current_ticket
-                                        && ticket.products.len() == 1
-                                    {
-                                        tasks.push(Task::perform(
-                                            TemporalTicket::delete(
-                                                database.clone(),
-                                                ticket.id.unwrap_or_default(),
-                                            ),
-                                            |res| match res {
-                                                Ok(_) => Message::FetchTemporalTickets,
-                                                Err(err) => {
-                                                    eprintln!("{err}");
-                                                    Message::FetchTemporalTickets
-                                                }
-                                            },
-                                        ));
-                                    }
+                                // the active line if it belongs to the ticket, if not the first line of the ticket
+                                let temporal_product_id = active_temporal_product
+                                    .temporal_product
+                                    .as_ref()
+                                    .and_then(|p| p.id)
+                                    .filter(|id| ticket.products.iter().any(|p| p.id == Some(*id)))
+                                    .or_else(|| ticket.products.first().and_then(|p| p.id));
 
-                                    return Action::Run(Task::batch(tasks));
-
-                                // if we don't have a product selected but there is a ticket and we pressed delete
-                                } else if let Some(ticket) = current_ticket {
-                                    if let Some(product) = ticket.products.first() {
-                                        let mut tasks = vec![];
-                                        // we delete the first product of the list
-                                        tasks.push(Task::perform(
-                                            TemporalProduct::delete(
-                                                database.clone(),
-                                                product.id.unwrap_or_default(),
-                                            ),
-                                            |res| match res {
-                                                Ok(_) => Message::FetchTemporalTickets,
-                                                Err(err) => {
-                                                    eprintln!("{err}");
-                                                    Message::FetchTemporalTickets
-                                                }
-                                            },
-                                        ));
-
-                                        // check if there are no more products we will need to delete the temporal ticket
-                                        if ticket.products.len() == 1 {
-                                            tasks.push(Task::perform(
-                                                TemporalTicket::delete(
-                                                    database.clone(),
-                                                    ticket.id.unwrap_or_default(),
-                                                ),
-                                                |res| match res {
-                                                    Ok(_) => Message::FetchTemporalTickets,
-                                                    Err(err) => {
-                                                        eprintln!("{err}");
-                                                        Message::FetchTemporalTickets
-                                                    }
-                                                },
-                                            ));
-                                        }
-                                        return Action::Run(Task::batch(tasks));
-                                    }
-                                    return Action::None;
+                                if let Some(temporal_ticket_id) = ticket.id {
+                                    *void_form = Some(VoidForm {
+                                        temporal_ticket_id,
+                                        temporal_product_id,
+                                        target: if temporal_product_id.is_some() {
+                                            VoidTarget::Line
+                                        } else {
+                                            VoidTarget::Ticket
+                                        },
+                                        reason: String::new(),
+                                        voided_by: String::new(),
+                                        edited_line: None,
+                                    });
                                 }
+                                return Action::None;
                             }
                             // we clicked the erase button of the numpad
                             NumPadAction::Erase => {
@@ -529,16 +558,50 @@ impl Bar {
                 }
                 Action::None
             }
-            Message::OnTableChange(table_index) => {
+            Message::OnTableChange(table_id) => {
                 if let State::Ready { sub_screen, .. } = &mut self.state {
                     #[allow(clippy::collapsible_match)]
                     if let SubScreen::Bar {
                         active_temporal_product,
                         current_position,
+                        ticket_transfer,
                         ..
                     } = sub_screen
                     {
-                        current_position.table_index = table_index as i32;
+                        // the clicked table is the target of the pending ticket move/merge
+                        if let Some(transfer) = ticket_transfer.take() {
+                            current_position.table_id = table_id;
+                            active_temporal_product.temporal_product = None;
+                            active_temporal_product.temporal_product_field = None;
+
+                            let ticket_location = current_position.table_location;
+                            let callback = |res: Result<(), sqlx::Error>| {
+                                Message::TicketTransferred(res.map_err(|e| e.to_string()))
+                            };
+
+                            return Action::Run(match transfer.kind {
+                                TicketTransferKind::Move => Task::perform(
+                                    TemporalTicket::move_to_table(
+                                        database.clone(),
+                                        transfer.temporal_ticket_id,
+                                        table_id,
+                                        ticket_location,
+                                    ),
+                                    callback,
+                                ),
+                                TicketTransferKind::Merge => Task::perform(
+                                    TemporalTicket::merge_into_table(
+                                        database.clone(),
+                                        transfer.temporal_ticket_id,
+                                        table_id,
+                                        ticket_location,
+                                    ),
+                                    callback,
+                                ),
+                            });
+                        }
+
+                        current_position.table_id = table_id;
                         active_temporal_product.temporal_product = None;
                         active_temporal_product.temporal_product_field = None;
                         return self.update(Message::FetchTemporalTickets, &database.clone(), now);
@@ -550,10 +613,36 @@ impl Bar {
                 if let State::Ready { sub_screen, .. } = &mut self.state {
                     #[allow(clippy::collapsible_match)]
                     if let SubScreen::Bar {
-                        current_position, ..
+                        bar_tables,
+                        current_position,
+                        active_temporal_product,
+                        ..
                     } = sub_screen
                     {
+                        // select the first table of the new location
                         current_position.table_location = location;
+                        current_position.table_id = bar_tables
+                            .iter()
+                            .find(|t| t.location_id == Some(location))
+                            .and_then(|t| t.id)
+                            .unwrap_or_default();
+                        active_temporal_product.temporal_product = None;
+                        active_temporal_product.temporal_product_field = None;
+                    }
+                }
+                Action::None
+            }
+            Message::ToggleTablesView => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        current_position, ..
+                    } = sub_screen
+                    {
+                        current_position.tables_view = match current_position.tables_view {
+                            TablesView::Grid => TablesView::FloorPlan,
+                            TablesView::FloorPlan => TablesView::Grid,
+                        };
                     }
                 }
                 Action::None
@@ -563,18 +652,18 @@ impl Bar {
                     #[allow(clippy::collapsible_match)]
                     if let SubScreen::Bar {
                         temporal_tickets,
+                        product_category_products,
+                        modifier_groups,
                         current_position,
                         active_temporal_product,
+                        modifier_selection,
                         ..
                     } = sub_screen
                     {
                         // not allow input the current temporal ticket is_some and simple_invoice_id is_some
                         let current_ticket = temporal_tickets.iter().find(|x| {
-                            x.ticket_location
-                                == super::match_table_location_with_number(
-                                    &current_position.table_location,
-                                )
-                                && x.table_id == current_position.table_index
+                            x.ticket_location == current_position.table_location
+                                && x.table_id == current_position.table_id
                         });
 
                         if current_ticket.is_some_and(|x| x.simple_invoice_id.is_some()) {
@@ -585,37 +674,97 @@ impl Bar {
                             // Deselect the active temporal product
                             active_temporal_product.temporal_product = None;
 
-                            let temporal_ticket = TemporalTicket {
-                                id: None,
-                                table_id: current_position.table_index,
-                                ticket_location: super::match_table_location_with_number(
-                                    &current_position.table_location.clone(),
-                                ),
-                                ticket_status: 0,
-                                simple_invoice_id: None,
-                                products: Vec::new(),
-                            };
+                            // if the product has modifiers they have to be chosen before adding it
+                            let product = product_category_products
+                                .iter()
+                                .flatten()
+                                .find(|p| p.id == Some(new_product_id));
+                            let groups: Vec<ModifierGroup> = modifier_groups
+                                .iter()
+                                .filter(|g| {
+                                    g.applies_to(
+                                        Some(new_product_id),
+                                        product.and_then(|p| p.category_id),
+                                    )
+                                })
+                                .cloned()
+                                .collect();
+
+                            if !groups.is_empty() {
+                                *modifier_selection = Some(ModifierSelection {
+                                    product_id: new_product_id,
+                                    product_name: product
+                                        .map(|p| p.name.clone())
+                                        .unwrap_or_default(),
+                                    groups,
+                                    selected: Vec::new(),
+                                });
+                                return Action::None;
+                            }
 
                             // Upsert a temporal ticket with the clicked product
-                            return Action::Run(Task::perform(
-                                TemporalTicket::upsert_ticket_by_id_and_tableloc(
-                                    database.clone(),
-                                    temporal_ticket,
-                                    new_product_id,
-                                ),
-                                |res| match res {
-                                    Ok(_) => Message::FetchTemporalTickets,
-                                    Err(err) => {
-                                        eprintln!("{err}");
-                                        Message::AddToast(Toast::error_toast(err.to_string()))
-                                    }
-                                },
+                            return Action::Run(add_product_to_table(
+                                database,
+                                current_position,
+                                new_product_id,
+                                Vec::new(),
                             ));
                         }
                     }
                 }
                 Action::None
             }
+            Message::ToggleModifier(modifier_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        modifier_selection: Some(selection),
+                        ..
+                    } = sub_screen
+                    {
+                        if let Some(index) =
+                            selection.selected.iter().position(|id| *id == modifier_id)
+                        {
+                            selection.selected.remove(index);
+                        } else {
+                            selection.selected.push(modifier_id);
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::ConfirmModifiers => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        current_position,
+                        modifier_selection,
+                        ..
+                    } = sub_screen
+                        && let Some(selection) = modifier_selection.take()
+                    {
+                        return Action::Run(add_product_to_table(
+                            database,
+                            current_position,
+                            selection.product_id,
+                            selection.selected,
+                        ));
+                    }
+                }
+                Action::None
+            }
+            Message::CancelModifiers => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        modifier_selection, ..
+                    } = sub_screen
+                    {
+                        *modifier_selection = None;
+                    }
+                }
+                Action::None
+            }
             Message::UnlockTicket(ticket) => Action::Run(Task::perform(
                 SimpleInvoice::unlock_temporal_ticket(database.clone(), ticket.clone()),
                 |res| match res {
@@ -637,6 +786,14 @@ impl Bar {
                         Action::None
                     }
                     super::PrintTicketModalActions::PrintTicket(ticket) => {
+                        if self.printer_modal.ticket_type == TicketType::Invoice
+                            && !self.printer_modal.invoice_recipient.is_valid()
+                        {
+                            return Action::AddToast(Toast::error_toast(
+                                "Missing invoice recipient data",
+                            ));
+                        }
+
                         match ticket.simple_invoice_id {
                             Some(invoice_id) => {
                                 // if the current ticket is already a simple invoice get it and print it
@@ -679,11 +836,77 @@ impl Bar {
                 self.printer_modal.ticket_type = ticket_type;
                 Action::None
             }
+            Message::UpdateInvoiceRecipient(field, new_value) => {
+                // the fiscal data no longer is the one of the company
+                let recipient = &mut self.printer_modal.invoice_recipient;
+                recipient.company_id = None;
+                match field {
+                    InvoiceRecipientField::Name => recipient.name = new_value,
+                    InvoiceRecipientField::Nif => recipient.nif = new_value,
+                    InvoiceRecipientField::Address => recipient.address = new_value,
+                }
+                Action::None
+            }
+            Message::RecipientSearchInput(new_value) => {
+                self.printer_modal.recipient_search = new_value;
+                Action::None
+            }
+            Message::SearchRecipients => {
+                if self.printer_modal.recipient_search.trim().is_empty() {
+                    return Action::None;
+                }
+
+                Action::Run(Task::perform(
+                    InvoiceRecipient::search(
+                        database.clone(),
+                        self.printer_modal.recipient_search.clone(),
+                    ),
+                    |res| match res {
+                        Ok(recipients) => Message::RecipientsFound(recipients),
+                        Err(err) => {
+                            eprintln!("{err}");
+                            Message::AddToast(Toast::error_toast(err))
+                        }
+                    },
+                ))
+            }
+            Message::RecipientsFound(recipients) => {
+                self.printer_modal.recipient_search_results = recipients;
+                Action::None
+            }
+            Message::SelectRecipient(recipient) => {
+                self.printer_modal.invoice_recipient = recipient;
+                self.printer_modal.recipient_search_results.clear();
+                Action::None
+            }
             // Callback after creating a simple invoice from the selected temporal ticket in order to print it
             Message::PrintTicket(invoice) => {
                 // TODO: Change TemporalTicket ticket_status when printed
                 if let Some(p) = self.printer_modal.selected_printer.as_ref() {
                     let printer = Arc::new(p.clone());
+
+                    // full invoices need the recipient saved on the invoice before printing
+                    if self.printer_modal.ticket_type == TicketType::Invoice {
+                        let mut invoice = *invoice;
+                        let recipient = self.printer_modal.invoice_recipient.clone();
+                        invoice.recipient = Some(recipient.clone());
+
+                        let database = database.clone();
+                        return Action::Run(Task::perform(
+                            async move {
+                                SimpleInvoice::set_recipient(
+                                    database,
+                                    invoice.id.unwrap_or_default(),
+                                    recipient,
+                                )
+                                .await
+                                .map_err(|_| "Failed to save the invoice recipient")?;
+                                printer.print(invoice, TicketType::Invoice).await
+                            },
+                            Message::PrintJobCompleted,
+                        ));
+                    }
+
                     return Action::Run(Task::perform(
                         printer.print(*invoice, self.printer_modal.ticket_type.clone()),
                         Message::PrintJobCompleted,
@@ -711,9 +934,378 @@ impl Bar {
                 }
 
                 self.printer_modal.ticket_type = TicketType::default();
+                self.printer_modal.invoice_recipient = InvoiceRecipient::default();
+                self.printer_modal.recipient_search.clear();
+                self.printer_modal.recipient_search_results.clear();
                 self.update(Message::FetchTemporalTickets, &database.clone(), now)
             }
 
+            Message::StartTicketTransfer(temporal_ticket_id, kind) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        ticket_transfer, ..
+                    } = sub_screen
+                    {
+                        *ticket_transfer = Some(TicketTransfer {
+                            temporal_ticket_id,
+                            kind,
+                        });
+                    }
+                }
+                Action::None
+            }
+            Message::CancelTicketTransfer => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        ticket_transfer, ..
+                    } = sub_screen
+                    {
+                        *ticket_transfer = None;
+                    }
+                }
+                Action::None
+            }
+            Message::TicketTransferred(result) => match result {
+                Ok(_) => self.update(Message::FetchTemporalTickets, &database.clone(), now),
+                Err(e) => {
+                    eprintln!("{e}");
+                    Action::AddToast(Toast::error_toast(e))
+                }
+            },
+
+            Message::SendKitchenOrder(temporal_ticket_id) => Action::Run(Task::perform(
+                super::send_kitchen_orders(
+                    database.clone(),
+                    temporal_ticket_id,
+                    self.printer_modal.all_printers.clone(),
+                ),
+                Message::KitchenOrderSent,
+            )),
+            Message::KitchenOrderSent(result) => {
+                let toast = match result {
+                    Ok(_) => Toast::success_toast(fl!("order-sent")),
+                    Err(e) => {
+                        eprintln!("{e}");
+                        Toast::error_toast(e)
+                    }
+                };
+
+                // even if a station failed the rest may have been sent, so the tickets are always refreshed
+                match self.update(Message::FetchTemporalTickets, &database.clone(), now) {
+                    Action::Run(task) => {
+                        Action::Run(Task::batch([task, Task::done(Message::AddToast(toast))]))
+                    }
+                    _ => Action::AddToast(toast),
+                }
+            }
+
+            Message::OpenDiscountModal(temporal_ticket_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        temporal_tickets,
+                        active_temporal_product,
+                        discount_form,
+                        ..
+                    } = sub_screen
+                    {
+                        // the discount can be given to the active line only if it belongs to the ticket
+                        let temporal_product_id = active_temporal_product
+                            .temporal_product
+                            .as_ref()
+                            .filter(|p| p.temporal_ticket_id == temporal_ticket_id)
+                            .and_then(|p| p.id)
+                            .filter(|id| {
+                                temporal_tickets
+                                    .iter()
+                                    .flat_map(|t| t.products.iter())
+                                    .any(|p| p.id == Some(*id))
+                            });
+
+                        *discount_form = Some(DiscountForm {
+                            temporal_ticket_id,
+                            temporal_product_id,
+                            target: if temporal_product_id.is_some() {
+                                DiscountTarget::Line
+                            } else {
+                                DiscountTarget::Ticket
+                            },
+                            kind: DiscountKind::default(),
+                            value_input: String::new(),
+                            reason: String::new(),
+                        });
+                    }
+                }
+                Action::None
+            }
+            Message::CloseDiscountModal => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar { discount_form, .. } = sub_screen {
+                        *discount_form = None;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateDiscountTarget(target) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        discount_form: Some(form),
+                        ..
+                    } = sub_screen
+                        && (target == DiscountTarget::Ticket || form.temporal_product_id.is_some())
+                    {
+                        form.target = target;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateDiscountKind(kind) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        discount_form: Some(form),
+                        ..
+                    } = sub_screen
+                    {
+                        form.kind = kind;
+                    }
+                }
+                Action::None
+            }
+            Message::DiscountValueInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        discount_form: Some(form),
+                        ..
+                    } = sub_screen
+                        && (new_value.is_empty() || parse_decimal(&new_value).is_ok())
+                    {
+                        form.value_input = new_value;
+                    }
+                }
+                Action::None
+            }
+            Message::DiscountReasonInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        discount_form: Some(form),
+                        ..
+                    } = sub_screen
+                    {
+                        form.reason = new_value;
+                    }
+                }
+                Action::None
+            }
+            Message::ApplyDiscount => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        active_temporal_product,
+                        discount_form,
+                        ..
+                    } = sub_screen
+                        && let Some(discount) = discount_form.as_ref().and_then(|f| f.discount())
+                        && let Some(form) = discount_form.take()
+                    {
+                        // the price of the active line is going to change, it has to be focused again to edit it
+                        active_temporal_product.temporal_product = None;
+
+                        return match (form.target, form.temporal_product_id) {
+                            (DiscountTarget::Line, Some(temporal_product_id)) => {
+                                Action::Run(Task::perform(
+                                    TemporalProduct::apply_discount(
+                                        database.clone(),
+                                        temporal_product_id,
+                                        discount,
+                                    ),
+                                    |res| Message::DiscountUpdated(res.map_err(|e| e.to_string())),
+                                ))
+                            }
+                            (DiscountTarget::Line, None) => Action::None,
+                            (DiscountTarget::Ticket, _) => Action::Run(Task::perform(
+                                TemporalTicket::apply_discount(
+                                    database.clone(),
+                                    form.temporal_ticket_id,
+                                    discount,
+                                ),
+                                |res| Message::DiscountUpdated(res.map_err(|e| e.to_string())),
+                            )),
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::RemoveDiscount => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        active_temporal_product,
+                        discount_form,
+                        ..
+                    } = sub_screen
+                        && let Some(form) = discount_form.take()
+                    {
+                        active_temporal_product.temporal_product = None;
+
+                        return match (form.target, form.temporal_product_id) {
+                            (DiscountTarget::Line, Some(temporal_product_id)) => {
+                                Action::Run(Task::perform(
+                                    TemporalProduct::remove_discount(
+                                        database.clone(),
+                                        temporal_product_id,
+                                    ),
+                                    |res| Message::DiscountUpdated(res.map_err(|e| e.to_string())),
+                                ))
+                            }
+                            (DiscountTarget::Line, None) => Action::None,
+                            (DiscountTarget::Ticket, _) => Action::Run(Task::perform(
+                                TemporalTicket::remove_discount(
+                                    database.clone(),
+                                    form.temporal_ticket_id,
+                                ),
+                                |res| Message::DiscountUpdated(res.map_err(|e| e.to_string())),
+                            )),
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::DiscountUpdated(result) => match result {
+                Ok(_) => self.update(Message::FetchTemporalTickets, &database.clone(), now),
+                Err(e) => {
+                    eprintln!("{e}");
+                    Action::AddToast(Toast::error_toast(e))
+                }
+            },
+
+            Message::CloseVoidModal => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        active_temporal_product,
+                        void_form,
+                        ..
+                    } = sub_screen
+                    {
+                        // the line keeps its values, we drop the ones that were being edited
+                        if void_form
+                            .take()
+                            .is_some_and(|form| form.edited_line.is_some())
+                        {
+                            active_temporal_product.temporal_product = None;
+                            active_temporal_product.temporal_product_field = None;
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateVoidTarget(target) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        void_form: Some(form),
+                        ..
+                    } = sub_screen
+                        && (target == VoidTarget::Ticket || form.temporal_product_id.is_some())
+                    {
+                        form.target = target;
+                    }
+                }
+                Action::None
+            }
+            Message::VoidReasonInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        void_form: Some(form),
+                        ..
+                    } = sub_screen
+                    {
+                        form.reason = new_value;
+                    }
+                }
+                Action::None
+            }
+            Message::VoidedByInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        void_form: Some(form),
+                        ..
+                    } = sub_screen
+                    {
+                        form.voided_by = new_value;
+                    }
+                }
+                Action::None
+            }
+            Message::ConfirmVoid => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Bar {
+                        active_temporal_product,
+                        void_form,
+                        ..
+                    } = sub_screen
+                        && void_form.as_ref().is_some_and(|f| f.is_valid())
+                        && let Some(form) = void_form.take()
+                    {
+                        active_temporal_product.temporal_product = None;
+                        active_temporal_product.temporal_product_field = None;
+
+                        return match (form.target, form.temporal_product_id, form.edited_line) {
+                            (VoidTarget::Line, Some(_), Some(edited_line)) => {
+                                Action::Run(Task::perform(
+                                    TemporalProduct::void_reduction(
+                                        database.clone(),
+                                        edited_line,
+                                        form.reason,
+                                        form.voided_by,
+                                    ),
+                                    |res| Message::Voided(res.map_err(|e| e.to_string())),
+                                ))
+                            }
+                            (VoidTarget::Line, Some(temporal_product_id), None) => {
+                                Action::Run(Task::perform(
+                                    TemporalProduct::void(
+                                        database.clone(),
+                                        temporal_product_id,
+                                        form.reason,
+                                        form.voided_by,
+                                    ),
+                                    |res| Message::Voided(res.map_err(|e| e.to_string())),
+                                ))
+                            }
+                            (VoidTarget::Line, None, _) => Action::None,
+                            (VoidTarget::Ticket, _, _) => Action::Run(Task::perform(
+                                TemporalTicket::void(
+                                    database.clone(),
+                                    form.temporal_ticket_id,
+                                    form.reason,
+                                    form.voided_by,
+                                ),
+                                |res| Message::Voided(res.map_err(|e| e.to_string())),
+                            )),
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::Voided(result) => match result {
+                Ok(_) => self.update(Message::FetchTemporalTickets, &database.clone(), now),
+                Err(e) => {
+                    eprintln!("{e}");
+                    Action::AddToast(Toast::error_toast(e))
+                }
+            },
+
             Message::OpenPayScreen(ticket) => {
                 #[allow(clippy::collapsible_if)]
                 if let State::Ready { sub_screen, .. } = &mut self.state {
@@ -727,6 +1319,8 @@ impl Bar {
                             selected_payment_method: PaymentMethod::Efectivo,
                             selected_adeudo_room_id: None,
                             occupied_reservations: Vec::new(),
+                            split: SplitState::default(),
+                            payments: PaymentsState::default(),
                         };
                         return Action::Run(Task::perform(
                             Reservation::get_occupied(database.clone()),
@@ -773,11 +1367,15 @@ impl Bar {
                     if let SubScreen::Pay {
                         selected_payment_method,
                         selected_adeudo_room_id,
+                        payments,
                         ..
                     } = sub_screen
                     {
                         *selected_payment_method = p_method;
-                        *selected_adeudo_room_id = None;
+                        // the room is still needed if an adeudo payment has already been registered
+                        if !payments.has_adeudo() {
+                            *selected_adeudo_room_id = None;
+                        }
                     }
                 }
                 Action::None
@@ -802,23 +1400,60 @@ impl Bar {
                         ticket,
                         selected_payment_method,
                         selected_adeudo_room_id,
+                        split,
+                        payments,
                         ..
                     } = sub_screen
                     {
-                        if *selected_payment_method != PaymentMethod::Adeudo
-                            && selected_adeudo_room_id.is_some()
-                        {
+                        let bill_price = split.bill_price(ticket);
+                        if split.mode == SplitMode::ByLines && bill_price == Money::ZERO {
+                            return Action::AddToast(Toast::warning_toast(
+                                "No products selected for this bill",
+                            ));
+                        }
+
+                        // without registered payments the whole bill is paid with the selected method
+                        let bill_payments = if payments.registered.is_empty() {
+                            vec![InvoicePayment::new(*selected_payment_method, bill_price)]
+                        } else if payments.remaining(bill_price) != Money::ZERO {
+                            return Action::AddToast(Toast::warning_toast(format!(
+                                "The payments don't add up to the bill, {:.2} € left",
+                                payments.remaining(bill_price)
+                            )));
+                        } else {
+                            payments.registered.clone()
+                        };
+
+                        let has_adeudo = bill_payments
+                            .iter()
+                            .any(|p| p.payment_method == PaymentMethod::Adeudo);
+                        if !has_adeudo && selected_adeudo_room_id.is_some() {
                             return Action::AddToast(Toast::error_toast(
                                 "Room Id was selected when adeudo is not the payment method",
                             ));
-                        } else if *selected_payment_method == PaymentMethod::Adeudo
-                            && selected_adeudo_room_id.is_none()
-                        {
+                        } else if has_adeudo && selected_adeudo_room_id.is_none() {
                             return Action::AddToast(Toast::error_toast(
                                 "No room selected for adeudo",
                             ));
                         }
 
+                        // only part of the ticket is going to be paid, it gets it's own simple invoice
+                        if let Some(ticket_split) = split.ticket_split() {
+                            return Action::Run(Task::perform(
+                                SimpleInvoice::pay_temporal_ticket_split(
+                                    database.clone(),
+                                    ticket.id.unwrap_or_default(),
+                                    ticket_split,
+                                    bill_payments,
+                                    *selected_adeudo_room_id,
+                                ),
+                                |res| {
+                                    let mapped_result = res.map_err(|e| e.to_string());
+                                    Message::PaidTemporalTicketSplit(mapped_result)
+                                },
+                            ));
+                        }
+
                         // we get the id because we don't know if the ticket has been printed or not
                         // so we will retrieve it by id before commiting to the pay transaction
                         // this way we know if it's already a simple invoice or not
@@ -826,7 +1461,7 @@ impl Bar {
                             SimpleInvoice::pay_temporal_ticket(
                                 database.clone(),
                                 ticket.id.unwrap_or_default(),
-                                *selected_payment_method,
+                                bill_payments,
                                 *selected_adeudo_room_id,
                             ),
                             |res| {
@@ -841,12 +1476,277 @@ impl Bar {
             Message::PaidTemporalTicket(result) => {
                 match result {
                     Ok(_) => {
+                        let low_stock_check = self.check_low_stock(database);
                         // we can effectively go back, this will load the bar screen normally again
-                        self.update(Message::Back, &database.clone(), now)
+                        match self.update(Message::Back, &database.clone(), now) {
+                            Action::Run(task) => Action::Run(Task::batch([task, low_stock_check])),
+                            action => action,
+                        }
                     }
                     Err(e) => Action::AddToast(Toast::error_toast(e.to_string())),
                 }
             }
+            Message::PaymentAmountInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay { payments, .. } = sub_screen {
+                        if new_value.is_empty() || parse_decimal(&new_value).is_ok() {
+                            payments.amount_input = new_value;
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddPayment => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay {
+                        ticket,
+                        selected_payment_method,
+                        split,
+                        payments,
+                        ..
+                    } = sub_screen
+                    {
+                        let remaining = payments.remaining(split.bill_price(ticket));
+                        if remaining <= Money::ZERO {
+                            return Action::AddToast(Toast::warning_toast(
+                                "Nothing left to pay on this bill",
+                            ));
+                        }
+
+                        // an empty amount pays whatever is left
+                        let amount = if payments.amount_input.is_empty() {
+                            remaining
+                        } else {
+                            match payments.amount_input.parse::<Money>() {
+                                Ok(amount) if amount > Money::ZERO => amount,
+                                _ => {
+                                    return Action::AddToast(Toast::error_toast(
+                                        "Invalid payment amount",
+                                    ));
+                                }
+                            }
+                        };
+
+                        // only cash can be given in excess, the difference is the change
+                        payments.change = None;
+                        let amount = if amount > remaining {
+                            if *selected_payment_method != PaymentMethod::Efectivo {
+                                return Action::AddToast(Toast::error_toast(
+                                    "The amount is greater than what's left to pay",
+                                ));
+                            }
+                            payments.change = Some(amount - remaining);
+                            remaining
+                        } else {
+                            amount
+                        };
+
+                        payments
+                            .registered
+                            .push(InvoicePayment::new(*selected_payment_method, amount));
+                        payments.amount_input.clear();
+                    }
+                }
+                Action::None
+            }
+            Message::RemovePayment(index) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay { payments, .. } = sub_screen {
+                        if index < payments.registered.len() {
+                            payments.registered.remove(index);
+                            payments.change = None;
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateSplitMode(mode) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay {
+                        split, payments, ..
+                    } = sub_screen
+                    {
+                        split.mode = mode;
+                        split.selected_quantities.clear();
+                        *payments = PaymentsState::default();
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateSplitLineQuantity(temporal_product_id, quantity) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay { ticket, split, .. } = sub_screen {
+                        let max_quantity = ticket
+                            .products
+                            .iter()
+                            .find(|p| p.id == Some(temporal_product_id))
+                            .map(|p| p.quantity)
+                            .unwrap_or_default();
+
+                        let quantity = quantity.clamp(0, max_quantity);
+                        if quantity == 0 {
+                            split.selected_quantities.remove(&temporal_product_id);
+                        } else {
+                            split
+                                .selected_quantities
+                                .insert(temporal_product_id, quantity);
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateSplitPayers(payers) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay { split, .. } = sub_screen {
+                        split.payers = payers.max(1);
+                    }
+                }
+                Action::None
+            }
+            Message::PaidTemporalTicketSplit(result) => match result {
+                // the whole ticket has been paid, go back to the bar screen
+                Ok(true) => {
+                    let low_stock_check = self.check_low_stock(database);
+                    match self.update(Message::Back, &database.clone(), now) {
+                        Action::Run(task) => Action::Run(Task::batch([task, low_stock_check])),
+                        action => action,
+                    }
+                }
+                Ok(false) => {
+                    let low_stock_check = self.check_low_stock(database);
+                    if let State::Ready { sub_screen, .. } = &mut self.state {
+                        #[allow(clippy::collapsible_match)]
+                        if let SubScreen::Pay {
+                            ticket,
+                            split,
+                            payments,
+                            ..
+                        } = sub_screen
+                        {
+                            split.selected_quantities.clear();
+                            *payments = PaymentsState::default();
+                            if split.mode == SplitMode::Evenly {
+                                split.payers = (split.payers - 1).max(1);
+                            }
+
+                            return Action::Run(Task::batch([
+                                Task::perform(
+                                    TemporalTicket::get_single(
+                                        database.clone(),
+                                        ticket.id.unwrap_or_default(),
+                                    ),
+                                    |res| match res {
+                                        Ok(ticket) => Message::ReloadedPayTicket(ticket),
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                ),
+                                low_stock_check,
+                            ]));
+                        }
+                    }
+                    Action::None
+                }
+                Err(e) => Action::AddToast(Toast::error_toast(e)),
+            },
+            Message::ReloadedPayTicket(reloaded_ticket) => {
+                let Some(reloaded_ticket) = reloaded_ticket else {
+                    // nothing left on the ticket
+                    return self.update(Message::Back, &database.clone(), now);
+                };
+
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Pay { ticket, .. } = sub_screen {
+                        *ticket = reloaded_ticket;
+                    }
+                }
+                Action::AddToast(Toast::success_toast("Bill paid"))
+            }
+            Message::LowStockChecked(products) => {
+                if products.is_empty() {
+                    return Action::None;
+                }
+
+                let products = products
+                    .iter()
+                    .map(|p| format!("{} ({})", p.name, p.stock_quantity.unwrap_or_default()))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                Action::AddToast(Toast::warning_toast(format!(
+                    "{}: {products}",
+                    fl!("low-stock")
+                )))
+            }
         }
     }
+
+    /// Checks the stock of the products of the ticket of the pay screen (intended for calling once it has been paid)
+    fn check_low_stock(&self, database: &Arc<Pool<Postgres>>) -> Task<Message> {
+        let State::Ready {
+            sub_screen: SubScreen::Pay { ticket, .. },
+        } = &self.state
+        else {
+            return Task::none();
+        };
+
+        let product_ids = ticket
+            .products
+            .iter()
+            .map(|p| p.original_product_id)
+            .collect();
+
+        Task::perform(
+            Product::get_low_stock(database.clone(), product_ids),
+            |res| match res {
+                Ok(products) => Message::LowStockChecked(products),
+                Err(err) => {
+                    eprintln!("{err}");
+                    Message::AddToast(Toast::error_toast(err))
+                }
+            },
+        )
+    }
+}
+
+/// Adds a line of the given product (with the given modifiers) to the ticket of the current table
+fn add_product_to_table(
+    database: &Arc<Pool<Postgres>>,
+    current_position: &CurrentPosition,
+    product_id: i32,
+    modifier_ids: Vec<i32>,
+) -> Task<Message> {
+    let temporal_ticket = TemporalTicket {
+        id: None,
+        table_id: current_position.table_id,
+        ticket_location: current_position.table_location,
+        ticket_status: 0,
+        simple_invoice_id: None,
+        discount: None,
+        products: Vec::new(),
+    };
+
+    Task::perform(
+        TemporalTicket::upsert_ticket_by_id_and_tableloc(
+            database.clone(),
+            temporal_ticket,
+            product_id,
+            modifier_ids,
+        ),
+        |res| match res {
+            Ok(_) => Message::FetchTemporalTickets,
+            Err(err) => {
+                eprintln!("{err}");
+                Message::AddToast(Toast::error_toast(err.to_string()))
+            }
+        },
+    )
 }
@@ -2,7 +2,8 @@ use crate::{
     alegria::{
         core::{
             models::{
-                product::Product, product_category::ProductCategory, reservation::Reservation,
+                bar_table::BarTable, product::Product, product_category::ProductCategory,
+                reservation::Reservation, table_location::TableLocation,
                 temporal_ticket::TemporalTicket,
             },
             print::TicketType,
@@ -10,29 +11,37 @@ use crate::{
         screen::{
             Bar,
             bar::{
-                ActiveTemporalProduct, BarPagination, CurrentPosition, Message, NumPadAction,
-                PaginationAction, PrintModal, PrintTicketModalActions, State, SubScreen,
-                TableLocation, match_table_location_with_number,
+                ActiveTemporalProduct, BarPagination, CurrentPosition, DiscountForm,
+                DiscountTarget, InvoiceRecipientField, Message, ModifierSelection, NumPadAction,
+                PaginationAction, PaymentsState, PrintModal, PrintTicketModalActions, SplitMode,
+                SplitState, State, SubScreen, TablesView, TicketTransfer, TicketTransferKind,
+                VoidForm, VoidTarget,
             },
         },
         utils::{
+            entities::discount::DiscountKind,
             entities::payment_method::PaymentMethod,
+            entities::table_shape::TableShape,
+            money::Money,
             styling::*,
             temporal_tickets::{TemporalTicketStatus, match_number_with_temporal_ticket_status},
         },
-        widgets::{focusable_text_input::TextInput, modal::modal},
+        widgets::{floor_plan::floor_plan, focusable_text_input::TextInput, modal::modal},
     },
     fl,
 };
 use iced::{
     Alignment, Length, Pixels,
     time::Instant,
-    widget::{Column, Row, Scrollable, Space, button, column, container, pick_list, row, text},
+    widget::{
+        Column, Row, Scrollable, Space, button, column, container, pick_list, row, text, text_input,
+    },
 };
 
 // Tables Grid
 const TABLES_PER_ROW: usize = 5;
-const NUMBER_OF_TABLES: usize = 30;
+// Modifiers Modal
+const MODIFIERS_PER_ROW: usize = 3;
 
 impl Bar {
     pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
@@ -40,19 +49,32 @@ impl Bar {
             State::Loading => container(text("Loading...")).center(Length::Fill).into(),
             State::Ready { sub_screen } => match sub_screen {
                 SubScreen::Bar {
+                    table_locations,
+                    bar_tables,
                     temporal_tickets,
                     product_categories,
                     product_category_products,
                     pagination,
                     current_position,
                     active_temporal_product,
+                    ticket_transfer,
+                    modifier_selection,
+                    discount_form,
+                    void_form,
+                    ..
                 } => container(bar_view(
+                    table_locations,
+                    bar_tables,
                     temporal_tickets,
                     product_categories,
                     product_category_products,
                     pagination,
                     current_position,
                     active_temporal_product,
+                    ticket_transfer,
+                    modifier_selection,
+                    discount_form,
+                    void_form,
                     &self.printer_modal,
                 ))
                 .center(Length::Fill)
@@ -62,12 +84,16 @@ impl Bar {
                     occupied_reservations,
                     selected_payment_method,
                     selected_adeudo_room_id,
+                    split,
+                    payments,
                     ..
                 } => container(pay_view(
                     ticket,
                     occupied_reservations,
                     selected_payment_method,
                     selected_adeudo_room_id,
+                    split,
+                    payments,
                     &self.printer_modal,
                 ))
                 .center(Length::Fill)
@@ -78,24 +104,36 @@ impl Bar {
 }
 
 /// View of the bar subscreen
+#[allow(clippy::too_many_arguments)]
 fn bar_view<'a>(
+    table_locations: &'a [TableLocation],
+    bar_tables: &'a [BarTable],
     temporal_tickets: &'a [TemporalTicket],
     product_categories: &'a [ProductCategory],
     product_category_products: &'a Option<Vec<Product>>,
     pagination: &'a BarPagination,
     current_position: &'a CurrentPosition,
     _active_temporal_product: &'a ActiveTemporalProduct,
+    ticket_transfer: &'a Option<TicketTransfer>,
+    modifier_selection: &'a Option<ModifierSelection>,
+    discount_form: &'a Option<DiscountForm>,
+    void_form: &'a Option<VoidForm>,
     print_modal: &'a PrintModal,
 ) -> iced::Element<'a, Message> {
     let spacing = Pixels::from(GLOBAL_SPACING);
 
-    let header = bar_header(temporal_tickets, current_position);
+    let header = bar_header(temporal_tickets, current_position, ticket_transfer);
     let content = row![
         // LEFT SIDE COLUMN
         column![
             // UPPER LEFT SIDE
             row![
-                tables_grid(temporal_tickets, current_position),
+                tables_grid(
+                    table_locations,
+                    bar_tables,
+                    temporal_tickets,
+                    current_position
+                ),
                 column![
                     total_ticket_price(temporal_tickets, current_position),
                     numpad()
@@ -120,14 +158,37 @@ fn bar_view<'a>(
     ]
     .spacing(spacing);
 
+    if let Some(selection) = modifier_selection {
+        return modal(
+            column![header, content].padding(3.).spacing(spacing),
+            view_modifiers_modal(selection),
+            Message::CancelModifiers,
+        );
+    }
+
+    if let Some(form) = discount_form {
+        return modal(
+            column![header, content].padding(3.).spacing(spacing),
+            view_discount_modal(form, temporal_tickets),
+            Message::CloseDiscountModal,
+        );
+    }
+
+    if let Some(form) = void_form {
+        return modal(
+            column![header, content].padding(3.).spacing(spacing),
+            view_void_modal(form, temporal_tickets),
+            Message::CloseVoidModal,
+        );
+    }
+
     match print_modal.show_modal {
         true => {
             let current_ticket = temporal_tickets
                 .iter()
                 .find(|x| {
-                    x.ticket_location
-                        == super::match_table_location_with_number(&current_position.table_location)
-                        && x.table_id == current_position.table_index
+                    x.ticket_location == current_position.table_location
+                        && x.table_id == current_position.table_id
                 })
                 .unwrap(); // It's safe to unwrap here because we're checking on the button to open the print modal.
 
@@ -145,6 +206,7 @@ fn bar_view<'a>(
 fn bar_header<'a>(
     temporal_tickets: &'a [TemporalTicket],
     current_position: &'a CurrentPosition,
+    ticket_transfer: &'a Option<TicketTransfer>,
 ) -> iced::Element<'a, Message> {
     let spacing = Pixels::from(GLOBAL_SPACING);
     let button_height = Length::Fixed(GLOBAL_BUTTON_HEIGHT);
@@ -153,6 +215,32 @@ fn bar_header<'a>(
         .on_press(Message::Back)
         .height(button_height);
 
+    // while transferring a ticket the only possible action is cancelling it (or clicking the target table)
+    if let Some(transfer) = ticket_transfer {
+        let label = match transfer.kind {
+            TicketTransferKind::Move => fl!("select-move-target"),
+            TicketTransferKind::Merge => fl!("select-merge-target"),
+        };
+
+        return row![
+            back_button,
+            text(label).size(TITLE_TEXT_SIZE).align_y(Alignment::Center),
+            Space::new(Length::Fill, Length::Shrink),
+            button(
+                text(fl!("cancel"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::CancelTicketTransfer)
+            .style(button::danger)
+            .height(button_height)
+        ]
+        .width(Length::Fill)
+        .align_y(Alignment::Center)
+        .spacing(spacing)
+        .into();
+    }
+
     let mut header_row = row![
         back_button,
         text(fl!("bar"))
@@ -165,9 +253,8 @@ fn bar_header<'a>(
     .spacing(spacing);
 
     let current_ticket = temporal_tickets.iter().find(|x| {
-        x.ticket_location
-            == super::match_table_location_with_number(&current_position.table_location)
-            && x.table_id == current_position.table_index
+        x.ticket_location == current_position.table_location
+            && x.table_id == current_position.table_id
     });
 
     if let Some(c_ticket) = current_ticket {
@@ -183,6 +270,62 @@ fn bar_header<'a>(
                 .height(button_height),
             );
         }
+        if let Some(ticket_id) = c_ticket.id {
+            header_row = header_row.push(
+                button(
+                    text(fl!("move"))
+                        .align_x(Alignment::Center)
+                        .align_y(Alignment::Center),
+                )
+                .on_press(Message::StartTicketTransfer(
+                    ticket_id,
+                    TicketTransferKind::Move,
+                ))
+                .height(button_height),
+            );
+            // a locked ticket can't be merged, it's invoice would no longer match
+            if c_ticket.simple_invoice_id.is_none() {
+                header_row = header_row.push(
+                    button(
+                        text(fl!("merge"))
+                            .align_x(Alignment::Center)
+                            .align_y(Alignment::Center),
+                    )
+                    .on_press(Message::StartTicketTransfer(
+                        ticket_id,
+                        TicketTransferKind::Merge,
+                    ))
+                    .height(button_height),
+                );
+            }
+        }
+        if let Some(ticket_id) = c_ticket.id
+            && c_ticket.products.iter().any(|p| p.unsent_quantity() > 0)
+        {
+            header_row = header_row.push(
+                button(
+                    text(fl!("send-order"))
+                        .align_x(Alignment::Center)
+                        .align_y(Alignment::Center),
+                )
+                .on_press(Message::SendKitchenOrder(ticket_id))
+                .height(button_height),
+            );
+        }
+        if let Some(ticket_id) = c_ticket.id
+            && !c_ticket.products.is_empty()
+            && c_ticket.simple_invoice_id.is_none()
+        {
+            header_row = header_row.push(
+                button(
+                    text(fl!("discount"))
+                        .align_x(Alignment::Center)
+                        .align_y(Alignment::Center),
+                )
+                .on_press(Message::OpenDiscountModal(ticket_id))
+                .height(button_height),
+            );
+        }
         if !c_ticket.products.is_empty() {
             header_row = header_row.push(
                 button(
@@ -212,80 +355,115 @@ fn bar_header<'a>(
     header_row.into()
 }
 
-/// Returns the view of the header row of the bar screen
+/// Returns the view of the tables grid (with the location selector) of the bar screen
 fn tables_grid<'a>(
+    table_locations: &'a [TableLocation],
+    bar_tables: &'a [BarTable],
     temporal_tickets: &'a [TemporalTicket],
     current_position: &'a CurrentPosition,
 ) -> iced::Element<'a, Message> {
     let spacing = Pixels::from(GLOBAL_SPACING);
     let button_height = Length::Fixed(GLOBAL_BUTTON_HEIGHT);
 
-    let header = Row::new()
-        .push(
+    let mut header = Row::new().width(Length::Fill).spacing(spacing);
+    for location in table_locations {
+        let location_id = location.id.unwrap_or_default();
+        header = header.push(
             button(
-                text(fl!("bar"))
+                text(&location.name)
                     .align_x(Alignment::Center)
                     .align_y(Alignment::Center),
             )
-            .on_press(Message::ChangeCurrentTablesLocation(TableLocation::Bar))
-            .style(|t, s| {
-                determine_location_button_color(current_position, t, s, TableLocation::Bar)
-            })
+            .on_press(Message::ChangeCurrentTablesLocation(location_id))
+            .style(move |t, s| determine_location_button_color(current_position, t, s, location_id))
             .height(button_height)
             .width(Length::Fill),
+        );
+    }
+    let toggle_view_label = match current_position.tables_view {
+        TablesView::Grid => fl!("floor-plan"),
+        TablesView::FloorPlan => fl!("grid"),
+    };
+    header = header.push(
+        button(
+            text(toggle_view_label)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center),
         )
-        .push(
-            button(
-                text(fl!("restaurant"))
+        .on_press(Message::ToggleTablesView)
+        .height(button_height),
+    );
+
+    let location_tables: Vec<&BarTable> = bar_tables
+        .iter()
+        .filter(|t| t.location_id == Some(current_position.table_location))
+        .collect();
+
+    if current_position.tables_view == TablesView::FloorPlan {
+        let tables = location_tables.into_iter().map(move |table| {
+            let table_id = table.id.unwrap_or_default();
+            let radius = match table.shape {
+                TableShape::Square => 3.,
+                TableShape::Round => table.width.min(table.height) as f32 / 2.,
+            };
+
+            let table_button = button(text(&table.name).center())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(move |t, s| {
+                    let mut style = determine_table_button_color(
+                        current_position,
+                        temporal_tickets,
+                        t,
+                        s,
+                        table_id,
+                    );
+                    style.border.radius = radius.into();
+                    style
+                })
+                .on_press(Message::OnTableChange(table_id));
+
+            (table, table_button.into())
+        });
+
+        let plan = Scrollable::new(floor_plan(tables)).direction(
+            iced::widget::scrollable::Direction::Both {
+                vertical: iced::widget::scrollable::Scrollbar::default(),
+                horizontal: iced::widget::scrollable::Scrollbar::default(),
+            },
+        );
+
+        return column![header, plan]
+            .width(Length::Fill)
+            .spacing(spacing)
+            .into();
+    }
+
+    let mut tables_grid = Column::new().spacing(spacing).width(Length::Fill);
+    for tables in location_tables.chunks(TABLES_PER_ROW) {
+        let mut current_row = Row::new().spacing(spacing).width(Length::Fill);
+        for table in tables {
+            let table_id = table.id.unwrap_or_default();
+            let table_button = button(
+                text(&table.name)
+                    .width(Length::Fill)
                     .align_x(Alignment::Center)
                     .align_y(Alignment::Center),
             )
-            .on_press(Message::ChangeCurrentTablesLocation(
-                TableLocation::Resturant,
-            ))
-            .style(|t, s| {
-                determine_location_button_color(current_position, t, s, TableLocation::Resturant)
-            })
+            .width(Length::Fill)
             .height(button_height)
-            .width(Length::Fill),
-        )
-        .push(
-            button(
-                text(fl!("garden"))
-                    .align_x(Alignment::Center)
-                    .align_y(Alignment::Center),
-            )
-            .on_press(Message::ChangeCurrentTablesLocation(TableLocation::Garden))
-            .style(|t, s| {
-                determine_location_button_color(current_position, t, s, TableLocation::Garden)
+            .style(move |t, s| {
+                determine_table_button_color(current_position, temporal_tickets, t, s, table_id)
             })
-            .height(button_height)
-            .width(Length::Fill),
-        )
-        .width(Length::Fill)
-        .spacing(spacing);
-
-    let mut tables_grid = Column::new().spacing(spacing).width(Length::Fill);
-    let mut current_row = Row::new().spacing(spacing).width(Length::Fill);
-    for index in 0..NUMBER_OF_TABLES {
-        let table_button = button(
-            text(format!("{}", index + 1))
-                .width(Length::Fill)
-                .align_x(Alignment::Center)
-                .align_y(Alignment::Center),
-        )
-        .width(Length::Fill)
-        .height(button_height)
-        .style(move |t, s| {
-            determine_table_button_color(current_position, temporal_tickets, t, s, index)
-        })
-        .on_press(Message::OnTableChange(index));
-        current_row = current_row.push(table_button);
+            .on_press(Message::OnTableChange(table_id));
+            current_row = current_row.push(table_button);
+        }
 
-        if (index + 1) % TABLES_PER_ROW == 0 {
-            tables_grid = tables_grid.push(current_row);
-            current_row = Row::new().spacing(spacing).width(Length::Fill);
+        // keep the buttons of the last (not full) row the same width as the rest
+        for _ in tables.len()..TABLES_PER_ROW {
+            current_row = current_row.push(Space::new(Length::Fill, button_height));
         }
+        tables_grid = tables_grid.push(current_row);
     }
 
     column![header, tables_grid]
@@ -300,9 +478,8 @@ fn total_ticket_price<'a>(
     current_position: &'a CurrentPosition,
 ) -> iced::Element<'a, Message> {
     let current_ticket = temporal_tickets.iter().find(|x| {
-        x.ticket_location
-            == super::match_table_location_with_number(&current_position.table_location)
-            && x.table_id == current_position.table_index
+        x.ticket_location == current_position.table_location
+            && x.table_id == current_position.table_id
     });
 
     let text = if let Some(ticket) = current_ticket {
@@ -338,9 +515,8 @@ fn current_ticket_products<'a>(
     let spacing = Pixels::from(GLOBAL_SPACING);
 
     let current_ticket = temporal_tickets.iter().find(|x| {
-        x.ticket_location
-            == super::match_table_location_with_number(&current_position.table_location)
-            && x.table_id == current_position.table_index
+        x.ticket_location == current_position.table_location
+            && x.table_id == current_position.table_id
     });
 
     if let Some(current_ticket) = current_ticket {
@@ -379,13 +555,30 @@ fn current_ticket_products<'a>(
                 )
                 .size(25.);
 
-            let product_row = Row::new()
-                .push(
-                    text(&product.name)
-                        .size(25.)
-                        .width(Length::Fill)
+            let mut product_name =
+                Column::new().push(text(&product.name).size(25.).wrapping(text::Wrapping::None));
+            if !product.modifiers.is_empty() {
+                product_name = product_name.push(
+                    text(&product.modifiers)
+                        .size(15.)
                         .wrapping(text::Wrapping::None),
-                )
+                );
+            }
+            if product.original_price.is_some() {
+                product_name = product_name.push(
+                    text(format!(
+                        "{} -{:.2}€ ({})",
+                        fl!("discount"),
+                        product.discount(),
+                        product.discount_reason.as_deref().unwrap_or_default()
+                    ))
+                    .size(15.)
+                    .wrapping(text::Wrapping::None),
+                );
+            }
+
+            let product_row = Row::new()
+                .push(product_name.width(Length::Fill))
                 .push(quantity_input)
                 .push(price_input)
                 .spacing(spacing)
@@ -394,6 +587,14 @@ fn current_ticket_products<'a>(
             products_column = products_column.push(product_row);
         }
 
+        if let Some(discount) = &current_ticket.discount {
+            products_column = products_column.push(
+                text(format!("{}: {discount}", fl!("ticket-discount")))
+                    .size(20.)
+                    .wrapping(text::Wrapping::None),
+            );
+        }
+
         Scrollable::new(products_column).into()
     } else {
         row![
@@ -578,32 +779,345 @@ fn view_print_modal<'a>(
         )
         .width(Length::Fill);
 
+        // full invoices need the fiscal data of the recipient
+        let is_invoice = print_modal.ticket_type == TicketType::Invoice;
+        let recipient = &print_modal.invoice_recipient;
+
         let submit_button = button(
             text(fl!("print"))
                 .align_x(Alignment::Center)
                 .align_y(Alignment::Center),
         )
-        .on_press_maybe((print_modal.selected_printer.is_some()).then_some(
-            Message::PrintModalAction(PrintTicketModalActions::PrintTicket(ticket.clone())),
-        ))
+        .on_press_maybe(
+            (print_modal.selected_printer.is_some() && (!is_invoice || recipient.is_valid()))
+                .then_some(Message::PrintModalAction(
+                    PrintTicketModalActions::PrintTicket(ticket.clone()),
+                )),
+        )
         .width(Length::Fill);
 
-        container(
-            column![
-                column![printers_label, printer_selector].spacing(1.),
-                column![ticket_type_label, ticket_type_selector].spacing(1.),
-                submit_button
+        let mut modal_column = column![
+            column![printers_label, printer_selector].spacing(1.),
+            column![ticket_type_label, ticket_type_selector].spacing(1.),
+        ]
+        .spacing(GLOBAL_SPACING)
+        .width(Length::Fill);
+
+        if is_invoice {
+            let search_row = row![
+                text_input(fl!("search").as_str(), &print_modal.recipient_search)
+                    .on_input(Message::RecipientSearchInput)
+                    .on_submit(Message::SearchRecipients)
+                    .width(Length::Fill),
+                button(text(fl!("search")).center()).on_press(Message::SearchRecipients)
             ]
             .spacing(GLOBAL_SPACING)
+            .align_y(Alignment::Center);
+
+            let search_results = print_modal.recipient_search_results.iter().fold(
+                Column::new().spacing(3.),
+                |col, result| {
+                    col.push(
+                        button(text(format!("{} - {}", result.name, result.nif)))
+                            .on_press(Message::SelectRecipient(result.clone()))
+                            .style(button::text)
+                            .width(Length::Fill),
+                    )
+                },
+            );
+
+            let name_label = text(fl!("recipient-name")).width(Length::Fill);
+            let name_input = text_input(fl!("recipient-name").as_str(), &recipient.name)
+                .on_input(|value| {
+                    Message::UpdateInvoiceRecipient(InvoiceRecipientField::Name, value)
+                })
+                .width(Length::Fill);
+
+            let nif_label = text(fl!("recipient-nif")).width(Length::Fill);
+            let nif_input = text_input(fl!("recipient-nif").as_str(), &recipient.nif)
+                .on_input(|value| {
+                    Message::UpdateInvoiceRecipient(InvoiceRecipientField::Nif, value)
+                })
+                .width(Length::Fill);
+
+            let address_label = text(fl!("recipient-address")).width(Length::Fill);
+            let address_input = text_input(fl!("recipient-address").as_str(), &recipient.address)
+                .on_input(|value| {
+                    Message::UpdateInvoiceRecipient(InvoiceRecipientField::Address, value)
+                })
+                .width(Length::Fill);
+
+            modal_column = modal_column
+                .push(search_row)
+                .push(search_results)
+                .push(column![name_label, name_input].spacing(1.))
+                .push(column![nif_label, nif_input].spacing(1.))
+                .push(column![address_label, address_input].spacing(1.));
+        }
+
+        container(modal_column.push(submit_button))
+            .width(700)
+            .padding(30)
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center)
+            .style(container::rounded_box)
+            .into()
+    }
+}
+
+/// Returns the view of the modal to choose the modifiers of the product that's going to be added
+fn view_modifiers_modal<'a>(selection: &'a ModifierSelection) -> iced::Element<'a, Message> {
+    let mut modal_column = Column::new()
+        .push(text(&selection.product_name).size(TITLE_TEXT_SIZE))
+        .spacing(GLOBAL_SPACING)
+        .width(Length::Fill);
+
+    for group in &selection.groups {
+        let mut group_column = Column::new().push(text(&group.name)).spacing(1.);
+
+        for modifiers in group.modifiers.chunks(MODIFIERS_PER_ROW) {
+            let mut modifiers_row = Row::new().spacing(GLOBAL_SPACING);
+            for modifier in modifiers {
+                let modifier_id = modifier.id.unwrap_or_default();
+                let is_selected = selection.selected.contains(&modifier_id);
+
+                modifiers_row = modifiers_row.push(
+                    button(
+                        text(modifier.to_string())
+                            .align_x(Alignment::Center)
+                            .align_y(Alignment::Center),
+                    )
+                    .on_press(Message::ToggleModifier(modifier_id))
+                    .style(if is_selected {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .height(GLOBAL_BUTTON_HEIGHT)
+                    .width(Length::Fill),
+                );
+            }
+            // keep the buttons of the last row the same size
+            for _ in modifiers.len()..MODIFIERS_PER_ROW {
+                modifiers_row = modifiers_row.push(Space::new(Length::Fill, Length::Shrink));
+            }
+            group_column = group_column.push(modifiers_row);
+        }
+
+        modal_column = modal_column.push(group_column);
+    }
+
+    let buttons_row = row![
+        button(text(fl!("cancel")).center())
+            .on_press(Message::CancelModifiers)
+            .style(button::danger)
             .width(Length::Fill),
-        )
+        button(text(fl!("add-to-ticket")).center())
+            .on_press(Message::ConfirmModifiers)
+            .style(button::success)
+            .width(Length::Fill),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    container(modal_column.push(buttons_row))
         .width(700)
         .padding(30)
         .align_x(Alignment::Center)
         .align_y(Alignment::Center)
         .style(container::rounded_box)
         .into()
-    }
+}
+
+/// Returns the view of the modal to give a discount to the active line or to the whole ticket
+fn view_discount_modal<'a>(
+    form: &'a DiscountForm,
+    temporal_tickets: &'a [TemporalTicket],
+) -> iced::Element<'a, Message> {
+    let ticket = temporal_tickets
+        .iter()
+        .find(|t| t.id == Some(form.temporal_ticket_id));
+    let line = ticket
+        .into_iter()
+        .flat_map(|t| t.products.iter())
+        .find(|p| p.id.is_some() && p.id == form.temporal_product_id);
+
+    let target_button = |label: String, target: DiscountTarget, enabled: bool| {
+        button(
+            text(label)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center),
+        )
+        .on_press_maybe(enabled.then_some(Message::UpdateDiscountTarget(target)))
+        .style(if form.target == target {
+            button::primary
+        } else {
+            button::secondary
+        })
+        .height(GLOBAL_BUTTON_HEIGHT)
+        .width(Length::Fill)
+    };
+
+    let line_label = match line {
+        Some(line) => format!("{}: {}", fl!("selected-line"), line.name),
+        None => fl!("selected-line"),
+    };
+    let target_row = row![
+        target_button(line_label, DiscountTarget::Line, line.is_some()),
+        target_button(fl!("whole-ticket"), DiscountTarget::Ticket, true),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    let value_row = row![
+        pick_list(
+            DiscountKind::ALL,
+            Some(form.kind),
+            Message::UpdateDiscountKind
+        )
+        .width(Length::Fill),
+        text_input(&fl!("discount-value"), &form.value_input)
+            .on_input(Message::DiscountValueInput)
+            .width(Length::Fill),
+        text(match form.kind {
+            DiscountKind::Percentage => "%",
+            DiscountKind::Fixed => "€",
+        }),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING);
+
+    let has_discount = match form.target {
+        DiscountTarget::Line => line.is_some_and(|l| l.original_price.is_some()),
+        DiscountTarget::Ticket => ticket.is_some_and(|t| {
+            t.discount.is_some() || t.products.iter().any(|p| p.original_price.is_some())
+        }),
+    };
+
+    let buttons_row = row![
+        button(text(fl!("cancel")).center())
+            .on_press(Message::CloseDiscountModal)
+            .width(Length::Fill),
+        button(text(fl!("remove-discount")).center())
+            .on_press_maybe(has_discount.then_some(Message::RemoveDiscount))
+            .style(button::danger)
+            .width(Length::Fill),
+        button(text(fl!("apply-discount")).center())
+            .on_press_maybe(form.discount().map(|_| Message::ApplyDiscount))
+            .style(button::success)
+            .width(Length::Fill),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    container(
+        column![
+            text(fl!("discount")).size(TITLE_TEXT_SIZE),
+            target_row,
+            value_row,
+            text_input(&fl!("reason"), &form.reason).on_input(Message::DiscountReasonInput),
+            buttons_row,
+        ]
+        .spacing(GLOBAL_SPACING)
+        .width(Length::Fill),
+    )
+    .width(700)
+    .padding(30)
+    .align_x(Alignment::Center)
+    .align_y(Alignment::Center)
+    .style(container::rounded_box)
+    .into()
+}
+
+/// Returns the view of the modal to void the active line or the whole ticket
+fn view_void_modal<'a>(
+    form: &'a VoidForm,
+    temporal_tickets: &'a [TemporalTicket],
+) -> iced::Element<'a, Message> {
+    let ticket = temporal_tickets
+        .iter()
+        .find(|t| t.id == Some(form.temporal_ticket_id));
+    let line = ticket
+        .into_iter()
+        .flat_map(|t| t.products.iter())
+        .find(|p| p.id.is_some() && p.id == form.temporal_product_id);
+
+    let target_button = |label: String, target: VoidTarget, enabled: bool| {
+        button(
+            text(label)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center),
+        )
+        .on_press_maybe(enabled.then_some(Message::UpdateVoidTarget(target)))
+        .style(if form.target == target {
+            button::primary
+        } else {
+            button::secondary
+        })
+        .height(GLOBAL_BUTTON_HEIGHT)
+        .width(Length::Fill)
+    };
+
+    let line_label = match (line, &form.edited_line) {
+        (Some(line), Some(edited_line)) => format!(
+            "{}: {} ({} x {:.2}€ → {} x {:.2}€)",
+            fl!("selected-line"),
+            line.name,
+            line.quantity,
+            line.price.unwrap_or_default(),
+            edited_line.quantity,
+            edited_line.price.unwrap_or_default()
+        ),
+        (Some(line), None) => format!("{}: {}", fl!("selected-line"), line.name),
+        (None, _) => fl!("selected-line"),
+    };
+    let target_row = row![
+        target_button(line_label, VoidTarget::Line, line.is_some()),
+        // a lowered line only voids the difference, the whole ticket can be voided once it's cancelled
+        target_button(
+            fl!("whole-ticket"),
+            VoidTarget::Ticket,
+            form.edited_line.is_none()
+        ),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    let amount = match form.target {
+        VoidTarget::Line => line
+            .map(|l| match &form.edited_line {
+                Some(edited_line) => l.total_price() - edited_line.total_price(),
+                None => l.total_price(),
+            })
+            .unwrap_or_default(),
+        VoidTarget::Ticket => ticket.map(|t| t.total_price()).unwrap_or_default(),
+    };
+
+    let buttons_row = row![
+        button(text(fl!("cancel")).center())
+            .on_press(Message::CloseVoidModal)
+            .width(Length::Fill),
+        button(text(fl!("void")).center())
+            .on_press_maybe(form.is_valid().then_some(Message::ConfirmVoid))
+            .style(button::danger)
+            .width(Length::Fill),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    container(
+        column![
+            text(fl!("void")).size(TITLE_TEXT_SIZE),
+            target_row,
+            text(format!("{}: {:.2}€", fl!("amount"), amount)),
+            text_input(&fl!("reason"), &form.reason).on_input(Message::VoidReasonInput),
+            text_input(&fl!("voided-by"), &form.voided_by).on_input(Message::VoidedByInput),
+            buttons_row,
+        ]
+        .spacing(GLOBAL_SPACING)
+        .width(Length::Fill),
+    )
+    .width(700)
+    .padding(30)
+    .align_x(Alignment::Center)
+    .align_y(Alignment::Center)
+    .style(container::rounded_box)
+    .into()
 }
 
 /// View of the pay subscreen
@@ -612,6 +1126,8 @@ fn pay_view<'a>(
     occupied_reservations: &'a [Reservation],
     selected_payment_method: &'a PaymentMethod,
     selected_adeudo_room_id: &'a Option<i32>,
+    split: &'a SplitState,
+    payments: &'a PaymentsState,
     print_modal: &'a PrintModal,
 ) -> iced::Element<'a, Message> {
     let spacing = Pixels::from(GLOBAL_SPACING);
@@ -622,6 +1138,8 @@ fn pay_view<'a>(
         occupied_reservations,
         selected_payment_method,
         selected_adeudo_room_id,
+        split,
+        payments,
     );
 
     match print_modal.show_modal {
@@ -673,15 +1191,49 @@ fn pay_view_content<'a>(
     occupied_reservations: &'a [Reservation],
     selected_payment_method: &'a PaymentMethod,
     selected_adeudo_room_id: &'a Option<i32>,
+    split: &'a SplitState,
+    payments: &'a PaymentsState,
 ) -> iced::Element<'a, Message> {
     let spacing = Pixels::from(GLOBAL_SPACING);
     let button_height = Length::Fixed(GLOBAL_BUTTON_HEIGHT);
 
-    let total_price = {
-        let price = ticket.total_price();
-        text(format!("{price:.2} €")).size(25.).line_height(2.)
+    // when splitting, the big amount is the one of the current bill
+    let bill_price = split.bill_price(ticket);
+    let total_price = text(format!("{bill_price:.2} €")).size(25.).line_height(2.);
+
+    let pending_price = match split.mode {
+        SplitMode::Whole => text(""),
+        _ => text(format!("{}: {:.2} €", fl!("pending"), ticket.total_price())),
     };
 
+    let split_modes_buttons: Vec<iced::Element<Message>> = [
+        (SplitMode::Whole, fl!("pay-whole")),
+        (SplitMode::ByLines, fl!("split-by-lines")),
+        (SplitMode::Evenly, fl!("split-evenly")),
+    ]
+    .into_iter()
+    .map(|(mode, label)| {
+        button(
+            text(label)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center),
+        )
+        .style(if mode == split.mode {
+            button::success
+        } else {
+            button::secondary
+        })
+        // a locked ticket is paid as a whole with its invoice (which may already have a recipient)
+        .on_press_maybe(
+            (mode == SplitMode::Whole || ticket.simple_invoice_id.is_none())
+                .then_some(Message::UpdateSplitMode(mode)),
+        )
+        .height(button_height)
+        .into()
+    })
+    .collect();
+    let split_modes_row = Row::with_children(split_modes_buttons).spacing(spacing);
+
     let payment_methods_buttons: Vec<iced::Element<Message>> = PaymentMethod::ALL
         .iter()
         .map(|p_method| {
@@ -703,7 +1255,7 @@ fn pay_view_content<'a>(
     let payment_methods_column = Column::with_children(payment_methods_buttons).spacing(spacing);
 
     let reservations_selector_grid: iced::Element<Message> =
-        if *selected_payment_method == PaymentMethod::Adeudo {
+        if *selected_payment_method == PaymentMethod::Adeudo || payments.has_adeudo() {
             pay_screen_reservations_selector(occupied_reservations, selected_adeudo_room_id)
         } else {
             container(Space::new(Length::Shrink, Length::Shrink)).into()
@@ -718,7 +1270,20 @@ fn pay_view_content<'a>(
     .height(button_height);
 
     let content = row![
-        column![total_price, payment_methods_column, submit_button].spacing(spacing),
+        column![
+            total_price,
+            pending_price,
+            split_modes_row,
+            pay_screen_split_controls(ticket, split)
+        ]
+        .spacing(spacing),
+        column![
+            payment_methods_column,
+            pay_screen_payments(payments, bill_price),
+            submit_button
+        ]
+        .width(235.)
+        .spacing(spacing),
         reservations_selector_grid
     ]
     .spacing(spacing);
@@ -730,6 +1295,139 @@ fn pay_view_content<'a>(
         .into()
 }
 
+/// Returns the registered payments of the current bill and the controls to add new ones
+fn pay_screen_payments<'a>(
+    payments: &'a PaymentsState,
+    bill_price: Money,
+) -> iced::Element<'a, Message> {
+    let spacing = Pixels::from(GLOBAL_SPACING);
+    let button_height = Length::Fixed(GLOBAL_BUTTON_HEIGHT);
+
+    let amount_input = text_input(fl!("amount").as_str(), &payments.amount_input)
+        .on_input(Message::PaymentAmountInput)
+        .on_submit(Message::AddPayment)
+        .width(Length::Fill);
+
+    let add_payment_button = button(
+        text(fl!("add-payment"))
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center),
+    )
+    .on_press(Message::AddPayment)
+    .height(button_height);
+
+    let mut registered_payments = Column::new().spacing(spacing);
+    for (index, payment) in payments.registered.iter().enumerate() {
+        registered_payments = registered_payments.push(
+            row![
+                text(payment.payment_method).width(Length::Fill),
+                text(format!("{:.2} €", payment.amount)),
+                button(text("x").center())
+                    .on_press(Message::RemovePayment(index))
+                    .style(button::danger)
+            ]
+            .spacing(spacing)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let remaining = text(format!(
+        "{}: {:.2} €",
+        fl!("remaining"),
+        payments.remaining(bill_price)
+    ));
+    let change = match payments.change {
+        Some(change) => text(format!("{}: {change:.2} €", fl!("change"))).size(25.),
+        None => text(""),
+    };
+
+    column![
+        row![amount_input, add_payment_button]
+            .spacing(spacing)
+            .align_y(Alignment::Center),
+        registered_payments,
+        remaining,
+        change,
+        numpad()
+    ]
+    .spacing(spacing)
+    .into()
+}
+
+/// Returns the controls to choose what goes into the current bill when splitting the ticket
+fn pay_screen_split_controls<'a>(
+    ticket: &'a TemporalTicket,
+    split: &'a SplitState,
+) -> iced::Element<'a, Message> {
+    let spacing = Pixels::from(GLOBAL_SPACING);
+
+    match split.mode {
+        SplitMode::Whole => container(Space::new(Length::Shrink, Length::Shrink)).into(),
+        SplitMode::ByLines => {
+            let mut lines = Column::new().spacing(spacing).width(Length::Shrink);
+
+            for product in &ticket.products {
+                let Some(product_id) = product.id else {
+                    continue;
+                };
+                let selected_quantity = split
+                    .selected_quantities
+                    .get(&product_id)
+                    .copied()
+                    .unwrap_or_default();
+
+                let line = row![
+                    text(&product.name).width(250.).align_y(Alignment::Center),
+                    text(format!("{selected_quantity}/{}", product.quantity))
+                        .width(60.)
+                        .center(),
+                    button(text("-").center())
+                        .on_press(Message::UpdateSplitLineQuantity(
+                            product_id,
+                            selected_quantity - 1
+                        ))
+                        .width(GLOBAL_BUTTON_HEIGHT)
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                    button(text("+").center())
+                        .on_press(Message::UpdateSplitLineQuantity(
+                            product_id,
+                            selected_quantity + 1
+                        ))
+                        .width(GLOBAL_BUTTON_HEIGHT)
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                    button(text(fl!("all")).center())
+                        .on_press(Message::UpdateSplitLineQuantity(
+                            product_id,
+                            product.quantity
+                        ))
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                ]
+                .spacing(spacing)
+                .align_y(Alignment::Center);
+
+                lines = lines.push(line);
+            }
+
+            Scrollable::new(lines).height(Length::Shrink).into()
+        }
+        SplitMode::Evenly => row![
+            text(fl!("payers")).align_y(Alignment::Center),
+            button(text("-").center())
+                .on_press(Message::UpdateSplitPayers(split.payers - 1))
+                .width(GLOBAL_BUTTON_HEIGHT)
+                .height(GLOBAL_BUTTON_HEIGHT),
+            text(split.payers).width(40.).center(),
+            button(text("+").center())
+                .on_press(Message::UpdateSplitPayers(split.payers + 1))
+                .width(GLOBAL_BUTTON_HEIGHT)
+                .height(GLOBAL_BUTTON_HEIGHT),
+        ]
+        .spacing(spacing)
+        .align_y(Alignment::Center)
+        .into(),
+    }
+}
+
 fn pay_screen_reservations_selector<'a>(
     occupied_reservations: &'a [Reservation],
     selected_adeudo_room_id: &'a Option<i32>,
@@ -827,18 +1525,16 @@ fn pay_screen_reservations_selector<'a>(
 // HELPERS
 //
 
-/// Determines the color a button of the tables grid should be given the table index, using the temporal_tickets model
+/// Determines the color a button of the tables grid should be given the table id, using the temporal_tickets model
 fn determine_table_button_color(
     current_position: &CurrentPosition,
     temporal_tickets: &[TemporalTicket],
     t: &iced::Theme,
     s: button::Status,
-    t_id: usize,
+    table_id: i32,
 ) -> button::Style {
-    let table_id = t_id as i32;
-
     // We have it currently selected
-    if current_position.table_index == table_id {
+    if current_position.table_id == table_id {
         match s {
             button::Status::Hovered => {
                 return button::primary(t, button::Status::Hovered);
@@ -849,11 +1545,9 @@ fn determine_table_button_color(
         }
     }
 
-    let current_ticket = temporal_tickets.iter().find(|x| {
-        x.table_id == table_id
-            && x.ticket_location
-                == match_table_location_with_number(&current_position.table_location)
-    });
+    let current_ticket = temporal_tickets
+        .iter()
+        .find(|x| x.table_id == table_id && x.ticket_location == current_position.table_location);
 
     // there is not ticket on this table
     if current_ticket.is_none() {
@@ -895,10 +1589,10 @@ fn determine_location_button_color(
     current_position: &CurrentPosition,
     t: &iced::Theme,
     s: button::Status,
-    loc: TableLocation,
+    location_id: i32,
 ) -> button::Style {
     // we are currently in this location
-    if loc == current_position.table_location {
+    if location_id == current_position.table_location {
         match s {
             button::Status::Hovered => button::primary(t, button::Status::Hovered),
             _ => button::primary(t, button::Status::Active),
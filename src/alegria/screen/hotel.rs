@@ -12,6 +12,7 @@ use crate::alegria::widgets::toast::Toast;
 use crate::fl;
 
 mod clients;
+mod companies;
 mod reservations;
 mod room_types;
 mod rooms;
@@ -31,6 +32,7 @@ enum State {
 pub enum SubScreen {
     Home,
     Clients(clients::Clients),
+    Companies(companies::Companies),
     RoomTypes(room_types::RoomTypes),
     Rooms(rooms::Rooms),
     Reservations(reservations::Reservations),
@@ -43,6 +45,9 @@ pub enum Message {
     Clients(clients::Message),
     OpenClients,
 
+    Companies(companies::Message),
+    OpenCompanies,
+
     RoomTypes(room_types::Message),
     OpenRoomTypes,
 
@@ -115,6 +120,35 @@ impl Hotel {
                 Action::Run(task.map(Message::Clients))
             }
 
+            Message::Companies(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::Companies(companies) = sub_screen else {
+                    return Action::None;
+                };
+
+                match companies.update(message, database, now) {
+                    companies::Action::None => Action::None,
+                    companies::Action::Run(task) => Action::Run(task.map(Message::Companies)),
+                    companies::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    companies::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenCompanies => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (companies, task) = companies::Companies::new(database);
+                *sub_screen = SubScreen::Companies(companies);
+                Action::Run(task.map(Message::Companies))
+            }
+
             Message::RoomTypes(message) => {
                 let State::Ready { sub_screen } = &mut self.state else {
                     return Action::None;
@@ -224,6 +258,7 @@ impl Hotel {
                     .into()
                 }
                 SubScreen::Clients(clients) => clients.view(now).map(Message::Clients),
+                SubScreen::Companies(companies) => companies.view(now).map(Message::Companies),
                 SubScreen::RoomTypes(room_types) => room_types.view(now).map(Message::RoomTypes),
                 SubScreen::Rooms(rooms) => rooms.view(now).map(Message::Rooms),
                 SubScreen::Reservations(reservations) => {
@@ -241,6 +276,7 @@ impl Hotel {
         match sub_screen {
             SubScreen::Home => Subscription::none(),
             SubScreen::Clients(clients) => clients.subscription(now).map(Message::Clients),
+            SubScreen::Companies(companies) => companies.subscription(now).map(Message::Companies),
             SubScreen::RoomTypes(room_types) => {
                 room_types.subscription(now).map(Message::RoomTypes)
             }
@@ -317,6 +353,16 @@ fn home<'a>() -> iced::Element<'a, Message> {
             .width(SQUAREBUTTONXY)
             .height(SQUAREBUTTONXY),
         )
+        .push(
+            button(
+                text(fl!("companies"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenCompanies)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
         .spacing(5.);
 
     container(buttons_row).center(Length::Fill).into()
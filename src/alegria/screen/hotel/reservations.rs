@@ -25,6 +25,8 @@ use crate::alegria::utils::styling::{
 use crate::{alegria::widgets::toast::Toast, fl};
 
 mod add;
+mod check_in;
+mod check_out;
 mod edit;
 
 pub struct Reservations {
@@ -44,6 +46,8 @@ enum SubScreen {
     },
     Add(add::AddReservation),
     Edit(edit::EditReservation),
+    CheckIn(check_in::CheckIn),
+    CheckOut(check_out::CheckOut),
 }
 
 #[derive(Debug, Clone)]
@@ -124,6 +128,10 @@ pub enum Message {
     AddReservation(add::Message),
     /// Edit Reservation page messages
     EditReservation(edit::Message),
+    /// Check-in page messages
+    CheckIn(check_in::Message),
+    /// Check-out page messages
+    CheckOut(check_out::Message),
 
     /// Opens the add reservation page for the selected date and room
     OpenAddReservation(NaiveDate, Room),
@@ -181,7 +189,7 @@ impl Reservations {
                         SubScreen::Add(_) => {
                             return self.update(Message::LoadListPage, &database.clone(), now);
                         }
-                        SubScreen::Edit(_) => {
+                        SubScreen::Edit(_) | SubScreen::CheckIn(_) | SubScreen::CheckOut(_) => {
                             return self.update(Message::LoadListPage, &database.clone(), now);
                         }
                     }
@@ -386,6 +394,52 @@ impl Reservations {
                         self.update(Message::LoadListPage, &database.clone(), now)
                     }
                     edit::Action::AddToast(toast) => Action::AddToast(toast),
+                    edit::Action::CheckIn(reservation_id) => {
+                        let (check_in, task) = check_in::CheckIn::new(database, reservation_id);
+                        *sub_screen = SubScreen::CheckIn(check_in);
+                        Action::Run(task.map(Message::CheckIn))
+                    }
+                    edit::Action::CheckOut(reservation_id) => {
+                        let (check_out, task) = check_out::CheckOut::new(database, reservation_id);
+                        *sub_screen = SubScreen::CheckOut(check_out);
+                        Action::Run(task.map(Message::CheckOut))
+                    }
+                }
+            }
+            Message::CheckIn(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::CheckIn(check_in) = sub_screen else {
+                    return Action::None;
+                };
+
+                match check_in.update(message, database, now) {
+                    check_in::Action::None => Action::None,
+                    check_in::Action::Run(task) => Action::Run(task.map(Message::CheckIn)),
+                    check_in::Action::Back => {
+                        self.update(Message::LoadListPage, &database.clone(), now)
+                    }
+                    check_in::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::CheckOut(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::CheckOut(check_out) = sub_screen else {
+                    return Action::None;
+                };
+
+                match check_out.update(message, database, now) {
+                    check_out::Action::None => Action::None,
+                    check_out::Action::Run(task) => Action::Run(task.map(Message::CheckOut)),
+                    check_out::Action::Back => {
+                        self.update(Message::LoadListPage, &database.clone(), now)
+                    }
+                    check_out::Action::AddToast(toast) => Action::AddToast(toast),
                 }
             }
             Message::OpenAddReservation(initial_date, clicked_room) => {
@@ -432,6 +486,10 @@ impl Reservations {
                             price: clicked_room.default_room_price,
                             invoices: Vec::new(),
                             room_name: Box::from(""),
+                            price_input: clicked_room
+                                .default_room_price
+                                .map(|p| p.to_string())
+                                .unwrap_or_default(),
                         });
                     }
 
@@ -447,9 +505,13 @@ impl Reservations {
                     return Action::None;
                 };
 
-                let (edit, task) = edit::EditReservation::new(database, reservation_id);
-                *sub_screen = SubScreen::Edit(edit);
-                Action::Run(task.map(Message::EditReservation))
+                if let SubScreen::List { rooms, .. } = sub_screen {
+                    let (edit, task) =
+                        edit::EditReservation::new(database, rooms.clone(), reservation_id);
+                    *sub_screen = SubScreen::Edit(edit);
+                    return Action::Run(task.map(Message::EditReservation));
+                }
+                Action::None
             }
         }
     }
@@ -465,6 +527,8 @@ impl Reservations {
                 } => list_screen(date_filters, reservations, rooms),
                 SubScreen::Add(add) => add.view(now).map(Message::AddReservation),
                 SubScreen::Edit(edit) => edit.view(now).map(Message::EditReservation),
+                SubScreen::CheckIn(check_in) => check_in.view(now).map(Message::CheckIn),
+                SubScreen::CheckOut(check_out) => check_out.view(now).map(Message::CheckOut),
             },
         }
     }
@@ -478,6 +542,8 @@ impl Reservations {
             SubScreen::List { .. } => event::listen_with(handle_event),
             SubScreen::Add(add) => add.subscription(now).map(Message::AddReservation),
             SubScreen::Edit(edit) => edit.subscription(now).map(Message::EditReservation),
+            SubScreen::CheckIn(check_in) => check_in.subscription(now).map(Message::CheckIn),
+            SubScreen::CheckOut(check_out) => check_out.subscription(now).map(Message::CheckOut),
         }
     }
 }
@@ -665,6 +731,11 @@ fn reservations_calendar<'a>(
                         // departure date does not have an equal because we can book a room the day someone departs
                         && reservation.departure_date.unwrap_or_default().date() > current_date
                 {
+                    let tooltip_text = if reservation.company_name.is_empty() {
+                        reservation.client_name.clone()
+                    } else {
+                        format!("{} | {}", reservation.client_name, reservation.company_name)
+                    };
                     match reservation.occupied {
                         true => {
                             cell_content = container(Tooltip::new(
@@ -675,7 +746,7 @@ fn reservations_calendar<'a>(
                                     .style(button::success)
                                     .width(cell_width)
                                     .height(cell_height),
-                                container(reservation.client_name.as_str())
+                                container(text(tooltip_text))
                                     .style(container::rounded_box)
                                     .padding(3.),
                                 tooltip::Position::FollowCursor,
@@ -687,10 +758,15 @@ fn reservations_calendar<'a>(
                                     .on_press(Message::OpenEditReservation(
                                         reservation.id.unwrap_or_default(),
                                     ))
-                                    .style(button::danger)
+                                    // checked out reservations keep their (already free) nights on the calendar
+                                    .style(if reservation.checked_out_at.is_some() {
+                                        button::primary
+                                    } else {
+                                        button::danger
+                                    })
                                     .width(cell_width)
                                     .height(cell_height),
-                                container(reservation.client_name.as_str())
+                                container(text(tooltip_text))
                                     .style(container::rounded_box)
                                     .padding(3.),
                                 tooltip::Position::FollowCursor,
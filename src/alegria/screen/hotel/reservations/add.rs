@@ -13,11 +13,13 @@ use iced::widget::{
 use iced::{Alignment, Length, Subscription, event};
 use sqlx::{Pool, Postgres};
 
+use crate::alegria::core::models::company::Company;
 use crate::alegria::core::models::reservation::Reservation;
 use crate::alegria::core::models::room::Room;
 use crate::alegria::core::models::sold_room::SoldRoom;
 use crate::alegria::screen::hotel::clients::{self, Clients};
 use crate::alegria::utils::date::parse_date_to_naive_datetime;
+use crate::alegria::utils::money::Money;
 use crate::alegria::utils::styling::{
     GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
 };
@@ -36,6 +38,7 @@ enum State {
         reservation: Box<Reservation>,
         rooms: Arc<Vec<Room>>,
         reservations: Vec<Reservation>,
+        companies: Vec<Company>,
     },
 }
 
@@ -48,7 +51,6 @@ pub enum SubScreen {
 pub enum InputFields {
     EntryDate,
     DepartureDate,
-    Occupied,
 }
 
 #[derive(Debug, Clone)]
@@ -61,18 +63,25 @@ pub enum Message {
     Hotkey(Hotkey),
 
     /// Callback after initial page load
-    PageLoaded(Box<Reservation>, Arc<Vec<Room>>, Vec<Reservation>),
+    PageLoaded(
+        Box<Reservation>,
+        Arc<Vec<Room>>,
+        Vec<Reservation>,
+        Vec<Company>,
+    ),
 
     /// Callback when using the form inputs
     FormInputUpdate(String, InputFields),
     /// Asks to add a room to the vec of booked rooms of the current add reservation
-    AddReservationRoom(i32, Option<f32>),
+    AddReservationRoom(i32, Option<Money>),
     /// Asks to remove a room to the vec of booked rooms of the current add reservation
     RemoveReservationRoom(i32),
     /// Asks to open the client selector page/component
     OpenClientSelector,
     /// Messages of the clients (selector) page
     Clients(clients::Message),
+    /// Sets (or clears) the company that pays the current reservation
+    SetCompany(Option<Company>),
     /// Tries to add the current reservation to the database
     AddReservation,
 }
@@ -95,21 +104,27 @@ impl AddReservation {
                 state: State::Loading,
             },
             Task::perform(
-                Reservation::get_all(
-                    database.clone(),
-                    reservation.entry_date.unwrap().date(),
-                    reservation
-                        .entry_date
-                        .unwrap()
-                        .date()
-                        .checked_add_days(chrono::Days::new(120)) // is this a sensible number of dates to check?
-                        .unwrap_or_default(),
-                ),
+                {
+                    let database = database.clone();
+                    let initial_date = reservation.entry_date.unwrap().date();
+                    async move {
+                        tokio::join!(
+                            Reservation::get_all(
+                                database.clone(),
+                                initial_date,
+                                initial_date
+                                    .checked_add_days(chrono::Days::new(120)) // is this a sensible number of dates to check?
+                                    .unwrap_or_default(),
+                            ),
+                            Company::get_all(database.clone())
+                        )
+                    }
+                },
                 |res| match res {
-                    Ok(reservations) => {
-                        Message::PageLoaded(Box::from(reservation), rooms, reservations)
+                    (Ok(reservations), Ok(companies)) => {
+                        Message::PageLoaded(Box::from(reservation), rooms, reservations, companies)
                     }
-                    Err(err) => {
+                    (Err(err), _) | (_, Err(err)) => {
                         eprintln!("{err}");
                         Message::AddToast(Toast::error_toast(err))
                     }
@@ -142,12 +157,13 @@ impl AddReservation {
                 }
                 Action::None
             }
-            Message::PageLoaded(reservation, rooms, reservations) => {
+            Message::PageLoaded(reservation, rooms, reservations, companies) => {
                 self.state = State::Ready {
                     sub_screen: SubScreen::None,
                     reservation,
                     rooms,
                     reservations,
+                    companies,
                 };
                 Action::None
             }
@@ -160,9 +176,6 @@ impl AddReservation {
                         InputFields::DepartureDate => {
                             reservation.departure_date_string = new_value;
                         }
-                        InputFields::Occupied => {
-                            reservation.occupied = !reservation.occupied;
-                        }
                     }
                 }
                 Action::None
@@ -182,6 +195,7 @@ impl AddReservation {
                             price: room_price,
                             invoices: Vec::new(),
                             room_name: Box::from(""),
+                            price_input: room_price.map(|p| p.to_string()).unwrap_or_default(),
                         });
                     }
                 }
@@ -235,6 +249,13 @@ impl AddReservation {
                     }
                 }
             }
+            Message::SetCompany(company) => {
+                if let State::Ready { reservation, .. } = &mut self.state {
+                    reservation.company_id = company.as_ref().and_then(|c| c.id);
+                    reservation.company_name = company.map(|c| c.name).unwrap_or_default();
+                }
+                Action::None
+            }
             Message::OpenClientSelector => {
                 let State::Ready { sub_screen, .. } = &mut self.state else {
                     return Action::None;
@@ -298,8 +319,9 @@ impl AddReservation {
                 reservation,
                 rooms,
                 reservations,
+                companies,
             } => match sub_screen {
-                SubScreen::None => add_form(reservation, rooms, reservations),
+                SubScreen::None => add_form(reservation, rooms, reservations, companies),
                 SubScreen::ClientsSelection(clients) => clients.view(now).map(Message::Clients),
             },
         }
@@ -338,9 +360,10 @@ fn add_form<'a>(
     reservation: &'a Reservation,
     rooms: &'a [Room],
     reservations: &'a [Reservation],
+    companies: &'a [Company],
 ) -> iced::Element<'a, Message> {
     let header = header();
-    let content = form_content(reservation, rooms, reservations);
+    let content = form_content(reservation, rooms, reservations, companies);
 
     column![
         header,
@@ -371,6 +394,7 @@ fn form_content<'a>(
     new_reservation: &'a Reservation,
     rooms: &'a [Room],
     reservations: &'a [Reservation],
+    companies: &'a [Company],
 ) -> iced::Element<'a, Message> {
     let entry_date_label = text(format!("{} (yyyy-mm-dd)", fl!("entry-date"))).width(Length::Fill);
     let entry_date_input = text_input(
@@ -391,9 +415,8 @@ fn form_content<'a>(
     .size(TEXT_SIZE)
     .width(Length::Fill);
 
-    let occupied = checkbox(fl!("occupied"), new_reservation.occupied)
-        .text_size(TEXT_SIZE)
-        .on_toggle(|_| Message::FormInputUpdate(String::new(), InputFields::Occupied));
+    // read-only, only the check-in occupies a reservation
+    let occupied = checkbox(fl!("occupied"), new_reservation.occupied).text_size(TEXT_SIZE);
 
     // Rooms Selector
     let available_rooms = rooms
@@ -448,6 +471,24 @@ fn form_content<'a>(
     .align_y(Alignment::Center)
     .spacing(GLOBAL_SPACING);
 
+    // Company Selection
+    let selected_company = companies
+        .iter()
+        .find(|c| new_reservation.company_id.is_some() && c.id == new_reservation.company_id);
+    let company_row = row![
+        pick_list(companies, selected_company, |c| Message::SetCompany(Some(
+            c
+        )))
+        .width(Length::Fill),
+        button(text("X").center()).on_press_maybe(
+            new_reservation
+                .company_id
+                .map(|_| Message::SetCompany(None))
+        )
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING);
+
     // Submit
     let submit_button = button(text(fl!("add")).width(Length::Fill).center())
         .on_press_maybe(
@@ -473,8 +514,16 @@ fn form_content<'a>(
         .width(850.)
         .spacing(1.);
 
+    let company_selection_column = column![
+        text(fl!("paid-by-company")).width(Length::Fill),
+        company_row
+    ]
+    .width(850.)
+    .spacing(1.);
+
     Column::new()
         .push(client_selection_column)
+        .push(company_selection_column)
         .push(entry_date_input_column)
         .push(departure_date_input_column)
         .push(occupied)
@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use chrono::{Local, NaiveDate};
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Space, button, column, focus_next, focus_previous, row, scrollable, text,
+};
+use iced::{Alignment, Length, Subscription, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::client::Client;
+use crate::alegria::core::models::reservation::Reservation;
+use crate::alegria::core::models::sold_room::SoldRoom;
+use crate::alegria::screen::hotel::clients::{self, Clients};
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{alegria::widgets::toast::Toast, fl};
+
+pub struct CheckIn {
+    state: State,
+}
+
+enum State {
+    Loading,
+    // We need to preserve the state of the check-in screen when we open the client selection
+    Ready {
+        sub_screen: SubScreen,
+        reservation: Box<Reservation>,
+    },
+}
+
+pub enum SubScreen {
+    None,
+    /// Selecting (or creating) a guest for the given sold room
+    GuestSelection(Clients, i32),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Callback after initial page load
+    PageLoaded(Box<Reservation>),
+
+    /// Asks to open the client selector page/component to add a guest to the given sold room
+    OpenGuestSelector(i32),
+    /// Messages of the clients (selector) page
+    Clients(clients::Message),
+    /// Callback after fetching the full data of the selected guest for the given sold room
+    GuestLoaded(i32, Box<Client>),
+    /// Removes the given guest (client id) from the given sold room
+    RemoveGuest(i32, i32),
+    /// Tries to check in the reservation with the registered guests
+    CheckIn,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl CheckIn {
+    pub fn new(database: &Arc<Pool<Postgres>>, reservation_id: i32) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(
+                Reservation::get_single(database.clone(), reservation_id),
+                |res| match res {
+                    Ok(reservation) => Message::PageLoaded(Box::from(reservation)),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            ),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { .. } = &mut self.state {
+                    return match hotkey {
+                        Hotkey::Tab(modifiers) => {
+                            if modifiers.shift() {
+                                Action::Run(focus_previous())
+                            } else {
+                                Action::Run(focus_next())
+                            }
+                        }
+                    };
+                }
+                Action::None
+            }
+            Message::PageLoaded(reservation) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::None,
+                    reservation,
+                };
+                Action::None
+            }
+            Message::OpenGuestSelector(sold_room_id) => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (clients, task) = clients::Clients::new(database, clients::PageMode::Select);
+                *sub_screen = SubScreen::GuestSelection(clients, sold_room_id);
+                Action::Run(task.map(Message::Clients))
+            }
+            Message::Clients(message) => {
+                let State::Ready {
+                    sub_screen,
+                    reservation,
+                } = &mut self.state
+                else {
+                    return Action::None;
+                };
+
+                let SubScreen::GuestSelection(clients_selector_page, sold_room_id) = sub_screen
+                else {
+                    return Action::None;
+                };
+                let sold_room_id = *sold_room_id;
+
+                match clients_selector_page.update(message, &database.clone(), now) {
+                    clients::Action::None => Action::None,
+                    clients::Action::Back => {
+                        *sub_screen = SubScreen::None;
+                        Action::None
+                    }
+                    clients::Action::Run(task) => Action::Run(task.map(Message::Clients)),
+                    clients::Action::AddToast(toast) => Action::AddToast(toast),
+                    clients::Action::ClientSelected(client) => {
+                        *sub_screen = SubScreen::None;
+
+                        let already_registered = reservation
+                            .rooms
+                            .iter()
+                            .flat_map(|sold_room| &sold_room.guests)
+                            .any(|guest| guest.id == client.id);
+                        if already_registered {
+                            return Action::AddToast(Toast::warning_toast(
+                                "The client is already registered as a guest of this reservation",
+                            ));
+                        }
+
+                        // the clients list doesn't have the birthdate nor the document dates we need to validate the guest
+                        Action::Run(Task::perform(
+                            Client::get_single(database.clone(), client.id.unwrap_or_default()),
+                            move |res| match res {
+                                Ok(client) => Message::GuestLoaded(sold_room_id, Box::from(client)),
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ))
+                    }
+                }
+            }
+            Message::GuestLoaded(sold_room_id, client) => {
+                if let State::Ready { reservation, .. } = &mut self.state
+                    && let Some(sold_room) = reservation
+                        .rooms
+                        .iter_mut()
+                        .find(|sold_room| sold_room.id == Some(sold_room_id))
+                    && !sold_room.guests.iter().any(|guest| guest.id == client.id)
+                {
+                    sold_room.guests.push(*client);
+                }
+                Action::None
+            }
+            Message::RemoveGuest(sold_room_id, client_id) => {
+                if let State::Ready { reservation, .. } = &mut self.state
+                    && let Some(sold_room) = reservation
+                        .rooms
+                        .iter_mut()
+                        .find(|sold_room| sold_room.id == Some(sold_room_id))
+                {
+                    sold_room.guests.retain(|guest| guest.id != Some(client_id));
+                }
+                Action::None
+            }
+            Message::CheckIn => {
+                if let State::Ready { reservation, .. } = &self.state {
+                    return Action::Run(Task::perform(
+                        Reservation::check_in(database.clone(), *reservation.clone()),
+                        |res| match res {
+                            Ok(_) => Message::Back,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready {
+                sub_screen,
+                reservation,
+            } => match sub_screen {
+                SubScreen::None => check_in_form(reservation),
+                SubScreen::GuestSelection(clients, _) => clients.view(now).map(Message::Clients),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn check_in_form<'a>(reservation: &'a Reservation) -> iced::Element<'a, Message> {
+    let today = Local::now().date_naive();
+    let header = header(reservation);
+
+    let rooms = reservation
+        .rooms
+        .iter()
+        .fold(Column::new().spacing(GLOBAL_SPACING), |col, sold_room| {
+            col.push(room_guests(sold_room, today))
+        });
+
+    // Submit
+    let can_check_in = reservation.can_check_in_on(today)
+        && reservation
+            .rooms
+            .iter()
+            .all(|sold_room| sold_room.check_in_error(today).is_none());
+    let submit_button = button(text(fl!("check-in")).width(Length::Fill).center())
+        .on_press_maybe(can_check_in.then_some(Message::CheckIn))
+        .width(Length::Fill);
+
+    column![
+        header,
+        container(
+            column![scrollable(rooms).height(Length::Fill), submit_button]
+                .spacing(GLOBAL_SPACING)
+                .width(850.)
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(Alignment::Center)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .height(Length::Fill)
+    .width(Length::Fill)
+    .into()
+}
+
+fn header<'a>(reservation: &'a Reservation) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("check-in")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        text(&reservation.client_name).size(TEXT_SIZE)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// Returns the guests of the given sold room with their validation status and a button to add more
+fn room_guests<'a>(sold_room: &'a SoldRoom, today: NaiveDate) -> iced::Element<'a, Message> {
+    let sold_room_id = sold_room.id.unwrap_or_default();
+
+    let title_row = row![
+        text(&*sold_room.room_name)
+            .size(TITLE_TEXT_SIZE)
+            .width(Length::Fill),
+        button(text(fl!("add-guest")).center())
+            .on_press(Message::OpenGuestSelector(sold_room_id))
+            .height(GLOBAL_BUTTON_HEIGHT)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING);
+
+    let mut room_column = Column::new().push(title_row).spacing(3.);
+
+    if sold_room.guests.is_empty() {
+        room_column = room_column.push(text(fl!("no-guests")).size(TEXT_SIZE));
+    }
+
+    for guest in &sold_room.guests {
+        let status = match guest.guest_registration_error(today) {
+            Some(err) => text(err).size(TEXT_SIZE).style(text::danger),
+            None if guest.is_adult_on(today) => text(fl!("adult")).size(TEXT_SIZE),
+            None => text(fl!("minor")).size(TEXT_SIZE),
+        };
+
+        room_column = room_column.push(
+            Row::new()
+                .push(
+                    text(format!(
+                        "{} {} {}",
+                        guest.name, guest.first_surname, guest.second_surname
+                    ))
+                    .size(TEXT_SIZE)
+                    .width(300.),
+                )
+                .push(text(&guest.identity_document).size(TEXT_SIZE).width(150.))
+                .push(status.width(Length::Fill))
+                .push(button("X").on_press(Message::RemoveGuest(
+                    sold_room_id,
+                    guest.id.unwrap_or_default(),
+                )))
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        );
+    }
+
+    if let Some(err) = sold_room.check_in_error(today)
+        && !sold_room
+            .guests
+            .iter()
+            .any(|guest| guest.is_adult_on(today))
+    {
+        room_column = room_column.push(text(err).size(TEXT_SIZE).style(text::danger));
+    }
+
+    room_column.into()
+}
@@ -0,0 +1,532 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Space, button, column, focus_next, focus_previous, pick_list, row, scrollable,
+    text, text_input,
+};
+use iced::{Alignment, Length, Subscription, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::folio::{Folio, FolioLine};
+use crate::alegria::core::models::invoice_payment::InvoicePayment;
+use crate::alegria::core::models::product::Product;
+use crate::alegria::utils::entities::payment_method::PaymentMethod;
+use crate::alegria::utils::money::{Money, parse_decimal};
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{alegria::widgets::toast::Toast, fl};
+
+pub struct CheckOut {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready {
+        folio: Box<Folio>,
+        /// Products that can be added as extras
+        products: Vec<Product>,
+        selected_payment_method: PaymentMethod,
+        payments: Vec<InvoicePayment>,
+        amount_input: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Callback after initial page load
+    PageLoaded(Box<Folio>, Vec<Product>),
+
+    /// Adds one unit of the given product (by id) as an extra of the folio
+    AddExtra(i32),
+    /// Removes one unit of the given extra (by product id) of the folio
+    RemoveExtra(i32),
+    /// Updates the currently selected payment method
+    UpdateSelectedPaymentMethod(PaymentMethod),
+    /// text_input of the amount of the payment that's going to be added
+    PaymentAmountInput(String),
+    /// Adds a payment (selected method and amount) to the folio
+    AddPayment,
+    /// Removes a registered payment (by index) of the folio
+    RemovePayment(usize),
+    /// Tries to check out the reservation with the registered payments
+    CheckOut,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl CheckOut {
+    pub fn new(database: &Arc<Pool<Postgres>>, reservation_id: i32) -> (Self, Task<Message>) {
+        let database = database.clone();
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(
+                async move {
+                    let (folio, products) = tokio::join!(
+                        Folio::get(database.clone(), reservation_id),
+                        Product::get_all(database.clone())
+                    );
+                    Ok::<_, sqlx::Error>((folio?, products?))
+                },
+                |res| match res {
+                    Ok((folio, products)) => Message::PageLoaded(Box::from(folio), products),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            ),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        _now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { .. } = &mut self.state {
+                    return match hotkey {
+                        Hotkey::Tab(modifiers) => {
+                            if modifiers.shift() {
+                                Action::Run(focus_previous())
+                            } else {
+                                Action::Run(focus_next())
+                            }
+                        }
+                    };
+                }
+                Action::None
+            }
+            Message::PageLoaded(folio, products) => {
+                self.state = State::Ready {
+                    folio,
+                    // extras are charged at the inside price, products without it can't be added
+                    products: products
+                        .into_iter()
+                        .filter(|product| product.inside_price.is_some())
+                        .collect(),
+                    selected_payment_method: PaymentMethod::Efectivo,
+                    payments: Vec::new(),
+                    amount_input: String::new(),
+                };
+                Action::None
+            }
+            Message::AddExtra(product_id) => {
+                if let State::Ready {
+                    folio,
+                    products,
+                    payments,
+                    ..
+                } = &mut self.state
+                    && let Some(product) = products.iter().find(|p| p.id == Some(product_id))
+                {
+                    folio.add_extra(product);
+                    // the total has changed, the payments have to be registered again
+                    payments.clear();
+                }
+                Action::None
+            }
+            Message::RemoveExtra(product_id) => {
+                if let State::Ready {
+                    folio, payments, ..
+                } = &mut self.state
+                {
+                    folio.remove_extra(product_id);
+                    payments.clear();
+                }
+                Action::None
+            }
+            Message::UpdateSelectedPaymentMethod(payment_method) => {
+                if let State::Ready {
+                    selected_payment_method,
+                    ..
+                } = &mut self.state
+                    && payment_method != PaymentMethod::Adeudo
+                {
+                    *selected_payment_method = payment_method;
+                }
+                Action::None
+            }
+            Message::PaymentAmountInput(new_value) => {
+                if let State::Ready { amount_input, .. } = &mut self.state
+                    && (new_value.is_empty() || parse_decimal(&new_value).is_ok())
+                {
+                    *amount_input = new_value;
+                }
+                Action::None
+            }
+            Message::AddPayment => {
+                let State::Ready {
+                    folio,
+                    selected_payment_method,
+                    payments,
+                    amount_input,
+                    ..
+                } = &mut self.state
+                else {
+                    return Action::None;
+                };
+
+                let remaining = remaining(folio, payments);
+                if remaining <= Money::ZERO {
+                    return Action::AddToast(Toast::warning_toast(
+                        "Nothing left to pay on this folio",
+                    ));
+                }
+
+                // an empty amount pays whatever is left
+                let amount = if amount_input.is_empty() {
+                    remaining
+                } else {
+                    match amount_input.parse::<Money>() {
+                        Ok(amount) if amount > Money::ZERO && amount <= remaining => amount,
+                        Ok(amount) if amount > remaining => {
+                            return Action::AddToast(Toast::error_toast(
+                                "The amount is greater than what's left to pay",
+                            ));
+                        }
+                        _ => {
+                            return Action::AddToast(Toast::error_toast("Invalid payment amount"));
+                        }
+                    }
+                };
+
+                payments.push(InvoicePayment::new(*selected_payment_method, amount));
+                amount_input.clear();
+                Action::None
+            }
+            Message::RemovePayment(index) => {
+                if let State::Ready { payments, .. } = &mut self.state
+                    && index < payments.len()
+                {
+                    payments.remove(index);
+                }
+                Action::None
+            }
+            Message::CheckOut => {
+                if let State::Ready {
+                    folio, payments, ..
+                } = &self.state
+                {
+                    return Action::Run(Task::perform(
+                        Folio::check_out(database.clone(), *folio.clone(), payments.clone()),
+                        |res| match res {
+                            Ok(_) => Message::Back,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready {
+                folio,
+                products,
+                selected_payment_method,
+                payments,
+                amount_input,
+            } => check_out_view(
+                folio,
+                products,
+                *selected_payment_method,
+                payments,
+                amount_input,
+            ),
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Returns what's left to pay of the folio with the given payments
+fn remaining(folio: &Folio, payments: &[InvoicePayment]) -> Money {
+    folio.total() - payments.iter().map(|p| p.amount).sum::<Money>()
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn check_out_view<'a>(
+    folio: &'a Folio,
+    products: &'a [Product],
+    selected_payment_method: PaymentMethod,
+    payments: &'a [InvoicePayment],
+    amount_input: &'a str,
+) -> iced::Element<'a, Message> {
+    let header = header(folio);
+
+    let content = row![
+        scrollable(folio_lines(folio, products))
+            .height(Length::Fill)
+            .width(Length::Fill),
+        folio_payments(folio, selected_payment_method, payments, amount_input)
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        container(content.width(1100.))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Center)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .height(Length::Fill)
+    .width(Length::Fill)
+    .into()
+}
+
+fn header<'a>(folio: &'a Folio) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let recipient = match &folio.recipient {
+        Some(recipient) => format!("{} | {}", folio.reservation.client_name, recipient.name),
+        None => folio.reservation.client_name.clone(),
+    };
+
+    row![
+        back_button,
+        text(fl!("check-out")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        text(recipient).size(TEXT_SIZE)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// Returns a line (name, quantity, unit price and total) of the final invoice
+fn folio_line<'a>(line: &'a FolioLine) -> Row<'a, Message> {
+    let name = if line.modifiers.is_empty() {
+        line.name.clone()
+    } else {
+        format!("{} ({})", line.name, line.modifiers)
+    };
+
+    Row::new()
+        .push(text(name).size(TEXT_SIZE).width(Length::Fill))
+        .push(text(format!("{} x {:.2} €", line.quantity, line.price)).size(TEXT_SIZE))
+        .push(
+            text(format!("{:.2} €", line.total_price()))
+                .size(TEXT_SIZE)
+                .width(100.)
+                .align_x(Alignment::End),
+        )
+        .align_y(Alignment::Center)
+        .spacing(GLOBAL_SPACING)
+}
+
+/// Returns the room nights, the extras (with the controls to add them) and the adeudos of the folio
+fn folio_lines<'a>(folio: &'a Folio, products: &'a [Product]) -> iced::Element<'a, Message> {
+    // Room nights
+    let mut rooms_column = Column::new()
+        .push(text(format!("{} ({})", fl!("rooms"), fl!("nights"))).size(TITLE_TEXT_SIZE))
+        .spacing(3.);
+    for line in &folio.room_lines {
+        rooms_column = rooms_column.push(folio_line(line));
+    }
+
+    // Extras
+    let extras_selector = pick_list(products, None::<&Product>, |product| {
+        Message::AddExtra(product.id.unwrap_or_default())
+    })
+    .placeholder(fl!("add-extra"))
+    .width(Length::Fill);
+
+    let mut extras_column = Column::new()
+        .push(text(fl!("extras")).size(TITLE_TEXT_SIZE))
+        .push(extras_selector)
+        .spacing(3.);
+    for line in &folio.extras {
+        extras_column = extras_column.push(
+            folio_line(line).push(button("X").on_press(Message::RemoveExtra(line.product_id))),
+        );
+    }
+
+    // Adeudos (already invoiced)
+    let mut adeudos_column = Column::new()
+        .push(text(fl!("adeudos")).size(TITLE_TEXT_SIZE))
+        .spacing(3.);
+    for adeudo in &folio.adeudos {
+        adeudos_column = adeudos_column.push(
+            row![
+                text(adeudo.invoice.invoice_number.as_deref().unwrap_or_default())
+                    .size(TEXT_SIZE)
+                    .width(Length::Fill),
+                text(
+                    adeudo
+                        .invoice
+                        .created_at
+                        .map(|date| date.format("%d-%m-%Y %H:%M").to_string())
+                        .unwrap_or_default()
+                )
+                .size(TEXT_SIZE),
+                // only the part of the invoice that was charged to the room
+                text(format!("{:.2} €", adeudo.amount))
+                    .size(TEXT_SIZE)
+                    .width(100.)
+                    .align_x(Alignment::End)
+            ]
+            .align_y(Alignment::Center)
+            .spacing(GLOBAL_SPACING),
+        );
+    }
+
+    let totals = column![
+        text(format!(
+            "{}: {:.2} €",
+            fl!("final-invoice"),
+            folio.invoice_total()
+        ))
+        .size(TEXT_SIZE),
+        text(format!(
+            "{}: {:.2} €",
+            fl!("adeudos"),
+            folio.adeudos_total()
+        ))
+        .size(TEXT_SIZE),
+        text(format!("{}: {:.2} €", fl!("folio-total"), folio.total())).size(TITLE_TEXT_SIZE)
+    ]
+    .spacing(3.);
+
+    column![rooms_column, extras_column, adeudos_column, totals]
+        .spacing(GLOBAL_SPACING)
+        .into()
+}
+
+/// Returns the registered payments of the folio, the controls to add new ones and the check-out button
+fn folio_payments<'a>(
+    folio: &'a Folio,
+    selected_payment_method: PaymentMethod,
+    payments: &'a [InvoicePayment],
+    amount_input: &'a str,
+) -> iced::Element<'a, Message> {
+    // the check-out can't be charged to the rooms it's settling
+    let payment_methods_buttons = PaymentMethod::ALL
+        .iter()
+        .filter(|p_method| **p_method != PaymentMethod::Adeudo)
+        .fold(
+            Row::new().spacing(GLOBAL_SPACING),
+            |payment_methods_row, p_method| {
+                payment_methods_row.push(
+                    button(text(p_method.to_string()).center())
+                        .on_press(Message::UpdateSelectedPaymentMethod(*p_method))
+                        .style(if *p_method == selected_payment_method {
+                            button::success
+                        } else {
+                            button::secondary
+                        })
+                        .width(Length::Fill)
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                )
+            },
+        );
+
+    let amount_input = text_input(fl!("amount").as_str(), amount_input)
+        .on_input(Message::PaymentAmountInput)
+        .on_submit(Message::AddPayment)
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    let add_payment_button = button(text(fl!("add-payment")).center())
+        .on_press(Message::AddPayment)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let mut registered_payments = Column::new().spacing(GLOBAL_SPACING);
+    for (index, payment) in payments.iter().enumerate() {
+        registered_payments = registered_payments.push(
+            row![
+                text(payment.payment_method).width(Length::Fill),
+                text(format!("{:.2} €", payment.amount)),
+                button(text("x").center())
+                    .on_press(Message::RemovePayment(index))
+                    .style(button::danger)
+            ]
+            .spacing(GLOBAL_SPACING)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let remaining = remaining(folio, payments);
+    let check_out_button = button(text(fl!("check-out")).width(Length::Fill).center())
+        .on_press_maybe((remaining == Money::ZERO).then_some(Message::CheckOut))
+        .style(button::success)
+        .width(Length::Fill)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    column![
+        payment_methods_buttons,
+        row![amount_input, add_payment_button]
+            .spacing(GLOBAL_SPACING)
+            .align_y(Alignment::Center),
+        registered_payments,
+        text(format!("{}: {remaining:.2} €", fl!("remaining"))).size(TEXT_SIZE),
+        Space::new(Length::Shrink, Length::Fill),
+        check_out_button
+    ]
+    .spacing(GLOBAL_SPACING)
+    .width(350.)
+    .into()
+}
@@ -2,20 +2,25 @@
 
 use std::sync::Arc;
 
+use chrono::Local;
 use iced::keyboard::key::Named;
 use iced::keyboard::{self, Key, Modifiers};
 use iced::time::Instant;
 use iced::widget::{
-    Column, Row, Space, Tooltip, button, column, focus_next, focus_previous, row, scrollable, text,
-    text_input, tooltip,
+    Column, Row, Space, button, checkbox, column, focus_next, focus_previous, pick_list, row, text,
+    text_input,
 };
 use iced::{Alignment, Length, Subscription, event};
 use iced::{Task, widget::container};
 use sqlx::{Pool, Postgres};
 
+use crate::alegria::core::models::company::Company;
 use crate::alegria::core::models::reservation::Reservation;
 use crate::alegria::core::models::room::Room;
-use crate::alegria::utils::date::{check_date_format, parse_date_to_naive_datetime};
+use crate::alegria::core::models::sold_room::SoldRoom;
+use crate::alegria::screen::hotel::clients::{self, Clients};
+use crate::alegria::utils::date::parse_date_to_naive_datetime;
+use crate::alegria::utils::money::Money;
 use crate::alegria::utils::styling::{
     GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
 };
@@ -28,7 +33,25 @@ pub struct EditReservation {
 
 enum State {
     Loading,
-    Ready,
+    // We need to preserve the state of the edit screen when we open the client selection
+    Ready {
+        sub_screen: SubScreen,
+        reservation: Box<Reservation>,
+        rooms: Arc<Vec<Room>>,
+        reservations: Vec<Reservation>,
+        companies: Vec<Company>,
+    },
+}
+
+pub enum SubScreen {
+    None,
+    ClientsSelection(Clients),
+}
+
+#[derive(Debug, Clone)]
+pub enum InputFields {
+    EntryDate,
+    DepartureDate,
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +62,37 @@ pub enum Message {
     AddToast(Toast),
     /// Hotkey (Subscription) pressed
     Hotkey(Hotkey),
+
+    /// Callback after initial page load
+    PageLoaded(
+        Box<Reservation>,
+        Arc<Vec<Room>>,
+        Vec<Reservation>,
+        Vec<Company>,
+    ),
+
+    /// Callback when using the form inputs
+    FormInputUpdate(String, InputFields),
+    /// Callback when using the price input of a booked room (by room id)
+    RoomPriceUpdate(i32, String),
+    /// Asks to add a room to the vec of booked rooms of the current reservation
+    AddReservationRoom(i32, Option<Money>),
+    /// Asks to remove a room to the vec of booked rooms of the current reservation
+    RemoveReservationRoom(i32),
+    /// Asks to open the client selector page/component
+    OpenClientSelector,
+    /// Messages of the clients (selector) page
+    Clients(clients::Message),
+    /// Sets (or clears) the company that pays the current reservation
+    SetCompany(Option<Company>),
+    /// Tries to save the changes of the current reservation to the database
+    EditReservation,
+    /// Tries to cancel (soft delete) the current reservation
+    CancelReservation,
+    /// Asks to open the check-in page of the current reservation
+    OpenCheckIn,
+    /// Asks to open the check-out (folio) page of the current reservation
+    OpenCheckOut,
 }
 
 pub enum Action {
@@ -46,11 +100,56 @@ pub enum Action {
     Back,
     Run(Task<Message>),
     AddToast(Toast),
+    /// Asks the parent to open the check-in page of the given reservation
+    CheckIn(i32),
+    /// Asks the parent to open the check-out page of the given reservation
+    CheckOut(i32),
 }
 
 impl EditReservation {
-    pub fn new(database: &Arc<Pool<Postgres>>, reservation_id: i32) -> (Self, Task<Message>) {
-        todo!()
+    pub fn new(
+        database: &Arc<Pool<Postgres>>,
+        rooms: Arc<Vec<Room>>,
+        reservation_id: i32,
+    ) -> (Self, Task<Message>) {
+        let database = database.clone();
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(
+                async move {
+                    let reservation =
+                        Reservation::get_single(database.clone(), reservation_id).await?;
+                    let initial_date = reservation.entry_date.unwrap_or_default().date();
+                    let (reservations, companies) = tokio::join!(
+                        Reservation::get_all(
+                            database.clone(),
+                            initial_date,
+                            initial_date
+                                .checked_add_days(chrono::Days::new(120)) // same range the add page checks
+                                .unwrap_or_default(),
+                        ),
+                        Company::get_all(database.clone())
+                    );
+                    Ok::<_, sqlx::Error>((reservation, reservations?, companies?))
+                },
+                move |res| match res {
+                    Ok((reservation, reservations, companies)) => {
+                        // the reservation being edited doesn't make it's own rooms unavailable
+                        let reservations = reservations
+                            .into_iter()
+                            .filter(|r| r.id != reservation.id)
+                            .collect();
+                        Message::PageLoaded(Box::from(reservation), rooms, reservations, companies)
+                    }
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            ),
+        )
     }
 
     #[allow(clippy::only_used_in_recursion)]
@@ -60,11 +159,247 @@ impl EditReservation {
         database: &Arc<Pool<Postgres>>,
         now: Instant,
     ) -> Action {
-        todo!()
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { .. } = &mut self.state {
+                    return match hotkey {
+                        Hotkey::Tab(modifiers) => {
+                            if modifiers.shift() {
+                                Action::Run(focus_previous())
+                            } else {
+                                Action::Run(focus_next())
+                            }
+                        }
+                    };
+                }
+                Action::None
+            }
+            Message::PageLoaded(reservation, rooms, reservations, companies) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::None,
+                    reservation,
+                    rooms,
+                    reservations,
+                    companies,
+                };
+                Action::None
+            }
+            Message::FormInputUpdate(new_value, field) => {
+                if let State::Ready { reservation, .. } = &mut self.state {
+                    match field {
+                        InputFields::EntryDate => {
+                            reservation.entry_date_string = new_value;
+                        }
+                        InputFields::DepartureDate => {
+                            reservation.departure_date_string = new_value;
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::RoomPriceUpdate(room_id, new_value) => {
+                if let State::Ready { reservation, .. } = &mut self.state
+                    && let Some(sold_room) = reservation
+                        .rooms
+                        .iter_mut()
+                        .find(|sold_room| sold_room.room_id == Some(room_id))
+                {
+                    // We ignore the input if we already have two decimals and we're trying to add more
+                    let ignore_action = new_value.len() > sold_room.price_input.len()
+                        && sold_room
+                            .price_input
+                            .find('.')
+                            .is_some_and(|idx| sold_room.price_input.len() - idx > 2);
+
+                    if !ignore_action {
+                        if let Ok(num) = new_value.parse::<Money>() {
+                            sold_room.price = Some(num);
+                            sold_room.price_input = new_value;
+                        } else if new_value.is_empty() {
+                            sold_room.price = Some(Money::ZERO);
+                            sold_room.price_input = new_value;
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddReservationRoom(room_id, room_price) => {
+                if let State::Ready { reservation, .. } = &mut self.state {
+                    let room_already_exists = reservation
+                        .rooms
+                        .iter()
+                        .any(|sold_room| sold_room.room_id == Some(room_id));
+
+                    if !room_already_exists {
+                        reservation.rooms.push(SoldRoom {
+                            id: None,
+                            room_id: Some(room_id),
+                            guests: Vec::new(),
+                            price: room_price,
+                            invoices: Vec::new(),
+                            room_name: Box::from(""),
+                            price_input: room_price.map(|p| p.to_string()).unwrap_or_default(),
+                        });
+                    }
+                }
+                Action::None
+            }
+            Message::RemoveReservationRoom(room_id) => {
+                if let State::Ready { reservation, .. } = &mut self.state {
+                    // rooms with invoices charged to them can't be removed
+                    reservation
+                        .rooms
+                        .retain(|room| room.room_id != Some(room_id) || !room.invoices.is_empty());
+                }
+                Action::None
+            }
+            Message::Clients(message) => {
+                let State::Ready {
+                    sub_screen,
+                    reservation,
+                    ..
+                } = &mut self.state
+                else {
+                    return Action::None;
+                };
+
+                let SubScreen::ClientsSelection(clients_selector_page) = sub_screen else {
+                    return Action::None;
+                };
+
+                match clients_selector_page.update(message, &database.clone(), now) {
+                    clients::Action::None => Action::None,
+                    clients::Action::Back => {
+                        *sub_screen = SubScreen::None;
+                        Action::None
+                    }
+                    clients::Action::Run(task) => Action::Run(task.map(Message::Clients)),
+                    clients::Action::AddToast(toast) => Action::AddToast(toast),
+                    clients::Action::ClientSelected(client) => {
+                        reservation.client_id = client.id;
+                        reservation.client_name = format!(
+                            "{} {} {} | {}",
+                            client.name,
+                            client.first_surname,
+                            client.second_surname,
+                            client.country
+                        );
+                        *sub_screen = SubScreen::None;
+                        Action::None
+                    }
+                }
+            }
+            Message::SetCompany(company) => {
+                if let State::Ready { reservation, .. } = &mut self.state {
+                    reservation.company_id = company.as_ref().and_then(|c| c.id);
+                    reservation.company_name = company.map(|c| c.name).unwrap_or_default();
+                }
+                Action::None
+            }
+            Message::OpenClientSelector => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (clients, task) = clients::Clients::new(database, clients::PageMode::Select);
+                *sub_screen = SubScreen::ClientsSelection(clients);
+                Action::Run(task.map(Message::Clients))
+            }
+            Message::EditReservation => {
+                if let State::Ready { reservation, .. } = &mut self.state
+                    && reservation.is_valid()
+                {
+                    // since we validated we can unwrap the date, we know it's valid
+                    reservation.entry_date = Some(
+                        parse_date_to_naive_datetime(&reservation.entry_date_string)
+                            .unwrap()
+                            .date()
+                            .and_hms_opt(0, 0, 0)
+                            .unwrap(),
+                    );
+                    reservation.departure_date = Some(
+                        parse_date_to_naive_datetime(&reservation.departure_date_string)
+                            .unwrap()
+                            .date()
+                            .and_hms_opt(0, 0, 0)
+                            .unwrap(),
+                    );
+
+                    if reservation.entry_date.unwrap().date()
+                        >= reservation.departure_date.unwrap().date()
+                    {
+                        return Action::AddToast(Toast::error_toast(
+                            "Entry date must not be greater than departure date",
+                        ));
+                    }
+
+                    return Action::Run(Task::perform(
+                        Reservation::edit(database.clone(), *reservation.clone()),
+                        |res| match res {
+                            Ok(_) => Message::Back,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+
+                Action::None
+            }
+            Message::OpenCheckIn => {
+                if let State::Ready { reservation, .. } = &self.state
+                    && let Some(reservation_id) = reservation.id
+                {
+                    return Action::CheckIn(reservation_id);
+                }
+                Action::None
+            }
+            Message::OpenCheckOut => {
+                if let State::Ready { reservation, .. } = &self.state
+                    && let Some(reservation_id) = reservation.id
+                {
+                    return Action::CheckOut(reservation_id);
+                }
+                Action::None
+            }
+            Message::CancelReservation => {
+                if let State::Ready { reservation, .. } = &self.state
+                    && let Some(reservation_id) = reservation.id
+                {
+                    return Action::Run(Task::perform(
+                        Reservation::delete(database.clone(), reservation_id),
+                        |res| match res {
+                            Ok(_) => Message::Back,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+
+                Action::None
+            }
+        }
     }
 
-    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
-        todo!();
+    pub fn view(&self, now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready {
+                sub_screen,
+                reservation,
+                rooms,
+                reservations,
+                companies,
+            } => match sub_screen {
+                SubScreen::None => edit_form(reservation, rooms, reservations, companies),
+                SubScreen::ClientsSelection(clients) => clients.view(now).map(Message::Clients),
+            },
+        }
     }
 
     pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
@@ -91,3 +426,230 @@ fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> O
         _ => None,
     }
 }
+
+//
+// VIEW COMPOSING
+//
+
+fn edit_form<'a>(
+    reservation: &'a Reservation,
+    rooms: &'a [Room],
+    reservations: &'a [Reservation],
+    companies: &'a [Company],
+) -> iced::Element<'a, Message> {
+    let header = header(reservation);
+    let content = form_content(reservation, rooms, reservations, companies);
+
+    column![
+        header,
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Center)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .height(Length::Fill)
+    .width(Length::Fill)
+    .into()
+}
+
+fn header<'a>(reservation: &'a Reservation) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    // reservations with invoices charged to their rooms, checked in or already checked out can't be cancelled
+    let checked_out = reservation.checked_out_at.is_some();
+    let has_invoices = reservation
+        .rooms
+        .iter()
+        .any(|sold_room| !sold_room.invoices.is_empty());
+    let cancel_button = button(text(fl!("cancel-reservation")).center())
+        .style(button::danger)
+        .on_press_maybe(
+            (!has_invoices && !reservation.occupied && !checked_out)
+                .then_some(Message::CancelReservation),
+        )
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let check_in_button = button(text(fl!("check-in")).center())
+        .style(button::success)
+        .on_press_maybe(
+            reservation
+                .can_check_in_on(Local::now().date_naive())
+                .then_some(Message::OpenCheckIn),
+        )
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let check_out_button = button(text(fl!("check-out")).center())
+        .style(button::success)
+        .on_press_maybe((reservation.occupied && !checked_out).then_some(Message::OpenCheckOut))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("edit-reservation")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        check_in_button,
+        check_out_button,
+        cancel_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+fn form_content<'a>(
+    edit_reservation: &'a Reservation,
+    rooms: &'a [Room],
+    reservations: &'a [Reservation],
+    companies: &'a [Company],
+) -> iced::Element<'a, Message> {
+    let entry_date_label = text(format!("{} (yyyy-mm-dd)", fl!("entry-date"))).width(Length::Fill);
+    let entry_date_input = text_input(
+        fl!("entry-date").as_str(),
+        &edit_reservation.entry_date_string,
+    )
+    .on_input(|c| Message::FormInputUpdate(c, InputFields::EntryDate))
+    .size(TEXT_SIZE)
+    .width(Length::Fill);
+
+    let departure_date_label =
+        text(format!("{} (yyyy-mm-dd)", fl!("departure-date"))).width(Length::Fill);
+    let departure_date_input = text_input(
+        fl!("departure-date").as_str(),
+        &edit_reservation.departure_date_string,
+    )
+    .on_input(|c| Message::FormInputUpdate(c, InputFields::DepartureDate))
+    .size(TEXT_SIZE)
+    .width(Length::Fill);
+
+    // read-only, only the check-in occupies a reservation
+    let occupied = checkbox(fl!("occupied"), edit_reservation.occupied).text_size(TEXT_SIZE);
+
+    // Rooms Selector
+    let available_rooms = rooms
+        .iter()
+        .filter(|room| {
+            !reservations.iter().any(|reservation| {
+                reservation.rooms.iter().any(|r| r.room_id == room.id)
+                    && reservation.entry_date.unwrap() < edit_reservation.departure_date.unwrap()
+                    && reservation.departure_date.unwrap() > edit_reservation.entry_date.unwrap()
+            })
+        })
+        .cloned()
+        .collect::<Vec<Room>>();
+    let rooms_label = text(fl!("rooms")).width(Length::Fill);
+    let selected_room = available_rooms.first().cloned();
+    let rooms_selector = pick_list(available_rooms, selected_room, |r| {
+        Message::AddReservationRoom(r.id.unwrap(), r.default_room_price)
+    })
+    .width(Length::Fill);
+
+    // Already Selected Rooms (with their prices)
+    let mut reservation_rooms_column = Column::new()
+        .push(text(fl!("rooms")))
+        .width(Length::Fill)
+        .spacing(GLOBAL_SPACING);
+    for sold_room in &edit_reservation.rooms {
+        let room = rooms.iter().find(|r| r.id == sold_room.room_id);
+        if let Some(room) = room {
+            let room_id = room.id.unwrap_or_default();
+            reservation_rooms_column = reservation_rooms_column.push(
+                Row::new()
+                    .push(text(&room.name).width(Length::Fill))
+                    .push(
+                        text_input(fl!("price").as_str(), &sold_room.price_input)
+                            .on_input(move |c| Message::RoomPriceUpdate(room_id, c))
+                            .size(TEXT_SIZE)
+                            .width(100.),
+                    )
+                    .push(
+                        button("X").on_press_maybe(
+                            sold_room
+                                .invoices
+                                .is_empty()
+                                .then_some(Message::RemoveReservationRoom(room_id)),
+                        ),
+                    )
+                    .align_y(Alignment::Center)
+                    .spacing(GLOBAL_SPACING)
+                    .width(Length::Fill),
+            )
+        }
+    }
+
+    // Client Selection
+    let client_text = if edit_reservation.client_name.is_empty() {
+        fl!("no-client-selected")
+    } else {
+        edit_reservation.client_name.clone()
+    };
+    let client_row = row![
+        text(client_text).width(Length::Fill),
+        button(text(fl!("select")).center()).on_press(Message::OpenClientSelector)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING);
+
+    // Company Selection
+    let selected_company = companies
+        .iter()
+        .find(|c| edit_reservation.company_id.is_some() && c.id == edit_reservation.company_id);
+    let company_row = row![
+        pick_list(companies, selected_company, |c| Message::SetCompany(Some(
+            c
+        )))
+        .width(Length::Fill),
+        button(text("X").center()).on_press_maybe(
+            edit_reservation
+                .company_id
+                .map(|_| Message::SetCompany(None))
+        )
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING);
+
+    // Submit
+    let submit_button = button(text(fl!("save")).width(Length::Fill).center())
+        .on_press_maybe(
+            (edit_reservation.is_valid() && edit_reservation.checked_out_at.is_none())
+                .then_some(Message::EditReservation),
+        )
+        .width(Length::Fill);
+
+    let entry_date_input_column = column![entry_date_label, entry_date_input]
+        .width(850.)
+        .spacing(1.);
+    let departure_date_input_column = column![departure_date_label, departure_date_input]
+        .width(850.)
+        .spacing(1.);
+    let rooms_input_column = row![
+        column![rooms_label, rooms_selector].width(425.).spacing(1.),
+        reservation_rooms_column.width(425.)
+    ]
+    .width(850.)
+    .spacing(GLOBAL_SPACING);
+    let client_selection_column = column![text(fl!("main-client")).width(Length::Fill), client_row]
+        .width(850.)
+        .spacing(1.);
+    let company_selection_column = column![
+        text(fl!("paid-by-company")).width(Length::Fill),
+        company_row
+    ]
+    .width(850.)
+    .spacing(1.);
+
+    Column::new()
+        .push(client_selection_column)
+        .push(company_selection_column)
+        .push(entry_date_input_column)
+        .push(departure_date_input_column)
+        .push(occupied)
+        .push(rooms_input_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING)
+        .into()
+}
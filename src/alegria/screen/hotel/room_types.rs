@@ -13,6 +13,7 @@ use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
 use iced::{Task, widget::container};
 use sqlx::{Pool, Postgres};
 
+use crate::alegria::utils::money::Money;
 use crate::alegria::utils::styling::{
     GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
 };
@@ -224,11 +225,11 @@ impl RoomTypes {
                                         .is_some_and(|idx| room_type.price_input.len() - idx > 2);
 
                                 if !ignore_action {
-                                    if let Ok(num) = new_value.parse::<f32>() {
+                                    if let Ok(num) = new_value.parse::<Money>() {
                                         room_type.price = Some(num);
                                         room_type.price_input = new_value;
                                     } else if new_value.is_empty() {
-                                        room_type.price = Some(0.0);
+                                        room_type.price = Some(Money::ZERO);
                                         room_type.price_input = new_value;
                                     }
                                 }
@@ -398,7 +399,7 @@ fn list_screen<'a>(
                         .align_y(Alignment::Center),
                 )
                 .push(
-                    text(format!("{:.2} €", room_type.price.unwrap_or(0.)))
+                    text(format!("{:.2} €", room_type.price.unwrap_or_default()))
                         .size(TEXT_SIZE)
                         .width(300.)
                         .align_x(Alignment::Start)
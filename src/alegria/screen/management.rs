@@ -11,9 +11,21 @@ use crate::alegria::utils::styling::*;
 use crate::alegria::widgets::toast::Toast;
 use crate::fl;
 
+mod bar_tables;
+mod cash_register;
+mod floor_plan;
+mod ingredients;
+mod invoice_series;
+mod modifier_groups;
+mod preparation_stations;
+mod pricing_rules;
 mod product_categories;
 mod products;
+mod recipes;
 mod simple_invoices;
+mod stock;
+mod table_locations;
+mod voided_lines;
 
 pub struct Management {
     state: State,
@@ -32,6 +44,18 @@ pub enum SubScreen {
     Products(products::Products),
     ProductCategories(product_categories::ProductCategories),
     SimpleInvoices(simple_invoices::SimpleInvoices),
+    CashRegister(cash_register::CashRegister),
+    TableLocations(table_locations::TableLocations),
+    BarTables(bar_tables::BarTables),
+    FloorPlan(floor_plan::FloorPlan),
+    PreparationStations(preparation_stations::PreparationStations),
+    ModifierGroups(modifier_groups::ModifierGroups),
+    PricingRules(pricing_rules::PricingRules),
+    Stock(stock::Stock),
+    Ingredients(ingredients::Ingredients),
+    Recipes(recipes::Recipes),
+    VoidedLines(voided_lines::VoidedLines),
+    InvoiceSeries(invoice_series::InvoiceSeriesScreen),
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +67,30 @@ pub enum Message {
     OpenProductCategories,
     SimpleInvoices(simple_invoices::Message),
     OpenSimpleInvoices,
+    CashRegister(cash_register::Message),
+    OpenCashRegister,
+    TableLocations(table_locations::Message),
+    OpenTableLocations,
+    BarTables(bar_tables::Message),
+    OpenBarTables,
+    FloorPlan(floor_plan::Message),
+    OpenFloorPlan,
+    PreparationStations(preparation_stations::Message),
+    OpenPreparationStations,
+    ModifierGroups(modifier_groups::Message),
+    OpenModifierGroups,
+    PricingRules(pricing_rules::Message),
+    OpenPricingRules,
+    Stock(stock::Message),
+    OpenStock,
+    Ingredients(ingredients::Message),
+    OpenIngredients,
+    Recipes(recipes::Message),
+    OpenRecipes,
+    VoidedLines(voided_lines::Message),
+    OpenVoidedLines,
+    InvoiceSeries(invoice_series::Message),
+    OpenInvoiceSeries,
 }
 
 pub enum Action {
@@ -162,6 +210,355 @@ impl Management {
                 *sub_screen = SubScreen::SimpleInvoices(simple_invoices);
                 Action::Run(task.map(Message::SimpleInvoices))
             }
+            Message::CashRegister(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::CashRegister(cash_register) = sub_screen else {
+                    return Action::None;
+                };
+
+                match cash_register.update(message, database, now) {
+                    cash_register::Action::None => Action::None,
+                    cash_register::Action::Run(task) => {
+                        Action::Run(task.map(Message::CashRegister))
+                    }
+                    cash_register::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    cash_register::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenCashRegister => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (cash_register, task) = cash_register::CashRegister::new(database);
+                *sub_screen = SubScreen::CashRegister(cash_register);
+                Action::Run(task.map(Message::CashRegister))
+            }
+            Message::TableLocations(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::TableLocations(table_locations) = sub_screen else {
+                    return Action::None;
+                };
+
+                match table_locations.update(message, database, now) {
+                    table_locations::Action::None => Action::None,
+                    table_locations::Action::Run(task) => {
+                        Action::Run(task.map(Message::TableLocations))
+                    }
+                    table_locations::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    table_locations::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenTableLocations => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (table_locations, task) = table_locations::TableLocations::new(database);
+                *sub_screen = SubScreen::TableLocations(table_locations);
+                Action::Run(task.map(Message::TableLocations))
+            }
+            Message::BarTables(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::BarTables(bar_tables) = sub_screen else {
+                    return Action::None;
+                };
+
+                match bar_tables.update(message, database, now) {
+                    bar_tables::Action::None => Action::None,
+                    bar_tables::Action::Run(task) => Action::Run(task.map(Message::BarTables)),
+                    bar_tables::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    bar_tables::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenBarTables => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (bar_tables, task) = bar_tables::BarTables::new(database);
+                *sub_screen = SubScreen::BarTables(bar_tables);
+                Action::Run(task.map(Message::BarTables))
+            }
+            Message::FloorPlan(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::FloorPlan(floor_plan) = sub_screen else {
+                    return Action::None;
+                };
+
+                match floor_plan.update(message, database, now) {
+                    floor_plan::Action::None => Action::None,
+                    floor_plan::Action::Run(task) => Action::Run(task.map(Message::FloorPlan)),
+                    floor_plan::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    floor_plan::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenFloorPlan => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (floor_plan, task) = floor_plan::FloorPlan::new(database);
+                *sub_screen = SubScreen::FloorPlan(floor_plan);
+                Action::Run(task.map(Message::FloorPlan))
+            }
+            Message::PreparationStations(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::PreparationStations(preparation_stations) = sub_screen else {
+                    return Action::None;
+                };
+
+                match preparation_stations.update(message, database, now) {
+                    preparation_stations::Action::None => Action::None,
+                    preparation_stations::Action::Run(task) => {
+                        Action::Run(task.map(Message::PreparationStations))
+                    }
+                    preparation_stations::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    preparation_stations::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenPreparationStations => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (preparation_stations, task) =
+                    preparation_stations::PreparationStations::new(database);
+                *sub_screen = SubScreen::PreparationStations(preparation_stations);
+                Action::Run(task.map(Message::PreparationStations))
+            }
+            Message::ModifierGroups(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::ModifierGroups(modifier_groups) = sub_screen else {
+                    return Action::None;
+                };
+
+                match modifier_groups.update(message, database, now) {
+                    modifier_groups::Action::None => Action::None,
+                    modifier_groups::Action::Run(task) => {
+                        Action::Run(task.map(Message::ModifierGroups))
+                    }
+                    modifier_groups::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    modifier_groups::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenModifierGroups => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (modifier_groups, task) = modifier_groups::ModifierGroups::new(database);
+                *sub_screen = SubScreen::ModifierGroups(modifier_groups);
+                Action::Run(task.map(Message::ModifierGroups))
+            }
+            Message::PricingRules(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::PricingRules(pricing_rules) = sub_screen else {
+                    return Action::None;
+                };
+
+                match pricing_rules.update(message, database, now) {
+                    pricing_rules::Action::None => Action::None,
+                    pricing_rules::Action::Run(task) => {
+                        Action::Run(task.map(Message::PricingRules))
+                    }
+                    pricing_rules::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    pricing_rules::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenPricingRules => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (pricing_rules, task) = pricing_rules::PricingRules::new(database);
+                *sub_screen = SubScreen::PricingRules(pricing_rules);
+                Action::Run(task.map(Message::PricingRules))
+            }
+            Message::Stock(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::Stock(stock) = sub_screen else {
+                    return Action::None;
+                };
+
+                match stock.update(message, database, now) {
+                    stock::Action::None => Action::None,
+                    stock::Action::Run(task) => Action::Run(task.map(Message::Stock)),
+                    stock::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    stock::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenStock => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (stock, task) = stock::Stock::new(database);
+                *sub_screen = SubScreen::Stock(stock);
+                Action::Run(task.map(Message::Stock))
+            }
+            Message::Ingredients(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::Ingredients(ingredients) = sub_screen else {
+                    return Action::None;
+                };
+
+                match ingredients.update(message, database, now) {
+                    ingredients::Action::None => Action::None,
+                    ingredients::Action::Run(task) => Action::Run(task.map(Message::Ingredients)),
+                    ingredients::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    ingredients::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenIngredients => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (ingredients, task) = ingredients::Ingredients::new(database);
+                *sub_screen = SubScreen::Ingredients(ingredients);
+                Action::Run(task.map(Message::Ingredients))
+            }
+            Message::Recipes(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::Recipes(recipes) = sub_screen else {
+                    return Action::None;
+                };
+
+                match recipes.update(message, database, now) {
+                    recipes::Action::None => Action::None,
+                    recipes::Action::Run(task) => Action::Run(task.map(Message::Recipes)),
+                    recipes::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    recipes::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenRecipes => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (recipes, task) = recipes::Recipes::new(database);
+                *sub_screen = SubScreen::Recipes(recipes);
+                Action::Run(task.map(Message::Recipes))
+            }
+            Message::VoidedLines(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::VoidedLines(voided_lines) = sub_screen else {
+                    return Action::None;
+                };
+
+                match voided_lines.update(message, database, now) {
+                    voided_lines::Action::None => Action::None,
+                    voided_lines::Action::Run(task) => Action::Run(task.map(Message::VoidedLines)),
+                    voided_lines::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    voided_lines::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenVoidedLines => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (voided_lines, task) = voided_lines::VoidedLines::new(database);
+                *sub_screen = SubScreen::VoidedLines(voided_lines);
+                Action::Run(task.map(Message::VoidedLines))
+            }
+            Message::InvoiceSeries(message) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let SubScreen::InvoiceSeries(invoice_series) = sub_screen else {
+                    return Action::None;
+                };
+
+                match invoice_series.update(message, database, now) {
+                    invoice_series::Action::None => Action::None,
+                    invoice_series::Action::Run(task) => {
+                        Action::Run(task.map(Message::InvoiceSeries))
+                    }
+                    invoice_series::Action::Back => {
+                        *sub_screen = SubScreen::Home;
+                        Action::None
+                    }
+                    invoice_series::Action::AddToast(toast) => Action::AddToast(toast),
+                }
+            }
+            Message::OpenInvoiceSeries => {
+                let State::Ready { sub_screen, .. } = &mut self.state else {
+                    return Action::None;
+                };
+
+                let (invoice_series, task) = invoice_series::InvoiceSeriesScreen::new(database);
+                *sub_screen = SubScreen::InvoiceSeries(invoice_series);
+                Action::Run(task.map(Message::InvoiceSeries))
+            }
         }
     }
 
@@ -191,6 +588,34 @@ impl Management {
                 SubScreen::SimpleInvoices(simple_invoices) => {
                     simple_invoices.view(now).map(Message::SimpleInvoices)
                 }
+                SubScreen::CashRegister(cash_register) => {
+                    cash_register.view(now).map(Message::CashRegister)
+                }
+                SubScreen::TableLocations(table_locations) => {
+                    table_locations.view(now).map(Message::TableLocations)
+                }
+                SubScreen::BarTables(bar_tables) => bar_tables.view(now).map(Message::BarTables),
+                SubScreen::FloorPlan(floor_plan) => floor_plan.view(now).map(Message::FloorPlan),
+                SubScreen::PreparationStations(preparation_stations) => preparation_stations
+                    .view(now)
+                    .map(Message::PreparationStations),
+                SubScreen::ModifierGroups(modifier_groups) => {
+                    modifier_groups.view(now).map(Message::ModifierGroups)
+                }
+                SubScreen::PricingRules(pricing_rules) => {
+                    pricing_rules.view(now).map(Message::PricingRules)
+                }
+                SubScreen::Stock(stock) => stock.view(now).map(Message::Stock),
+                SubScreen::Ingredients(ingredients) => {
+                    ingredients.view(now).map(Message::Ingredients)
+                }
+                SubScreen::Recipes(recipes) => recipes.view(now).map(Message::Recipes),
+                SubScreen::VoidedLines(voided_lines) => {
+                    voided_lines.view(now).map(Message::VoidedLines)
+                }
+                SubScreen::InvoiceSeries(invoice_series) => {
+                    invoice_series.view(now).map(Message::InvoiceSeries)
+                }
             },
         }
     }
@@ -209,6 +634,38 @@ impl Management {
             SubScreen::SimpleInvoices(simple_invoices) => simple_invoices
                 .subscription(now)
                 .map(Message::SimpleInvoices),
+            SubScreen::CashRegister(cash_register) => {
+                cash_register.subscription(now).map(Message::CashRegister)
+            }
+            SubScreen::TableLocations(table_locations) => table_locations
+                .subscription(now)
+                .map(Message::TableLocations),
+            SubScreen::BarTables(bar_tables) => {
+                bar_tables.subscription(now).map(Message::BarTables)
+            }
+            SubScreen::FloorPlan(floor_plan) => {
+                floor_plan.subscription(now).map(Message::FloorPlan)
+            }
+            SubScreen::PreparationStations(preparation_stations) => preparation_stations
+                .subscription(now)
+                .map(Message::PreparationStations),
+            SubScreen::ModifierGroups(modifier_groups) => modifier_groups
+                .subscription(now)
+                .map(Message::ModifierGroups),
+            SubScreen::PricingRules(pricing_rules) => {
+                pricing_rules.subscription(now).map(Message::PricingRules)
+            }
+            SubScreen::Stock(stock) => stock.subscription(now).map(Message::Stock),
+            SubScreen::Ingredients(ingredients) => {
+                ingredients.subscription(now).map(Message::Ingredients)
+            }
+            SubScreen::Recipes(recipes) => recipes.subscription(now).map(Message::Recipes),
+            SubScreen::VoidedLines(voided_lines) => {
+                voided_lines.subscription(now).map(Message::VoidedLines)
+            }
+            SubScreen::InvoiceSeries(invoice_series) => {
+                invoice_series.subscription(now).map(Message::InvoiceSeries)
+            }
         }
     }
 }
@@ -268,6 +725,126 @@ fn home<'a>() -> iced::Element<'a, Message> {
             .width(SQUAREBUTTONXY)
             .height(SQUAREBUTTONXY),
         )
+        .push(
+            button(
+                text(fl!("cash-register"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenCashRegister)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("table-locations"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenTableLocations)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("bar-tables"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenBarTables)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("floor-plan"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenFloorPlan)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("preparation-stations"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenPreparationStations)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("modifier-groups"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenModifierGroups)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("pricing-rules"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenPricingRules)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("stock"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenStock)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("ingredients"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenIngredients)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("recipes"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenRecipes)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("voided-lines"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenVoidedLines)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
+        .push(
+            button(
+                text(fl!("invoice-series"))
+                    .align_x(Alignment::Center)
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::OpenInvoiceSeries)
+            .width(SQUAREBUTTONXY)
+            .height(SQUAREBUTTONXY),
+        )
         .spacing(5.);
 
     container(buttons_row).center(Length::Fill).into()
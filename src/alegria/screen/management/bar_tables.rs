@@ -0,0 +1,676 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::bar_table::BarTable;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{
+    alegria::{
+        core::models::table_location::TableLocation,
+        utils::pagination::{PaginationAction, PaginationConfig},
+        widgets::toast::Toast,
+    },
+    fl,
+};
+
+pub struct BarTables {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    List {
+        pagination_state: PaginationConfig,
+        bar_tables: Vec<BarTable>,
+    },
+    Upsert {
+        bar_table: Box<BarTable>,
+        table_locations: Vec<TableLocation>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum BarTableTextInputFields {
+    Name,
+    Capacity,
+    SortOrder,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to update the current list of bar_tables
+    FetchBarTables,
+    /// Callback after initial page loading, set's the bar_tables list on the state
+    PageLoaded(Vec<BarTable>),
+
+    /// Try to go left or right a page
+    PaginationAction(PaginationAction),
+
+    /// Callback after asking to edit a bar_table, searches the bar_table on the db
+    AskEditBarTable(i32),
+    /// Changes the upsert screen, with a default BarTable and grabs the table_locations (intended for calling when we need to create a new bar_table)
+    AskOpenUpsertScreen,
+    /// Changes the upsert screen with the given bar_table (we also need to get the table locations for the selector)
+    OpenUpsertScreen(Box<BarTable>, Vec<TableLocation>),
+
+    /// Callback when using the text inputs to add or edit a bar_table
+    TextInputUpdate(String, BarTableTextInputFields),
+    /// Callback after selecting a new TableLocationId for the current bar_table
+    UpdatedSelectedTableLocationId(i32),
+
+    /// Tries to Add or Edit the current bar_table to the database
+    UpsertCurrentBarTable,
+    /// Callback after upserting the bar_table on the database
+    UpsertedCurrentBarTable,
+    /// Tries to delete the current bar_table
+    DeleteCurrentBarTable,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl BarTables {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(BarTable::get_all(database.clone()), |res| match res {
+                Ok(res) => Message::PageLoaded(res),
+                Err(err) => {
+                    eprintln!("{err}");
+                    Message::AddToast(Toast::error_toast(err))
+                }
+            }),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match sub_screen {
+                        SubScreen::List { .. } => return Action::Back,
+                        SubScreen::Upsert { .. } => {
+                            return self.update(Message::FetchBarTables, &database.clone(), now);
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { .. } = sub_screen {
+                        return match hotkey {
+                            Hotkey::Tab(modifiers) => {
+                                if modifiers.shift() {
+                                    Action::Run(focus_previous())
+                                } else {
+                                    Action::Run(focus_next())
+                                }
+                            }
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::FetchBarTables => Action::Run(Task::perform(
+                BarTable::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::PageLoaded(res) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::List {
+                        pagination_state: PaginationConfig::default(),
+                        bar_tables: res,
+                    },
+                };
+                Action::None
+            }
+            Message::PaginationAction(pagination_action) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List {
+                        bar_tables,
+                        pagination_state,
+                        ..
+                    } = sub_screen
+                    {
+                        match pagination_action {
+                            PaginationAction::Up => {}
+                            PaginationAction::Down => {}
+                            PaginationAction::Back => {
+                                if pagination_state.current_page > 0 {
+                                    pagination_state.current_page -= 1;
+                                }
+                            }
+                            PaginationAction::Forward => {
+                                let next_page_start = (pagination_state.current_page + 1)
+                                    * pagination_state.items_per_page;
+                                if next_page_start < bar_tables.len().try_into().unwrap_or_default()
+                                {
+                                    pagination_state.current_page += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AskEditBarTable(bar_table_id) => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        let (bar_table, table_locations) = tokio::join!(
+                            BarTable::get_single(database.clone(), bar_table_id),
+                            TableLocation::get_all(database.clone())
+                        );
+                        (bar_table, table_locations)
+                    },
+                    |(bar_table, table_locations)| match (bar_table, table_locations) {
+                        (Ok(bar_table), Ok(table_locations)) => {
+                            Message::OpenUpsertScreen(Box::from(bar_table), table_locations)
+                        }
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching table or table locations",
+                        )),
+                    },
+                ))
+            }
+            Message::AskOpenUpsertScreen => Action::Run(Task::perform(
+                TableLocation::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::OpenUpsertScreen(Box::from(BarTable::default()), res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::OpenUpsertScreen(bar_table, table_locations) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Upsert {
+                        bar_table,
+                        table_locations,
+                    },
+                };
+
+                // Set a default selection on the table location
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        bar_table,
+                        table_locations,
+                    } = sub_screen
+                    {
+                        #[warn(clippy::collapsible_if)]
+                        if !table_locations.is_empty() && bar_table.location_id.is_none() {
+                            bar_table.location_id = table_locations.first().unwrap().id;
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { bar_table, .. } = sub_screen {
+                        match field {
+                            BarTableTextInputFields::Name => bar_table.name = new_value,
+                            BarTableTextInputFields::Capacity => {
+                                if let Ok(num) = new_value.parse::<i32>() {
+                                    bar_table.capacity = Some(num);
+                                    bar_table.capacity_input = new_value;
+                                } else if new_value.is_empty() {
+                                    bar_table.capacity = None;
+                                    bar_table.capacity_input = new_value;
+                                }
+                            }
+                            BarTableTextInputFields::SortOrder => {
+                                if let Ok(num) = new_value.parse::<i32>() {
+                                    bar_table.sort_order = Some(num);
+                                    bar_table.sort_order_input = new_value;
+                                } else if new_value.is_empty() {
+                                    bar_table.sort_order = None;
+                                    bar_table.sort_order_input = new_value;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedTableLocationId(table_location_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { bar_table, .. } = sub_screen {
+                        bar_table.location_id = Some(table_location_id)
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertCurrentBarTable => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { bar_table, .. } = sub_screen {
+                        #[allow(clippy::collapsible_if)]
+                        if bar_table.is_valid() {
+                            return match bar_table.id {
+                                Some(_id) => Action::Run(Task::perform(
+                                    BarTable::edit(database.clone(), *bar_table.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentBarTable,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                                None => Action::Run(Task::perform(
+                                    BarTable::add(database.clone(), *bar_table.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentBarTable,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                            };
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertedCurrentBarTable => {
+                self.update(Message::FetchBarTables, &database.clone(), now)
+            }
+            Message::DeleteCurrentBarTable => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { bar_table, .. } = sub_screen {
+                        return Action::Run(Task::perform(
+                            BarTable::delete(database.clone(), bar_table.id.unwrap_or_default()),
+                            |res| match res {
+                                Ok(_) => Message::FetchBarTables,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => match sub_screen {
+                SubScreen::List {
+                    pagination_state,
+                    bar_tables,
+                } => list_screen(pagination_state, bar_tables),
+                SubScreen::Upsert {
+                    bar_table,
+                    table_locations,
+                } => upsert_screen(bar_table, table_locations),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+// LIST SCREEN
+
+fn list_screen<'a>(
+    pagination_state: &'a PaginationConfig,
+    bar_tables: &'a [BarTable],
+) -> iced::Element<'a, Message> {
+    let header = list_header();
+    let grid: Element<'a, Message, Theme, Renderer> = if bar_tables.is_empty() {
+        container(text(fl!("no-bar-tables")).size(TITLE_TEXT_SIZE))
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(50.)
+            .into()
+    } else {
+        let title_row = Row::new()
+            .push(
+                text(fl!("name"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                text(fl!("table-location"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("capacity"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("edit"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::End),
+            )
+            .width(Length::Shrink)
+            .align_y(Alignment::Center);
+
+        // Calculate the indices for the current page
+        let start_index: usize =
+            pagination_state.current_page as usize * pagination_state.items_per_page as usize;
+        let end_index = usize::min(
+            start_index + pagination_state.items_per_page as usize,
+            bar_tables.len(),
+        );
+
+        let mut grid = Column::new()
+            .push(title_row)
+            .spacing(GLOBAL_SPACING)
+            .width(Length::Shrink);
+
+        for bar_table in &bar_tables[start_index..end_index] {
+            let row = Row::new()
+                .push(
+                    text(&bar_table.name)
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(&*bar_table.location_name)
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(bar_table.capacity.unwrap_or_default().to_string())
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    row![
+                        Space::new(Length::Fill, Length::Shrink),
+                        button(text(fl!("edit")).size(TEXT_SIZE).align_y(Alignment::Center))
+                            .on_press(Message::AskEditBarTable(bar_table.id.unwrap()))
+                            .width(Length::Shrink)
+                    ]
+                    .width(200.),
+                )
+                .align_y(Alignment::Center);
+
+            // Limit Rule size to sum of all column widths
+            grid = grid.push(row![Rule::horizontal(1.)].width(800.));
+            grid = grid.push(row);
+        }
+
+        scrollable(grid).spacing(GLOBAL_SPACING).into()
+    };
+
+    let page_controls = Column::new()
+        .push(row![Rule::horizontal(1.)].width(800.))
+        .push(
+            text(format!(
+                "{} {}",
+                fl!("page").as_str(),
+                &pagination_state.current_page + 1
+            ))
+            .align_x(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .width(800.)
+                .push(
+                    button(
+                        text(fl!("back"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Back)),
+                )
+                .push(
+                    button(
+                        text(fl!("next"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Forward)),
+                )
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_x(Alignment::Center);
+
+    let content = container(
+        column![grid, page_controls]
+            .spacing(GLOBAL_SPACING)
+            .width(800.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
+fn list_header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let add_button = button(text(fl!("add")).center())
+        .on_press(Message::AskOpenUpsertScreen)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("bar-tables")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        add_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+// UPSERT SCREEN
+
+fn upsert_screen<'a>(
+    bar_table: &'a BarTable,
+    table_locations: &'a [TableLocation],
+) -> iced::Element<'a, Message> {
+    let header = upsert_header(bar_table);
+
+    // Name
+    let name_label = text(format!("{}*", fl!("name"))).width(Length::Fill);
+    let name_input = text_input(fl!("name").as_str(), &bar_table.name)
+        .on_input(|c| Message::TextInputUpdate(c, BarTableTextInputFields::Name))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Table Location
+    let table_location_label = text(fl!("table-location")).width(Length::Fill);
+    let selected = table_locations
+        .iter()
+        .find(|rt| rt.id == bar_table.location_id);
+    let table_location_selector = pick_list(table_locations, selected, |table_location| {
+        Message::UpdatedSelectedTableLocationId(table_location.id.unwrap_or_default())
+    })
+    .width(Length::Fill);
+
+    // Capacity
+    let capacity_label = text(format!("{}*", fl!("capacity"))).width(Length::Fill);
+    let capacity_input = text_input(fl!("capacity").as_str(), &bar_table.capacity_input)
+        .on_input(|c| Message::TextInputUpdate(c, BarTableTextInputFields::Capacity))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Sort Order
+    let sort_order_label = text(format!("{}*", fl!("sort-order"))).width(Length::Fill);
+    let sort_order_input = text_input(fl!("sort-order").as_str(), &bar_table.sort_order_input)
+        .on_input(|c| Message::TextInputUpdate(c, BarTableTextInputFields::SortOrder))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Submit
+    let submit_button_text = if bar_table.id.is_some() {
+        text(fl!("edit"))
+    } else {
+        text(fl!("add"))
+    };
+    let submit_button = button(submit_button_text.center().size(TEXT_SIZE))
+        .on_press_maybe(
+            bar_table
+                .is_valid()
+                .then_some(Message::UpsertCurrentBarTable),
+        )
+        .width(Length::Fill);
+
+    // Input Columns
+    let name_input_column = column![name_label, name_input].width(850.).spacing(1.);
+    let table_location_column = column![table_location_label, table_location_selector]
+        .width(850.)
+        .spacing(1.);
+    let capacity_input_column = column![capacity_label, capacity_input]
+        .width(850.)
+        .spacing(1.);
+    let sort_order_input_column = column![sort_order_label, sort_order_input]
+        .width(850.)
+        .spacing(1.);
+
+    let form_column = Column::new()
+        .push(name_input_column)
+        .push(table_location_column)
+        .push(capacity_input_column)
+        .push(sort_order_input_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        container(form_column)
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .padding(50.)
+    ]
+    .into()
+}
+
+fn upsert_header<'a>(bar_table: &'a BarTable) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let delete_button = button(text(fl!("delete")).center())
+        .style(button::danger)
+        .on_press_maybe(bar_table.id.map(|_| Message::DeleteCurrentBarTable))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("bar-tables")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        delete_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
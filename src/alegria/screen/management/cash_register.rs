@@ -0,0 +1,678 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Length, Subscription, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::cash_session::{CashMovement, CashSession, ZReport};
+use crate::alegria::core::print::AlegriaPrinter;
+use crate::alegria::utils::money::Money;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+use crate::{alegria::widgets::toast::Toast, fl};
+
+pub struct CashRegister {
+    printers: Printers,
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    /// There is no open session, a new one can be opened (and the Z report of the last one printed)
+    Closed {
+        new_session: CashSession,
+        z_report: Option<Box<ZReport>>,
+    },
+    /// There is an open session, movements can be added and it can be closed
+    Open {
+        session: Box<CashSession>,
+        new_movement: CashMovement,
+    },
+}
+
+/// Printers available to print the Z report
+#[derive(Default)]
+struct Printers {
+    selected_printer: Option<AlegriaPrinter>,
+    all_printers: Vec<AlegriaPrinter>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to retrieve the currently open session
+    FetchSession,
+    /// Callback after retrieving the currently open session (if any)
+    SessionLoaded(Option<Box<CashSession>>),
+    /// Sets the printers on the state
+    SetPrinters(Option<AlegriaPrinter>, Vec<AlegriaPrinter>),
+    /// Updates the selected printer
+    UpdateSelectedPrinter(AlegriaPrinter),
+
+    /// Updates a text input of the screen
+    TextInputUpdate(String, CashRegisterTextInputFields),
+    /// Tries to open a new session with the given opening float
+    OpenSession,
+    /// Tries to add a cash movement to the current session
+    AddMovement(CashMovementKind),
+    /// Tries to close the current session with the counted amount
+    CloseSession,
+    /// Callback after closing the session, shows its Z report
+    SessionClosed(Box<ZReport>),
+    /// Prints the Z report of the last closed session
+    PrintZReport,
+    /// Callback after print job is completed
+    PrintJobCompleted(Result<(), &'static str>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CashRegisterTextInputFields {
+    OpeningFloat,
+    MovementAmount,
+    MovementReason,
+    CountedAmount,
+}
+
+/// Identifies if cash is put into or taken out of the drawer
+#[derive(Debug, Clone, PartialEq)]
+pub enum CashMovementKind {
+    In,
+    Out,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl CashRegister {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                printers: Printers::default(),
+                state: State::Loading,
+            },
+            Task::batch([
+                Task::perform(CashSession::get_open(database.clone()), |res| match res {
+                    Ok(session) => Message::SessionLoaded(session.map(Box::from)),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                }),
+                Task::perform(AlegriaPrinter::load_printers(), |res| {
+                    Message::SetPrinters(res.0, res.1)
+                }),
+            ]),
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        _now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => match hotkey {
+                Hotkey::Tab(modifiers) => {
+                    if modifiers.shift() {
+                        Action::Run(focus_previous())
+                    } else {
+                        Action::Run(focus_next())
+                    }
+                }
+            },
+            Message::FetchSession => Action::Run(Task::perform(
+                CashSession::get_open(database.clone()),
+                |res| match res {
+                    Ok(session) => Message::SessionLoaded(session.map(Box::from)),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::SessionLoaded(session) => {
+                self.state = State::Ready {
+                    sub_screen: match session {
+                        Some(session) => SubScreen::Open {
+                            new_movement: CashMovement {
+                                cash_session_id: session.id,
+                                ..Default::default()
+                            },
+                            session,
+                        },
+                        None => SubScreen::Closed {
+                            new_session: CashSession::default(),
+                            z_report: None,
+                        },
+                    },
+                };
+                Action::None
+            }
+            Message::SetPrinters(default_printer, all_printers) => {
+                self.printers.selected_printer = default_printer;
+                self.printers.all_printers = all_printers;
+                Action::None
+            }
+            Message::UpdateSelectedPrinter(printer) => {
+                self.printers.selected_printer = Some(printer);
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                let State::Ready { sub_screen } = &mut self.state else {
+                    return Action::None;
+                };
+
+                match (sub_screen, field) {
+                    (
+                        SubScreen::Closed { new_session, .. },
+                        CashRegisterTextInputFields::OpeningFloat,
+                    ) => {
+                        if let Some(amount) = parse_amount_input(&new_value) {
+                            new_session.opening_float = amount;
+                            new_session.opening_float_input = new_value;
+                        }
+                    }
+                    (
+                        SubScreen::Open { new_movement, .. },
+                        CashRegisterTextInputFields::MovementAmount,
+                    ) => {
+                        if let Some(amount) = parse_amount_input(&new_value) {
+                            new_movement.amount = amount;
+                            new_movement.amount_input = new_value;
+                        }
+                    }
+                    (
+                        SubScreen::Open { new_movement, .. },
+                        CashRegisterTextInputFields::MovementReason,
+                    ) => {
+                        new_movement.reason = new_value;
+                    }
+                    (
+                        SubScreen::Open { session, .. },
+                        CashRegisterTextInputFields::CountedAmount,
+                    ) => {
+                        if let Some(amount) = parse_amount_input(&new_value) {
+                            session.counted_amount = amount;
+                            session.counted_amount_input = new_value;
+                        }
+                    }
+                    _ => {}
+                }
+                Action::None
+            }
+            Message::OpenSession => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Closed { new_session, .. } = sub_screen
+                {
+                    if !new_session.is_valid() {
+                        return Action::AddToast(Toast::warning_toast("Invalid opening float"));
+                    }
+
+                    return Action::Run(Task::perform(
+                        CashSession::open(database.clone(), new_session.clone()),
+                        |res| match res {
+                            Ok(_) => Message::FetchSession,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::AddMovement(kind) => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Open { new_movement, .. } = sub_screen
+                {
+                    let mut movement = new_movement.clone();
+                    // the amount is always typed as a positive number, the kind gives it's sign
+                    movement.amount = movement.amount.map(|amount| match kind {
+                        CashMovementKind::In => amount,
+                        CashMovementKind::Out => -amount,
+                    });
+
+                    if !movement.is_valid() {
+                        return Action::AddToast(Toast::warning_toast(
+                            "A cash movement needs an amount and a reason",
+                        ));
+                    }
+
+                    return Action::Run(Task::perform(
+                        CashMovement::add(database.clone(), movement),
+                        |res| match res {
+                            Ok(_) => Message::FetchSession,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::CloseSession => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Open { session, .. } = sub_screen
+                {
+                    let Some(counted_amount) = session.counted_amount else {
+                        return Action::AddToast(Toast::warning_toast(
+                            "Count the cash in the drawer before closing",
+                        ));
+                    };
+
+                    return Action::Run(Task::perform(
+                        CashSession::close(
+                            database.clone(),
+                            session.id.unwrap_or_default(),
+                            counted_amount,
+                        ),
+                        |res| match res {
+                            Ok(report) => Message::SessionClosed(Box::from(report)),
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::SessionClosed(report) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Closed {
+                        new_session: CashSession::default(),
+                        z_report: Some(report),
+                    },
+                };
+                Action::None
+            }
+            Message::PrintZReport => {
+                if let State::Ready { sub_screen } = &self.state
+                    && let SubScreen::Closed {
+                        z_report: Some(report),
+                        ..
+                    } = sub_screen
+                {
+                    let Some(printer) = self.printers.selected_printer.clone() else {
+                        return Action::AddToast(Toast::warning_toast("No printer selected"));
+                    };
+
+                    return Action::Run(Task::perform(
+                        Arc::new(printer).print_z_report(*report.clone()),
+                        Message::PrintJobCompleted,
+                    ));
+                }
+                Action::None
+            }
+            Message::PrintJobCompleted(result) => match result {
+                Ok(_) => Action::None,
+                Err(err) => {
+                    eprintln!("{err}");
+                    Action::AddToast(Toast::error_toast(err))
+                }
+            },
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => {
+                let content = match sub_screen {
+                    SubScreen::Closed {
+                        new_session,
+                        z_report,
+                    } => closed_view(new_session, z_report, &self.printers),
+                    SubScreen::Open {
+                        session,
+                        new_movement,
+                    } => open_view(session, new_movement),
+                };
+
+                column![
+                    header(),
+                    container(content)
+                        .width(Length::Fill)
+                        .align_x(Alignment::Center)
+                        .padding(50.)
+                ]
+                .spacing(GLOBAL_SPACING)
+                .into()
+            }
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Parses the input of an amount, returns None if the input should be ignored (invalid or with more than two decimals)
+fn parse_amount_input(input: &str) -> Option<Option<Money>> {
+    if input.is_empty() {
+        return Some(None);
+    }
+
+    let too_many_decimals = input
+        .replace(',', ".")
+        .find('.')
+        .is_some_and(|idx| input.len() - idx > 3);
+    if too_many_decimals {
+        return None;
+    }
+
+    input
+        .parse::<Money>()
+        .ok()
+        .filter(|amount| *amount >= Money::ZERO)
+        .map(Some)
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("cash-register")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// View when there is no open session: the form to open a new one and the Z report of the last closed one
+fn closed_view<'a>(
+    new_session: &'a CashSession,
+    z_report: &'a Option<Box<ZReport>>,
+    printers: &'a Printers,
+) -> iced::Element<'a, Message> {
+    let opening_float_input = text_input(
+        fl!("opening-float").as_str(),
+        &new_session.opening_float_input,
+    )
+    .on_input(|c| Message::TextInputUpdate(c, CashRegisterTextInputFields::OpeningFloat))
+    .on_submit(Message::OpenSession)
+    .size(TEXT_SIZE);
+
+    let open_form = column![
+        text(fl!("cash-register-closed")).size(TITLE_TEXT_SIZE),
+        text(fl!("opening-float")).width(Length::Fill),
+        opening_float_input,
+        button(text(fl!("open-cash-register")).center())
+            .on_press(Message::OpenSession)
+            .style(button::success)
+            .height(GLOBAL_BUTTON_HEIGHT)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .width(400.);
+
+    let Some(report) = z_report else {
+        return open_form.into();
+    };
+
+    let printer_selector = pick_list(
+        printers.all_printers.as_slice(),
+        printers.selected_printer.clone(),
+        Message::UpdateSelectedPrinter,
+    )
+    .width(Length::Fill);
+
+    let print_button = button(text(fl!("print")).center())
+        .on_press_maybe(
+            printers
+                .selected_printer
+                .as_ref()
+                .map(|_| Message::PrintZReport),
+        )
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        open_form,
+        column![
+            z_report_view(report),
+            row![printer_selector, print_button]
+                .spacing(GLOBAL_SPACING)
+                .align_y(Alignment::Center)
+        ]
+        .spacing(GLOBAL_SPACING)
+        .width(400.)
+    ]
+    .spacing(GLOBAL_SPACING * 4.)
+    .into()
+}
+
+/// Returns the view of a Z report
+fn z_report_view<'a>(report: &'a ZReport) -> iced::Element<'a, Message> {
+    let report_row = |label: String, value: String| {
+        row![
+            text(label).size(TEXT_SIZE).width(Length::Fill),
+            text(value).size(TEXT_SIZE)
+        ]
+        .align_y(Alignment::Center)
+    };
+
+    let mut report_column = Column::new()
+        .spacing(GLOBAL_SPACING)
+        .push(text(fl!("z-report")).size(TITLE_TEXT_SIZE))
+        .push(report_row(
+            fl!("opened-at"),
+            report.opened_at.format("%d/%m/%Y %H:%M").to_string(),
+        ))
+        .push(report_row(
+            fl!("closed-at"),
+            report.closed_at.format("%d/%m/%Y %H:%M").to_string(),
+        ))
+        .push(report_row(
+            fl!("simple-invoices"),
+            report.invoices.to_string(),
+        ))
+        .push(Rule::horizontal(1.));
+
+    for (payment_method, amount) in &report.payments {
+        report_column = report_column.push(report_row(
+            payment_method.to_string(),
+            format!("{amount:.2}€"),
+        ));
+    }
+    report_column = report_column.push(Rule::horizontal(1.));
+
+    for tax in &report.taxes {
+        report_column = report_column.push(report_row(
+            format!("{} {:.2}%", fl!("tax-base"), tax.tax_percentage),
+            format!("{:.2}€", tax.base),
+        ));
+        report_column = report_column.push(report_row(
+            format!("{} {:.2}%", fl!("tax"), tax.tax_percentage),
+            format!("{:.2}€", tax.tax),
+        ));
+    }
+
+    report_column = report_column.push(report_row(
+        fl!("total-invoice"),
+        format!("{:.2}€", report.total),
+    ));
+
+    if !report.discounts.is_empty() {
+        report_column = report_column.push(Rule::horizontal(1.));
+        for (reason, amount) in &report.discounts {
+            report_column = report_column.push(report_row(
+                format!("{} ({reason})", fl!("discount")),
+                format!("{amount:.2}€"),
+            ));
+        }
+        report_column = report_column.push(report_row(
+            fl!("total-discounts"),
+            format!("{:.2}€", report.total_discounts()),
+        ));
+    }
+
+    report_column
+        .push(Rule::horizontal(1.))
+        .push(report_row(
+            fl!("opening-float"),
+            format!("{:.2}€", report.opening_float),
+        ))
+        .push(report_row(
+            fl!("cash-movements"),
+            format!("{:.2}€", report.cash_movements),
+        ))
+        .push(report_row(
+            fl!("expected-cash"),
+            format!("{:.2}€", report.expected_cash),
+        ))
+        .push(report_row(
+            fl!("counted-cash"),
+            format!("{:.2}€", report.counted_amount),
+        ))
+        .push(report_row(
+            fl!("cash-difference"),
+            format!("{:.2}€", report.difference()),
+        ))
+        .into()
+}
+
+/// View when there is an open session: its movements, the form to add new ones and the close form
+fn open_view<'a>(
+    session: &'a CashSession,
+    new_movement: &'a CashMovement,
+) -> iced::Element<'a, Message> {
+    let session_info = column![
+        text(fl!("cash-register-open")).size(TITLE_TEXT_SIZE),
+        text(format!(
+            "{}: {}",
+            fl!("opened-at"),
+            session
+                .opened_at
+                .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                .unwrap_or_default()
+        )),
+        text(format!(
+            "{}: {:.2}€",
+            fl!("opening-float"),
+            session.opening_float.unwrap_or_default()
+        )),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    let mut movements = Column::new().spacing(GLOBAL_SPACING).width(Length::Fill);
+    for movement in &session.movements {
+        movements = movements.push(
+            Row::new()
+                .push(
+                    text(
+                        movement
+                            .created_at
+                            .map(|d| d.format("%H:%M").to_string())
+                            .unwrap_or_default(),
+                    )
+                    .width(60.),
+                )
+                .push(text(&movement.reason).width(Length::Fill))
+                .push(text(format!("{:.2}€", movement.amount.unwrap_or_default())))
+                .spacing(GLOBAL_SPACING),
+        );
+    }
+
+    let new_movement_form = column![
+        text(fl!("cash-movements")).size(TITLE_TEXT_SIZE),
+        scrollable(movements).height(200.),
+        text_input(fl!("amount").as_str(), &new_movement.amount_input)
+            .on_input(|c| Message::TextInputUpdate(c, CashRegisterTextInputFields::MovementAmount))
+            .size(TEXT_SIZE),
+        text_input(fl!("reason").as_str(), &new_movement.reason)
+            .on_input(|c| Message::TextInputUpdate(c, CashRegisterTextInputFields::MovementReason))
+            .size(TEXT_SIZE),
+        row![
+            button(text(fl!("cash-in")).center())
+                .on_press(Message::AddMovement(CashMovementKind::In))
+                .width(Length::Fill)
+                .height(GLOBAL_BUTTON_HEIGHT),
+            button(text(fl!("cash-out")).center())
+                .on_press(Message::AddMovement(CashMovementKind::Out))
+                .width(Length::Fill)
+                .height(GLOBAL_BUTTON_HEIGHT),
+        ]
+        .spacing(GLOBAL_SPACING)
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    let close_form = column![
+        text(fl!("counted-cash")),
+        text_input(fl!("counted-cash").as_str(), &session.counted_amount_input)
+            .on_input(|c| Message::TextInputUpdate(c, CashRegisterTextInputFields::CountedAmount))
+            .on_submit(Message::CloseSession)
+            .size(TEXT_SIZE),
+        button(text(fl!("close-cash-register")).center())
+            .on_press(Message::CloseSession)
+            .style(button::danger)
+            .height(GLOBAL_BUTTON_HEIGHT)
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    column![
+        session_info,
+        Rule::horizontal(1.),
+        new_movement_form,
+        Rule::horizontal(1.),
+        close_form
+    ]
+    .spacing(GLOBAL_SPACING)
+    .width(600.)
+    .into()
+}
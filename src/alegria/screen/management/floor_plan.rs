@@ -0,0 +1,481 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::time::Instant;
+use iced::widget::{
+    Column, Space, button, column, container, mouse_area, pick_list, row, scrollable, text,
+};
+use iced::{Alignment, Border, Length, Point, Subscription, Task, Vector};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::bar_table::BarTable;
+use crate::alegria::core::models::table_location::TableLocation;
+use crate::alegria::utils::entities::table_shape::TableShape;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+use crate::alegria::widgets::floor_plan::{FLOOR_PLAN_HEIGHT, FLOOR_PLAN_WIDTH, floor_plan};
+use crate::alegria::widgets::toast::Toast;
+use crate::fl;
+
+/// Tables are placed and sized in steps of this size
+const GRID_STEP: i32 = 10;
+/// Minimum width and height of a table on the floor plan
+const MIN_TABLE_SIZE: i32 = 40;
+
+pub struct FloorPlan {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready {
+        table_locations: Vec<TableLocation>,
+        bar_tables: Vec<BarTable>,
+        /// Location whose floor plan is being edited
+        selected_location: Option<i32>,
+        /// Table whose size and shape can be edited
+        selected_table: Option<i32>,
+        /// Table being dragged, if any
+        drag: Option<Drag>,
+        /// Last known position of the cursor over the floor plan
+        cursor: Point,
+        /// True if there are changes that have not been saved yet
+        modified: bool,
+    },
+}
+
+/// A table being dragged over the floor plan
+struct Drag {
+    table_id: i32,
+    /// Distance from the top left corner of the table to the cursor when the drag started
+    offset: Vector,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+
+    /// Asks to (re)load the locations and tables, discarding unsaved changes
+    FetchLayout,
+    /// Callback after loading the locations and tables
+    PageLoaded(Vec<TableLocation>, Vec<BarTable>),
+
+    /// Changes the location whose floor plan is being edited
+    SelectLocation(i32),
+    /// The cursor moved over the floor plan (position relative to the floor plan)
+    CursorMoved(Point),
+    /// Selects a table (by id) and starts dragging it
+    StartDrag(i32),
+    /// Drops the table being dragged
+    EndDrag,
+    /// Changes the width and height of the selected table by the given steps
+    ResizeSelectedTable(i32, i32),
+    /// Changes the shape of the selected table
+    UpdateSelectedTableShape(TableShape),
+
+    /// Saves the layout of all the tables
+    SaveLayout,
+    /// Callback after saving the layout
+    SavedLayout,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl FloorPlan {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(fetch_layout(database.clone()), |res| match res {
+                Ok((table_locations, bar_tables)) => {
+                    Message::PageLoaded(table_locations, bar_tables)
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    Message::AddToast(Toast::error_toast(err))
+                }
+            }),
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        _now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::FetchLayout => Action::Run(Task::perform(
+                fetch_layout(database.clone()),
+                |res| match res {
+                    Ok((table_locations, bar_tables)) => {
+                        Message::PageLoaded(table_locations, bar_tables)
+                    }
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::PageLoaded(table_locations, bar_tables) => {
+                // keep the location we were editing if it still exists
+                let previous_location = match &self.state {
+                    State::Ready {
+                        selected_location, ..
+                    } => *selected_location,
+                    State::Loading => None,
+                };
+                let selected_location = previous_location
+                    .filter(|id| table_locations.iter().any(|l| l.id == Some(*id)))
+                    .or_else(|| table_locations.first().and_then(|l| l.id));
+
+                self.state = State::Ready {
+                    table_locations,
+                    bar_tables,
+                    selected_location,
+                    selected_table: None,
+                    drag: None,
+                    cursor: Point::ORIGIN,
+                    modified: false,
+                };
+                Action::None
+            }
+            Message::SelectLocation(location_id) => {
+                if let State::Ready {
+                    selected_location,
+                    selected_table,
+                    drag,
+                    ..
+                } = &mut self.state
+                {
+                    *selected_location = Some(location_id);
+                    *selected_table = None;
+                    *drag = None;
+                }
+                Action::None
+            }
+            Message::CursorMoved(position) => {
+                if let State::Ready {
+                    bar_tables,
+                    drag,
+                    cursor,
+                    modified,
+                    ..
+                } = &mut self.state
+                {
+                    *cursor = position;
+
+                    if let Some(drag) = drag
+                        && let Some(table) =
+                            bar_tables.iter_mut().find(|t| t.id == Some(drag.table_id))
+                    {
+                        let position = position - drag.offset;
+                        table.pos_x =
+                            snap(position.x as i32, FLOOR_PLAN_WIDTH as i32 - table.width);
+                        table.pos_y =
+                            snap(position.y as i32, FLOOR_PLAN_HEIGHT as i32 - table.height);
+                        *modified = true;
+                    }
+                }
+                Action::None
+            }
+            Message::StartDrag(table_id) => {
+                if let State::Ready {
+                    bar_tables,
+                    selected_table,
+                    drag,
+                    cursor,
+                    ..
+                } = &mut self.state
+                    && let Some(table) = bar_tables.iter().find(|t| t.id == Some(table_id))
+                {
+                    *selected_table = Some(table_id);
+                    *drag = Some(Drag {
+                        table_id,
+                        offset: *cursor - Point::new(table.pos_x as f32, table.pos_y as f32),
+                    });
+                }
+                Action::None
+            }
+            Message::EndDrag => {
+                if let State::Ready { drag, .. } = &mut self.state {
+                    *drag = None;
+                }
+                Action::None
+            }
+            Message::ResizeSelectedTable(width_steps, height_steps) => {
+                if let State::Ready {
+                    bar_tables,
+                    selected_table,
+                    modified,
+                    ..
+                } = &mut self.state
+                    && let Some(table) = bar_tables
+                        .iter_mut()
+                        .find(|t| t.id.is_some() && t.id == *selected_table)
+                {
+                    // the table can't grow out of the floor plan
+                    table.width = (table.width + width_steps * GRID_STEP)
+                        .clamp(MIN_TABLE_SIZE, FLOOR_PLAN_WIDTH as i32 - table.pos_x);
+                    table.height = (table.height + height_steps * GRID_STEP)
+                        .clamp(MIN_TABLE_SIZE, FLOOR_PLAN_HEIGHT as i32 - table.pos_y);
+                    *modified = true;
+                }
+                Action::None
+            }
+            Message::UpdateSelectedTableShape(shape) => {
+                if let State::Ready {
+                    bar_tables,
+                    selected_table,
+                    modified,
+                    ..
+                } = &mut self.state
+                    && let Some(table) = bar_tables
+                        .iter_mut()
+                        .find(|t| t.id.is_some() && t.id == *selected_table)
+                {
+                    table.shape = shape;
+                    *modified = true;
+                }
+                Action::None
+            }
+            Message::SaveLayout => {
+                if let State::Ready { bar_tables, .. } = &self.state {
+                    return Action::Run(Task::perform(
+                        BarTable::save_layout(database.clone(), bar_tables.clone()),
+                        |res| match res {
+                            Ok(_) => Message::SavedLayout,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::SavedLayout => {
+                if let State::Ready { modified, .. } = &mut self.state {
+                    *modified = false;
+                }
+                Action::AddToast(Toast::success_toast(fl!("floor-plan-saved")))
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready {
+                table_locations,
+                bar_tables,
+                selected_location,
+                selected_table,
+                modified,
+                ..
+            } => {
+                let selected = bar_tables
+                    .iter()
+                    .find(|t| t.id.is_some() && t.id == *selected_table);
+
+                let content = row![
+                    side_panel(table_locations, *selected_location, selected),
+                    scrollable(plan(bar_tables, *selected_location, *selected_table)).direction(
+                        scrollable::Direction::Both {
+                            vertical: scrollable::Scrollbar::default(),
+                            horizontal: scrollable::Scrollbar::default(),
+                        }
+                    )
+                ]
+                .spacing(GLOBAL_SPACING)
+                .padding(3.);
+
+                column![header(*modified), content]
+                    .spacing(GLOBAL_SPACING)
+                    .height(Length::Fill)
+                    .width(Length::Fill)
+                    .into()
+            }
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        Subscription::none()
+    }
+}
+
+/// Loads the locations and the tables of the floor plans
+async fn fetch_layout(
+    database: Arc<Pool<Postgres>>,
+) -> Result<(Vec<TableLocation>, Vec<BarTable>), sqlx::Error> {
+    let table_locations = TableLocation::get_all(database.clone()).await?;
+    let bar_tables = BarTable::get_all(database.clone()).await?;
+    Ok((table_locations, bar_tables))
+}
+
+/// Snaps the given coordinate to the grid, keeping it between 0 and max
+fn snap(value: i32, max: i32) -> i32 {
+    let snapped = (value as f32 / GRID_STEP as f32).round() as i32 * GRID_STEP;
+    snapped.clamp(0, max.max(0))
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn header<'a>(modified: bool) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let discard_button = button(text(fl!("discard-changes")).center())
+        .style(button::danger)
+        .on_press_maybe(modified.then_some(Message::FetchLayout))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let save_button = button(text(fl!("save")).center())
+        .on_press_maybe(modified.then_some(Message::SaveLayout))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("floor-plan")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        discard_button,
+        save_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// Location selector and the size and shape controls of the selected table
+fn side_panel<'a>(
+    table_locations: &'a [TableLocation],
+    selected_location: Option<i32>,
+    selected_table: Option<&'a BarTable>,
+) -> iced::Element<'a, Message> {
+    let mut locations_column = Column::new()
+        .push(text(fl!("table-locations")).size(TITLE_TEXT_SIZE))
+        .spacing(GLOBAL_SPACING);
+    for location in table_locations {
+        let location_id = location.id.unwrap_or_default();
+        locations_column = locations_column.push(
+            button(text(&location.name).center().width(Length::Fill))
+                .on_press(Message::SelectLocation(location_id))
+                .style(if selected_location == Some(location_id) {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .width(Length::Fill)
+                .height(GLOBAL_BUTTON_HEIGHT),
+        );
+    }
+
+    let table_controls: iced::Element<'a, Message> = match selected_table {
+        Some(table) => {
+            let resize_row = |label: String, size: i32, width_steps: i32, height_steps: i32| {
+                row![
+                    text(format!("{label}: {size}"))
+                        .size(TEXT_SIZE)
+                        .width(Length::Fill),
+                    button(text("-").center())
+                        .on_press(Message::ResizeSelectedTable(-width_steps, -height_steps))
+                        .width(GLOBAL_BUTTON_HEIGHT)
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                    button(text("+").center())
+                        .on_press(Message::ResizeSelectedTable(width_steps, height_steps))
+                        .width(GLOBAL_BUTTON_HEIGHT)
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING)
+            };
+
+            column![
+                text(&table.name).size(TITLE_TEXT_SIZE),
+                resize_row(fl!("width"), table.width, 1, 0),
+                resize_row(fl!("height"), table.height, 0, 1),
+                text(fl!("shape")).size(TEXT_SIZE),
+                pick_list(
+                    TableShape::ALL,
+                    Some(table.shape),
+                    Message::UpdateSelectedTableShape
+                )
+                .width(Length::Fill),
+            ]
+            .spacing(GLOBAL_SPACING)
+            .into()
+        }
+        None => text(fl!("select-table-to-edit")).size(TEXT_SIZE).into(),
+    };
+
+    column![locations_column, table_controls]
+        .spacing(GLOBAL_SPACING * 4.)
+        .width(300.)
+        .into()
+}
+
+/// Floor plan of the selected location, tables can be dragged around
+fn plan<'a>(
+    bar_tables: &'a [BarTable],
+    selected_location: Option<i32>,
+    selected_table: Option<i32>,
+) -> iced::Element<'a, Message> {
+    let tables = bar_tables
+        .iter()
+        .filter(|t| t.location_id.is_some() && t.location_id == selected_location)
+        .map(|table| {
+            let is_selected = table.id.is_some() && table.id == selected_table;
+            let radius = match table.shape {
+                TableShape::Square => 3.,
+                TableShape::Round => table.width.min(table.height) as f32 / 2.,
+            };
+
+            let content = mouse_area(
+                container(text(&table.name).size(TEXT_SIZE))
+                    .center(Length::Fill)
+                    .style(move |theme: &iced::Theme| {
+                        let palette = theme.extended_palette();
+                        let pair = if is_selected {
+                            palette.primary.strong
+                        } else {
+                            palette.secondary.base
+                        };
+
+                        container::Style {
+                            background: Some(pair.color.into()),
+                            text_color: Some(pair.text),
+                            border: Border {
+                                radius: radius.into(),
+                                ..Border::default()
+                            },
+                            ..container::Style::default()
+                        }
+                    }),
+            )
+            .on_press(Message::StartDrag(table.id.unwrap_or_default()));
+
+            (table, content.into())
+        });
+
+    mouse_area(floor_plan(tables))
+        .on_move(Message::CursorMoved)
+        .on_release(Message::EndDrag)
+        .into()
+}
@@ -0,0 +1,776 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use chrono::{Datelike, Local, NaiveDate};
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, row, scrollable, text,
+    text_input,
+};
+use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::ingredient::{Ingredient, IngredientConsumption};
+use crate::alegria::utils::date::parse_date_to_naive_datetime;
+use crate::alegria::utils::money::parse_decimal;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{
+    alegria::{
+        utils::pagination::{PaginationAction, PaginationConfig},
+        widgets::toast::Toast,
+    },
+    fl,
+};
+
+pub struct Ingredients {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    List {
+        pagination_state: PaginationConfig,
+        ingredients: Vec<Ingredient>,
+    },
+    Upsert {
+        ingredient: Box<Ingredient>,
+    },
+    /// Theoretical consumption of the ingredients between two dates (both included)
+    Consumption {
+        from_input: String,
+        to_input: String,
+        consumption: Option<Vec<IngredientConsumption>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum IngredientTextInputFields {
+    Name,
+    Unit,
+    StockQuantity,
+    ConsumptionFrom,
+    ConsumptionTo,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to update the current list of ingredients
+    FetchIngredients,
+    /// Callback after initial page loading, set's the ingredients list on the state
+    PageLoaded(Vec<Ingredient>),
+
+    /// Try to go left or right a page
+    PaginationAction(PaginationAction),
+
+    /// Callback after asking to edit a ingredient, searches the ingredient on the db
+    AskEditIngredient(i32),
+    /// Changes the upsert screen with the given ingredient
+    OpenUpsertScreen(Box<Ingredient>),
+
+    /// Callback when using the text inputs of the screen
+    TextInputUpdate(String, IngredientTextInputFields),
+
+    /// Tries to Add or Edit the current ingredient to the database
+    UpsertCurrentIngredient,
+    /// Callback after upserting the ingredient on the database
+    UpsertedCurrentIngredient,
+    /// Tries to delete the current ingredient
+    DeleteCurrentIngredient,
+
+    /// Changes to the theoretical consumption screen (the current month by default)
+    OpenConsumptionScreen,
+    /// Calculates the theoretical consumption between the dates of the consumption screen
+    CalculateConsumption,
+    /// Callback after calculating the theoretical consumption
+    CalculatedConsumption(Vec<IngredientConsumption>),
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl Ingredients {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(Ingredient::get_all(database.clone()), |res| match res {
+                Ok(res) => Message::PageLoaded(res),
+                Err(err) => {
+                    eprintln!("{err}");
+                    Message::AddToast(Toast::error_toast(err))
+                }
+            }),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match sub_screen {
+                        SubScreen::List { .. } => return Action::Back,
+                        SubScreen::Upsert { .. } | SubScreen::Consumption { .. } => {
+                            return self.update(Message::FetchIngredients, &database.clone(), now);
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state
+                    && !matches!(sub_screen, SubScreen::List { .. })
+                {
+                    return match hotkey {
+                        Hotkey::Tab(modifiers) => {
+                            if modifiers.shift() {
+                                Action::Run(focus_previous())
+                            } else {
+                                Action::Run(focus_next())
+                            }
+                        }
+                    };
+                }
+                Action::None
+            }
+            Message::FetchIngredients => Action::Run(Task::perform(
+                Ingredient::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::PageLoaded(res) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::List {
+                        pagination_state: PaginationConfig::default(),
+                        ingredients: res,
+                    },
+                };
+                Action::None
+            }
+            Message::PaginationAction(pagination_action) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List {
+                        ingredients,
+                        pagination_state,
+                        ..
+                    } = sub_screen
+                    {
+                        match pagination_action {
+                            PaginationAction::Up => {}
+                            PaginationAction::Down => {}
+                            PaginationAction::Back => {
+                                if pagination_state.current_page > 0 {
+                                    pagination_state.current_page -= 1;
+                                }
+                            }
+                            PaginationAction::Forward => {
+                                let next_page_start = (pagination_state.current_page + 1)
+                                    * pagination_state.items_per_page;
+                                if next_page_start
+                                    < ingredients.len().try_into().unwrap_or_default()
+                                {
+                                    pagination_state.current_page += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AskEditIngredient(ingredient_id) => Action::Run(Task::perform(
+                Ingredient::get_single(database.clone(), ingredient_id),
+                |res| match res {
+                    Ok(res) => Message::OpenUpsertScreen(Box::from(res)),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::OpenUpsertScreen(ingredient) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Upsert { ingredient },
+                };
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match (sub_screen, field) {
+                        (SubScreen::Upsert { ingredient }, IngredientTextInputFields::Name) => {
+                            ingredient.name = new_value;
+                        }
+                        (SubScreen::Upsert { ingredient }, IngredientTextInputFields::Unit) => {
+                            ingredient.unit = new_value;
+                        }
+                        (
+                            SubScreen::Upsert { ingredient },
+                            IngredientTextInputFields::StockQuantity,
+                        ) => {
+                            if let Ok(num) = parse_decimal(&new_value) {
+                                ingredient.stock_quantity = Some(num);
+                                ingredient.stock_quantity_input = new_value;
+                            } else if new_value.is_empty() || new_value == "-" {
+                                ingredient.stock_quantity = None;
+                                ingredient.stock_quantity_input = new_value;
+                            }
+                        }
+                        (
+                            SubScreen::Consumption { from_input, .. },
+                            IngredientTextInputFields::ConsumptionFrom,
+                        ) => *from_input = new_value,
+                        (
+                            SubScreen::Consumption { to_input, .. },
+                            IngredientTextInputFields::ConsumptionTo,
+                        ) => *to_input = new_value,
+                        _ => {}
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertCurrentIngredient => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { ingredient } = sub_screen {
+                        #[allow(clippy::collapsible_if)]
+                        if ingredient.is_valid() {
+                            return match ingredient.id {
+                                Some(_id) => Action::Run(Task::perform(
+                                    Ingredient::edit(database.clone(), *ingredient.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentIngredient,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                                None => Action::Run(Task::perform(
+                                    Ingredient::add(database.clone(), *ingredient.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentIngredient,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                            };
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertedCurrentIngredient => {
+                self.update(Message::FetchIngredients, &database.clone(), now)
+            }
+            Message::DeleteCurrentIngredient => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { ingredient } = sub_screen {
+                        return Action::Run(Task::perform(
+                            Ingredient::delete(database.clone(), ingredient.id.unwrap_or_default()),
+                            |res| match res {
+                                Ok(_) => Message::FetchIngredients,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+            Message::OpenConsumptionScreen => {
+                let today = Local::now().date_naive();
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Consumption {
+                        from_input: today.with_day(1).unwrap_or(today).to_string(),
+                        to_input: today.to_string(),
+                        consumption: None,
+                    },
+                };
+                self.update(Message::CalculateConsumption, &database.clone(), now)
+            }
+            Message::CalculateConsumption => {
+                if let State::Ready { sub_screen, .. } = &mut self.state
+                    && let SubScreen::Consumption {
+                        from_input,
+                        to_input,
+                        ..
+                    } = sub_screen
+                {
+                    let (Some(from), Some(to)) = (parse_date(from_input), parse_date(to_input))
+                    else {
+                        return Action::AddToast(Toast::warning_toast(
+                            "Invalid dates, use the yyyy-mm-dd format",
+                        ));
+                    };
+
+                    return Action::Run(Task::perform(
+                        Ingredient::get_theoretical_consumption(database.clone(), from, to),
+                        |res| match res {
+                            Ok(res) => Message::CalculatedConsumption(res),
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::CalculatedConsumption(res) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state
+                    && let SubScreen::Consumption { consumption, .. } = sub_screen
+                {
+                    *consumption = Some(res);
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => match sub_screen {
+                SubScreen::List {
+                    pagination_state,
+                    ingredients,
+                } => list_screen(pagination_state, ingredients),
+                SubScreen::Upsert { ingredient } => upsert_screen(ingredient),
+                SubScreen::Consumption {
+                    from_input,
+                    to_input,
+                    consumption,
+                } => consumption_screen(from_input, to_input, consumption),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Parses a date input with the yyyy-(m)m-(d)d format
+fn parse_date(input: &str) -> Option<NaiveDate> {
+    parse_date_to_naive_datetime(input.trim()).map(|date| date.date())
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+// LIST SCREEN
+
+fn list_screen<'a>(
+    pagination_state: &'a PaginationConfig,
+    ingredients: &'a [Ingredient],
+) -> iced::Element<'a, Message> {
+    let header = list_header();
+    let grid: Element<'a, Message, Theme, Renderer> = if ingredients.is_empty() {
+        container(text(fl!("no-ingredients")).size(TITLE_TEXT_SIZE))
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(50.)
+            .into()
+    } else {
+        let title_row = Row::new()
+            .push(
+                text(fl!("name"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(300.)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                text(fl!("stock"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(300.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("edit"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::End),
+            )
+            .width(Length::Shrink)
+            .align_y(Alignment::Center);
+
+        // Calculate the indices for the current page
+        let start_index: usize =
+            pagination_state.current_page as usize * pagination_state.items_per_page as usize;
+        let end_index = usize::min(
+            start_index + pagination_state.items_per_page as usize,
+            ingredients.len(),
+        );
+
+        let mut grid = Column::new()
+            .push(title_row)
+            .spacing(GLOBAL_SPACING)
+            .width(Length::Shrink);
+
+        for ingredient in &ingredients[start_index..end_index] {
+            let row = Row::new()
+                .push(
+                    text(&ingredient.name)
+                        .size(TEXT_SIZE)
+                        .width(300.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(format!(
+                        "{} {}",
+                        ingredient.stock_quantity.unwrap_or_default().normalize(),
+                        ingredient.unit
+                    ))
+                    .size(TEXT_SIZE)
+                    .width(300.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::Center),
+                )
+                .push(
+                    row![
+                        Space::new(Length::Fill, Length::Shrink),
+                        button(text(fl!("edit")).size(TEXT_SIZE).align_y(Alignment::Center))
+                            .on_press(Message::AskEditIngredient(ingredient.id.unwrap()))
+                            .width(Length::Shrink)
+                    ]
+                    .width(200.),
+                )
+                .align_y(Alignment::Center);
+
+            // Limit Rule size to sum of all column widths
+            grid = grid.push(row![Rule::horizontal(1.)].width(800.));
+            grid = grid.push(row);
+        }
+
+        scrollable(grid).spacing(GLOBAL_SPACING).into()
+    };
+
+    let page_controls = Column::new()
+        .push(row![Rule::horizontal(1.)].width(800.))
+        .push(
+            text(format!(
+                "{} {}",
+                fl!("page").as_str(),
+                &pagination_state.current_page + 1
+            ))
+            .align_x(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .width(800.)
+                .push(
+                    button(
+                        text(fl!("back"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Back)),
+                )
+                .push(
+                    button(
+                        text(fl!("next"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Forward)),
+                )
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_x(Alignment::Center);
+
+    let content = container(
+        column![grid, page_controls]
+            .spacing(GLOBAL_SPACING)
+            .width(800.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
+fn list_header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let consumption_button = button(text(fl!("theoretical-consumption")).center())
+        .on_press(Message::OpenConsumptionScreen)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let add_button = button(text(fl!("add")).center())
+        .on_press(Message::OpenUpsertScreen(Box::default()))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("ingredients")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        consumption_button,
+        add_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+// UPSERT SCREEN
+
+fn upsert_screen<'a>(ingredient: &'a Ingredient) -> iced::Element<'a, Message> {
+    let header = upsert_header(ingredient);
+
+    // Name
+    let name_label = text(format!("{}*", fl!("name"))).width(Length::Fill);
+    let name_input = text_input(fl!("name").as_str(), &ingredient.name)
+        .on_input(|c| Message::TextInputUpdate(c, IngredientTextInputFields::Name))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Unit
+    let unit_label = text(format!("{}*", fl!("unit"))).width(Length::Fill);
+    let unit_input = text_input(fl!("unit").as_str(), &ingredient.unit)
+        .on_input(|c| Message::TextInputUpdate(c, IngredientTextInputFields::Unit))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Stock
+    let stock_quantity_label = text(format!("{}*", fl!("stock"))).width(Length::Fill);
+    let stock_quantity_input = text_input(fl!("stock").as_str(), &ingredient.stock_quantity_input)
+        .on_input(|c| Message::TextInputUpdate(c, IngredientTextInputFields::StockQuantity))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Submit
+    let submit_button_text = if ingredient.id.is_some() {
+        text(fl!("edit"))
+    } else {
+        text(fl!("add"))
+    };
+    let submit_button = button(submit_button_text.center().size(TEXT_SIZE))
+        .on_press_maybe(
+            ingredient
+                .is_valid()
+                .then_some(Message::UpsertCurrentIngredient),
+        )
+        .width(Length::Fill);
+
+    // Input Columns
+    let name_input_column = column![name_label, name_input].width(850.).spacing(1.);
+    let unit_input_column = column![unit_label, unit_input].width(850.).spacing(1.);
+    let stock_quantity_input_column = column![stock_quantity_label, stock_quantity_input]
+        .width(850.)
+        .spacing(1.);
+
+    let form_column = Column::new()
+        .push(name_input_column)
+        .push(unit_input_column)
+        .push(stock_quantity_input_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        container(form_column)
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .padding(50.)
+    ]
+    .into()
+}
+
+fn upsert_header<'a>(ingredient: &'a Ingredient) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let delete_button = button(text(fl!("delete")).center())
+        .style(button::danger)
+        .on_press_maybe(ingredient.id.map(|_| Message::DeleteCurrentIngredient))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("ingredient")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        delete_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+// CONSUMPTION SCREEN
+
+fn consumption_screen<'a>(
+    from_input: &'a str,
+    to_input: &'a str,
+    consumption: &'a Option<Vec<IngredientConsumption>>,
+) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let header = row![
+        back_button,
+        text(fl!("theoretical-consumption")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.);
+
+    let dates_row = row![
+        column![
+            text(format!("{} (yyyy-mm-dd)", fl!("initial-date"))),
+            text_input(fl!("initial-date").as_str(), from_input)
+                .on_input(|c| Message::TextInputUpdate(
+                    c,
+                    IngredientTextInputFields::ConsumptionFrom
+                ))
+                .on_submit(Message::CalculateConsumption)
+                .size(TEXT_SIZE)
+        ]
+        .spacing(1.)
+        .width(Length::Fill),
+        column![
+            text(format!("{} (yyyy-mm-dd)", fl!("last-date"))),
+            text_input(fl!("last-date").as_str(), to_input)
+                .on_input(|c| Message::TextInputUpdate(c, IngredientTextInputFields::ConsumptionTo))
+                .on_submit(Message::CalculateConsumption)
+                .size(TEXT_SIZE)
+        ]
+        .spacing(1.)
+        .width(Length::Fill),
+        button(text(fl!("calculate")).center())
+            .on_press(Message::CalculateConsumption)
+            .height(GLOBAL_BUTTON_HEIGHT)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .align_y(Alignment::End);
+
+    let mut grid = Column::new().spacing(GLOBAL_SPACING);
+    match consumption {
+        None => grid = grid.push(text("Loading...")),
+        Some(consumption) if consumption.is_empty() => {
+            grid = grid.push(text(fl!("no-consumption")).size(TEXT_SIZE));
+        }
+        Some(consumption) => {
+            grid = grid.push(
+                row![
+                    text(fl!("ingredient"))
+                        .size(TITLE_TEXT_SIZE)
+                        .width(Length::Fill),
+                    text(fl!("quantity")).size(TITLE_TEXT_SIZE).width(200.)
+                ]
+                .align_y(Alignment::Center),
+            );
+            for ingredient in consumption {
+                grid = grid.push(Rule::horizontal(1.));
+                grid = grid.push(
+                    row![
+                        text(&ingredient.name).size(TEXT_SIZE).width(Length::Fill),
+                        text(format!(
+                            "{} {}",
+                            ingredient.quantity.normalize(),
+                            ingredient.unit
+                        ))
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                    ]
+                    .align_y(Alignment::Center),
+                );
+            }
+        }
+    }
+
+    let content = container(
+        column![dates_row, scrollable(grid)]
+            .spacing(GLOBAL_SPACING)
+            .width(800.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use chrono::{Datelike, Local};
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, row, text, text_input,
+};
+use iced::{Alignment, Length, Subscription, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::invoice_series::InvoiceSeries;
+use crate::alegria::utils::entities::invoice_series_kind::InvoiceSeriesKind;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+use crate::{alegria::widgets::toast::Toast, fl};
+
+pub struct InvoiceSeriesScreen {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { invoice_series: Vec<InvoiceSeries> },
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to retrieve the invoice series
+    FetchInvoiceSeries,
+    /// Callback after retrieving the invoice series
+    InvoiceSeriesLoaded(Vec<InvoiceSeries>),
+
+    /// Updates the prefix input of the given series
+    PrefixInput(InvoiceSeriesKind, String),
+    /// Tries to save the prefix of the given series
+    SavePrefix(InvoiceSeriesKind),
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl InvoiceSeriesScreen {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            fetch_invoice_series(database),
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        _now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => match hotkey {
+                Hotkey::Tab(modifiers) => {
+                    if modifiers.shift() {
+                        Action::Run(focus_previous())
+                    } else {
+                        Action::Run(focus_next())
+                    }
+                }
+            },
+            Message::FetchInvoiceSeries => Action::Run(fetch_invoice_series(database)),
+            Message::InvoiceSeriesLoaded(res) => {
+                self.state = State::Ready {
+                    invoice_series: res,
+                };
+                Action::None
+            }
+            Message::PrefixInput(kind, new_value) => {
+                if let State::Ready { invoice_series } = &mut self.state
+                    && let Some(series) = invoice_series.iter_mut().find(|s| s.kind == kind)
+                {
+                    series.prefix = new_value;
+                }
+                Action::None
+            }
+            Message::SavePrefix(kind) => {
+                if let State::Ready { invoice_series } = &self.state
+                    && let Some(series) = invoice_series.iter().find(|s| s.kind == kind)
+                {
+                    if !series.is_valid() {
+                        return Action::AddToast(Toast::warning_toast(
+                            "The prefix can only have capital letters (A-Z)",
+                        ));
+                    }
+
+                    // the printed numbers of different series can't be the same
+                    if invoice_series
+                        .iter()
+                        .any(|s| s.kind != kind && s.prefix.trim() == series.prefix.trim())
+                    {
+                        return Action::AddToast(Toast::warning_toast(
+                            "Another series already uses this prefix",
+                        ));
+                    }
+
+                    return Action::Run(Task::perform(
+                        InvoiceSeries::edit(database.clone(), series.clone()),
+                        |res| match res {
+                            Ok(_) => Message::FetchInvoiceSeries,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { invoice_series } => column![
+                header(),
+                container(invoice_series_list(invoice_series))
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center)
+                    .padding(50.)
+            ]
+            .spacing(GLOBAL_SPACING)
+            .into(),
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Fetches all the invoice series
+fn fetch_invoice_series(database: &Arc<Pool<Postgres>>) -> Task<Message> {
+    Task::perform(InvoiceSeries::get_all(database.clone()), |res| match res {
+        Ok(res) => Message::InvoiceSeriesLoaded(res),
+        Err(err) => {
+            eprintln!("{err}");
+            Message::AddToast(Toast::error_toast(err))
+        }
+    })
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("invoice-series")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// Series with their editable prefix and the last number given on the current year
+fn invoice_series_list<'a>(invoice_series: &'a [InvoiceSeries]) -> iced::Element<'a, Message> {
+    let year = Local::now().year();
+
+    let title_row = Row::new()
+        .push(
+            text(fl!("invoice-series"))
+                .size(TITLE_TEXT_SIZE)
+                .width(200.),
+        )
+        .push(
+            text(fl!("prefix"))
+                .size(TITLE_TEXT_SIZE)
+                .width(Length::Fill),
+        )
+        .push(
+            text(format!("{} ({year})", fl!("last-number")))
+                .size(TITLE_TEXT_SIZE)
+                .width(250.),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_y(Alignment::Center);
+
+    let mut grid = Column::new()
+        .push(title_row)
+        .spacing(GLOBAL_SPACING)
+        .width(800.);
+
+    for series in invoice_series {
+        let kind = series.kind;
+        let last_number = if series.last_number == 0 {
+            String::from("-")
+        } else {
+            series.last_number.to_string()
+        };
+
+        grid = grid.push(Rule::horizontal(1.));
+        grid = grid.push(
+            Row::new()
+                .push(text(series.kind).size(TEXT_SIZE).width(200.))
+                .push(
+                    text_input(fl!("prefix").as_str(), &series.prefix)
+                        .on_input(move |c| Message::PrefixInput(kind, c))
+                        .on_submit(Message::SavePrefix(kind))
+                        .size(TEXT_SIZE)
+                        .width(Length::Fill),
+                )
+                .push(
+                    button(text(fl!("save")).center())
+                        .on_press(Message::SavePrefix(kind))
+                        .height(GLOBAL_BUTTON_HEIGHT),
+                )
+                .push(text(last_number).size(TEXT_SIZE).width(250.))
+                .spacing(GLOBAL_SPACING)
+                .align_y(Alignment::Center),
+        );
+    }
+
+    grid.into()
+}
@@ -0,0 +1,755 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::modifier_group::{Modifier, ModifierGroup};
+use crate::alegria::utils::money::Money;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{
+    alegria::{
+        core::models::{product::Product, product_category::ProductCategory},
+        utils::pagination::{PaginationAction, PaginationConfig},
+        widgets::toast::Toast,
+    },
+    fl,
+};
+
+pub struct ModifierGroups {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    List {
+        pagination_state: PaginationConfig,
+        modifier_groups: Vec<ModifierGroup>,
+    },
+    Upsert {
+        modifier_group: Box<ModifierGroup>,
+        products: Vec<Product>,
+        product_categories: Vec<ProductCategory>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum ModifierGroupTextInputFields {
+    Name,
+    /// Name of the modifier on the given index
+    ModifierName(usize),
+    /// Price delta of the modifier on the given index
+    ModifierPriceDelta(usize),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to update the current list of modifier_groups
+    FetchModifierGroups,
+    /// Callback after initial page loading, set's the modifier_groups list on the state
+    PageLoaded(Vec<ModifierGroup>),
+
+    /// Try to go left or right a page
+    PaginationAction(PaginationAction),
+
+    /// Callback after asking to edit a modifier_group, searches the modifier_group on the db
+    AskEditModifierGroup(i32),
+    /// Changes the upsert screen, with a default ModifierGroup and grabs the products and categories (intended for calling when we need to create a new modifier_group)
+    AskOpenUpsertScreen,
+    /// Changes the upsert screen with the given modifier_group (we also need the products and categories for the selectors)
+    OpenUpsertScreen(Box<ModifierGroup>, Vec<Product>, Vec<ProductCategory>),
+
+    /// Callback when using the text inputs to add or edit a modifier_group
+    TextInputUpdate(String, ModifierGroupTextInputFields),
+    /// Callback after selecting the product the current modifier_group applies to
+    UpdatedSelectedProductId(i32),
+    /// Callback after selecting the category the current modifier_group applies to
+    UpdatedSelectedProductCategoryId(i32),
+    /// Adds an empty modifier to the current modifier_group
+    AddModifier,
+    /// Removes the modifier on the given index from the current modifier_group
+    RemoveModifier(usize),
+
+    /// Tries to Add or Edit the current modifier_group to the database
+    UpsertCurrentModifierGroup,
+    /// Callback after upserting the modifier_group on the database
+    UpsertedCurrentModifierGroup,
+    /// Tries to delete the current modifier_group
+    DeleteCurrentModifierGroup,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl ModifierGroups {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(ModifierGroup::get_all(database.clone()), |res| match res {
+                Ok(res) => Message::PageLoaded(res),
+                Err(err) => {
+                    eprintln!("{err}");
+                    Message::AddToast(Toast::error_toast(err))
+                }
+            }),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match sub_screen {
+                        SubScreen::List { .. } => return Action::Back,
+                        SubScreen::Upsert { .. } => {
+                            return self.update(
+                                Message::FetchModifierGroups,
+                                &database.clone(),
+                                now,
+                            );
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { .. } = sub_screen {
+                        return match hotkey {
+                            Hotkey::Tab(modifiers) => {
+                                if modifiers.shift() {
+                                    Action::Run(focus_previous())
+                                } else {
+                                    Action::Run(focus_next())
+                                }
+                            }
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::FetchModifierGroups => Action::Run(Task::perform(
+                ModifierGroup::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::PageLoaded(res) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::List {
+                        pagination_state: PaginationConfig::default(),
+                        modifier_groups: res,
+                    },
+                };
+                Action::None
+            }
+            Message::PaginationAction(pagination_action) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List {
+                        modifier_groups,
+                        pagination_state,
+                        ..
+                    } = sub_screen
+                    {
+                        match pagination_action {
+                            PaginationAction::Up => {}
+                            PaginationAction::Down => {}
+                            PaginationAction::Back => {
+                                if pagination_state.current_page > 0 {
+                                    pagination_state.current_page -= 1;
+                                }
+                            }
+                            PaginationAction::Forward => {
+                                let next_page_start = (pagination_state.current_page + 1)
+                                    * pagination_state.items_per_page;
+                                if next_page_start
+                                    < modifier_groups.len().try_into().unwrap_or_default()
+                                {
+                                    pagination_state.current_page += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AskEditModifierGroup(modifier_group_id) => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        tokio::join!(
+                            ModifierGroup::get_single(database.clone(), modifier_group_id),
+                            Product::get_all(database.clone()),
+                            ProductCategory::get_all(database.clone())
+                        )
+                    },
+                    |res| match res {
+                        (Ok(modifier_group), Ok(products), Ok(product_categories)) => {
+                            Message::OpenUpsertScreen(
+                                Box::from(modifier_group),
+                                products,
+                                product_categories,
+                            )
+                        }
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching modifier group, products or product categories",
+                        )),
+                    },
+                ))
+            }
+            Message::AskOpenUpsertScreen => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        tokio::join!(
+                            Product::get_all(database.clone()),
+                            ProductCategory::get_all(database.clone())
+                        )
+                    },
+                    |res| match res {
+                        (Ok(products), Ok(product_categories)) => Message::OpenUpsertScreen(
+                            Box::from(ModifierGroup {
+                                modifiers: vec![Modifier::default()],
+                                ..Default::default()
+                            }),
+                            products,
+                            product_categories,
+                        ),
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching products or product categories",
+                        )),
+                    },
+                ))
+            }
+            Message::OpenUpsertScreen(modifier_group, products, product_categories) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Upsert {
+                        modifier_group,
+                        products,
+                        product_categories,
+                    },
+                };
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen {
+                        match field {
+                            ModifierGroupTextInputFields::Name => modifier_group.name = new_value,
+                            ModifierGroupTextInputFields::ModifierName(index) => {
+                                if let Some(modifier) = modifier_group.modifiers.get_mut(index) {
+                                    modifier.name = new_value;
+                                }
+                            }
+                            ModifierGroupTextInputFields::ModifierPriceDelta(index) => {
+                                if let Some(modifier) = modifier_group.modifiers.get_mut(index) {
+                                    // We ignore the input if we already have two decimals and we're trying to add more
+                                    let ignore_action = new_value.len()
+                                        > modifier.price_delta_input.len()
+                                        && modifier.price_delta_input.find('.').is_some_and(
+                                            |idx| modifier.price_delta_input.len() - idx > 2,
+                                        );
+
+                                    if !ignore_action {
+                                        if let Ok(num) = new_value.parse::<Money>() {
+                                            modifier.price_delta = Some(num);
+                                            modifier.price_delta_input = new_value;
+                                        } else if new_value.is_empty() || new_value == "-" {
+                                            // a lone minus sign is the start of a discount
+                                            modifier.price_delta = None;
+                                            modifier.price_delta_input = new_value;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedProductId(product_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen {
+                        // a group applies either to a product or to a category
+                        modifier_group.product_id = Some(product_id);
+                        modifier_group.category_id = None;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedProductCategoryId(category_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen {
+                        modifier_group.category_id = Some(category_id);
+                        modifier_group.product_id = None;
+                    }
+                }
+                Action::None
+            }
+            Message::AddModifier => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen {
+                        modifier_group.modifiers.push(Modifier::default());
+                    }
+                }
+                Action::None
+            }
+            Message::RemoveModifier(index) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen
+                        && index < modifier_group.modifiers.len()
+                    {
+                        modifier_group.modifiers.remove(index);
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertCurrentModifierGroup => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen {
+                        #[allow(clippy::collapsible_if)]
+                        if modifier_group.is_valid() {
+                            return match modifier_group.id {
+                                Some(_id) => Action::Run(Task::perform(
+                                    ModifierGroup::edit(database.clone(), *modifier_group.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentModifierGroup,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                                None => Action::Run(Task::perform(
+                                    ModifierGroup::add(database.clone(), *modifier_group.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentModifierGroup,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                            };
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertedCurrentModifierGroup => {
+                self.update(Message::FetchModifierGroups, &database.clone(), now)
+            }
+            Message::DeleteCurrentModifierGroup => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { modifier_group, .. } = sub_screen {
+                        return Action::Run(Task::perform(
+                            ModifierGroup::delete(
+                                database.clone(),
+                                modifier_group.id.unwrap_or_default(),
+                            ),
+                            |res| match res {
+                                Ok(_) => Message::FetchModifierGroups,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => match sub_screen {
+                SubScreen::List {
+                    pagination_state,
+                    modifier_groups,
+                } => list_screen(pagination_state, modifier_groups),
+                SubScreen::Upsert {
+                    modifier_group,
+                    products,
+                    product_categories,
+                } => upsert_screen(modifier_group, products, product_categories),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+// LIST SCREEN
+
+fn list_screen<'a>(
+    pagination_state: &'a PaginationConfig,
+    modifier_groups: &'a [ModifierGroup],
+) -> iced::Element<'a, Message> {
+    let header = list_header();
+    let grid: Element<'a, Message, Theme, Renderer> = if modifier_groups.is_empty() {
+        container(text(fl!("no-modifier-groups")).size(TITLE_TEXT_SIZE))
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(50.)
+            .into()
+    } else {
+        let title_row = Row::new()
+            .push(
+                text(fl!("name"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(300.)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                text(fl!("applies-to"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(300.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("edit"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::End),
+            )
+            .width(Length::Shrink)
+            .align_y(Alignment::Center);
+
+        // Calculate the indices for the current page
+        let start_index: usize =
+            pagination_state.current_page as usize * pagination_state.items_per_page as usize;
+        let end_index = usize::min(
+            start_index + pagination_state.items_per_page as usize,
+            modifier_groups.len(),
+        );
+
+        let mut grid = Column::new()
+            .push(title_row)
+            .spacing(GLOBAL_SPACING)
+            .width(Length::Shrink);
+
+        for modifier_group in &modifier_groups[start_index..end_index] {
+            let row = Row::new()
+                .push(
+                    text(&modifier_group.name)
+                        .size(TEXT_SIZE)
+                        .width(300.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(modifier_group.target_name.as_ref())
+                        .size(TEXT_SIZE)
+                        .width(300.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    row![
+                        Space::new(Length::Fill, Length::Shrink),
+                        button(text(fl!("edit")).size(TEXT_SIZE).align_y(Alignment::Center))
+                            .on_press(Message::AskEditModifierGroup(modifier_group.id.unwrap()))
+                            .width(Length::Shrink)
+                    ]
+                    .width(200.),
+                )
+                .align_y(Alignment::Center);
+
+            // Limit Rule size to sum of all column widths
+            grid = grid.push(row![Rule::horizontal(1.)].width(800.));
+            grid = grid.push(row);
+        }
+
+        scrollable(grid).spacing(GLOBAL_SPACING).into()
+    };
+
+    let page_controls = Column::new()
+        .push(row![Rule::horizontal(1.)].width(800.))
+        .push(
+            text(format!(
+                "{} {}",
+                fl!("page").as_str(),
+                &pagination_state.current_page + 1
+            ))
+            .align_x(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .width(800.)
+                .push(
+                    button(
+                        text(fl!("back"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Back)),
+                )
+                .push(
+                    button(
+                        text(fl!("next"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Forward)),
+                )
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_x(Alignment::Center);
+
+    let content = container(
+        column![grid, page_controls]
+            .spacing(GLOBAL_SPACING)
+            .width(800.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
+fn list_header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let add_button = button(text(fl!("add")).center())
+        .on_press(Message::AskOpenUpsertScreen)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("modifier-groups")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        add_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+// UPSERT SCREEN
+
+fn upsert_screen<'a>(
+    modifier_group: &'a ModifierGroup,
+    products: &'a [Product],
+    product_categories: &'a [ProductCategory],
+) -> iced::Element<'a, Message> {
+    let header = upsert_header(modifier_group);
+
+    // Name
+    let name_label = text(format!("{}*", fl!("name"))).width(Length::Fill);
+    let name_input = text_input(fl!("name").as_str(), &modifier_group.name)
+        .on_input(|c| Message::TextInputUpdate(c, ModifierGroupTextInputFields::Name))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Applies to (a product or a whole category)
+    let applies_to_label = text(format!("{}*", fl!("applies-to"))).width(Length::Fill);
+    let selected_product = products.iter().find(|p| p.id == modifier_group.product_id);
+    let product_selector = pick_list(products, selected_product, |product| {
+        Message::UpdatedSelectedProductId(product.id.unwrap_or_default())
+    })
+    .placeholder(fl!("product"))
+    .width(Length::Fill);
+    let selected_category = product_categories
+        .iter()
+        .find(|c| c.id == modifier_group.category_id);
+    let category_selector = pick_list(product_categories, selected_category, |category| {
+        Message::UpdatedSelectedProductCategoryId(category.id.unwrap_or_default())
+    })
+    .placeholder(fl!("product-category"))
+    .width(Length::Fill);
+
+    // Modifiers
+    let modifiers_label = text(format!("{}*", fl!("modifiers"))).width(Length::Fill);
+    let mut modifiers_column = Column::new().spacing(GLOBAL_SPACING);
+    for (index, modifier) in modifier_group.modifiers.iter().enumerate() {
+        modifiers_column = modifiers_column.push(
+            row![
+                text_input(fl!("name").as_str(), &modifier.name)
+                    .on_input(move |c| Message::TextInputUpdate(
+                        c,
+                        ModifierGroupTextInputFields::ModifierName(index)
+                    ))
+                    .size(TEXT_SIZE)
+                    .width(Length::Fill),
+                text_input(fl!("price-delta").as_str(), &modifier.price_delta_input)
+                    .on_input(move |c| Message::TextInputUpdate(
+                        c,
+                        ModifierGroupTextInputFields::ModifierPriceDelta(index)
+                    ))
+                    .size(TEXT_SIZE)
+                    .width(200.),
+                button(text(fl!("delete")).center())
+                    .style(button::danger)
+                    .on_press(Message::RemoveModifier(index))
+            ]
+            .spacing(GLOBAL_SPACING)
+            .align_y(Alignment::Center),
+        );
+    }
+    let add_modifier_button =
+        button(text(fl!("add-modifier")).center()).on_press(Message::AddModifier);
+
+    // Submit
+    let submit_button_text = if modifier_group.id.is_some() {
+        text(fl!("edit"))
+    } else {
+        text(fl!("add"))
+    };
+    let submit_button = button(submit_button_text.center().size(TEXT_SIZE))
+        .on_press_maybe(
+            modifier_group
+                .is_valid()
+                .then_some(Message::UpsertCurrentModifierGroup),
+        )
+        .width(Length::Fill);
+
+    // Input Columns
+    let name_input_column = column![name_label, name_input].width(850.).spacing(1.);
+    let applies_to_column = column![
+        applies_to_label,
+        row![product_selector, category_selector].spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
+    let modifiers_input_column = column![modifiers_label, modifiers_column, add_modifier_button]
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    let form_column = Column::new()
+        .push(name_input_column)
+        .push(applies_to_column)
+        .push(modifiers_input_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        scrollable(
+            container(form_column)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center)
+                .width(Length::Fill)
+                .padding(50.)
+        )
+    ]
+    .into()
+}
+
+fn upsert_header<'a>(modifier_group: &'a ModifierGroup) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let delete_button = button(text(fl!("delete")).center())
+        .style(button::danger)
+        .on_press_maybe(
+            modifier_group
+                .id
+                .map(|_| Message::DeleteCurrentModifierGroup),
+        )
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("modifier-group")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        delete_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
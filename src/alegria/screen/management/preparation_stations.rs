@@ -0,0 +1,663 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{
+    alegria::{
+        core::{models::preparation_station::PreparationStation, print::AlegriaPrinter},
+        utils::pagination::{PaginationAction, PaginationConfig},
+        widgets::toast::Toast,
+    },
+    fl,
+};
+
+pub struct PreparationStations {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    List {
+        pagination_state: PaginationConfig,
+        preparation_stations: Vec<PreparationStation>,
+    },
+    Upsert {
+        preparation_station: Box<PreparationStation>,
+        printers: Vec<AlegriaPrinter>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum PreparationStationTextInputFields {
+    Name,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to update the current list of preparation-stations
+    FetchPreparationStations,
+    /// Callback after initial page loading, set's the preparation-stations list on the state
+    PageLoaded(Vec<PreparationStation>),
+
+    /// Try to go left or right a page
+    PaginationAction(PaginationAction),
+
+    /// Callback after asking to edit a preparation_station, searches the preparation_station on the db
+    AskEditPreparationStation(i32),
+    /// Changes the upsert screen, with a default PreparationStation and loads the system printers (intended for calling when we need to create a new preparation_station)
+    AskOpenUpsertScreen,
+    /// Changes the upsert screen with the given preparation-station and system printers
+    OpenUpsertScreen(Box<PreparationStation>, Vec<AlegriaPrinter>),
+
+    /// Callback when using the text inputs to add or edit a preparation-station
+    TextInputUpdate(String, PreparationStationTextInputFields),
+    /// Callback after selecting (or clearing) the printer of the current preparation-station
+    UpdatedSelectedPrinter(Option<String>),
+
+    /// Tries to Add or Edit the current preparation_station to the database
+    UpsertCurrentPreparationStation,
+    /// Callback after upserting the preparation-station on the database
+    UpsertedCurrentPreparationStation,
+    /// Tries to delete the current preparation-station
+    DeleteCurrentPreparationStation,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl PreparationStations {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(
+                PreparationStation::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            ),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match sub_screen {
+                        SubScreen::List { .. } => return Action::Back,
+                        SubScreen::Upsert { .. } => {
+                            return self.update(
+                                Message::FetchPreparationStations,
+                                &database.clone(),
+                                now,
+                            );
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { .. } = sub_screen {
+                        return match hotkey {
+                            Hotkey::Tab(modifiers) => {
+                                if modifiers.shift() {
+                                    Action::Run(focus_previous())
+                                } else {
+                                    Action::Run(focus_next())
+                                }
+                            }
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::FetchPreparationStations => Action::Run(Task::perform(
+                PreparationStation::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::PageLoaded(res) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::List {
+                        pagination_state: PaginationConfig::default(),
+                        preparation_stations: res,
+                    },
+                };
+                Action::None
+            }
+            Message::PaginationAction(pagination_action) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List {
+                        preparation_stations,
+                        pagination_state,
+                        ..
+                    } = sub_screen
+                    {
+                        match pagination_action {
+                            PaginationAction::Up => {}
+                            PaginationAction::Down => {}
+                            PaginationAction::Back => {
+                                if pagination_state.current_page > 0 {
+                                    pagination_state.current_page -= 1;
+                                }
+                            }
+                            PaginationAction::Forward => {
+                                let next_page_start = (pagination_state.current_page + 1)
+                                    * pagination_state.items_per_page;
+                                if next_page_start
+                                    < preparation_stations.len().try_into().unwrap_or_default()
+                                {
+                                    pagination_state.current_page += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AskEditPreparationStation(preparation_station_id) => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        let (preparation_station, (_default_printer, printers)) = tokio::join!(
+                            PreparationStation::get_single(
+                                database.clone(),
+                                preparation_station_id
+                            ),
+                            AlegriaPrinter::load_printers()
+                        );
+                        (preparation_station, printers)
+                    },
+                    |(preparation_station, printers)| match preparation_station {
+                        Ok(preparation_station) => {
+                            Message::OpenUpsertScreen(Box::from(preparation_station), printers)
+                        }
+                        Err(err) => {
+                            eprintln!("{err}");
+                            Message::AddToast(Toast::error_toast(err))
+                        }
+                    },
+                ))
+            }
+            Message::AskOpenUpsertScreen => Action::Run(Task::perform(
+                AlegriaPrinter::load_printers(),
+                |(_default_printer, printers)| {
+                    Message::OpenUpsertScreen(Box::from(PreparationStation::default()), printers)
+                },
+            )),
+            Message::OpenUpsertScreen(preparation_station, printers) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Upsert {
+                        preparation_station,
+                        printers,
+                    },
+                };
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        preparation_station,
+                        ..
+                    } = sub_screen
+                    {
+                        match field {
+                            PreparationStationTextInputFields::Name => {
+                                preparation_station.name = new_value
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedPrinter(printer_name) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        preparation_station,
+                        ..
+                    } = sub_screen
+                    {
+                        preparation_station.printer_name = printer_name;
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertCurrentPreparationStation => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        preparation_station,
+                        ..
+                    } = sub_screen
+                    {
+                        #[allow(clippy::collapsible_if)]
+                        if preparation_station.is_valid() {
+                            return match preparation_station.id {
+                                Some(_id) => Action::Run(Task::perform(
+                                    PreparationStation::edit(
+                                        database.clone(),
+                                        *preparation_station.clone(),
+                                    ),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentPreparationStation,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                                None => Action::Run(Task::perform(
+                                    PreparationStation::add(
+                                        database.clone(),
+                                        *preparation_station.clone(),
+                                    ),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentPreparationStation,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                            };
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertedCurrentPreparationStation => {
+                self.update(Message::FetchPreparationStations, &database.clone(), now)
+            }
+            Message::DeleteCurrentPreparationStation => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        preparation_station,
+                        ..
+                    } = sub_screen
+                    {
+                        return Action::Run(Task::perform(
+                            PreparationStation::delete(
+                                database.clone(),
+                                preparation_station.id.unwrap_or_default(),
+                            ),
+                            |res| match res {
+                                Ok(_) => Message::FetchPreparationStations,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => match sub_screen {
+                SubScreen::List {
+                    pagination_state,
+                    preparation_stations,
+                } => list_screen(pagination_state, preparation_stations),
+                SubScreen::Upsert {
+                    preparation_station,
+                    printers,
+                } => upsert_screen(preparation_station, printers),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+// LIST SCREEN
+
+fn list_screen<'a>(
+    pagination_state: &'a PaginationConfig,
+    preparation_stations: &'a [PreparationStation],
+) -> iced::Element<'a, Message> {
+    let header = list_header();
+    let grid: Element<'a, Message, Theme, Renderer> = if preparation_stations.is_empty() {
+        container(text(fl!("no-preparation-stations")).size(TITLE_TEXT_SIZE))
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(50.)
+            .into()
+    } else {
+        let title_row = Row::new()
+            .push(
+                text(fl!("name"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(400.)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                text(fl!("printer"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("edit"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::End),
+            )
+            .width(Length::Shrink)
+            .align_y(Alignment::Center);
+
+        // Calculate the indices for the current page
+        let start_index: usize =
+            pagination_state.current_page as usize * pagination_state.items_per_page as usize;
+        let end_index = usize::min(
+            start_index + pagination_state.items_per_page as usize,
+            preparation_stations.len(),
+        );
+
+        let mut grid = Column::new()
+            .push(title_row)
+            .spacing(GLOBAL_SPACING)
+            .width(Length::Shrink);
+
+        for preparation_station in &preparation_stations[start_index..end_index] {
+            let row = Row::new()
+                .push(
+                    text(&preparation_station.name)
+                        .size(TEXT_SIZE)
+                        .width(400.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(preparation_station.printer_name.as_deref().unwrap_or("-"))
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    row![
+                        Space::new(Length::Fill, Length::Shrink),
+                        button(text(fl!("edit")).size(TEXT_SIZE).align_y(Alignment::Center))
+                            .on_press(Message::AskEditPreparationStation(
+                                preparation_station.id.unwrap()
+                            ))
+                            .width(Length::Shrink)
+                    ]
+                    .width(200.),
+                )
+                .align_y(Alignment::Center);
+
+            // Limit Rule size to sum of all column widths
+            grid = grid.push(row![Rule::horizontal(1.)].width(800.));
+            grid = grid.push(row);
+        }
+
+        scrollable(grid).spacing(GLOBAL_SPACING).into()
+    };
+
+    let page_controls = Column::new()
+        .push(row![Rule::horizontal(1.)].width(800.))
+        .push(
+            text(format!(
+                "{} {}",
+                fl!("page").as_str(),
+                &pagination_state.current_page + 1
+            ))
+            .align_x(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .width(800.)
+                .push(
+                    button(
+                        text(fl!("back"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Back)),
+                )
+                .push(
+                    button(
+                        text(fl!("next"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Forward)),
+                )
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_x(Alignment::Center);
+
+    let content = container(
+        column![grid, page_controls]
+            .spacing(GLOBAL_SPACING)
+            .width(800.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
+fn list_header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let add_button = button(text(fl!("add")).center())
+        .on_press(Message::AskOpenUpsertScreen)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("preparation-stations")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        add_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+// UPSERT SCREEN
+
+fn upsert_screen<'a>(
+    preparation_station: &'a PreparationStation,
+    printers: &'a [AlegriaPrinter],
+) -> iced::Element<'a, Message> {
+    let header = upsert_header(preparation_station);
+
+    // Name
+    let name_label = text(format!("{}*", fl!("name"))).width(Length::Fill);
+    let name_input = text_input(fl!("name").as_str(), &preparation_station.name)
+        .on_input(|c| Message::TextInputUpdate(c, PreparationStationTextInputFields::Name))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Printer (the configured one may not be connected to this machine, it's shown as the placeholder)
+    let printer_label = text(fl!("printer")).width(Length::Fill);
+    let selected = printers.iter().find(|p| {
+        preparation_station
+            .printer_name
+            .as_deref()
+            .is_some_and(|name| p.to_string() == name)
+    });
+    let printer_selector = pick_list(printers, selected, |printer| {
+        Message::UpdatedSelectedPrinter(Some(printer.to_string()))
+    })
+    .placeholder(
+        preparation_station
+            .printer_name
+            .as_deref()
+            .unwrap_or_default(),
+    )
+    .width(Length::Fill);
+    let clear_printer_button = button(text(fl!("clear")).center()).on_press_maybe(
+        preparation_station
+            .printer_name
+            .as_ref()
+            .map(|_| Message::UpdatedSelectedPrinter(None)),
+    );
+
+    // Submit
+    let submit_button_text = if preparation_station.id.is_some() {
+        text(fl!("edit"))
+    } else {
+        text(fl!("add"))
+    };
+    let submit_button = button(submit_button_text.center().size(TEXT_SIZE))
+        .on_press_maybe(
+            preparation_station
+                .is_valid()
+                .then_some(Message::UpsertCurrentPreparationStation),
+        )
+        .width(Length::Fill);
+
+    // Input Columns
+    let name_input_column = column![name_label, name_input].width(850.).spacing(1.);
+    let printer_column = column![
+        printer_label,
+        row![printer_selector, clear_printer_button].spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
+
+    let form_column = Column::new()
+        .push(name_input_column)
+        .push(printer_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        container(form_column)
+            .align_x(Alignment::Center)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .padding(50.)
+    ]
+    .into()
+}
+
+fn upsert_header<'a>(preparation_station: &'a PreparationStation) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let delete_button = button(text(fl!("delete")).center())
+        .style(button::danger)
+        .on_press_maybe(
+            preparation_station
+                .id
+                .map(|_| Message::DeleteCurrentPreparationStation),
+        )
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("preparation-station")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        delete_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
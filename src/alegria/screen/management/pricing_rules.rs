@@ -0,0 +1,1106 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use chrono::{Datelike, Duration, Local, NaiveTime};
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::pricing_rule::{ALL_WEEKDAYS, PricingRule};
+use crate::alegria::utils::entities::price_adjustment::PriceAdjustment;
+use crate::alegria::utils::money::parse_decimal;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{
+    alegria::{
+        core::models::{
+            product::Product, product_category::ProductCategory, table_location::TableLocation,
+        },
+        utils::pagination::{PaginationAction, PaginationConfig},
+        widgets::toast::Toast,
+    },
+    fl,
+};
+
+pub struct PricingRules {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    List {
+        pagination_state: PaginationConfig,
+        pricing_rules: Vec<PricingRule>,
+        preview: PricePreview,
+    },
+    Upsert {
+        pricing_rule: Box<PricingRule>,
+        table_locations: Vec<TableLocation>,
+        products: Vec<Product>,
+        product_categories: Vec<ProductCategory>,
+    },
+}
+
+/// Product, location and moment the manager wants to know the price of (and which rule gives it)
+pub struct PricePreview {
+    products: Vec<Product>,
+    table_locations: Vec<TableLocation>,
+    product_id: Option<i32>,
+    location_id: Option<i32>,
+    /// Day of the week (0 is Monday)
+    weekday: u32,
+    time_input: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PricingRuleTextInputFields {
+    Name,
+    Priority,
+    StartTime,
+    EndTime,
+    AdjustmentValue,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to update the current list of pricing_rules
+    FetchPricingRules,
+    /// Callback after initial page loading, set's the pricing_rules list (and the products and locations for the preview) on the state
+    PageLoaded(Vec<PricingRule>, Vec<Product>, Vec<TableLocation>),
+
+    /// Try to go left or right a page
+    PaginationAction(PaginationAction),
+
+    /// Callback after selecting the product of the price preview
+    UpdatedPreviewProductId(Option<i32>),
+    /// Callback after selecting the location of the price preview
+    UpdatedPreviewLocationId(Option<i32>),
+    /// Callback after selecting the day of the week (0 is Monday) of the price preview
+    UpdatedPreviewWeekday(u32),
+    /// text_input of the time of the price preview
+    PreviewTimeInput(String),
+
+    /// Callback after asking to edit a pricing_rule, searches the pricing_rule on the db
+    AskEditPricingRule(i32),
+    /// Changes the upsert screen, with a default PricingRule and grabs the locations, products and categories (intended for calling when we need to create a new pricing_rule)
+    AskOpenUpsertScreen,
+    /// Changes the upsert screen with the given pricing_rule (we also need the locations, products and categories for the selectors)
+    OpenUpsertScreen(
+        Box<PricingRule>,
+        Vec<TableLocation>,
+        Vec<Product>,
+        Vec<ProductCategory>,
+    ),
+
+    /// Callback when using the text inputs to add or edit a pricing_rule
+    TextInputUpdate(String, PricingRuleTextInputFields),
+    /// Callback after selecting (or clearing) the location of the current pricing_rule
+    UpdatedSelectedLocationId(Option<i32>),
+    /// Callback after selecting (or clearing) the product the current pricing_rule applies to
+    UpdatedSelectedProductId(Option<i32>),
+    /// Callback after selecting (or clearing) the category the current pricing_rule applies to
+    UpdatedSelectedProductCategoryId(Option<i32>),
+    /// Adds or removes a day of the week (0 is Monday) of the current pricing_rule
+    ToggleWeekday(u32),
+    /// Callback after selecting how the current pricing_rule changes the price
+    UpdatedSelectedAdjustment(PriceAdjustment),
+
+    /// Tries to Add or Edit the current pricing_rule to the database
+    UpsertCurrentPricingRule,
+    /// Callback after upserting the pricing_rule on the database
+    UpsertedCurrentPricingRule,
+    /// Tries to delete the current pricing_rule
+    DeleteCurrentPricingRule,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl PricingRules {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            fetch_page(database),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match sub_screen {
+                        SubScreen::List { .. } => return Action::Back,
+                        SubScreen::Upsert { .. } => {
+                            return self.update(Message::FetchPricingRules, &database.clone(), now);
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { .. } = sub_screen {
+                        return match hotkey {
+                            Hotkey::Tab(modifiers) => {
+                                if modifiers.shift() {
+                                    Action::Run(focus_previous())
+                                } else {
+                                    Action::Run(focus_next())
+                                }
+                            }
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::FetchPricingRules => Action::Run(fetch_page(database)),
+            Message::PageLoaded(pricing_rules, products, table_locations) => {
+                let current_time = Local::now();
+                self.state = State::Ready {
+                    sub_screen: SubScreen::List {
+                        pagination_state: PaginationConfig::default(),
+                        pricing_rules,
+                        preview: PricePreview {
+                            product_id: products.first().and_then(|p| p.id),
+                            location_id: table_locations.first().and_then(|l| l.id),
+                            products,
+                            table_locations,
+                            weekday: current_time.weekday().num_days_from_monday(),
+                            time_input: current_time.format("%H:%M").to_string(),
+                        },
+                    },
+                };
+                Action::None
+            }
+            Message::PaginationAction(pagination_action) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List {
+                        pricing_rules,
+                        pagination_state,
+                        ..
+                    } = sub_screen
+                    {
+                        match pagination_action {
+                            PaginationAction::Up => {}
+                            PaginationAction::Down => {}
+                            PaginationAction::Back => {
+                                if pagination_state.current_page > 0 {
+                                    pagination_state.current_page -= 1;
+                                }
+                            }
+                            PaginationAction::Forward => {
+                                let next_page_start = (pagination_state.current_page + 1)
+                                    * pagination_state.items_per_page;
+                                if next_page_start
+                                    < pricing_rules.len().try_into().unwrap_or_default()
+                                {
+                                    pagination_state.current_page += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedPreviewProductId(product_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List { preview, .. } = sub_screen {
+                        preview.product_id = product_id;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedPreviewLocationId(location_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List { preview, .. } = sub_screen {
+                        preview.location_id = location_id;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedPreviewWeekday(weekday) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List { preview, .. } = sub_screen {
+                        preview.weekday = weekday;
+                    }
+                }
+                Action::None
+            }
+            Message::PreviewTimeInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List { preview, .. } = sub_screen {
+                        preview.time_input = new_value;
+                    }
+                }
+                Action::None
+            }
+            Message::AskEditPricingRule(pricing_rule_id) => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        tokio::join!(
+                            PricingRule::get_single(database.clone(), pricing_rule_id),
+                            TableLocation::get_all(database.clone()),
+                            Product::get_all(database.clone()),
+                            ProductCategory::get_all(database.clone())
+                        )
+                    },
+                    |res| match res {
+                        (
+                            Ok(pricing_rule),
+                            Ok(table_locations),
+                            Ok(products),
+                            Ok(product_categories),
+                        ) => Message::OpenUpsertScreen(
+                            Box::from(pricing_rule),
+                            table_locations,
+                            products,
+                            product_categories,
+                        ),
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching pricing rule, table locations, products or product categories",
+                        )),
+                    },
+                ))
+            }
+            Message::AskOpenUpsertScreen => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        tokio::join!(
+                            TableLocation::get_all(database.clone()),
+                            Product::get_all(database.clone()),
+                            ProductCategory::get_all(database.clone())
+                        )
+                    },
+                    |res| match res {
+                        (Ok(table_locations), Ok(products), Ok(product_categories)) => {
+                            Message::OpenUpsertScreen(
+                                Box::from(PricingRule::default()),
+                                table_locations,
+                                products,
+                                product_categories,
+                            )
+                        }
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching table locations, products or product categories",
+                        )),
+                    },
+                ))
+            }
+            Message::OpenUpsertScreen(
+                pricing_rule,
+                table_locations,
+                products,
+                product_categories,
+            ) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Upsert {
+                        pricing_rule,
+                        table_locations,
+                        products,
+                        product_categories,
+                    },
+                };
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        match field {
+                            PricingRuleTextInputFields::Name => pricing_rule.name = new_value,
+                            PricingRuleTextInputFields::Priority => {
+                                if let Ok(num) = new_value.parse::<i32>() {
+                                    pricing_rule.priority = Some(num);
+                                    pricing_rule.priority_input = new_value;
+                                } else if new_value.is_empty() || new_value == "-" {
+                                    pricing_rule.priority = None;
+                                    pricing_rule.priority_input = new_value;
+                                }
+                            }
+                            PricingRuleTextInputFields::StartTime => {
+                                // the time is only set once the input is a complete HH:MM
+                                pricing_rule.start_time =
+                                    NaiveTime::parse_from_str(new_value.trim(), "%H:%M").ok();
+                                pricing_rule.start_time_input = new_value;
+                            }
+                            PricingRuleTextInputFields::EndTime => {
+                                pricing_rule.end_time =
+                                    NaiveTime::parse_from_str(new_value.trim(), "%H:%M").ok();
+                                pricing_rule.end_time_input = new_value;
+                            }
+                            PricingRuleTextInputFields::AdjustmentValue => {
+                                if let Ok(num) = parse_decimal(&new_value) {
+                                    pricing_rule.adjustment_value = Some(num);
+                                    pricing_rule.adjustment_value_input = new_value;
+                                } else if new_value.is_empty() || new_value == "-" {
+                                    // a lone minus sign is the start of a discount
+                                    pricing_rule.adjustment_value = None;
+                                    pricing_rule.adjustment_value_input = new_value;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedLocationId(location_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        pricing_rule.location_id = location_id;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedProductId(product_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        // a rule applies either to a product, to a category or to every product
+                        pricing_rule.product_id = product_id;
+                        pricing_rule.category_id = None;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedProductCategoryId(category_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        pricing_rule.category_id = category_id;
+                        pricing_rule.product_id = None;
+                    }
+                }
+                Action::None
+            }
+            Message::ToggleWeekday(weekday) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        pricing_rule.weekdays ^= 1 << weekday;
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedAdjustment(adjustment) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        pricing_rule.adjustment = adjustment;
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertCurrentPricingRule => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        #[allow(clippy::collapsible_if)]
+                        if pricing_rule.is_valid() {
+                            return match pricing_rule.id {
+                                Some(_id) => Action::Run(Task::perform(
+                                    PricingRule::edit(database.clone(), *pricing_rule.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentPricingRule,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                                None => Action::Run(Task::perform(
+                                    PricingRule::add(database.clone(), *pricing_rule.clone()),
+                                    |res| match res {
+                                        Ok(_) => Message::UpsertedCurrentPricingRule,
+                                        Err(err) => {
+                                            eprintln!("{err}");
+                                            Message::AddToast(Toast::error_toast(err))
+                                        }
+                                    },
+                                )),
+                            };
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertedCurrentPricingRule => {
+                self.update(Message::FetchPricingRules, &database.clone(), now)
+            }
+            Message::DeleteCurrentPricingRule => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { pricing_rule, .. } = sub_screen {
+                        return Action::Run(Task::perform(
+                            PricingRule::delete(
+                                database.clone(),
+                                pricing_rule.id.unwrap_or_default(),
+                            ),
+                            |res| match res {
+                                Ok(_) => Message::FetchPricingRules,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => match sub_screen {
+                SubScreen::List {
+                    pagination_state,
+                    pricing_rules,
+                    preview,
+                } => list_screen(pagination_state, pricing_rules, preview),
+                SubScreen::Upsert {
+                    pricing_rule,
+                    table_locations,
+                    products,
+                    product_categories,
+                } => upsert_screen(pricing_rule, table_locations, products, product_categories),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Fetches the pricing rules (and the products and locations for the price preview)
+fn fetch_page(database: &Arc<Pool<Postgres>>) -> Task<Message> {
+    let database = database.clone();
+    Task::perform(
+        async move {
+            tokio::join!(
+                PricingRule::get_all(database.clone()),
+                Product::get_all(database.clone()),
+                TableLocation::get_all(database.clone())
+            )
+        },
+        |res| match res {
+            (Ok(pricing_rules), Ok(products), Ok(table_locations)) => {
+                Message::PageLoaded(pricing_rules, products, table_locations)
+            }
+            _ => Message::AddToast(Toast::error_toast(
+                "Error fetching pricing rules, products or table locations",
+            )),
+        },
+    )
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+/// Returns the name of the given day of the week (0 is Monday)
+fn weekday_name(weekday: u32) -> String {
+    match weekday {
+        0 => fl!("monday"),
+        1 => fl!("tuesday"),
+        2 => fl!("wednesday"),
+        3 => fl!("thursday"),
+        4 => fl!("friday"),
+        5 => fl!("saturday"),
+        _ => fl!("sunday"),
+    }
+}
+
+/// Returns a row with a button for each day of the week
+fn weekday_buttons<'a>(
+    is_selected: impl Fn(u32) -> bool,
+    on_press: impl Fn(u32) -> Message,
+) -> iced::Element<'a, Message> {
+    let mut weekdays_row = Row::new().spacing(GLOBAL_SPACING);
+    for weekday in 0..7 {
+        weekdays_row = weekdays_row.push(
+            button(text(weekday_name(weekday)).center())
+                .on_press(on_press(weekday))
+                .style(if is_selected(weekday) {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .width(Length::Fill),
+        );
+    }
+    weekdays_row.into()
+}
+
+// LIST SCREEN
+
+fn list_screen<'a>(
+    pagination_state: &'a PaginationConfig,
+    pricing_rules: &'a [PricingRule],
+    preview: &'a PricePreview,
+) -> iced::Element<'a, Message> {
+    let header = list_header();
+    let grid: Element<'a, Message, Theme, Renderer> = if pricing_rules.is_empty() {
+        container(text(fl!("no-pricing-rules")).size(TITLE_TEXT_SIZE))
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(50.)
+            .into()
+    } else {
+        let title_row = Row::new()
+            .push(
+                text(fl!("name"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                text(fl!("applies-to"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(250.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("schedule"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("price"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(100.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("edit"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(150.)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::End),
+            )
+            .width(Length::Shrink)
+            .align_y(Alignment::Center);
+
+        // Calculate the indices for the current page
+        let start_index: usize =
+            pagination_state.current_page as usize * pagination_state.items_per_page as usize;
+        let end_index = usize::min(
+            start_index + pagination_state.items_per_page as usize,
+            pricing_rules.len(),
+        );
+
+        let mut grid = Column::new()
+            .push(title_row)
+            .spacing(GLOBAL_SPACING)
+            .width(Length::Shrink);
+
+        for pricing_rule in &pricing_rules[start_index..end_index] {
+            let row = Row::new()
+                .push(
+                    text(&pricing_rule.name)
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(target_label(pricing_rule))
+                        .size(TEXT_SIZE)
+                        .width(250.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(schedule_label(pricing_rule))
+                        .size(TEXT_SIZE)
+                        .width(200.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(adjustment_label(pricing_rule))
+                        .size(TEXT_SIZE)
+                        .width(100.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    row![
+                        Space::new(Length::Fill, Length::Shrink),
+                        button(text(fl!("edit")).size(TEXT_SIZE).align_y(Alignment::Center))
+                            .on_press(Message::AskEditPricingRule(pricing_rule.id.unwrap()))
+                            .width(Length::Shrink)
+                    ]
+                    .width(150.),
+                )
+                .align_y(Alignment::Center);
+
+            // Limit Rule size to sum of all column widths
+            grid = grid.push(row![Rule::horizontal(1.)].width(900.));
+            grid = grid.push(row);
+        }
+
+        scrollable(grid).spacing(GLOBAL_SPACING).into()
+    };
+
+    let page_controls = Column::new()
+        .push(row![Rule::horizontal(1.)].width(900.))
+        .push(
+            text(format!(
+                "{} {}",
+                fl!("page").as_str(),
+                &pagination_state.current_page + 1
+            ))
+            .align_x(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .width(900.)
+                .push(
+                    button(
+                        text(fl!("back"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Back)),
+                )
+                .push(
+                    button(
+                        text(fl!("next"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Forward)),
+                )
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_x(Alignment::Center);
+
+    let content = container(
+        column![grid, page_controls, preview_view(pricing_rules, preview)]
+            .spacing(GLOBAL_SPACING)
+            .width(900.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
+fn list_header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let add_button = button(text(fl!("add")).center())
+        .on_press(Message::AskOpenUpsertScreen)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("pricing-rules")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        add_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// Returns the location and the product (or category) a rule applies to
+fn target_label(pricing_rule: &PricingRule) -> String {
+    let location = if pricing_rule.location_id.is_some() {
+        pricing_rule.location_name.to_string()
+    } else {
+        fl!("all-locations")
+    };
+    let target = if pricing_rule.product_id.is_some() || pricing_rule.category_id.is_some() {
+        pricing_rule.target_name.to_string()
+    } else {
+        fl!("all-products")
+    };
+
+    format!("{location} - {target}")
+}
+
+/// Returns the days and hours a rule applies on
+fn schedule_label(pricing_rule: &PricingRule) -> String {
+    let days = if pricing_rule.weekdays & ALL_WEEKDAYS == ALL_WEEKDAYS {
+        fl!("every-day")
+    } else {
+        (0..7)
+            .filter(|weekday| pricing_rule.weekdays & (1 << weekday) != 0)
+            .map(|weekday| weekday_name(weekday).chars().take(3).collect::<String>())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    match (pricing_rule.start_time, pricing_rule.end_time) {
+        (Some(start), Some(end)) => {
+            format!("{days} {}-{}", start.format("%H:%M"), end.format("%H:%M"))
+        }
+        _ => days,
+    }
+}
+
+/// Returns how a rule changes the price
+fn adjustment_label(pricing_rule: &PricingRule) -> String {
+    let value = pricing_rule.adjustment_value.unwrap_or_default();
+    match pricing_rule.adjustment {
+        PriceAdjustment::FixedPrice => format!("{value:.2}€"),
+        PriceAdjustment::Percentage if value >= Decimal::ZERO => {
+            format!("+{}%", value.normalize())
+        }
+        PriceAdjustment::Percentage => format!("{}%", value.normalize()),
+    }
+}
+
+/// Returns the view of the price preview, it shows the price a product would have on a location at a given moment
+/// and the rule that gives it (the same way the bar resolves it when the product is added to a ticket)
+fn preview_view<'a>(
+    pricing_rules: &'a [PricingRule],
+    preview: &'a PricePreview,
+) -> iced::Element<'a, Message> {
+    let selected_product = preview
+        .products
+        .iter()
+        .find(|p| p.id.is_some() && p.id == preview.product_id);
+    let product_selector = pick_list(preview.products.as_slice(), selected_product, |product| {
+        Message::UpdatedPreviewProductId(product.id)
+    })
+    .placeholder(fl!("product"))
+    .width(Length::Fill);
+
+    let selected_location = preview
+        .table_locations
+        .iter()
+        .find(|l| l.id.is_some() && l.id == preview.location_id);
+    let location_selector = pick_list(
+        preview.table_locations.as_slice(),
+        selected_location,
+        |location| Message::UpdatedPreviewLocationId(location.id),
+    )
+    .placeholder(fl!("table-location"))
+    .width(Length::Fill);
+
+    let time_input = text_input("HH:MM", &preview.time_input)
+        .on_input(Message::PreviewTimeInput)
+        .size(TEXT_SIZE)
+        .width(100.);
+
+    let time = NaiveTime::parse_from_str(preview.time_input.trim(), "%H:%M").ok();
+    let result = match (selected_product, selected_location, time) {
+        (Some(product), Some(location), Some(time)) => {
+            // the preview is done on the selected day of the current week
+            let today = Local::now().date_naive();
+            let date = today - Duration::days(today.weekday().num_days_from_monday().into())
+                + Duration::days(preview.weekday.into());
+
+            let (price, rule) = PricingRule::resolve(
+                pricing_rules,
+                product,
+                location.id.unwrap_or_default(),
+                location.inside,
+                date.and_time(time),
+            );
+            let price = price
+                .map(|p| format!("{p:.2}€"))
+                .unwrap_or_else(|| fl!("unknown"));
+            let rule = rule
+                .map(|r| r.name.clone())
+                .unwrap_or_else(|| fl!("no-pricing-rule"));
+
+            format!(
+                "{}: {price} ({}: {rule})",
+                fl!("price"),
+                fl!("applied-rule")
+            )
+        }
+        _ => fl!("select-preview"),
+    };
+
+    column![
+        text(fl!("price-preview")).size(TITLE_TEXT_SIZE),
+        row![product_selector, location_selector, time_input].spacing(GLOBAL_SPACING),
+        weekday_buttons(
+            |weekday| weekday == preview.weekday,
+            Message::UpdatedPreviewWeekday
+        ),
+        text(result).size(TEXT_SIZE),
+    ]
+    .spacing(GLOBAL_SPACING)
+    .width(900.)
+    .into()
+}
+
+// UPSERT SCREEN
+
+fn upsert_screen<'a>(
+    pricing_rule: &'a PricingRule,
+    table_locations: &'a [TableLocation],
+    products: &'a [Product],
+    product_categories: &'a [ProductCategory],
+) -> iced::Element<'a, Message> {
+    let header = upsert_header(pricing_rule);
+
+    // Name
+    let name_label = text(format!("{}*", fl!("name"))).width(Length::Fill);
+    let name_input = text_input(fl!("name").as_str(), &pricing_rule.name)
+        .on_input(|c| Message::TextInputUpdate(c, PricingRuleTextInputFields::Name))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Priority
+    let priority_label = text(format!("{}*", fl!("priority"))).width(Length::Fill);
+    let priority_input = text_input(fl!("priority").as_str(), &pricing_rule.priority_input)
+        .on_input(|c| Message::TextInputUpdate(c, PricingRuleTextInputFields::Priority))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Location (none means every location)
+    let location_label = text(fl!("table-location")).width(Length::Fill);
+    let selected_location = table_locations
+        .iter()
+        .find(|l| l.id == pricing_rule.location_id);
+    let location_selector = pick_list(table_locations, selected_location, |location| {
+        Message::UpdatedSelectedLocationId(location.id)
+    })
+    .placeholder(fl!("all-locations"))
+    .width(Length::Fill);
+    let clear_location_button = button(text(fl!("clear")).center()).on_press_maybe(
+        pricing_rule
+            .location_id
+            .map(|_| Message::UpdatedSelectedLocationId(None)),
+    );
+
+    // Applies to (a product, a whole category or every product)
+    let applies_to_label = text(fl!("applies-to")).width(Length::Fill);
+    let selected_product = products.iter().find(|p| p.id == pricing_rule.product_id);
+    let product_selector = pick_list(products, selected_product, |product| {
+        Message::UpdatedSelectedProductId(product.id)
+    })
+    .placeholder(fl!("product"))
+    .width(Length::Fill);
+    let selected_category = product_categories
+        .iter()
+        .find(|c| c.id == pricing_rule.category_id);
+    let category_selector = pick_list(product_categories, selected_category, |category| {
+        Message::UpdatedSelectedProductCategoryId(category.id)
+    })
+    .placeholder(fl!("product-category"))
+    .width(Length::Fill);
+    let clear_target_button = button(text(fl!("clear")).center()).on_press_maybe(
+        (pricing_rule.product_id.is_some() || pricing_rule.category_id.is_some())
+            .then_some(Message::UpdatedSelectedProductId(None)),
+    );
+
+    // Days and time window (empty means the whole day)
+    let weekdays_label = text(format!("{}*", fl!("weekdays"))).width(Length::Fill);
+    let time_label = text(fl!("time-window")).width(Length::Fill);
+    let start_time_input = text_input("HH:MM", &pricing_rule.start_time_input)
+        .on_input(|c| Message::TextInputUpdate(c, PricingRuleTextInputFields::StartTime))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+    let end_time_input = text_input("HH:MM", &pricing_rule.end_time_input)
+        .on_input(|c| Message::TextInputUpdate(c, PricingRuleTextInputFields::EndTime))
+        .size(TEXT_SIZE)
+        .width(Length::Fill);
+
+    // Adjustment
+    let adjustment_label = text(format!("{}*", fl!("price"))).width(Length::Fill);
+    let adjustment_selector = pick_list(
+        PriceAdjustment::ALL,
+        Some(pricing_rule.adjustment),
+        Message::UpdatedSelectedAdjustment,
+    )
+    .width(Length::Fill);
+    let adjustment_value_input = text_input(
+        match pricing_rule.adjustment {
+            PriceAdjustment::FixedPrice => "€",
+            PriceAdjustment::Percentage => "%",
+        },
+        &pricing_rule.adjustment_value_input,
+    )
+    .on_input(|c| Message::TextInputUpdate(c, PricingRuleTextInputFields::AdjustmentValue))
+    .size(TEXT_SIZE)
+    .width(Length::Fill);
+
+    // Submit
+    let submit_button_text = if pricing_rule.id.is_some() {
+        text(fl!("edit"))
+    } else {
+        text(fl!("add"))
+    };
+    let submit_button = button(submit_button_text.center().size(TEXT_SIZE))
+        .on_press_maybe(
+            pricing_rule
+                .is_valid()
+                .then_some(Message::UpsertCurrentPricingRule),
+        )
+        .width(Length::Fill);
+
+    // Input Columns
+    let name_input_column = column![name_label, name_input].width(850.).spacing(1.);
+    let priority_input_column = column![priority_label, priority_input]
+        .width(850.)
+        .spacing(1.);
+    let location_column = column![
+        location_label,
+        row![location_selector, clear_location_button].spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
+    let applies_to_column = column![
+        applies_to_label,
+        row![product_selector, category_selector, clear_target_button].spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
+    let weekdays_column = column![
+        weekdays_label,
+        weekday_buttons(
+            |weekday| pricing_rule.weekdays & (1 << weekday) != 0,
+            Message::ToggleWeekday
+        )
+    ]
+    .width(850.)
+    .spacing(1.);
+    let time_column = column![
+        time_label,
+        row![start_time_input, end_time_input].spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
+    let adjustment_column = column![
+        adjustment_label,
+        row![adjustment_selector, adjustment_value_input].spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
+
+    let form_column = Column::new()
+        .push(name_input_column)
+        .push(priority_input_column)
+        .push(location_column)
+        .push(applies_to_column)
+        .push(weekdays_column)
+        .push(time_column)
+        .push(adjustment_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        scrollable(
+            container(form_column)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center)
+                .width(Length::Fill)
+                .padding(50.)
+        )
+    ]
+    .into()
+}
+
+fn upsert_header<'a>(pricing_rule: &'a PricingRule) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let delete_button = button(text(fl!("delete")).center())
+        .style(button::danger)
+        .on_press_maybe(pricing_rule.id.map(|_| Message::DeleteCurrentPricingRule))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("pricing-rule")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        delete_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
@@ -6,8 +6,8 @@ use iced::keyboard::key::Named;
 use iced::keyboard::{self, Key, Modifiers};
 use iced::time::Instant;
 use iced::widget::{
-    Column, Row, Rule, Space, button, column, focus_next, focus_previous, row, scrollable, text,
-    text_input,
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
 };
 use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
 use iced::{Task, widget::container};
@@ -19,7 +19,9 @@ use crate::alegria::utils::styling::{
 
 use crate::{
     alegria::{
-        core::models::product_category::ProductCategory,
+        core::models::{
+            preparation_station::PreparationStation, product_category::ProductCategory,
+        },
         utils::pagination::{PaginationAction, PaginationConfig},
         widgets::toast::Toast,
     },
@@ -42,6 +44,7 @@ pub enum SubScreen {
     },
     Upsert {
         product_category: Box<ProductCategory>,
+        preparation_stations: Vec<PreparationStation>,
     },
 }
 
@@ -69,11 +72,15 @@ pub enum Message {
 
     /// Callback after asking to edit a product_category, searches the product_category on the db
     AskEditProductCategory(i32),
-    /// Changes the upsert screen with the given product-category
-    OpenUpsertScreen(Box<ProductCategory>),
+    /// Changes the upsert screen, with a default ProductCategory and grabs the preparation_stations (intended for calling when we need to create a new product_category)
+    AskOpenUpsertScreen,
+    /// Changes the upsert screen with the given product-category and preparation stations
+    OpenUpsertScreen(Box<ProductCategory>, Vec<PreparationStation>),
 
     /// Callback when using the text inputs to add or edit a client
     TextInputUpdate(String, ProductCategoryTextInputFields),
+    /// Callback after selecting (or clearing) the PreparationStationId for the current product_category
+    UpdatedSelectedPreparationStationId(Option<i32>),
 
     /// Tries to Add or Edit the current product_category to the database
     UpsertCurrentProductCategory,
@@ -200,19 +207,50 @@ impl ProductCategories {
                 }
                 Action::None
             }
-            Message::AskEditProductCategory(product_category_id) => Action::Run(Task::perform(
-                ProductCategory::get_single(database.clone(), product_category_id),
+            Message::AskEditProductCategory(product_category_id) => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        let (product_category, preparation_stations) = tokio::join!(
+                            ProductCategory::get_single(database.clone(), product_category_id),
+                            PreparationStation::get_all(database.clone())
+                        );
+                        (product_category, preparation_stations)
+                    },
+                    |(product_category, preparation_stations)| match (
+                        product_category,
+                        preparation_stations,
+                    ) {
+                        (Ok(product_category), Ok(preparation_stations)) => {
+                            Message::OpenUpsertScreen(
+                                Box::from(product_category),
+                                preparation_stations,
+                            )
+                        }
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching product category or preparation stations",
+                        )),
+                    },
+                ))
+            }
+            Message::AskOpenUpsertScreen => Action::Run(Task::perform(
+                PreparationStation::get_all(database.clone()),
                 |res| match res {
-                    Ok(res) => Message::OpenUpsertScreen(Box::from(res)),
+                    Ok(res) => {
+                        Message::OpenUpsertScreen(Box::from(ProductCategory::default()), res)
+                    }
                     Err(err) => {
                         eprintln!("{err}");
                         Message::AddToast(Toast::error_toast(err))
                     }
                 },
             )),
-            Message::OpenUpsertScreen(product_category) => {
+            Message::OpenUpsertScreen(product_category, preparation_stations) => {
                 self.state = State::Ready {
-                    sub_screen: SubScreen::Upsert { product_category },
+                    sub_screen: SubScreen::Upsert {
+                        product_category,
+                        preparation_stations,
+                    },
                 };
                 Action::None
             }
@@ -232,6 +270,18 @@ impl ProductCategories {
                 }
                 Action::None
             }
+            Message::UpdatedSelectedPreparationStationId(preparation_station_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        product_category, ..
+                    } = sub_screen
+                    {
+                        product_category.preparation_station_id = preparation_station_id;
+                    }
+                }
+                Action::None
+            }
             Message::UpsertCurrentProductCategory => {
                 if let State::Ready { sub_screen, .. } = &mut self.state {
                     #[allow(clippy::collapsible_match)]
@@ -312,7 +362,10 @@ impl ProductCategories {
                     pagination_state,
                     product_categories,
                 } => list_screen(pagination_state, product_categories),
-                SubScreen::Upsert { product_category } => upsert_screen(product_category),
+                SubScreen::Upsert {
+                    product_category,
+                    preparation_stations,
+                } => upsert_screen(product_category, preparation_stations),
             },
         }
     }
@@ -479,9 +532,7 @@ fn list_header<'a>() -> iced::Element<'a, Message> {
         .height(GLOBAL_BUTTON_HEIGHT);
 
     let add_button = button(text(fl!("add")).center())
-        .on_press(Message::OpenUpsertScreen(Box::from(
-            ProductCategory::default(),
-        )))
+        .on_press(Message::AskOpenUpsertScreen)
         .height(GLOBAL_BUTTON_HEIGHT);
 
     row![
@@ -498,7 +549,10 @@ fn list_header<'a>() -> iced::Element<'a, Message> {
 
 // UPSERT SCREEN
 
-fn upsert_screen<'a>(product_category: &'a ProductCategory) -> iced::Element<'a, Message> {
+fn upsert_screen<'a>(
+    product_category: &'a ProductCategory,
+    preparation_stations: &'a [PreparationStation],
+) -> iced::Element<'a, Message> {
     let header = upsert_header(product_category);
 
     // Name
@@ -508,6 +562,22 @@ fn upsert_screen<'a>(product_category: &'a ProductCategory) -> iced::Element<'a,
         .size(TEXT_SIZE)
         .width(Length::Fill);
 
+    // Preparation Station
+    let preparation_station_label = text(fl!("preparation-station")).width(Length::Fill);
+    let selected = preparation_stations
+        .iter()
+        .find(|ps| ps.id == product_category.preparation_station_id);
+    let preparation_station_selector =
+        pick_list(preparation_stations, selected, |preparation_station| {
+            Message::UpdatedSelectedPreparationStationId(preparation_station.id)
+        })
+        .width(Length::Fill);
+    let clear_preparation_station_button = button(text(fl!("clear")).center()).on_press_maybe(
+        product_category
+            .preparation_station_id
+            .map(|_| Message::UpdatedSelectedPreparationStationId(None)),
+    );
+
     // Submit
     let submit_button_text = if product_category.id.is_some() {
         text(fl!("edit"))
@@ -524,9 +594,20 @@ fn upsert_screen<'a>(product_category: &'a ProductCategory) -> iced::Element<'a,
 
     // Input Columns
     let name_input_column = column![name_label, name_input].width(850.).spacing(1.);
+    let preparation_station_column = column![
+        preparation_station_label,
+        row![
+            preparation_station_selector,
+            clear_preparation_station_button
+        ]
+        .spacing(GLOBAL_SPACING)
+    ]
+    .width(850.)
+    .spacing(1.);
 
     let form_column = Column::new()
         .push(name_input_column)
+        .push(preparation_station_column)
         .push(submit_button)
         .width(850.)
         .spacing(GLOBAL_SPACING);
@@ -11,9 +11,11 @@ use iced::widget::{
 };
 use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
 use iced::{Task, widget::container};
+use rust_decimal::Decimal;
 use sqlx::{Pool, Postgres};
 
 use crate::alegria::core::models::product::Product;
+use crate::alegria::utils::money::{Money, parse_decimal};
 use crate::alegria::utils::styling::{
     GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
 };
@@ -53,6 +55,7 @@ pub enum ProductTextInputFields {
     InsidePrice,
     OutsidePrice,
     TaxPercentage,
+    LowStockThreshold,
 }
 
 #[derive(Debug, Clone)]
@@ -269,11 +272,11 @@ impl Products {
                                     });
 
                                 if !ignore_action {
-                                    if let Ok(num) = new_value.parse::<f32>() {
+                                    if let Ok(num) = new_value.parse::<Money>() {
                                         product.inside_price = Some(num);
                                         product.inside_price_input = new_value;
                                     } else if new_value.is_empty() {
-                                        product.inside_price = Some(0.0);
+                                        product.inside_price = Some(Money::ZERO);
                                         product.inside_price_input = new_value;
                                     }
                                 }
@@ -287,11 +290,11 @@ impl Products {
                                     });
 
                                 if !ignore_action {
-                                    if let Ok(num) = new_value.parse::<f32>() {
+                                    if let Ok(num) = new_value.parse::<Money>() {
                                         product.outside_price = Some(num);
                                         product.outside_price_input = new_value;
                                     } else if new_value.is_empty() {
-                                        product.outside_price = Some(0.0);
+                                        product.outside_price = Some(Money::ZERO);
                                         product.outside_price_input = new_value;
                                     }
                                 }
@@ -305,15 +308,25 @@ impl Products {
                                     });
 
                                 if !ignore_action {
-                                    if let Ok(num) = new_value.parse::<f32>() {
+                                    if let Ok(num) = parse_decimal(&new_value) {
                                         product.tax_percentage = Some(num);
                                         product.tax_percentage_input = new_value;
                                     } else if new_value.is_empty() {
-                                        product.tax_percentage = Some(0.0);
+                                        product.tax_percentage = Some(Decimal::ZERO);
                                         product.tax_percentage_input = new_value;
                                     }
                                 }
                             }
+                            ProductTextInputFields::LowStockThreshold => {
+                                // an empty threshold means we never warn about the stock of the product
+                                if let Ok(num) = new_value.parse::<i32>() {
+                                    product.low_stock_threshold = Some(num);
+                                    product.low_stock_threshold_input = new_value;
+                                } else if new_value.is_empty() {
+                                    product.low_stock_threshold = None;
+                                    product.low_stock_threshold_input = new_value;
+                                }
+                            }
                         }
                     }
                 }
@@ -654,6 +667,16 @@ fn upsert_screen<'a>(
     .size(TEXT_SIZE)
     .width(Length::Fill);
 
+    // Low Stock Threshold
+    let low_stock_threshold_label = text(fl!("low-stock-threshold")).width(Length::Fill);
+    let low_stock_threshold_input = text_input(
+        fl!("low-stock-threshold").as_str(),
+        &product.low_stock_threshold_input,
+    )
+    .on_input(|c| Message::TextInputUpdate(c, ProductTextInputFields::LowStockThreshold))
+    .size(TEXT_SIZE)
+    .width(Length::Fill);
+
     // Submit
     let submit_button_text = if product.id.is_some() {
         text(fl!("edit"))
@@ -678,6 +701,10 @@ fn upsert_screen<'a>(
     let tax_input_column = column![tax_percentage_label, tax_percentage_input]
         .width(850.)
         .spacing(1.);
+    let low_stock_threshold_input_column =
+        column![low_stock_threshold_label, low_stock_threshold_input]
+            .width(850.)
+            .spacing(1.);
 
     let form_column = Column::new()
         .push(name_input_column)
@@ -685,6 +712,7 @@ fn upsert_screen<'a>(
         .push(inside_price_input_column)
         .push(outside_price_input_column)
         .push(tax_input_column)
+        .push(low_stock_threshold_input_column)
         .push(submit_button)
         .width(850.)
         .spacing(GLOBAL_SPACING);
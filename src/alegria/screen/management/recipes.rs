@@ -0,0 +1,706 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::ingredient::Ingredient;
+use crate::alegria::core::models::recipe::{Recipe, RecipeIngredient};
+use crate::alegria::utils::money::parse_decimal;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+
+use crate::{
+    alegria::{
+        core::models::product::Product,
+        utils::pagination::{PaginationAction, PaginationConfig},
+        widgets::toast::Toast,
+    },
+    fl,
+};
+
+pub struct Recipes {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready { sub_screen: SubScreen },
+}
+
+pub enum SubScreen {
+    List {
+        pagination_state: PaginationConfig,
+        recipes: Vec<Recipe>,
+    },
+    Upsert {
+        recipe: Box<Recipe>,
+        /// True if we're editing an already existing recipe (it's product can't be changed)
+        editing: bool,
+        products: Vec<Product>,
+        ingredients: Vec<Ingredient>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum RecipeTextInputFields {
+    /// Quantity of the ingredient on the given index
+    IngredientQuantity(usize),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to update the current list of recipes
+    FetchRecipes,
+    /// Callback after initial page loading, set's the recipes list on the state
+    PageLoaded(Vec<Recipe>),
+
+    /// Try to go left or right a page
+    PaginationAction(PaginationAction),
+
+    /// Callback after asking to edit the recipe of a product, searches the recipe on the db
+    AskEditRecipe(i32),
+    /// Changes the upsert screen, with a default Recipe and grabs the products and ingredients (intended for calling when we need to create a new recipe)
+    AskOpenUpsertScreen,
+    /// Changes the upsert screen with the given recipe (we also need the products and ingredients for the selectors)
+    OpenUpsertScreen(Box<Recipe>, bool, Vec<Product>, Vec<Ingredient>),
+
+    /// Callback when using the text inputs to add or edit a recipe
+    TextInputUpdate(String, RecipeTextInputFields),
+    /// Callback after selecting the product of the current recipe
+    UpdatedSelectedProductId(i32),
+    /// Callback after selecting the ingredient on the given index of the current recipe
+    UpdatedSelectedIngredientId(usize, i32),
+    /// Adds an empty ingredient to the current recipe
+    AddIngredient,
+    /// Removes the ingredient on the given index from the current recipe
+    RemoveIngredient(usize),
+
+    /// Tries to save the current recipe to the database
+    UpsertCurrentRecipe,
+    /// Callback after saving the recipe on the database
+    UpsertedCurrentRecipe,
+    /// Tries to delete the current recipe
+    DeleteCurrentRecipe,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl Recipes {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            Task::perform(Recipe::get_all(database.clone()), |res| match res {
+                Ok(res) => Message::PageLoaded(res),
+                Err(err) => {
+                    eprintln!("{err}");
+                    Message::AddToast(Toast::error_toast(err))
+                }
+            }),
+        )
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    match sub_screen {
+                        SubScreen::List { .. } => return Action::Back,
+                        SubScreen::Upsert { .. } => {
+                            return self.update(Message::FetchRecipes, &database.clone(), now);
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { .. } = sub_screen {
+                        return match hotkey {
+                            Hotkey::Tab(modifiers) => {
+                                if modifiers.shift() {
+                                    Action::Run(focus_previous())
+                                } else {
+                                    Action::Run(focus_next())
+                                }
+                            }
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::FetchRecipes => Action::Run(Task::perform(
+                Recipe::get_all(database.clone()),
+                |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::PageLoaded(res) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::List {
+                        pagination_state: PaginationConfig::default(),
+                        recipes: res,
+                    },
+                };
+                Action::None
+            }
+            Message::PaginationAction(pagination_action) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::List {
+                        recipes,
+                        pagination_state,
+                        ..
+                    } = sub_screen
+                    {
+                        match pagination_action {
+                            PaginationAction::Up => {}
+                            PaginationAction::Down => {}
+                            PaginationAction::Back => {
+                                if pagination_state.current_page > 0 {
+                                    pagination_state.current_page -= 1;
+                                }
+                            }
+                            PaginationAction::Forward => {
+                                let next_page_start = (pagination_state.current_page + 1)
+                                    * pagination_state.items_per_page;
+                                if next_page_start < recipes.len().try_into().unwrap_or_default() {
+                                    pagination_state.current_page += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::AskEditRecipe(product_id) => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        tokio::join!(
+                            Recipe::get_single(database.clone(), product_id),
+                            Product::get_all(database.clone()),
+                            Ingredient::get_all(database.clone())
+                        )
+                    },
+                    |res| match res {
+                        (Ok(recipe), Ok(products), Ok(ingredients)) => Message::OpenUpsertScreen(
+                            Box::from(recipe),
+                            true,
+                            products,
+                            ingredients,
+                        ),
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching recipe, products or ingredients",
+                        )),
+                    },
+                ))
+            }
+            Message::AskOpenUpsertScreen => {
+                let database = database.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        tokio::join!(
+                            Product::get_all(database.clone()),
+                            Ingredient::get_all(database.clone())
+                        )
+                    },
+                    |res| match res {
+                        (Ok(products), Ok(ingredients)) => Message::OpenUpsertScreen(
+                            Box::from(Recipe {
+                                ingredients: vec![RecipeIngredient::default()],
+                                ..Default::default()
+                            }),
+                            false,
+                            products,
+                            ingredients,
+                        ),
+                        _ => Message::AddToast(Toast::error_toast(
+                            "Error fetching products or ingredients",
+                        )),
+                    },
+                ))
+            }
+            Message::OpenUpsertScreen(recipe, editing, products, ingredients) => {
+                self.state = State::Ready {
+                    sub_screen: SubScreen::Upsert {
+                        recipe,
+                        editing,
+                        products,
+                        ingredients,
+                    },
+                };
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { recipe, .. } = sub_screen {
+                        match field {
+                            RecipeTextInputFields::IngredientQuantity(index) => {
+                                if let Some(ingredient) = recipe.ingredients.get_mut(index) {
+                                    if let Ok(num) = parse_decimal(&new_value) {
+                                        ingredient.quantity = Some(num);
+                                        ingredient.quantity_input = new_value;
+                                    } else if new_value.is_empty() {
+                                        ingredient.quantity = None;
+                                        ingredient.quantity_input = new_value;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedProductId(product_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert {
+                        recipe, editing, ..
+                    } = sub_screen
+                        && !*editing
+                    {
+                        recipe.product_id = Some(product_id);
+                    }
+                }
+                Action::None
+            }
+            Message::UpdatedSelectedIngredientId(index, ingredient_id) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { recipe, .. } = sub_screen
+                        && let Some(ingredient) = recipe.ingredients.get_mut(index)
+                    {
+                        ingredient.ingredient_id = Some(ingredient_id);
+                    }
+                }
+                Action::None
+            }
+            Message::AddIngredient => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { recipe, .. } = sub_screen {
+                        recipe.ingredients.push(RecipeIngredient::default());
+                    }
+                }
+                Action::None
+            }
+            Message::RemoveIngredient(index) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { recipe, .. } = sub_screen
+                        && index < recipe.ingredients.len()
+                    {
+                        recipe.ingredients.remove(index);
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertCurrentRecipe => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { recipe, .. } = sub_screen
+                        && recipe.is_valid()
+                    {
+                        return Action::Run(Task::perform(
+                            Recipe::save(database.clone(), *recipe.clone()),
+                            |res| match res {
+                                Ok(_) => Message::UpsertedCurrentRecipe,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+            Message::UpsertedCurrentRecipe => {
+                self.update(Message::FetchRecipes, &database.clone(), now)
+            }
+            Message::DeleteCurrentRecipe => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Upsert { recipe, .. } = sub_screen {
+                        return Action::Run(Task::perform(
+                            Recipe::delete(database.clone(), recipe.product_id.unwrap_or_default()),
+                            |res| match res {
+                                Ok(_) => Message::FetchRecipes,
+                                Err(err) => {
+                                    eprintln!("{err}");
+                                    Message::AddToast(Toast::error_toast(err))
+                                }
+                            },
+                        ));
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready { sub_screen } => match sub_screen {
+                SubScreen::List {
+                    pagination_state,
+                    recipes,
+                } => list_screen(pagination_state, recipes),
+                SubScreen::Upsert {
+                    recipe,
+                    editing,
+                    products,
+                    ingredients,
+                } => upsert_screen(recipe, *editing, products, ingredients),
+            },
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+// LIST SCREEN
+
+fn list_screen<'a>(
+    pagination_state: &'a PaginationConfig,
+    recipes: &'a [Recipe],
+) -> iced::Element<'a, Message> {
+    let header = list_header();
+    let grid: Element<'a, Message, Theme, Renderer> = if recipes.is_empty() {
+        container(text(fl!("no-recipes")).size(TITLE_TEXT_SIZE))
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(50.)
+            .into()
+    } else {
+        let title_row = Row::new()
+            .push(
+                text(fl!("product"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(300.)
+                    .align_y(Alignment::Center),
+            )
+            .push(
+                text(fl!("ingredients"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(300.)
+                    .align_x(Alignment::Start)
+                    .align_y(Alignment::End),
+            )
+            .push(
+                text(fl!("edit"))
+                    .size(TITLE_TEXT_SIZE)
+                    .width(200.)
+                    .align_y(Alignment::Center)
+                    .align_x(Alignment::End),
+            )
+            .width(Length::Shrink)
+            .align_y(Alignment::Center);
+
+        // Calculate the indices for the current page
+        let start_index: usize =
+            pagination_state.current_page as usize * pagination_state.items_per_page as usize;
+        let end_index = usize::min(
+            start_index + pagination_state.items_per_page as usize,
+            recipes.len(),
+        );
+
+        let mut grid = Column::new()
+            .push(title_row)
+            .spacing(GLOBAL_SPACING)
+            .width(Length::Shrink);
+
+        for recipe in &recipes[start_index..end_index] {
+            let row = Row::new()
+                .push(
+                    text(recipe.product_name.as_ref())
+                        .size(TEXT_SIZE)
+                        .width(300.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    text(recipe.ingredients.len().to_string())
+                        .size(TEXT_SIZE)
+                        .width(300.)
+                        .align_x(Alignment::Start)
+                        .align_y(Alignment::Center),
+                )
+                .push(
+                    row![
+                        Space::new(Length::Fill, Length::Shrink),
+                        button(text(fl!("edit")).size(TEXT_SIZE).align_y(Alignment::Center))
+                            .on_press(Message::AskEditRecipe(recipe.product_id.unwrap()))
+                            .width(Length::Shrink)
+                    ]
+                    .width(200.),
+                )
+                .align_y(Alignment::Center);
+
+            // Limit Rule size to sum of all column widths
+            grid = grid.push(row![Rule::horizontal(1.)].width(800.));
+            grid = grid.push(row);
+        }
+
+        scrollable(grid).spacing(GLOBAL_SPACING).into()
+    };
+
+    let page_controls = Column::new()
+        .push(row![Rule::horizontal(1.)].width(800.))
+        .push(
+            text(format!(
+                "{} {}",
+                fl!("page").as_str(),
+                &pagination_state.current_page + 1
+            ))
+            .align_x(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .width(800.)
+                .push(
+                    button(
+                        text(fl!("back"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Back)),
+                )
+                .push(
+                    button(
+                        text(fl!("next"))
+                            .center()
+                            .width(Length::Fill)
+                            .height(GLOBAL_BUTTON_HEIGHT),
+                    )
+                    .on_press(Message::PaginationAction(PaginationAction::Forward)),
+                )
+                .align_y(Alignment::Center)
+                .spacing(GLOBAL_SPACING),
+        )
+        .spacing(GLOBAL_SPACING)
+        .align_x(Alignment::Center);
+
+    let content = container(
+        column![grid, page_controls]
+            .spacing(GLOBAL_SPACING)
+            .width(800.),
+    )
+    .width(Length::Fill)
+    .align_x(Alignment::Center)
+    .padding(50.);
+
+    column![header, content]
+        .spacing(GLOBAL_SPACING)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+}
+
+fn list_header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let add_button = button(text(fl!("add")).center())
+        .on_press(Message::AskOpenUpsertScreen)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("recipes")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        add_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+// UPSERT SCREEN
+
+fn upsert_screen<'a>(
+    recipe: &'a Recipe,
+    editing: bool,
+    products: &'a [Product],
+    ingredients: &'a [Ingredient],
+) -> iced::Element<'a, Message> {
+    let header = upsert_header(editing);
+
+    // Product
+    let product_label = text(format!("{}*", fl!("product"))).width(Length::Fill);
+    let selected_product = products.iter().find(|p| p.id == recipe.product_id);
+    let product_selector: Element<'a, Message> = if editing {
+        text_input(fl!("product").as_str(), &recipe.product_name)
+            .size(TEXT_SIZE)
+            .width(Length::Fill)
+            .into()
+    } else {
+        pick_list(products, selected_product, |product| {
+            Message::UpdatedSelectedProductId(product.id.unwrap_or_default())
+        })
+        .placeholder(fl!("product"))
+        .width(Length::Fill)
+        .into()
+    };
+
+    // Ingredients
+    let ingredients_label = text(format!("{}*", fl!("ingredients"))).width(Length::Fill);
+    let mut ingredients_column = Column::new().spacing(GLOBAL_SPACING);
+    for (index, recipe_ingredient) in recipe.ingredients.iter().enumerate() {
+        let selected_ingredient = ingredients
+            .iter()
+            .find(|i| i.id == recipe_ingredient.ingredient_id);
+
+        ingredients_column = ingredients_column.push(
+            row![
+                pick_list(ingredients, selected_ingredient, move |ingredient| {
+                    Message::UpdatedSelectedIngredientId(index, ingredient.id.unwrap_or_default())
+                })
+                .placeholder(fl!("ingredient"))
+                .width(Length::Fill),
+                text_input(fl!("quantity").as_str(), &recipe_ingredient.quantity_input)
+                    .on_input(move |c| Message::TextInputUpdate(
+                        c,
+                        RecipeTextInputFields::IngredientQuantity(index)
+                    ))
+                    .size(TEXT_SIZE)
+                    .width(200.),
+                button(text(fl!("delete")).center())
+                    .style(button::danger)
+                    .on_press(Message::RemoveIngredient(index))
+            ]
+            .spacing(GLOBAL_SPACING)
+            .align_y(Alignment::Center),
+        );
+    }
+    let add_ingredient_button =
+        button(text(fl!("add-ingredient")).center()).on_press(Message::AddIngredient);
+
+    // Submit
+    let submit_button_text = if editing {
+        text(fl!("edit"))
+    } else {
+        text(fl!("add"))
+    };
+    let submit_button = button(submit_button_text.center().size(TEXT_SIZE))
+        .on_press_maybe(recipe.is_valid().then_some(Message::UpsertCurrentRecipe))
+        .width(Length::Fill);
+
+    // Input Columns
+    let product_input_column = column![product_label, product_selector]
+        .width(850.)
+        .spacing(1.);
+    let ingredients_input_column =
+        column![ingredients_label, ingredients_column, add_ingredient_button]
+            .width(850.)
+            .spacing(GLOBAL_SPACING);
+
+    let form_column = Column::new()
+        .push(product_input_column)
+        .push(ingredients_input_column)
+        .push(submit_button)
+        .width(850.)
+        .spacing(GLOBAL_SPACING);
+
+    column![
+        header,
+        scrollable(
+            container(form_column)
+                .align_x(Alignment::Center)
+                .align_y(Alignment::Center)
+                .width(Length::Fill)
+                .padding(50.)
+        )
+    ]
+    .into()
+}
+
+fn upsert_header<'a>(editing: bool) -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let delete_button = button(text(fl!("delete")).center())
+        .style(button::danger)
+        .on_press_maybe(editing.then_some(Message::DeleteCurrentRecipe))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("recipe")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink),
+        delete_button
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
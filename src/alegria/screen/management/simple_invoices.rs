@@ -1,18 +1,23 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use iced::keyboard::key::Named;
 use iced::keyboard::{self, Key, Modifiers};
 use iced::time::Instant;
 use iced::widget::{
-    Column, Row, Rule, Space, button, column, focus_next, focus_previous, row, scrollable, text,
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
 };
 use iced::{Alignment, Element, Length, Renderer, Subscription, Theme, event};
 use iced::{Task, widget::container};
 use sqlx::{Pool, Postgres};
 
-use crate::alegria::core::models::simple_invoice::SimpleInvoice;
+use crate::alegria::core::facturae;
+use crate::alegria::core::models::invoice_record::{ChainBreak, InvoiceRecord};
+use crate::alegria::core::models::simple_invoice::{InvoiceRecipient, SimpleInvoice};
+use crate::alegria::core::print::{AlegriaPrinter, TicketType};
+use crate::alegria::screen::bar::InvoiceRecipientField;
 use crate::alegria::utils::styling::{
     GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
 };
@@ -26,6 +31,7 @@ use crate::{
 };
 
 pub struct SimpleInvoices {
+    printers: Printers,
     state: State,
 }
 
@@ -38,12 +44,33 @@ pub enum SubScreen {
     List {
         pagination_state: PaginationConfig,
         simple_invoices: Vec<SimpleInvoice>,
+        /// Result of the last verification of the invoice records chain (None if it hasn't been verified)
+        chain_breaks: Option<Vec<ChainBreak>>,
     },
     Details {
         simple_invoice: Box<SimpleInvoice>,
+        /// Why the invoice is being refunded (required to refund it)
+        refund_reason: String,
+        full_invoice_form: FullInvoiceForm,
     },
 }
 
+/// Recipient of the full invoice that can be issued in place of a simplified invoice
+#[derive(Default)]
+pub struct FullInvoiceForm {
+    recipient: InvoiceRecipient,
+    /// Text used to search the recipient among the clients
+    search: String,
+    search_results: Vec<InvoiceRecipient>,
+}
+
+/// Printers available to print the invoices
+#[derive(Default)]
+struct Printers {
+    selected_printer: Option<AlegriaPrinter>,
+    all_printers: Vec<AlegriaPrinter>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     /// Asks the parent to go back
@@ -61,13 +88,54 @@ pub enum Message {
     /// Try to go left or right a page
     PaginationAction(PaginationAction),
 
+    /// Asks to verify the invoice records chain
+    VerifyChain,
+    /// Callback after verifying the invoice records chain, with the points where it's broken
+    ChainVerified(Vec<ChainBreak>),
+    /// Asks to submit the invoice records that haven't been sent yet
+    SubmitRecords,
+    /// Callback after submitting the invoice records, with how many have been sent
+    RecordsSubmitted(usize),
+
     /// Callback after asking to see the details of a simple invoice, searches the simple_invoice on the db
     AskDetailsSimpleInvoice(i32),
     /// Changes to the details screen with the given simple_invoice
     OpenDetailsScreen(Box<SimpleInvoice>),
 
-    /// Tries to delete the current simple invoice
-    DeleteCurrentSimpleInvoice,
+    /// Sets the printers on the state
+    SetPrinters(Option<AlegriaPrinter>, Vec<AlegriaPrinter>),
+    /// Updates the selected printer
+    UpdateSelectedPrinter(AlegriaPrinter),
+    /// Prints the current simple invoice (as a full invoice if it has a recipient or rectifies another one)
+    PrintCurrentSimpleInvoice,
+    /// Callback after printing the current simple invoice
+    PrintJobCompleted(Result<(), &'static str>),
+    /// Exports the current simple invoice as a Facturae file (only issued invoices with a recipient)
+    ExportFacturae,
+    /// Callback after exporting the current simple invoice, with the path of the file
+    FacturaeExported(Result<PathBuf, &'static str>),
+
+    /// Callback when using the refund reason text input
+    RefundReasonInput(String),
+    /// Tries to refund the current (paid) simple invoice, issuing a rectifying invoice
+    RefundCurrentSimpleInvoice,
+    /// Callback after refunding the current simple invoice, opens the rectifying invoice (by id)
+    RefundedCurrentSimpleInvoice(i32),
+
+    /// Callback when using the full invoice recipient search text input
+    RecipientSearchInput(String),
+    /// Searches the companies and clients that match the full invoice recipient search
+    SearchRecipients,
+    /// Callback after searching the full invoice recipient
+    RecipientsFound(Vec<InvoiceRecipient>),
+    /// Fills the full invoice recipient with the fiscal data of a search result
+    SelectRecipient(InvoiceRecipient),
+    /// Updates a field of the full invoice recipient
+    RecipientFieldInput(InvoiceRecipientField, String),
+    /// Tries to issue a full invoice in place of the current (simplified) invoice
+    IssueFullInvoice,
+    /// Callback after issuing the full invoice, opens it (by id)
+    IssuedFullInvoice(i32),
 }
 
 pub enum Action {
@@ -81,15 +149,21 @@ impl SimpleInvoices {
     pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
         (
             Self {
+                printers: Printers::default(),
                 state: State::Loading,
             },
-            Task::perform(SimpleInvoice::get_all(database.clone()), |res| match res {
-                Ok(res) => Message::PageLoaded(res),
-                Err(err) => {
-                    eprintln!("{err}");
-                    Message::AddToast(Toast::error_toast(err))
-                }
-            }),
+            Task::batch([
+                Task::perform(SimpleInvoice::get_all(database.clone()), |res| match res {
+                    Ok(res) => Message::PageLoaded(res),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                }),
+                Task::perform(AlegriaPrinter::load_printers(), |res| {
+                    Message::SetPrinters(res.0, res.1)
+                }),
+            ]),
         )
     }
 
@@ -149,6 +223,7 @@ impl SimpleInvoices {
                     sub_screen: SubScreen::List {
                         pagination_state: PaginationConfig::default(),
                         simple_invoices: res,
+                        chain_breaks: None,
                     },
                 };
                 Action::None
@@ -184,6 +259,37 @@ impl SimpleInvoices {
                 }
                 Action::None
             }
+            Message::VerifyChain => Action::Run(Task::perform(
+                InvoiceRecord::verify_chain(database.clone()),
+                |res| match res {
+                    Ok(chain_breaks) => Message::ChainVerified(chain_breaks),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::ChainVerified(res) => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::List { chain_breaks, .. } = sub_screen
+                {
+                    *chain_breaks = Some(res);
+                }
+                Action::None
+            }
+            Message::SubmitRecords => Action::Run(Task::perform(
+                InvoiceRecord::submit_pending(database.clone()),
+                |res| match res {
+                    Ok(submitted) => Message::RecordsSubmitted(submitted),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        Message::AddToast(Toast::error_toast(err))
+                    }
+                },
+            )),
+            Message::RecordsSubmitted(submitted) => Action::AddToast(Toast::success_toast(
+                format!("{}: {submitted}", fl!("records-submitted")),
+            )),
             Message::AskDetailsSimpleInvoice(simple_invoice_id) => {
                 let database = database.clone();
                 Action::Run(Task::perform(
@@ -196,21 +302,104 @@ impl SimpleInvoices {
             }
             Message::OpenDetailsScreen(simple_invoice) => {
                 self.state = State::Ready {
-                    sub_screen: SubScreen::Details { simple_invoice },
+                    sub_screen: SubScreen::Details {
+                        simple_invoice,
+                        refund_reason: String::new(),
+                        full_invoice_form: FullInvoiceForm::default(),
+                    },
                 };
                 Action::None
             }
-            Message::DeleteCurrentSimpleInvoice => {
+            Message::SetPrinters(default_printer, all_printers) => {
+                self.printers.selected_printer = default_printer;
+                self.printers.all_printers = all_printers;
+                Action::None
+            }
+            Message::UpdateSelectedPrinter(printer) => {
+                self.printers.selected_printer = Some(printer);
+                Action::None
+            }
+            Message::PrintCurrentSimpleInvoice => {
+                if let State::Ready { sub_screen } = &self.state
+                    && let SubScreen::Details { simple_invoice, .. } = sub_screen
+                {
+                    let Some(printer) = self.printers.selected_printer.clone() else {
+                        return Action::AddToast(Toast::warning_toast("No printer selected"));
+                    };
+
+                    let ticket_type = if simple_invoice.recipient.is_some()
+                        || simple_invoice.rectified_invoice_id.is_some()
+                    {
+                        TicketType::Invoice
+                    } else {
+                        TicketType::Receipt
+                    };
+
+                    return Action::Run(Task::perform(
+                        Arc::new(printer).print(*simple_invoice.clone(), ticket_type),
+                        Message::PrintJobCompleted,
+                    ));
+                }
+                Action::None
+            }
+            Message::PrintJobCompleted(result) => match result {
+                Ok(_) => Action::None,
+                Err(err) => {
+                    eprintln!("{err}");
+                    Action::AddToast(Toast::error_toast(err))
+                }
+            },
+            Message::ExportFacturae => {
+                if let State::Ready { sub_screen } = &self.state
+                    && let SubScreen::Details { simple_invoice, .. } = sub_screen
+                {
+                    return Action::Run(Task::perform(
+                        facturae::export(database.clone(), *simple_invoice.clone()),
+                        Message::FacturaeExported,
+                    ));
+                }
+                Action::None
+            }
+            Message::FacturaeExported(result) => match result {
+                Ok(path) => Action::AddToast(Toast::success_toast(format!(
+                    "{}: {}",
+                    fl!("facturae-exported"),
+                    path.display()
+                ))),
+                Err(err) => {
+                    eprintln!("{err}");
+                    Action::AddToast(Toast::error_toast(err))
+                }
+            },
+            Message::RefundReasonInput(new_value) => {
+                if let State::Ready { sub_screen, .. } = &mut self.state {
+                    #[allow(clippy::collapsible_match)]
+                    if let SubScreen::Details { refund_reason, .. } = sub_screen {
+                        *refund_reason = new_value;
+                    }
+                }
+                Action::None
+            }
+            Message::RefundCurrentSimpleInvoice => {
                 if let State::Ready { sub_screen, .. } = &mut self.state {
                     #[allow(clippy::collapsible_match)]
-                    if let SubScreen::Details { simple_invoice, .. } = sub_screen {
+                    if let SubScreen::Details {
+                        simple_invoice,
+                        refund_reason,
+                        ..
+                    } = sub_screen
+                        && !refund_reason.trim().is_empty()
+                    {
                         return Action::Run(Task::perform(
-                            SimpleInvoice::delete(
+                            SimpleInvoice::refund(
                                 database.clone(),
                                 simple_invoice.id.unwrap_or_default(),
+                                refund_reason.clone(),
                             ),
                             |res| match res {
-                                Ok(_) => Message::FetchSimpleInvoices,
+                                Ok(rectifying_invoice_id) => {
+                                    Message::RefundedCurrentSimpleInvoice(rectifying_invoice_id)
+                                }
                                 Err(err) => {
                                     eprintln!("{err}");
                                     Message::AddToast(Toast::error_toast(err))
@@ -221,6 +410,118 @@ impl SimpleInvoices {
                 }
                 Action::None
             }
+            Message::RefundedCurrentSimpleInvoice(rectifying_invoice_id) => self.update(
+                Message::AskDetailsSimpleInvoice(rectifying_invoice_id),
+                &database.clone(),
+                now,
+            ),
+            Message::RecipientSearchInput(new_value) => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Details {
+                        full_invoice_form, ..
+                    } = sub_screen
+                {
+                    full_invoice_form.search = new_value;
+                }
+                Action::None
+            }
+            Message::SearchRecipients => {
+                if let State::Ready { sub_screen } = &self.state
+                    && let SubScreen::Details {
+                        full_invoice_form, ..
+                    } = sub_screen
+                    && !full_invoice_form.search.trim().is_empty()
+                {
+                    return Action::Run(Task::perform(
+                        InvoiceRecipient::search(
+                            database.clone(),
+                            full_invoice_form.search.clone(),
+                        ),
+                        |res| match res {
+                            Ok(recipients) => Message::RecipientsFound(recipients),
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::RecipientsFound(recipients) => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Details {
+                        full_invoice_form, ..
+                    } = sub_screen
+                {
+                    full_invoice_form.search_results = recipients;
+                }
+                Action::None
+            }
+            Message::SelectRecipient(recipient) => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Details {
+                        full_invoice_form, ..
+                    } = sub_screen
+                {
+                    full_invoice_form.recipient = recipient;
+                    full_invoice_form.search_results.clear();
+                }
+                Action::None
+            }
+            Message::RecipientFieldInput(field, new_value) => {
+                if let State::Ready { sub_screen } = &mut self.state
+                    && let SubScreen::Details {
+                        full_invoice_form, ..
+                    } = sub_screen
+                {
+                    // the fiscal data no longer is the one of the company
+                    let recipient = &mut full_invoice_form.recipient;
+                    recipient.company_id = None;
+                    match field {
+                        InvoiceRecipientField::Name => recipient.name = new_value,
+                        InvoiceRecipientField::Nif => recipient.nif = new_value,
+                        InvoiceRecipientField::Address => recipient.address = new_value,
+                    }
+                }
+                Action::None
+            }
+            Message::IssueFullInvoice => {
+                if let State::Ready { sub_screen } = &self.state
+                    && let SubScreen::Details {
+                        simple_invoice,
+                        full_invoice_form,
+                        ..
+                    } = sub_screen
+                {
+                    if !full_invoice_form.recipient.is_valid() {
+                        return Action::AddToast(Toast::warning_toast(
+                            "Missing invoice recipient data",
+                        ));
+                    }
+
+                    return Action::Run(Task::perform(
+                        SimpleInvoice::issue_full_invoice(
+                            database.clone(),
+                            simple_invoice.id.unwrap_or_default(),
+                            full_invoice_form.recipient.clone(),
+                        ),
+                        |res| match res {
+                            Ok(full_invoice_id) => Message::IssuedFullInvoice(full_invoice_id),
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+            Message::IssuedFullInvoice(full_invoice_id) => self.update(
+                Message::AskDetailsSimpleInvoice(full_invoice_id),
+                &database.clone(),
+                now,
+            ),
         }
     }
 
@@ -231,8 +532,18 @@ impl SimpleInvoices {
                 SubScreen::List {
                     pagination_state,
                     simple_invoices,
-                } => list_screen(pagination_state, simple_invoices),
-                SubScreen::Details { simple_invoice } => details_screen(simple_invoice),
+                    chain_breaks,
+                } => list_screen(pagination_state, simple_invoices, chain_breaks),
+                SubScreen::Details {
+                    simple_invoice,
+                    refund_reason,
+                    full_invoice_form,
+                } => details_screen(
+                    simple_invoice,
+                    refund_reason,
+                    full_invoice_form,
+                    &self.printers,
+                ),
             },
         }
     }
@@ -271,6 +582,7 @@ fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> O
 fn list_screen<'a>(
     pagination_state: &'a PaginationConfig,
     simple_invoices: &'a [SimpleInvoice],
+    chain_breaks: &'a Option<Vec<ChainBreak>>,
 ) -> iced::Element<'a, Message> {
     let header = list_header();
     let grid: Element<'a, Message, Theme, Renderer> = if simple_invoices.is_empty() {
@@ -282,15 +594,15 @@ fn list_screen<'a>(
     } else {
         let title_row = Row::new()
             .push(
-                text(fl!("id"))
+                text(fl!("invoice-number"))
                     .size(TITLE_TEXT_SIZE)
-                    .width(100.)
+                    .width(250.)
                     .align_y(Alignment::Center),
             )
             .push(
                 text(fl!("total-invoice"))
                     .size(TITLE_TEXT_SIZE)
-                    .width(500.)
+                    .width(350.)
                     .align_x(Alignment::Start)
                     .align_y(Alignment::End),
             )
@@ -320,16 +632,17 @@ fn list_screen<'a>(
         for simple_invoice in &simple_invoices[start_index..end_index] {
             let row = Row::new()
                 .push(
-                    text(simple_invoice.id.unwrap_or_default())
+                    // unpaid invoices don't have a number yet
+                    text(simple_invoice.invoice_number.as_deref().unwrap_or("-"))
                         .size(TEXT_SIZE)
-                        .width(100.)
+                        .width(250.)
                         .align_x(Alignment::Start)
                         .align_y(Alignment::Center),
                 )
                 .push(
                     text(format!("{:.2}€", simple_invoice.total_price()))
                         .size(TEXT_SIZE)
-                        .width(500.)
+                        .width(350.)
                         .align_x(Alignment::Start)
                         .align_y(Alignment::Center),
                 )
@@ -394,7 +707,7 @@ fn list_screen<'a>(
         .align_x(Alignment::Center);
 
     let content = container(
-        column![grid, page_controls]
+        column![chain_breaks_view(chain_breaks), grid, page_controls]
             .spacing(GLOBAL_SPACING)
             .width(800.),
     )
@@ -414,10 +727,20 @@ fn list_header<'a>() -> iced::Element<'a, Message> {
         .on_press(Message::Back)
         .height(GLOBAL_BUTTON_HEIGHT);
 
+    let verify_button = button(text(fl!("verify-chain")).center())
+        .on_press(Message::VerifyChain)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    let submit_button = button(text(fl!("submit-records")).center())
+        .on_press(Message::SubmitRecords)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
     row![
         back_button,
         text(fl!("simple-invoices")).size(TITLE_TEXT_SIZE),
-        Space::new(Length::Fill, Length::Shrink)
+        Space::new(Length::Fill, Length::Shrink),
+        verify_button,
+        submit_button
     ]
     .align_y(Alignment::Center)
     .spacing(GLOBAL_SPACING)
@@ -425,12 +748,164 @@ fn list_header<'a>() -> iced::Element<'a, Message> {
     .into()
 }
 
+/// Result of the last verification of the invoice records chain
+fn chain_breaks_view<'a>(chain_breaks: &'a Option<Vec<ChainBreak>>) -> iced::Element<'a, Message> {
+    let Some(chain_breaks) = chain_breaks else {
+        return Space::new(Length::Shrink, Length::Shrink).into();
+    };
+
+    if chain_breaks.is_empty() {
+        return text(fl!("chain-intact"))
+            .size(TEXT_SIZE)
+            .style(text::success)
+            .into();
+    }
+
+    let mut breaks = Column::new()
+        .push(
+            text(fl!("chain-broken"))
+                .size(TITLE_TEXT_SIZE)
+                .style(text::danger),
+        )
+        .spacing(3.);
+    for chain_break in chain_breaks {
+        breaks = breaks.push(
+            text(format!(
+                "#{} {}: {}",
+                chain_break.sequence, chain_break.invoice_number, chain_break.kind
+            ))
+            .size(TEXT_SIZE)
+            .style(text::danger),
+        );
+    }
+
+    breaks.into()
+}
+
 // DETAILS SCREEN
 
-fn details_screen<'a>(simple_invoice: &'a SimpleInvoice) -> iced::Element<'a, Message> {
+fn details_screen<'a>(
+    simple_invoice: &'a SimpleInvoice,
+    refund_reason: &'a str,
+    full_invoice_form: &'a FullInvoiceForm,
+    printers: &'a Printers,
+) -> iced::Element<'a, Message> {
     let header = details_header(simple_invoice);
 
-    let content = text("Content");
+    let mut content = Column::new()
+        .push(
+            text(format!(
+                "{}: {:.2}€",
+                fl!("total-invoice"),
+                simple_invoice.total_price()
+            ))
+            .size(TITLE_TEXT_SIZE),
+        )
+        .spacing(GLOBAL_SPACING)
+        .width(800.);
+
+    if simple_invoice.paid {
+        let printer_selector = pick_list(
+            printers.all_printers.as_slice(),
+            printers.selected_printer.clone(),
+            Message::UpdateSelectedPrinter,
+        )
+        .width(Length::Fill);
+
+        let print_button = button(text(fl!("print")).center())
+            .on_press_maybe(
+                printers
+                    .selected_printer
+                    .as_ref()
+                    .map(|_| Message::PrintCurrentSimpleInvoice),
+            )
+            .height(GLOBAL_BUTTON_HEIGHT);
+
+        let export_button = button(text(fl!("export-facturae")).center())
+            .on_press_maybe(
+                (simple_invoice.recipient.is_some() && simple_invoice.invoice_number.is_some())
+                    .then_some(Message::ExportFacturae),
+            )
+            .height(GLOBAL_BUTTON_HEIGHT);
+
+        content = content.push(
+            row![printer_selector, print_button, export_button]
+                .spacing(GLOBAL_SPACING)
+                .align_y(Alignment::Center),
+        );
+    }
+
+    if simple_invoice.rectified_invoice_id.is_some() {
+        content = content.push(
+            text(format!(
+                "{} {}: {}",
+                fl!("refund-of"),
+                simple_invoice
+                    .rectified_invoice_number
+                    .as_deref()
+                    .unwrap_or_default(),
+                simple_invoice
+                    .rectification_reason
+                    .as_deref()
+                    .unwrap_or_default()
+            ))
+            .size(TEXT_SIZE),
+        );
+    } else if let Some(rectifying_invoice_id) = simple_invoice.rectifying_invoice_id {
+        content = content.push(
+            text(format!("{} #{rectifying_invoice_id}", fl!("refunded-by")))
+                .size(TEXT_SIZE)
+                .style(text::danger),
+        );
+    } else if let Some(replacing_invoice_id) = simple_invoice.replacing_invoice_id {
+        // the full invoice is the one that has to be refunded
+        content = content
+            .push(text(format!("{} #{replacing_invoice_id}", fl!("replaced-by"))).size(TEXT_SIZE));
+    } else if simple_invoice.paid {
+        // paid invoices are immutable, they are refunded with a rectifying invoice
+        content = content.push(
+            row![
+                text_input(fl!("reason").as_str(), refund_reason)
+                    .on_input(Message::RefundReasonInput)
+                    .on_submit(Message::RefundCurrentSimpleInvoice)
+                    .size(TEXT_SIZE)
+                    .width(Length::Fill),
+                button(text(fl!("refund")).center())
+                    .style(button::danger)
+                    .on_press_maybe(
+                        (!refund_reason.trim().is_empty())
+                            .then_some(Message::RefundCurrentSimpleInvoice)
+                    )
+                    .height(GLOBAL_BUTTON_HEIGHT)
+            ]
+            .spacing(GLOBAL_SPACING)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    if simple_invoice.replaced_invoice_id.is_some() {
+        content = content.push(
+            text(format!(
+                "{} {}",
+                fl!("replaces"),
+                simple_invoice
+                    .replaced_invoice_number
+                    .as_deref()
+                    .unwrap_or_default()
+            ))
+            .size(TEXT_SIZE),
+        );
+    }
+
+    // paid simplified invoices can be replaced by a full invoice (only once)
+    if simple_invoice.paid
+        && simple_invoice.recipient.is_none()
+        && simple_invoice.rectified_invoice_id.is_none()
+        && simple_invoice.rectifying_invoice_id.is_none()
+        && simple_invoice.replacing_invoice_id.is_none()
+    {
+        content = content.push(full_invoice_form_view(full_invoice_form));
+    }
 
     column![
         header,
@@ -443,25 +918,108 @@ fn details_screen<'a>(simple_invoice: &'a SimpleInvoice) -> iced::Element<'a, Me
     .into()
 }
 
+/// Recipient search (among the companies and clients), recipient fiscal data and button to issue a full invoice
+fn full_invoice_form_view<'a>(
+    full_invoice_form: &'a FullInvoiceForm,
+) -> iced::Element<'a, Message> {
+    let recipient = &full_invoice_form.recipient;
+
+    let search_row = row![
+        text_input(fl!("search").as_str(), &full_invoice_form.search)
+            .on_input(Message::RecipientSearchInput)
+            .on_submit(Message::SearchRecipients)
+            .size(TEXT_SIZE)
+            .width(Length::Fill),
+        button(text(fl!("search")).center())
+            .on_press(Message::SearchRecipients)
+            .height(GLOBAL_BUTTON_HEIGHT)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .align_y(Alignment::Center);
+
+    let search_results =
+        full_invoice_form
+            .search_results
+            .iter()
+            .fold(Column::new().spacing(3.), |col, result| {
+                col.push(
+                    button(text(format!("{} - {}", result.name, result.nif)).size(TEXT_SIZE))
+                        .on_press(Message::SelectRecipient(result.clone()))
+                        .style(button::text)
+                        .width(Length::Fill),
+                )
+            });
+
+    let fields = [
+        (
+            InvoiceRecipientField::Name,
+            fl!("recipient-name"),
+            &recipient.name,
+        ),
+        (
+            InvoiceRecipientField::Nif,
+            fl!("recipient-nif"),
+            &recipient.nif,
+        ),
+        (
+            InvoiceRecipientField::Address,
+            fl!("recipient-address"),
+            &recipient.address,
+        ),
+    ]
+    .into_iter()
+    .fold(
+        Column::new().spacing(GLOBAL_SPACING),
+        |col, (field, label, value)| {
+            col.push(
+                row![
+                    text(label.clone()).size(TEXT_SIZE).width(250.),
+                    text_input(label.as_str(), value)
+                        .on_input(move |c| Message::RecipientFieldInput(field.clone(), c))
+                        .size(TEXT_SIZE)
+                        .width(Length::Fill)
+                ]
+                .spacing(GLOBAL_SPACING)
+                .align_y(Alignment::Center),
+            )
+        },
+    );
+
+    let issue_button = button(text(fl!("issue-full-invoice")).center())
+        .on_press_maybe(recipient.is_valid().then_some(Message::IssueFullInvoice))
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    column![
+        Rule::horizontal(1.),
+        text(fl!("issue-full-invoice")).size(TITLE_TEXT_SIZE),
+        search_row,
+        search_results,
+        fields,
+        issue_button
+    ]
+    .spacing(GLOBAL_SPACING)
+    .into()
+}
+
 fn details_header<'a>(simple_invoice: &'a SimpleInvoice) -> iced::Element<'a, Message> {
     let back_button = button(text(fl!("back")).center())
         .on_press(Message::Back)
         .height(GLOBAL_BUTTON_HEIGHT);
 
-    let delete_button = button(text(fl!("delete")).center())
-        .style(button::danger)
-        .on_press_maybe(
-            simple_invoice
-                .id
-                .map(|_| Message::DeleteCurrentSimpleInvoice),
-        )
-        .height(GLOBAL_BUTTON_HEIGHT);
+    let title = if simple_invoice.rectified_invoice_id.is_some() {
+        fl!("rectifying-invoice")
+    } else {
+        fl!("simple-invoice")
+    };
 
     row![
         back_button,
-        text(fl!("simple-invoices")).size(TITLE_TEXT_SIZE),
-        Space::new(Length::Fill, Length::Shrink),
-        delete_button
+        text(format!(
+            "{title} {}",
+            simple_invoice.invoice_number.as_deref().unwrap_or_default()
+        ))
+        .size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink)
     ]
     .align_y(Alignment::Center)
     .spacing(GLOBAL_SPACING)
@@ -0,0 +1,375 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, pick_list, row,
+    scrollable, text, text_input,
+};
+use iced::{Alignment, Length, Subscription, Theme, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::product::Product;
+use crate::alegria::core::models::stock_movement::StockMovement;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+use crate::{alegria::widgets::toast::Toast, fl};
+
+pub struct Stock {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready {
+        /// Every product, the ones with tracked stock make the stock report
+        products: Vec<Product>,
+        movements: Vec<StockMovement>,
+        new_movement: StockMovement,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to retrieve the products and the latest stock movements
+    FetchStock,
+    /// Callback after retrieving the products and the latest stock movements
+    StockLoaded(Vec<Product>, Vec<StockMovement>),
+
+    /// Callback after selecting the product of the new stock movement
+    UpdatedSelectedProductId(i32),
+    /// Updates a text input of the screen
+    TextInputUpdate(String, StockTextInputFields),
+    /// Tries to add the new stock movement
+    AddStockMovement,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StockTextInputFields {
+    Quantity,
+    Reason,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl Stock {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        (
+            Self {
+                state: State::Loading,
+            },
+            fetch_stock(database),
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        _now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => match hotkey {
+                Hotkey::Tab(modifiers) => {
+                    if modifiers.shift() {
+                        Action::Run(focus_previous())
+                    } else {
+                        Action::Run(focus_next())
+                    }
+                }
+            },
+            Message::FetchStock => Action::Run(fetch_stock(database)),
+            Message::StockLoaded(products, movements) => {
+                self.state = State::Ready {
+                    products,
+                    movements,
+                    new_movement: StockMovement::default(),
+                };
+                Action::None
+            }
+            Message::UpdatedSelectedProductId(product_id) => {
+                if let State::Ready { new_movement, .. } = &mut self.state {
+                    new_movement.product_id = Some(product_id);
+                }
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready { new_movement, .. } = &mut self.state {
+                    match field {
+                        StockTextInputFields::Quantity => {
+                            // a lone minus sign is the start of a negative adjustment
+                            if let Ok(num) = new_value.parse::<i32>() {
+                                new_movement.quantity = Some(num);
+                                new_movement.quantity_input = new_value;
+                            } else if new_value.is_empty() || new_value == "-" {
+                                new_movement.quantity = None;
+                                new_movement.quantity_input = new_value;
+                            }
+                        }
+                        StockTextInputFields::Reason => new_movement.reason = new_value,
+                    }
+                }
+                Action::None
+            }
+            Message::AddStockMovement => {
+                if let State::Ready { new_movement, .. } = &mut self.state {
+                    if !new_movement.is_valid() {
+                        return Action::AddToast(Toast::warning_toast(
+                            "A stock adjustment needs a product, a quantity and a reason",
+                        ));
+                    }
+
+                    return Action::Run(Task::perform(
+                        StockMovement::add(database.clone(), new_movement.clone()),
+                        |res| match res {
+                            Ok(_) => Message::FetchStock,
+                            Err(err) => {
+                                eprintln!("{err}");
+                                Message::AddToast(Toast::error_toast(err))
+                            }
+                        },
+                    ));
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready {
+                products,
+                movements,
+                new_movement,
+            } => column![
+                header(),
+                container(
+                    row![
+                        stock_report(products),
+                        adjustment_view(products, movements, new_movement)
+                    ]
+                    .spacing(GLOBAL_SPACING * 4.)
+                )
+                .width(Length::Fill)
+                .align_x(Alignment::Center)
+                .padding(50.)
+            ]
+            .spacing(GLOBAL_SPACING)
+            .into(),
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Fetches the products and the latest stock movements
+fn fetch_stock(database: &Arc<Pool<Postgres>>) -> Task<Message> {
+    let database = database.clone();
+    Task::perform(
+        async move {
+            tokio::join!(
+                Product::get_all(database.clone()),
+                StockMovement::get_latest(database.clone())
+            )
+        },
+        |res| match res {
+            (Ok(products), Ok(movements)) => Message::StockLoaded(products, movements),
+            _ => Message::AddToast(Toast::error_toast(
+                "Error fetching products or stock movements",
+            )),
+        },
+    )
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("stock")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+/// Stock of every product with tracked stock, the ones that are low on stock first
+fn stock_report<'a>(products: &'a [Product]) -> iced::Element<'a, Message> {
+    let mut tracked: Vec<&Product> = products
+        .iter()
+        .filter(|p| p.stock_quantity.is_some())
+        .collect();
+    tracked.sort_by_key(|p| (!p.is_low_on_stock(), p.name.to_lowercase()));
+
+    let title_row = Row::new()
+        .push(text(fl!("product")).size(TITLE_TEXT_SIZE).width(250.))
+        .push(text(fl!("stock")).size(TITLE_TEXT_SIZE).width(100.))
+        .push(
+            text(fl!("low-stock-threshold"))
+                .size(TITLE_TEXT_SIZE)
+                .width(150.),
+        )
+        .align_y(Alignment::Center);
+
+    let mut grid = Column::new().push(title_row).spacing(GLOBAL_SPACING);
+
+    if tracked.is_empty() {
+        grid = grid.push(text(fl!("no-tracked-stock")).size(TEXT_SIZE));
+    }
+
+    for product in tracked {
+        let style: fn(&Theme) -> text::Style = if product.is_low_on_stock() {
+            text::danger
+        } else {
+            text::default
+        };
+
+        grid = grid.push(row![Rule::horizontal(1.)].width(500.));
+        grid = grid.push(
+            Row::new()
+                .push(text(&product.name).size(TEXT_SIZE).width(250.).style(style))
+                .push(
+                    text(product.stock_quantity.unwrap_or_default().to_string())
+                        .size(TEXT_SIZE)
+                        .width(100.)
+                        .style(style),
+                )
+                .push(
+                    text(
+                        product
+                            .low_stock_threshold
+                            .map(|t| t.to_string())
+                            .unwrap_or_else(|| String::from("-")),
+                    )
+                    .size(TEXT_SIZE)
+                    .width(150.),
+                )
+                .align_y(Alignment::Center),
+        );
+    }
+
+    column![
+        text(fl!("stock-report")).size(TITLE_TEXT_SIZE),
+        scrollable(grid).height(Length::Fill)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .width(500.)
+    .into()
+}
+
+/// Form to adjust the stock of a product and the latest stock movements
+fn adjustment_view<'a>(
+    products: &'a [Product],
+    movements: &'a [StockMovement],
+    new_movement: &'a StockMovement,
+) -> iced::Element<'a, Message> {
+    let selected = products.iter().find(|p| p.id == new_movement.product_id);
+    let product_selector = pick_list(products, selected, |product| {
+        Message::UpdatedSelectedProductId(product.id.unwrap_or_default())
+    })
+    .placeholder(fl!("product"))
+    .width(Length::Fill);
+
+    let adjustment_form = column![
+        text(fl!("stock-adjustment")).size(TITLE_TEXT_SIZE),
+        product_selector,
+        text_input(fl!("quantity").as_str(), &new_movement.quantity_input)
+            .on_input(|c| Message::TextInputUpdate(c, StockTextInputFields::Quantity))
+            .size(TEXT_SIZE),
+        text_input(fl!("reason").as_str(), &new_movement.reason)
+            .on_input(|c| Message::TextInputUpdate(c, StockTextInputFields::Reason))
+            .on_submit(Message::AddStockMovement)
+            .size(TEXT_SIZE),
+        button(text(fl!("add")).center())
+            .on_press_maybe(new_movement.is_valid().then_some(Message::AddStockMovement))
+            .width(Length::Fill)
+            .height(GLOBAL_BUTTON_HEIGHT),
+    ]
+    .spacing(GLOBAL_SPACING);
+
+    let mut movements_column = Column::new().spacing(GLOBAL_SPACING).width(Length::Fill);
+    for movement in movements {
+        let description = if movement.reason.is_empty() {
+            movement.kind.to_string()
+        } else {
+            format!("{} ({})", movement.kind, movement.reason)
+        };
+
+        movements_column = movements_column.push(
+            Row::new()
+                .push(
+                    text(
+                        movement
+                            .created_at
+                            .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                            .unwrap_or_default(),
+                    )
+                    .width(140.),
+                )
+                .push(text(&*movement.product_name).width(150.))
+                .push(text(description).width(Length::Fill))
+                .push(text(format!("{:+}", movement.quantity.unwrap_or_default())))
+                .spacing(GLOBAL_SPACING),
+        );
+    }
+
+    column![
+        adjustment_form,
+        Rule::horizontal(1.),
+        text(fl!("stock-movements")).size(TITLE_TEXT_SIZE),
+        scrollable(movements_column).height(Length::Fill)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .width(600.)
+    .into()
+}
@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use chrono::{Datelike, Local, NaiveDate};
+use iced::keyboard::key::Named;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::time::Instant;
+use iced::widget::{
+    Column, Row, Rule, Space, button, column, focus_next, focus_previous, row, scrollable, text,
+    text_input,
+};
+use iced::{Alignment, Length, Subscription, event};
+use iced::{Task, widget::container};
+use sqlx::{Pool, Postgres};
+
+use crate::alegria::core::models::voided_line::VoidedLine;
+use crate::alegria::utils::date::parse_date_to_naive_datetime;
+use crate::alegria::utils::money::Money;
+use crate::alegria::utils::styling::{
+    GLOBAL_BUTTON_HEIGHT, GLOBAL_SPACING, TEXT_SIZE, TITLE_TEXT_SIZE,
+};
+use crate::{alegria::widgets::toast::Toast, fl};
+
+pub struct VoidedLines {
+    state: State,
+}
+
+enum State {
+    Loading,
+    Ready {
+        from_input: String,
+        to_input: String,
+        voided_lines: Vec<VoidedLine>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks the parent to go back
+    Back,
+    /// Asks the parent to add a toast
+    AddToast(Toast),
+    /// Hotkey (Subscription) pressed
+    Hotkey(Hotkey),
+
+    /// Asks to retrieve the lines voided between the dates of the screen
+    FetchVoidedLines,
+    /// Callback after retrieving the voided lines
+    VoidedLinesLoaded(Vec<VoidedLine>),
+
+    /// Updates a text input of the screen
+    TextInputUpdate(String, VoidedLinesTextInputFields),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VoidedLinesTextInputFields {
+    From,
+    To,
+}
+
+pub enum Action {
+    None,
+    Back,
+    Run(Task<Message>),
+    AddToast(Toast),
+}
+
+impl VoidedLines {
+    pub fn new(database: &Arc<Pool<Postgres>>) -> (Self, Task<Message>) {
+        // by default we show the current month
+        let today = Local::now().date_naive();
+        let from = today.with_day(1).unwrap_or(today);
+
+        (
+            Self {
+                state: State::Loading,
+            },
+            fetch_voided_lines(database, from, today),
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        message: Message,
+        database: &Arc<Pool<Postgres>>,
+        _now: Instant,
+    ) -> Action {
+        match message {
+            Message::Back => Action::Back,
+            Message::AddToast(toast) => Action::AddToast(toast),
+            Message::Hotkey(hotkey) => match hotkey {
+                Hotkey::Tab(modifiers) => {
+                    if modifiers.shift() {
+                        Action::Run(focus_previous())
+                    } else {
+                        Action::Run(focus_next())
+                    }
+                }
+            },
+            Message::FetchVoidedLines => {
+                if let State::Ready {
+                    from_input,
+                    to_input,
+                    ..
+                } = &self.state
+                {
+                    let (Some(from), Some(to)) = (parse_date(from_input), parse_date(to_input))
+                    else {
+                        return Action::AddToast(Toast::warning_toast(
+                            "Invalid dates, use the yyyy-mm-dd format",
+                        ));
+                    };
+
+                    return Action::Run(fetch_voided_lines(database, from, to));
+                }
+                Action::None
+            }
+            Message::VoidedLinesLoaded(res) => {
+                match &mut self.state {
+                    State::Ready { voided_lines, .. } => *voided_lines = res,
+                    State::Loading => {
+                        let today = Local::now().date_naive();
+                        self.state = State::Ready {
+                            from_input: today.with_day(1).unwrap_or(today).to_string(),
+                            to_input: today.to_string(),
+                            voided_lines: res,
+                        };
+                    }
+                }
+                Action::None
+            }
+            Message::TextInputUpdate(new_value, field) => {
+                if let State::Ready {
+                    from_input,
+                    to_input,
+                    ..
+                } = &mut self.state
+                {
+                    match field {
+                        VoidedLinesTextInputFields::From => *from_input = new_value,
+                        VoidedLinesTextInputFields::To => *to_input = new_value,
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&self, _now: Instant) -> iced::Element<'_, Message> {
+        match &self.state {
+            State::Loading => container(text("Loading...")).center(Length::Fill).into(),
+            State::Ready {
+                from_input,
+                to_input,
+                voided_lines,
+            } => column![
+                header(),
+                container(
+                    column![
+                        dates_row(from_input, to_input),
+                        voided_lines_list(voided_lines)
+                    ]
+                    .spacing(GLOBAL_SPACING)
+                    .width(1000.)
+                )
+                .width(Length::Fill)
+                .align_x(Alignment::Center)
+                .padding(50.)
+            ]
+            .spacing(GLOBAL_SPACING)
+            .into(),
+        }
+    }
+
+    pub fn subscription(&self, _now: Instant) -> Subscription<Message> {
+        event::listen_with(handle_event)
+    }
+}
+
+/// Fetches the lines voided between the given dates (both included)
+fn fetch_voided_lines(
+    database: &Arc<Pool<Postgres>>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Task<Message> {
+    Task::perform(
+        VoidedLine::get_between(database.clone(), from, to),
+        |res| match res {
+            Ok(res) => Message::VoidedLinesLoaded(res),
+            Err(err) => {
+                eprintln!("{err}");
+                Message::AddToast(Toast::error_toast(err))
+            }
+        },
+    )
+}
+
+/// Parses a date input with the yyyy-(m)m-(d)d format
+fn parse_date(input: &str) -> Option<NaiveDate> {
+    parse_date_to_naive_datetime(input.trim()).map(|date| date.date())
+}
+
+//
+// SUBSCRIPTION HANDLING
+//
+
+#[derive(Debug, Clone)]
+pub enum Hotkey {
+    Tab(Modifiers),
+}
+
+fn handle_event(event: event::Event, _: event::Status, _: iced::window::Id) -> Option<Message> {
+    match event {
+        #[allow(clippy::collapsible_match)]
+        event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+            Key::Named(Named::Tab) => Some(Message::Hotkey(Hotkey::Tab(modifiers))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//
+// VIEW COMPOSING
+//
+
+fn header<'a>() -> iced::Element<'a, Message> {
+    let back_button = button(text(fl!("back")).center())
+        .on_press(Message::Back)
+        .height(GLOBAL_BUTTON_HEIGHT);
+
+    row![
+        back_button,
+        text(fl!("voided-lines")).size(TITLE_TEXT_SIZE),
+        Space::new(Length::Fill, Length::Shrink)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(GLOBAL_SPACING)
+    .padding(3.)
+    .into()
+}
+
+fn dates_row<'a>(from_input: &'a str, to_input: &'a str) -> iced::Element<'a, Message> {
+    row![
+        column![
+            text(format!("{} (yyyy-mm-dd)", fl!("initial-date"))),
+            text_input(fl!("initial-date").as_str(), from_input)
+                .on_input(|c| Message::TextInputUpdate(c, VoidedLinesTextInputFields::From))
+                .on_submit(Message::FetchVoidedLines)
+                .size(TEXT_SIZE)
+        ]
+        .spacing(1.)
+        .width(Length::Fill),
+        column![
+            text(format!("{} (yyyy-mm-dd)", fl!("last-date"))),
+            text_input(fl!("last-date").as_str(), to_input)
+                .on_input(|c| Message::TextInputUpdate(c, VoidedLinesTextInputFields::To))
+                .on_submit(Message::FetchVoidedLines)
+                .size(TEXT_SIZE)
+        ]
+        .spacing(1.)
+        .width(Length::Fill),
+        button(text(fl!("filter")).center())
+            .on_press(Message::FetchVoidedLines)
+            .height(GLOBAL_BUTTON_HEIGHT)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .align_y(Alignment::End)
+    .into()
+}
+
+/// Voided lines (newest first) and the total amount voided
+fn voided_lines_list<'a>(voided_lines: &'a [VoidedLine]) -> iced::Element<'a, Message> {
+    let title_row = Row::new()
+        .push(text(fl!("date")).size(TITLE_TEXT_SIZE).width(150.))
+        .push(text(fl!("table")).size(TITLE_TEXT_SIZE).width(100.))
+        .push(text(fl!("product")).size(TITLE_TEXT_SIZE).width(200.))
+        .push(text(fl!("amount")).size(TITLE_TEXT_SIZE).width(120.))
+        .push(
+            text(fl!("reason"))
+                .size(TITLE_TEXT_SIZE)
+                .width(Length::Fill),
+        )
+        .push(text(fl!("voided-by")).size(TITLE_TEXT_SIZE).width(150.))
+        .spacing(GLOBAL_SPACING)
+        .align_y(Alignment::Center);
+
+    let mut grid = Column::new().push(title_row).spacing(GLOBAL_SPACING);
+
+    if voided_lines.is_empty() {
+        grid = grid.push(text(fl!("no-voided-lines")).size(TEXT_SIZE));
+    }
+
+    for voided_line in voided_lines {
+        grid = grid.push(Rule::horizontal(1.));
+        grid = grid.push(
+            Row::new()
+                .push(
+                    text(
+                        voided_line
+                            .created_at
+                            .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                            .unwrap_or_default(),
+                    )
+                    .size(TEXT_SIZE)
+                    .width(150.),
+                )
+                .push(
+                    text(voided_line.table_name.as_ref())
+                        .size(TEXT_SIZE)
+                        .width(100.),
+                )
+                .push(
+                    text(format!("{} x{}", voided_line.name, voided_line.quantity))
+                        .size(TEXT_SIZE)
+                        .width(200.),
+                )
+                .push(
+                    text(format!("{:.2}€", voided_line.amount))
+                        .size(TEXT_SIZE)
+                        .width(120.),
+                )
+                .push(
+                    text(&voided_line.reason)
+                        .size(TEXT_SIZE)
+                        .width(Length::Fill),
+                )
+                .push(text(&voided_line.voided_by).size(TEXT_SIZE).width(150.))
+                .spacing(GLOBAL_SPACING)
+                .align_y(Alignment::Center),
+        );
+    }
+
+    let total: Money = voided_lines.iter().map(|l| l.amount).sum();
+
+    column![
+        scrollable(grid).height(Length::Fill),
+        Rule::horizontal(1.),
+        text(format!("{}: {:.2}€", fl!("total-voided"), total)).size(TITLE_TEXT_SIZE)
+    ]
+    .spacing(GLOBAL_SPACING)
+    .into()
+}
@@ -2,6 +2,7 @@
 
 pub mod date;
 pub mod entities;
+pub mod money;
 pub mod pagination;
 pub mod styling;
 pub mod temporal_tickets;
@@ -1,3 +1,8 @@
+pub mod discount;
 pub mod gender;
 pub mod identity_document_type;
+pub mod invoice_series_kind;
 pub mod payment_method;
+pub mod price_adjustment;
+pub mod stock_movement_kind;
+pub mod table_shape;
@@ -0,0 +1,125 @@
+use std::fmt::Display;
+
+use iced::widget::text::IntoFragment;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Decode, Encode, Postgres, Type, postgres::PgTypeInfo};
+
+use crate::alegria::utils::money::Money;
+
+/// How the value of a discount is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DiscountKind {
+    /// Percentage of the price
+    #[default]
+    Percentage,
+    /// Amount taken from the price
+    Fixed,
+}
+
+impl Display for DiscountKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            DiscountKind::Percentage => write!(f, "Porcentaje"),
+            DiscountKind::Fixed => write!(f, "Importe fijo"),
+        }
+    }
+}
+
+impl<'a> IntoFragment<'a> for DiscountKind {
+    fn into_fragment(self) -> iced::widget::text::Fragment<'a> {
+        iced::widget::text::Fragment::Owned(self.to_string())
+    }
+}
+
+impl DiscountKind {
+    pub const ALL: &'static [Self] = &[Self::Percentage, Self::Fixed];
+
+    pub fn to_id(self) -> i32 {
+        match self {
+            DiscountKind::Percentage => 1,
+            DiscountKind::Fixed => 2,
+        }
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            1 => Some(DiscountKind::Percentage),
+            2 => Some(DiscountKind::Fixed),
+            _ => None,
+        }
+    }
+}
+
+// Implement Type trait to tell SQLx how to handle this type
+impl Type<Postgres> for DiscountKind {
+    fn type_info() -> PgTypeInfo {
+        <i32 as Type<Postgres>>::type_info()
+    }
+}
+
+// Implement Encode to convert enum to database value
+impl<'q> Encode<'q, Postgres> for DiscountKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i32 as Encode<Postgres>>::encode_by_ref(&self.to_id(), buf)
+    }
+}
+
+// Implement Decode to convert database value to enum
+impl<'r> Decode<'r, Postgres> for DiscountKind {
+    fn decode(
+        value: <Postgres as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let id = <i32 as Decode<Postgres>>::decode(value)?;
+        Self::from_id(id).ok_or_else(|| format!("Invalid discount_kind id: {id}").into())
+    }
+}
+
+/// A discount given to a line or to a whole ticket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Discount {
+    pub kind: DiscountKind,
+    /// Percentage (0-100] or amount, depending on the kind
+    pub value: Decimal,
+    /// Why the discount has been given, it's shown on the receipt and on the reports
+    pub reason: String,
+}
+
+impl Display for Discount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            DiscountKind::Percentage => write!(f, "-{}% ({})", self.value.normalize(), self.reason),
+            DiscountKind::Fixed => write!(f, "-{:.2}€ ({})", self.value, self.reason),
+        }
+    }
+}
+
+impl Discount {
+    /// Returns true if the discount is valid (ready to be applied)
+    pub fn is_valid(&self) -> bool {
+        let valid_value = match self.kind {
+            DiscountKind::Percentage => {
+                self.value > Decimal::ZERO && self.value <= Decimal::ONE_HUNDRED
+            }
+            DiscountKind::Fixed => self.value > Decimal::ZERO,
+        };
+
+        valid_value && !self.reason.trim().is_empty()
+    }
+
+    /// Returns the given price with the discount applied (never below zero)
+    pub fn apply(&self, price: Money) -> Money {
+        let price = Decimal::from(price);
+        let discounted = match self.kind {
+            DiscountKind::Percentage => {
+                price - price * self.value.min(Decimal::ONE_HUNDRED) / Decimal::ONE_HUNDRED
+            }
+            DiscountKind::Fixed => price - self.value,
+        };
+
+        Money::from(discounted.max(Decimal::ZERO))
+    }
+}
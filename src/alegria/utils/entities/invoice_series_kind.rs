@@ -0,0 +1,81 @@
+use std::fmt::Display;
+
+use iced::widget::text::IntoFragment;
+use serde::{Deserialize, Serialize};
+use sqlx::{Decode, Encode, Postgres, Type, postgres::PgTypeInfo};
+
+use crate::fl;
+
+/// Numbering series an invoice belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum InvoiceSeriesKind {
+    /// Simplified invoices (tickets)
+    #[default]
+    Simplified,
+    /// Full invoices (with the recipient fiscal data)
+    Full,
+    /// Invoices that rectify (refund) another invoice
+    Rectifying,
+}
+
+impl Display for InvoiceSeriesKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            InvoiceSeriesKind::Simplified => write!(f, "{}", fl!("simplified-series")),
+            InvoiceSeriesKind::Full => write!(f, "{}", fl!("full-series")),
+            InvoiceSeriesKind::Rectifying => write!(f, "{}", fl!("rectifying-series")),
+        }
+    }
+}
+
+impl<'a> IntoFragment<'a> for InvoiceSeriesKind {
+    fn into_fragment(self) -> iced::widget::text::Fragment<'a> {
+        iced::widget::text::Fragment::Owned(self.to_string())
+    }
+}
+
+impl InvoiceSeriesKind {
+    pub fn to_id(self) -> i32 {
+        match self {
+            InvoiceSeriesKind::Simplified => 1,
+            InvoiceSeriesKind::Full => 2,
+            InvoiceSeriesKind::Rectifying => 3,
+        }
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            1 => Some(InvoiceSeriesKind::Simplified),
+            2 => Some(InvoiceSeriesKind::Full),
+            3 => Some(InvoiceSeriesKind::Rectifying),
+            _ => None,
+        }
+    }
+}
+
+// Implement Type trait to tell SQLx how to handle this type
+impl Type<Postgres> for InvoiceSeriesKind {
+    fn type_info() -> PgTypeInfo {
+        <i32 as Type<Postgres>>::type_info()
+    }
+}
+
+// Implement Encode to convert enum to database value
+impl<'q> Encode<'q, Postgres> for InvoiceSeriesKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i32 as Encode<Postgres>>::encode_by_ref(&self.to_id(), buf)
+    }
+}
+
+// Implement Decode to convert database value to enum
+impl<'r> Decode<'r, Postgres> for InvoiceSeriesKind {
+    fn decode(
+        value: <Postgres as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let id = <i32 as Decode<Postgres>>::decode(value)?;
+        Self::from_id(id).ok_or_else(|| format!("Invalid invoice_series_kind id: {id}").into())
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt::Display;
+
+use iced::widget::text::IntoFragment;
+use serde::{Deserialize, Serialize};
+use sqlx::{Decode, Encode, Postgres, Type, postgres::PgTypeInfo};
+
+/// How a pricing rule changes the price of a product
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PriceAdjustment {
+    /// The product costs the value of the rule
+    #[default]
+    FixedPrice,
+    /// The value of the rule is added (as a percentage) to the base price, negative for a discount
+    Percentage,
+}
+
+impl Display for PriceAdjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            PriceAdjustment::FixedPrice => write!(f, "Precio fijo"),
+            PriceAdjustment::Percentage => write!(f, "Porcentaje"),
+        }
+    }
+}
+
+impl<'a> IntoFragment<'a> for PriceAdjustment {
+    fn into_fragment(self) -> iced::widget::text::Fragment<'a> {
+        iced::widget::text::Fragment::Owned(self.to_string())
+    }
+}
+
+impl PriceAdjustment {
+    pub const ALL: &'static [Self] = &[Self::FixedPrice, Self::Percentage];
+
+    pub fn to_id(self) -> i32 {
+        match self {
+            PriceAdjustment::FixedPrice => 1,
+            PriceAdjustment::Percentage => 2,
+        }
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            1 => Some(PriceAdjustment::FixedPrice),
+            2 => Some(PriceAdjustment::Percentage),
+            _ => None,
+        }
+    }
+}
+
+// Implement Type trait to tell SQLx how to handle this type
+impl Type<Postgres> for PriceAdjustment {
+    fn type_info() -> PgTypeInfo {
+        <i32 as Type<Postgres>>::type_info()
+    }
+}
+
+// Implement Encode to convert enum to database value
+impl<'q> Encode<'q, Postgres> for PriceAdjustment {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i32 as Encode<Postgres>>::encode_by_ref(&self.to_id(), buf)
+    }
+}
+
+// Implement Decode to convert database value to enum
+impl<'r> Decode<'r, Postgres> for PriceAdjustment {
+    fn decode(
+        value: <Postgres as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let id = <i32 as Decode<Postgres>>::decode(value)?;
+        Self::from_id(id).ok_or_else(|| format!("Invalid price_adjustment id: {id}").into())
+    }
+}
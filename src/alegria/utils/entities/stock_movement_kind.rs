@@ -0,0 +1,79 @@
+use std::fmt::Display;
+
+use iced::widget::text::IntoFragment;
+use serde::{Deserialize, Serialize};
+use sqlx::{Decode, Encode, Postgres, Type, postgres::PgTypeInfo};
+
+/// Why the stock of a product has changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum StockMovementKind {
+    /// The product has been sold (paid)
+    Sale,
+    /// The invoice of a sale has been refunded, the product is back in stock
+    Restored,
+    /// Manual adjustment (deliveries, breakages, stocktaking...)
+    #[default]
+    Adjustment,
+}
+
+impl Display for StockMovementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            StockMovementKind::Sale => write!(f, "Venta"),
+            StockMovementKind::Restored => write!(f, "Devolución"),
+            StockMovementKind::Adjustment => write!(f, "Ajuste"),
+        }
+    }
+}
+
+impl<'a> IntoFragment<'a> for StockMovementKind {
+    fn into_fragment(self) -> iced::widget::text::Fragment<'a> {
+        iced::widget::text::Fragment::Owned(self.to_string())
+    }
+}
+
+impl StockMovementKind {
+    pub fn to_id(self) -> i32 {
+        match self {
+            StockMovementKind::Sale => 1,
+            StockMovementKind::Restored => 2,
+            StockMovementKind::Adjustment => 3,
+        }
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            1 => Some(StockMovementKind::Sale),
+            2 => Some(StockMovementKind::Restored),
+            3 => Some(StockMovementKind::Adjustment),
+            _ => None,
+        }
+    }
+}
+
+// Implement Type trait to tell SQLx how to handle this type
+impl Type<Postgres> for StockMovementKind {
+    fn type_info() -> PgTypeInfo {
+        <i32 as Type<Postgres>>::type_info()
+    }
+}
+
+// Implement Encode to convert enum to database value
+impl<'q> Encode<'q, Postgres> for StockMovementKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i32 as Encode<Postgres>>::encode_by_ref(&self.to_id(), buf)
+    }
+}
+
+// Implement Decode to convert database value to enum
+impl<'r> Decode<'r, Postgres> for StockMovementKind {
+    fn decode(
+        value: <Postgres as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let id = <i32 as Decode<Postgres>>::decode(value)?;
+        Self::from_id(id).ok_or_else(|| format!("Invalid stock_movement_kind id: {id}").into())
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Display;
+
+use iced::widget::text::IntoFragment;
+use serde::{Deserialize, Serialize};
+use sqlx::{Decode, Encode, Postgres, Type, postgres::PgTypeInfo};
+
+/// Shape of a table on the floor plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TableShape {
+    #[default]
+    Square,
+    Round,
+}
+
+impl Display for TableShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            TableShape::Square => write!(f, "Cuadrada"),
+            TableShape::Round => write!(f, "Redonda"),
+        }
+    }
+}
+
+impl<'a> IntoFragment<'a> for TableShape {
+    fn into_fragment(self) -> iced::widget::text::Fragment<'a> {
+        iced::widget::text::Fragment::Owned(self.to_string())
+    }
+}
+
+impl TableShape {
+    pub const ALL: &'static [Self] = &[Self::Square, Self::Round];
+
+    pub fn to_id(self) -> i32 {
+        match self {
+            TableShape::Square => 1,
+            TableShape::Round => 2,
+        }
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            1 => Some(TableShape::Square),
+            2 => Some(TableShape::Round),
+            _ => None,
+        }
+    }
+}
+
+// Implement Type trait to tell SQLx how to handle this type
+impl Type<Postgres> for TableShape {
+    fn type_info() -> PgTypeInfo {
+        <i32 as Type<Postgres>>::type_info()
+    }
+}
+
+// Implement Encode to convert enum to database value
+impl<'q> Encode<'q, Postgres> for TableShape {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i32 as Encode<Postgres>>::encode_by_ref(&self.to_id(), buf)
+    }
+}
+
+// Implement Decode to convert database value to enum
+impl<'r> Decode<'r, Postgres> for TableShape {
+    fn decode(
+        value: <Postgres as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let id = <i32 as Decode<Postgres>>::decode(value)?;
+        Self::from_id(id).ok_or_else(|| format!("Invalid table_shape id: {id}").into())
+    }
+}
@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use iced::widget::text::IntoFragment;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::{Decode, Encode, Postgres, Type, postgres::PgTypeInfo};
+
+/// Exact monetary amount (euros with two decimals), stored as NUMERIC(12,2) on the db
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Money(Decimal);
+
+impl Money {
+    pub const ZERO: Money = Money(Decimal::ZERO);
+
+    /// Returns the share of the amount that corresponds to one of the given number of payers (rounded to cents).
+    /// The last payer (payers <= 1) pays whatever is left.
+    pub fn share(self, payers: i32) -> Money {
+        if payers <= 1 {
+            return self;
+        }
+
+        Money::from(self.0 / Decimal::from(payers))
+    }
+}
+
+impl From<Decimal> for Money {
+    /// Rounds the given decimal to cents (half away from zero, as the spanish invoicing regulation requires)
+    fn from(value: Decimal) -> Self {
+        Money(round_cents(value))
+    }
+}
+
+impl From<Money> for Decimal {
+    fn from(value: Money) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for Money {
+    type Err = rust_decimal::Error;
+
+    /// Parses a user input ("1", "1.", "1.5", "1,50"...) into an amount
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_decimal(s).map(Money::from)
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
+
+impl<'a> IntoFragment<'a> for Money {
+    fn into_fragment(self) -> iced::widget::text::Fragment<'a> {
+        iced::widget::text::Fragment::Owned(self.to_string())
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Self::Output {
+        Money(-self.0)
+    }
+}
+
+impl Mul<i32> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Money(self.0 * Decimal::from(rhs))
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Money::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Self {
+        iter.fold(Money::ZERO, |acc, x| acc + *x)
+    }
+}
+
+// Implement Type trait to tell SQLx how to handle this type
+impl Type<Postgres> for Money {
+    fn type_info() -> PgTypeInfo {
+        <Decimal as Type<Postgres>>::type_info()
+    }
+}
+
+// Implement Encode to convert the amount to a database value
+impl<'q> Encode<'q, Postgres> for Money {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <Decimal as Encode<Postgres>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+// Implement Decode to convert a database value to an amount
+impl<'r> Decode<'r, Postgres> for Money {
+    fn decode(
+        value: <Postgres as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let amount = <Decimal as Decode<Postgres>>::decode(value)?;
+        Ok(Money::from(amount))
+    }
+}
+
+/// Rounds a decimal to cents (half away from zero, as the spanish invoicing regulation requires)
+pub fn round_cents(value: Decimal) -> Decimal {
+    value.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Parses a user input ("1", "1.", "1.5", "1,50"...) into a decimal
+pub fn parse_decimal(input: &str) -> Result<Decimal, rust_decimal::Error> {
+    let input = input.trim().replace(',', ".");
+    Decimal::from_str(input.trim_end_matches('.'))
+}
+
+/// Splits a tax-included amount into its (base, tax) given the tax percentage.
+/// The base is rounded to cents and the tax is the difference, so base + tax always equals the amount.
+pub fn split_tax(amount: Money, tax_percentage: Decimal) -> (Money, Money) {
+    let divisor = Decimal::ONE_HUNDRED + tax_percentage;
+    if divisor.is_zero() {
+        return (amount, Money::ZERO);
+    }
+
+    let base = Money::from(amount.0 * Decimal::ONE_HUNDRED / divisor);
+    (base, amount - base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn rounds_midpoints_away_from_zero() {
+        for (value, rounded) in [
+            ("0.005", "0.01"),
+            ("0.015", "0.02"),
+            ("2.675", "2.68"),
+            ("-0.005", "-0.01"),
+            ("-2.675", "-2.68"),
+            ("1.004999", "1.00"),
+            ("1.10", "1.10"),
+        ] {
+            assert_eq!(
+                round_cents(Decimal::from_str(value).unwrap()),
+                Decimal::from_str(rounded).unwrap(),
+                "{value}"
+            );
+        }
+        assert_eq!(money("0.125").to_string(), "0.13");
+    }
+
+    #[test]
+    fn base_and_tax_add_up_to_the_amount() {
+        for tax_percentage in ["0", "4", "5", "10", "21", "7.5"] {
+            let tax_percentage = Decimal::from_str(tax_percentage).unwrap();
+            for cents in -1000..=1000 {
+                let amount = Money::from(Decimal::new(cents, 2));
+                let (base, tax) = split_tax(amount, tax_percentage);
+                assert_eq!(base + tax, amount, "{amount} at {tax_percentage}%");
+            }
+        }
+
+        // 0.05 at 10% is 0.04545... so the base rounds to 0.05 and the tax is what's left
+        assert_eq!(
+            split_tax(money("0.05"), Decimal::TEN),
+            (money("0.05"), Money::ZERO)
+        );
+        assert_eq!(
+            split_tax(money("12.10"), Decimal::from(21)),
+            (money("10.00"), money("2.10"))
+        );
+        // a -100% tax can't be split, everything is base
+        assert_eq!(
+            split_tax(money("1.00"), Decimal::from(-100)),
+            (money("1.00"), Money::ZERO)
+        );
+    }
+
+    #[test]
+    fn shares_add_up_to_the_amount() {
+        for cents in 0..=500 {
+            let amount = Money::from(Decimal::new(cents, 2));
+            for payers in 1..=7 {
+                // every payer pays a share of what's left between the payers still to pay
+                let mut left = amount;
+                let mut paid = Vec::new();
+                for payers_left in (1..=payers).rev() {
+                    let share = left.share(payers_left);
+                    left -= share;
+                    paid.push(share);
+                }
+
+                assert_eq!(left, Money::ZERO);
+                assert_eq!(paid.iter().sum::<Money>(), amount, "{amount} / {payers}");
+                // nobody pays more than a cent more than another payer
+                let (min, max) = (paid.iter().min().unwrap(), paid.iter().max().unwrap());
+                assert!(*max - *min <= money("0.01"), "{paid:?}");
+            }
+        }
+
+        assert_eq!(money("10.00").share(3), money("3.33"));
+        assert_eq!(money("0.05").share(2), money("0.03"));
+        assert_eq!(money("10.00").share(0), money("10.00"));
+    }
+
+    #[test]
+    fn parses_user_inputs() {
+        for (input, value) in [
+            ("1", "1"),
+            ("1.", "1"),
+            ("1.5", "1.5"),
+            ("1,50", "1.50"),
+            (" 2,5 ", "2.5"),
+            ("-3.25", "-3.25"),
+        ] {
+            assert_eq!(
+                parse_decimal(input).unwrap(),
+                Decimal::from_str(value).unwrap(),
+                "{input}"
+            );
+        }
+        assert_eq!(money("1,005"), money("1.01"));
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        for input in [
+            "", " ", ".", "abc", "1.2.3", "1,2,3", "1,5€", "12 50", "1.5a",
+        ] {
+            assert!(parse_decimal(input).is_err(), "{input:?}");
+            assert!(input.parse::<Money>().is_err(), "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use iced::{
+    Element,
+    widget::{Stack, container, pin},
+};
+
+use crate::alegria::core::models::bar_table::BarTable;
+
+/// Size of the floor plan of a location, the positions of the tables are relative to its top left corner
+pub const FLOOR_PLAN_WIDTH: f32 = 800.;
+pub const FLOOR_PLAN_HEIGHT: f32 = 600.;
+
+/// Places the element of each table at the position (and with the size) the table has on the floor plan
+pub fn floor_plan<'a, Message>(
+    tables: impl IntoIterator<Item = (&'a BarTable, Element<'a, Message>)>,
+) -> Element<'a, Message>
+where
+    Message: 'a,
+{
+    let plan = tables.into_iter().fold(
+        Stack::new()
+            .width(FLOOR_PLAN_WIDTH)
+            .height(FLOOR_PLAN_HEIGHT),
+        |plan, (table, content)| {
+            plan.push(
+                pin(container(content)
+                    .width(table.width as f32)
+                    .height(table.height as f32))
+                .x(table.pos_x as f32)
+                .y(table.pos_y as f32),
+            )
+        },
+    );
+
+    container(plan).style(container::bordered_box).into()
+}
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+pub mod floor_plan;
 pub mod focusable_text_input;
 pub mod modal;
 pub mod numpad;